@@ -46,6 +46,7 @@ fn main() {
             range: ByteRange::new(100, 120),
             kind: CallKind::Function,
             is_resolved: true,
+            receiver_type: None,
         },
     )];
 
@@ -6,22 +6,34 @@ use actix_web::{
     web::{Data, Json, JsonConfig, PayloadConfig},
     App, HttpResponse, HttpServer, Responder,
 };
+use doctown_common::{
+    Attribute, ExternalSymbol, Language, SymbolId, SymbolIdAllocator, SymbolKind, SymbolMetrics,
+};
 use doctown_events::{
     AssemblyClusterCreatedPayload, AssemblyCompletedPayload, AssemblyGraphCompletedPayload,
     AssemblyStartedPayload, Context, EdgeTypeBreakdown, Envelope, EventType, Status,
 };
 use log::{error, info};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 
 use crate::packer::{PackRequest, Packer};
 use crate::{
     cluster::Clusterer, context::ContextGenerator, graph::GraphBuilder, label::ClusterLabeler,
-    EdgeKind, SymbolContext,
+    DocumentExcerpt, EdgeKind, SymbolContext,
 };
 
+/// Cluster ID for test symbols when using [`TestHandling::Separate`].
+pub const TEST_CLUSTER_ID: &str = "cluster_tests";
+
+/// Label of the test cluster.
+const TEST_CLUSTER_LABEL: &str = "Tests";
+
+/// Mentions matching more symbols than this are too ambiguous to link.
+const MAX_MENTION_MATCHES: usize = 3;
+
 /// Request schema for the /assemble endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssembleRequest {
@@ -35,6 +47,25 @@ pub struct AssembleRequest {
     pub chunks: Vec<ChunkWithEmbedding>,
     /// Symbol metadata for graph construction.
     pub symbols: Vec<SymbolMetadata>,
+    /// How test symbols are treated during clustering and centrality.
+    #[serde(default)]
+    pub test_handling: TestHandling,
+    /// Documentation sections (README, docs/, ADRs) whose chunks are in `chunks`.
+    #[serde(default)]
+    pub documents: Vec<DocumentMetadata>,
+}
+
+/// How test code is treated during assembly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestHandling {
+    /// Treat test symbols like any other symbol.
+    #[default]
+    Include,
+    /// Leave test symbols unclustered and out of centrality.
+    Exclude,
+    /// Put test symbols in their own cluster and leave them out of centrality.
+    Separate,
 }
 
 /// A chunk with its embedding vector.
@@ -42,7 +73,7 @@ pub struct AssembleRequest {
 pub struct ChunkWithEmbedding {
     /// Chunk ID.
     pub chunk_id: String,
-    /// Embedding vector (384 dimensions for all-MiniLM-L6-v2).
+    /// Embedding vector, with the dimensions of the ingest embedding model.
     pub vector: Vec<f32>,
     /// Text content of the chunk.
     pub content: String,
@@ -51,7 +82,9 @@ pub struct ChunkWithEmbedding {
 /// Symbol metadata for graph construction.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SymbolMetadata {
-    /// Symbol ID.
+    /// Canonical symbol ID (see `SymbolId::derive`); derived from the language,
+    /// kind and qualified name when omitted.
+    #[serde(default)]
     pub symbol_id: String,
     /// Symbol name.
     pub name: String,
@@ -62,6 +95,9 @@ pub struct SymbolMetadata {
     pub language: String,
     /// File path.
     pub file_path: String,
+    /// Full path in the module tree (e.g., "my_crate::net::Client"), if known.
+    #[serde(default)]
+    pub qualified_name: Option<String>,
     /// Symbol signature.
     pub signature: String,
     /// Chunk IDs associated with this symbol.
@@ -69,9 +105,316 @@ pub struct SymbolMetadata {
     /// Calls made by this symbol.
     #[serde(default)]
     pub calls: Vec<String>,
-    /// Imports used by this symbol.
+    /// Symbols this symbol uses through an import of its file.
     #[serde(default)]
     pub imports: Vec<String>,
+    /// Base classes, interfaces, or supertraits this symbol inherits from.
+    #[serde(default)]
+    pub inherits: Vec<String>,
+    /// Traits or interfaces this symbol implements.
+    #[serde(default)]
+    pub implements: Vec<String>,
+    /// Types this symbol uses in its signature, fields, or annotations.
+    #[serde(default)]
+    pub references: Vec<String>,
+    /// Whether this symbol is test code.
+    #[serde(default)]
+    pub is_test: bool,
+    /// Size and complexity metrics computed during ingest.
+    #[serde(default)]
+    pub metrics: Option<SymbolMetrics>,
+    /// Attributes and decorators attached to this symbol.
+    #[serde(default)]
+    pub attributes: Vec<Attribute>,
+    /// Standard library and third-party symbols this symbol calls.
+    #[serde(default)]
+    pub external_calls: Vec<ExternalSymbol>,
+    /// Standard library and third-party symbols this symbol imports.
+    #[serde(default)]
+    pub external_imports: Vec<ExternalSymbol>,
+}
+
+/// A section of human-written documentation chunked during ingest.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DocumentMetadata {
+    /// Document ID.
+    pub document_id: String,
+    /// File path.
+    pub file_path: String,
+    /// Section heading (None for text before the first heading).
+    #[serde(default)]
+    pub heading: Option<String>,
+    /// Headings of the enclosing sections, outermost first.
+    #[serde(default)]
+    pub heading_path: Vec<String>,
+    /// Chunk IDs associated with this section.
+    pub chunk_ids: Vec<String>,
+    /// Code identifiers mentioned in the section (e.g., "Parser::parse").
+    #[serde(default)]
+    pub mentions: Vec<String>,
+}
+
+impl AssembleRequest {
+    /// Give symbols sent without an ID their canonical ID.
+    ///
+    /// Returns an error naming the first symbol sent with an invalid or
+    /// duplicate ID, or sent without one and with a language or kind that
+    /// isn't recognized.
+    pub fn assign_symbol_ids(&mut self) -> Result<(), String> {
+        let mut given = HashSet::new();
+        for symbol in self.symbols.iter().filter(|s| !s.symbol_id.is_empty()) {
+            SymbolId::new(symbol.symbol_id.as_str()).map_err(|e| {
+                format!(
+                    "Invalid ID `{}` for symbol `{}` in {}: {}",
+                    symbol.symbol_id, symbol.name, symbol.file_path, e
+                )
+            })?;
+            if !given.insert(symbol.symbol_id.as_str()) {
+                return Err(format!(
+                    "Duplicate ID `{}` for symbol `{}` in {}",
+                    symbol.symbol_id, symbol.name, symbol.file_path
+                ));
+            }
+        }
+
+        let mut ids = SymbolIdAllocator::new();
+        for symbol in self.symbols.iter_mut().filter(|s| s.symbol_id.is_empty()) {
+            let language =
+                serde_json::from_value(serde_json::Value::String(symbol.language.clone()))
+                    .ok()
+                    .or_else(|| Language::from_path(std::path::Path::new(&symbol.file_path)));
+            let kind: Option<SymbolKind> =
+                serde_json::from_value(serde_json::Value::String(symbol.kind.clone())).ok();
+            let (Some(language), Some(kind)) = (language, kind) else {
+                return Err(format!(
+                    "Cannot derive an ID for symbol `{}` in {}: unknown language or kind",
+                    symbol.name, symbol.file_path
+                ));
+            };
+            symbol.symbol_id = ids
+                .allocate(language, kind, &symbol.canonical_path())
+                .to_string();
+        }
+        Ok(())
+    }
+
+    /// Add the symbols to `builder` as nodes, with the edges their calls,
+    /// imports, type relations, type references and external dependencies
+    /// make. Similarity edges depend on the embeddings and are left out.
+    pub fn add_symbol_graph(&self, builder: &mut GraphBuilder) {
+        // Convert symbols to SymbolData
+        use crate::graph::SymbolData;
+        use doctown_common::types::{
+            ByteRange, Call, CallKind, Import, RelationKind, TypeReference, TypeRelation,
+        };
+
+        let symbol_data: Vec<SymbolData> = self
+            .symbols
+            .iter()
+            .map(|s| SymbolData {
+                symbol_id: s.symbol_id.clone(),
+                name: s.name.clone(),
+                kind: s.kind.clone(),
+                file_path: s.file_path.clone(),
+                signature: Some(s.signature.clone()),
+            })
+            .collect();
+
+        builder.build_nodes(&symbol_data);
+        builder.mark_test_nodes(
+            self.symbols
+                .iter()
+                .filter(|s| s.is_test)
+                .map(|s| s.symbol_id.as_str()),
+        );
+
+        // Build call edges
+        let call_data: Vec<(String, Call)> = self
+            .symbols
+            .iter()
+            .flat_map(|s| {
+                s.calls.iter().map(move |target| {
+                    (
+                        s.symbol_id.clone(),
+                        Call {
+                            name: target.clone(),
+                            range: ByteRange::new(0, 0), // Placeholder range
+                            kind: CallKind::Function,
+                            is_resolved: true,
+                            receiver_type: None,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        builder.build_calls_edges(&call_data);
+
+        // Build import edges
+        let import_data: Vec<(String, Import)> = self
+            .symbols
+            .iter()
+            .flat_map(|s| {
+                s.imports.iter().map(move |import| {
+                    (
+                        s.symbol_id.clone(),
+                        Import {
+                            module_path: import.clone(),
+                            imported_items: Some(vec![import.clone()]),
+                            alias: None,
+                            range: ByteRange::new(0, 0), // Placeholder range
+                            is_wildcard: false,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        builder.build_imports_edges(&import_data);
+
+        // Build inheritance and implementation edges
+        let relation_data: Vec<(String, TypeRelation)> = self
+            .symbols
+            .iter()
+            .flat_map(|s| {
+                let inherits = s.inherits.iter().map(|t| (t, RelationKind::Inherits));
+                let implements = s.implements.iter().map(|t| (t, RelationKind::Implements));
+                inherits.chain(implements).map(move |(target, kind)| {
+                    (
+                        s.symbol_id.clone(),
+                        TypeRelation {
+                            source: s.symbol_id.clone(),
+                            target: target.clone(),
+                            kind,
+                            range: ByteRange::new(0, 0), // Placeholder range
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        builder.build_type_relation_edges(&relation_data);
+
+        // Build type reference edges
+        let reference_data: Vec<(String, TypeReference)> = self
+            .symbols
+            .iter()
+            .flat_map(|s| {
+                s.references.iter().map(move |target| {
+                    (
+                        s.symbol_id.clone(),
+                        TypeReference {
+                            name: target.clone(),
+                            range: ByteRange::new(0, 0), // Placeholder range
+                            is_resolved: true,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        builder.build_references_edges(&reference_data);
+
+        // Build edges to stdlib and third-party symbols
+        let external_calls: Vec<(String, ExternalSymbol)> = self
+            .symbols
+            .iter()
+            .flat_map(|s| {
+                s.external_calls
+                    .iter()
+                    .map(|e| (s.symbol_id.clone(), e.clone()))
+            })
+            .collect();
+        builder.build_external_edges(&external_calls, EdgeKind::Calls);
+
+        let external_imports: Vec<(String, ExternalSymbol)> = self
+            .symbols
+            .iter()
+            .flat_map(|s| {
+                s.external_imports
+                    .iter()
+                    .map(|e| (s.symbol_id.clone(), e.clone()))
+            })
+            .collect();
+        builder.build_external_edges(&external_imports, EdgeKind::Imports);
+    }
+}
+
+impl SymbolMetadata {
+    /// The path identifying the symbol in its canonical ID: the qualified name
+    /// where known, otherwise the file path and name (e.g., "src/app.py::main").
+    pub fn canonical_path(&self) -> String {
+        match &self.qualified_name {
+            Some(qualified_name) => qualified_name.clone(),
+            None => format!("{}::{}", self.file_path, self.name),
+        }
+    }
+}
+
+impl DocumentMetadata {
+    /// Returns the IDs of the symbols this section mentions.
+    ///
+    /// A qualified mention such as `net::connect` links to the symbols whose
+    /// qualified name ends with it. Failing that, `Parser::parse` and
+    /// `parser.parse` link to the symbols named `parse` in a file defining
+    /// `Parser`. Only unqualified mentions link to any symbol of their name.
+    /// Mentions matching more than a few symbols are skipped as ambiguous.
+    pub fn linked_symbols(&self, symbols: &[SymbolMetadata]) -> Vec<String> {
+        let mut linked = Vec::new();
+        for mention in &self.mentions {
+            let matches = mentioned_symbols(mention, symbols);
+            if matches.len() > MAX_MENTION_MATCHES {
+                continue;
+            }
+            for symbol in matches {
+                if !linked.contains(&symbol.symbol_id) {
+                    linked.push(symbol.symbol_id.clone());
+                }
+            }
+        }
+        linked
+    }
+}
+
+/// The symbols `mention` may refer to (see [`DocumentMetadata::linked_symbols`]).
+fn mentioned_symbols<'a>(mention: &str, symbols: &'a [SymbolMetadata]) -> Vec<&'a SymbolMetadata> {
+    let path: Vec<&str> = mention
+        .split(['.', ':'])
+        .filter(|s| !s.is_empty())
+        .collect();
+    let Some((name, qualifiers)) = path.split_last() else {
+        return Vec::new();
+    };
+    let named = symbols.iter().filter(|s| s.name == *name);
+    let Some(owner) = qualifiers.last() else {
+        return named.collect();
+    };
+
+    let suffix = path.join("::");
+    let by_path: Vec<_> = named
+        .clone()
+        .filter(|s| {
+            s.qualified_name.as_deref().is_some_and(|qualified_name| {
+                qualified_name == suffix
+                    || qualified_name
+                        .strip_suffix(suffix.as_str())
+                        .is_some_and(|prefix| prefix.ends_with("::"))
+            })
+        })
+        .collect();
+    if !by_path.is_empty() {
+        return by_path;
+    }
+
+    // The owner may be a type (`Parser::parse`) or an instance of it (`parser.parse`)
+    let owner_files: HashSet<&str> = symbols
+        .iter()
+        .filter(|s| s.name.eq_ignore_ascii_case(owner))
+        .map(|s| s.file_path.as_str())
+        .collect();
+    named
+        .filter(|s| owner_files.contains(s.file_path.as_str()))
+        .collect()
 }
 
 /// Response schema for the /assemble endpoint.
@@ -85,6 +428,9 @@ pub struct AssembleResponse {
     pub nodes: Vec<NodeInfo>,
     /// Graph edges.
     pub edges: Vec<EdgeInfo>,
+    /// Documentation sections with their clusters and linked symbols.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<DocumentInfo>,
     /// Symbol contexts for LLM documentation.
     pub symbol_contexts: Vec<SymbolContext>,
     /// Statistics.
@@ -102,6 +448,28 @@ pub struct ClusterInfo {
     pub label: String,
     /// Symbol IDs in this cluster.
     pub members: Vec<String>,
+    /// Document IDs in this cluster.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<String>,
+    /// Packages the members call or import, sorted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+}
+
+/// Information about a documentation section.
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentInfo {
+    /// Document ID.
+    pub document_id: String,
+    /// File path.
+    pub file_path: String,
+    /// Section heading.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    /// Cluster ID of the section's chunks.
+    pub cluster_id: String,
+    /// IDs of the symbols the section mentions.
+    pub symbols: Vec<String>,
 }
 
 /// Information about a graph node.
@@ -115,6 +483,12 @@ pub struct NodeInfo {
     pub cluster_id: String,
     /// Centrality score (0-1).
     pub centrality: f64,
+    /// Size and complexity metrics (if computed during ingest).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<SymbolMetrics>,
+    /// Attributes and decorators as written in source.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
 }
 
 /// Information about a graph edge.
@@ -124,7 +498,7 @@ pub struct EdgeInfo {
     pub source: String,
     /// Target node ID.
     pub target: String,
-    /// Edge kind ("calls", "imports", "related").
+    /// Edge kind ("calls", "imports", "related", "inherits", "implements", "references").
     pub kind: String,
     /// Optional weight.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -166,6 +540,12 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
     let start = Instant::now();
     let mut events = Vec::new();
 
+    let mut req = req.into_inner();
+    if let Err(e) = req.assign_symbol_ids() {
+        error!("Invalid assemble request: {}", e);
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+
     // Create context for events
     use doctown_common::JobId;
     let job_id = JobId::new(&req.job_id).unwrap_or_else(|_| JobId::generate());
@@ -189,10 +569,32 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
         serde_json::to_value(&started_payload).unwrap(),
     ));
 
+    let test_symbols: HashSet<&str> = req
+        .symbols
+        .iter()
+        .filter(|s| s.is_test)
+        .map(|s| s.symbol_id.as_str())
+        .collect();
+    let isolate_tests = req.test_handling != TestHandling::Include && !test_symbols.is_empty();
+
     // Step 1: Cluster the embeddings
+    // When tests are isolated, chunks that only belong to test symbols are not clustered
+    let mut cluster_chunks: Vec<&ChunkWithEmbedding> = req
+        .chunks
+        .iter()
+        .filter(|c| !isolate_tests || !is_test_chunk(&req.symbols, &c.chunk_id))
+        .collect();
+    if cluster_chunks.is_empty() {
+        // Nothing but tests: cluster everything rather than nothing
+        cluster_chunks = req.chunks.iter().collect();
+    }
+
     let vectors = ndarray::Array2::from_shape_vec(
-        (req.chunks.len(), req.chunks[0].vector.len()),
-        req.chunks.iter().flat_map(|c| c.vector.clone()).collect(),
+        (cluster_chunks.len(), cluster_chunks[0].vector.len()),
+        cluster_chunks
+            .iter()
+            .flat_map(|c| c.vector.clone())
+            .collect(),
     )
     .map_err(|e| {
         error!("Failed to create vector array: {}", e);
@@ -203,8 +605,8 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
     .unwrap();
 
     // Determine optimal cluster count (sqrt(n/2) heuristic)
-    let k = ((req.chunks.len() as f64 / 2.0).sqrt().ceil() as usize).clamp(2, 20);
-    info!("Using k={} clusters for {} chunks", k, req.chunks.len());
+    let k = ((cluster_chunks.len() as f64 / 2.0).sqrt().ceil() as usize).clamp(2, 20);
+    info!("Using k={} clusters for {} chunks", k, cluster_chunks.len());
 
     let clusterer = Clusterer::new(k);
     let cluster_result = match clusterer.cluster(&vectors) {
@@ -231,7 +633,7 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
 
         let chunk_contents: Vec<String> = chunk_indices
             .iter()
-            .map(|&i| req.chunks[i].content.clone())
+            .map(|&i| cluster_chunks[i].content.clone())
             .collect();
 
         let label = ClusterLabeler::label_cluster(&chunk_contents);
@@ -239,12 +641,13 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
         // Find symbols in this cluster
         let cluster_chunk_ids: Vec<String> = chunk_indices
             .iter()
-            .map(|&i| req.chunks[i].chunk_id.clone())
+            .map(|&i| cluster_chunks[i].chunk_id.clone())
             .collect();
 
         let member_symbol_ids: Vec<String> = req
             .symbols
             .iter()
+            .filter(|s| !(isolate_tests && s.is_test))
             .filter(|s| {
                 s.chunk_ids
                     .iter()
@@ -253,10 +656,23 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
             .map(|s| s.symbol_id.clone())
             .collect();
 
+        let member_document_ids: Vec<String> = req
+            .documents
+            .iter()
+            .filter(|d| {
+                d.chunk_ids
+                    .iter()
+                    .any(|cid| cluster_chunk_ids.contains(cid))
+            })
+            .map(|d| d.document_id.clone())
+            .collect();
+
         clusters.push(ClusterInfo {
             cluster_id: format!("cluster_{}", cluster_id),
             label: label.clone(),
             members: member_symbol_ids.clone(),
+            documents: member_document_ids,
+            dependencies: cluster_dependencies(&req.symbols, &member_symbol_ids),
         });
 
         // Emit cluster_created event
@@ -272,71 +688,39 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
         ));
     }
 
+    // Test symbols get a cluster of their own
+    if isolate_tests && req.test_handling == TestHandling::Separate {
+        let members: Vec<String> = req
+            .symbols
+            .iter()
+            .filter(|s| s.is_test)
+            .map(|s| s.symbol_id.clone())
+            .collect();
+        let cluster_payload = AssemblyClusterCreatedPayload {
+            cluster_id: TEST_CLUSTER_ID.to_string(),
+            label: TEST_CLUSTER_LABEL.to_string(),
+            member_count: members.len(),
+        };
+        clusters.push(ClusterInfo {
+            cluster_id: TEST_CLUSTER_ID.to_string(),
+            label: TEST_CLUSTER_LABEL.to_string(),
+            dependencies: cluster_dependencies(&req.symbols, &members),
+            members,
+            documents: Vec::new(),
+        });
+        events.push(Envelope::typed(
+            EventType::AssemblyClusterCreated,
+            context.clone(),
+            serde_json::to_value(&cluster_payload).unwrap(),
+        ));
+    }
+
     info!("Created {} clusters", clusters.len());
 
     // Step 3: Build the graph
     let mut builder = GraphBuilder::new();
 
-    // Convert symbols to SymbolData
-    use crate::graph::SymbolData;
-    use doctown_common::types::{ByteRange, Call, CallKind, Import};
-
-    let symbol_data: Vec<SymbolData> = req
-        .symbols
-        .iter()
-        .map(|s| SymbolData {
-            symbol_id: s.symbol_id.clone(),
-            name: s.name.clone(),
-            kind: s.kind.clone(),
-            file_path: s.file_path.clone(),
-            signature: Some(s.signature.clone()),
-        })
-        .collect();
-
-    builder.build_nodes(&symbol_data);
-
-    // Build call edges
-    let call_data: Vec<(String, Call)> = req
-        .symbols
-        .iter()
-        .flat_map(|s| {
-            s.calls.iter().map(move |target| {
-                (
-                    s.symbol_id.clone(),
-                    Call {
-                        name: target.clone(),
-                        range: ByteRange::new(0, 0), // Placeholder range
-                        kind: CallKind::Function,
-                        is_resolved: true,
-                    },
-                )
-            })
-        })
-        .collect();
-
-    builder.build_calls_edges(&call_data);
-
-    // Build import edges
-    let import_data: Vec<(String, Import)> = req
-        .symbols
-        .iter()
-        .flat_map(|s| {
-            s.imports.iter().map(move |import| {
-                (
-                    s.symbol_id.clone(),
-                    Import {
-                        module_path: import.clone(),
-                        imported_items: None,
-                        alias: None,
-                        range: ByteRange::new(0, 0), // Placeholder range
-                        is_wildcard: false,
-                    },
-                )
-            })
-        })
-        .collect();
-
-    builder.build_imports_edges(&import_data);
+    req.add_symbol_graph(&mut builder);
 
     // Build similarity edges
     let mut embeddings_map: HashMap<String, Vec<f32>> = HashMap::new();
@@ -365,11 +749,17 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
     let mut calls_count = 0;
     let mut imports_count = 0;
     let mut related_count = 0;
+    let mut inherits_count = 0;
+    let mut implements_count = 0;
+    let mut references_count = 0;
     for edge in &graph.edges {
         match edge.kind {
             EdgeKind::Calls => calls_count += 1,
             EdgeKind::Imports => imports_count += 1,
             EdgeKind::Related => related_count += 1,
+            EdgeKind::Inherits => inherits_count += 1,
+            EdgeKind::Implements => implements_count += 1,
+            EdgeKind::References => references_count += 1,
         }
     }
 
@@ -381,6 +771,9 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
             calls: calls_count,
             imports: imports_count,
             related: related_count,
+            inherits: inherits_count,
+            implements: implements_count,
+            references: references_count,
         },
     };
     events.push(Envelope::typed(
@@ -410,19 +803,92 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
         imports_map.insert(symbol.symbol_id.clone(), symbol.imports.clone());
     }
 
+    // Build metrics map
+    let metrics_map: HashMap<String, SymbolMetrics> = req
+        .symbols
+        .iter()
+        .filter_map(|s| s.metrics.map(|m| (s.symbol_id.clone(), m)))
+        .collect();
+
+    // Build attributes map
+    let attributes_map: HashMap<String, Vec<Attribute>> = req
+        .symbols
+        .iter()
+        .filter(|s| !s.attributes.is_empty())
+        .map(|s| (s.symbol_id.clone(), s.attributes.clone()))
+        .collect();
+
+    // Link documentation sections to the symbols they mention
+    let chunk_contents: HashMap<&str, &str> = req
+        .chunks
+        .iter()
+        .map(|c| (c.chunk_id.as_str(), c.content.as_str()))
+        .collect();
+    let mut documents = Vec::new();
+    let mut document_excerpts: HashMap<String, Vec<DocumentExcerpt>> = HashMap::new();
+    for document in &req.documents {
+        let symbols = document.linked_symbols(&req.symbols);
+        let text: String = document
+            .chunk_ids
+            .iter()
+            .filter_map(|cid| chunk_contents.get(cid.as_str()).copied())
+            .collect();
+        for symbol_id in &symbols {
+            document_excerpts
+                .entry(symbol_id.clone())
+                .or_default()
+                .push(DocumentExcerpt::new(
+                    document.file_path.clone(),
+                    document.heading.clone(),
+                    &text,
+                ));
+        }
+
+        let cluster_id = clusters
+            .iter()
+            .find(|c| c.documents.contains(&document.document_id))
+            .map(|c| c.cluster_id.clone())
+            .unwrap_or_else(|| "unclustered".to_string());
+        documents.push(DocumentInfo {
+            document_id: document.document_id.clone(),
+            file_path: document.file_path.clone(),
+            heading: document.heading.clone(),
+            cluster_id,
+            symbols,
+        });
+    }
+
     // Generate contexts
     let context_generator = ContextGenerator::new()
         .with_cluster_labels(cluster_labels)
         .with_languages(languages)
-        .with_imports(imports_map);
+        .with_imports(imports_map)
+        .with_metrics(metrics_map.clone())
+        .with_attributes(attributes_map.clone())
+        .with_documents(document_excerpts);
 
     let symbol_contexts = context_generator.generate(&graph);
     info!("Generated {} symbol contexts", symbol_contexts.len());
 
     // Step 4: Compute centrality
+    // Isolated tests and external symbols score zero and don't count towards production centrality
+    let has_externals = graph.nodes.iter().any(|n| graph.is_external(&n.id));
+    let production_graph = (isolate_tests || has_externals).then(|| {
+        let production = graph.without_externals();
+        if isolate_tests {
+            production.without_tests()
+        } else {
+            production
+        }
+    });
     let mut nodes = Vec::new();
     for node in &graph.nodes {
-        let centrality = graph.degree_centrality(&node.id);
+        let centrality = match &production_graph {
+            Some(_) if graph.is_external(&node.id) => 0.0,
+            Some(_) if isolate_tests && test_symbols.contains(node.id.as_str()) => 0.0,
+            Some(production) => production.degree_centrality(&node.id),
+            None => graph.degree_centrality(&node.id),
+        };
 
         // Find cluster for this node
         let cluster_id = clusters
@@ -436,6 +902,11 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
             metadata: node.metadata.clone(),
             cluster_id,
             centrality,
+            metrics: metrics_map.get(&node.id).copied(),
+            attributes: attributes_map
+                .get(&node.id)
+                .map(|attrs| attrs.iter().map(|a| a.text.clone()).collect())
+                .unwrap_or_default(),
         });
     }
 
@@ -450,6 +921,9 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
                 EdgeKind::Calls => "calls".to_string(),
                 EdgeKind::Imports => "imports".to_string(),
                 EdgeKind::Related => "related".to_string(),
+                EdgeKind::Inherits => "inherits".to_string(),
+                EdgeKind::Implements => "implements".to_string(),
+                EdgeKind::References => "references".to_string(),
             },
             weight: e.weight,
         })
@@ -476,14 +950,16 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
 
     info!("Assembly completed in {}ms", duration_ms);
 
+    let cluster_count = clusters.len();
     let response = AssembleResponse {
         job_id: req.job_id.clone(),
         clusters,
         nodes,
         edges,
+        documents,
         symbol_contexts,
         stats: AssemblyStats {
-            cluster_count: k,
+            cluster_count,
             node_count: graph.nodes.len(),
             edge_count: graph.edges.len(),
             duration_ms,
@@ -494,6 +970,28 @@ async fn assemble(req: Json<AssembleRequest>) -> impl Responder {
     HttpResponse::Ok().json(response)
 }
 
+/// Returns true if the chunk belongs to test symbols only.
+fn is_test_chunk(symbols: &[SymbolMetadata], chunk_id: &str) -> bool {
+    let mut owners = symbols
+        .iter()
+        .filter(|s| s.chunk_ids.iter().any(|c| c == chunk_id))
+        .peekable();
+    owners.peek().is_some() && owners.all(|s| s.is_test)
+}
+
+/// Returns the packages the given symbols call or import, sorted and deduplicated.
+fn cluster_dependencies(symbols: &[SymbolMetadata], members: &[String]) -> Vec<String> {
+    let mut packages: Vec<String> = symbols
+        .iter()
+        .filter(|s| members.contains(&s.symbol_id))
+        .flat_map(|s| s.external_calls.iter().chain(&s.external_imports))
+        .map(|e| e.package.clone())
+        .collect();
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
 /// Start the Assembly Worker HTTP server.
 pub async fn start_server(host: &str, port: u16) -> std::io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
@@ -559,3 +1057,199 @@ async fn pack(req: Json<PackRequest>) -> impl Responder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(symbol_id: &str, name: &str) -> SymbolMetadata {
+        SymbolMetadata {
+            symbol_id: symbol_id.to_string(),
+            name: name.to_string(),
+            kind: "function".to_string(),
+            language: "rust".to_string(),
+            file_path: "src/lib.rs".to_string(),
+            qualified_name: None,
+            signature: String::new(),
+            chunk_ids: Vec::new(),
+            calls: Vec::new(),
+            imports: Vec::new(),
+            inherits: Vec::new(),
+            implements: Vec::new(),
+            references: Vec::new(),
+            is_test: false,
+            metrics: None,
+            attributes: Vec::new(),
+            external_calls: Vec::new(),
+            external_imports: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_document_linked_symbols() {
+        let symbols = vec![
+            symbol("sym_parse", "parse"),
+            symbol("sym_parser", "Parser"),
+            SymbolMetadata {
+                file_path: "src/config.rs".to_string(),
+                ..symbol("sym_config_parse", "parse")
+            },
+            SymbolMetadata {
+                qualified_name: Some("app::net::connect".to_string()),
+                ..symbol("sym_connect", "connect")
+            },
+            SymbolMetadata {
+                qualified_name: Some("app::db::connect".to_string()),
+                ..symbol("sym_db_connect", "connect")
+            },
+            symbol("sym_new_a", "new"),
+            symbol("sym_new_b", "new"),
+            symbol("sym_new_c", "new"),
+            symbol("sym_new_d", "new"),
+        ];
+        let document = DocumentMetadata {
+            document_id: "doc_usage".to_string(),
+            file_path: "README.md".to_string(),
+            heading: Some("Usage".to_string()),
+            heading_path: vec!["Usage".to_string()],
+            chunk_ids: Vec::new(),
+            mentions: vec![
+                "Parser::parse".to_string(),
+                "Parser".to_string(),
+                "parser.parse".to_string(),
+                "Client::new".to_string(),
+                "net::connect".to_string(),
+                "missing".to_string(),
+            ],
+        };
+
+        // `parse` in src/config.rs is not `Parser`'s, and `new` matches too
+        // many symbols to link
+        assert_eq!(
+            document.linked_symbols(&symbols),
+            vec!["sym_parse", "sym_parser", "sym_connect"]
+        );
+
+        let document = DocumentMetadata {
+            mentions: vec!["parse".to_string()],
+            ..document
+        };
+        assert_eq!(
+            document.linked_symbols(&symbols),
+            vec!["sym_parse", "sym_config_parse"]
+        );
+    }
+
+    #[test]
+    fn test_cluster_dependencies() {
+        use doctown_common::DependencyOrigin;
+
+        let mut fetch = symbol("sym_fetch", "fetch");
+        fetch.external_calls = vec![
+            ExternalSymbol::new("reqwest::Client", "reqwest", DependencyOrigin::ThirdParty),
+            ExternalSymbol::new("std::fs::read", "std", DependencyOrigin::Stdlib),
+        ];
+        let mut parse = symbol("sym_parse", "parse");
+        parse.external_imports = vec![ExternalSymbol::new(
+            "serde_json::from_str",
+            "serde_json",
+            DependencyOrigin::ThirdParty,
+        )];
+        let mut save = symbol("sym_save", "save");
+        save.external_calls = vec![ExternalSymbol::new(
+            "std::fs::write",
+            "std",
+            DependencyOrigin::Stdlib,
+        )];
+        let symbols = vec![fetch, parse, save];
+
+        let members = vec!["sym_fetch".to_string(), "sym_save".to_string()];
+        assert_eq!(
+            cluster_dependencies(&symbols, &members),
+            vec!["reqwest", "std"]
+        );
+
+        // External symbols are optional in requests
+        let json = r#"{
+            "symbol_id": "sym_1", "name": "f", "kind": "function", "file_path": "a.rs",
+            "signature": "fn f()", "chunk_ids": [],
+            "external_calls": [{"name": "numpy.array", "package": "numpy", "origin": "third_party"}]
+        }"#;
+        let symbol: SymbolMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            symbol.external_calls[0].origin,
+            DependencyOrigin::ThirdParty
+        );
+        assert!(symbol.external_imports.is_empty());
+    }
+
+    #[test]
+    fn test_assign_symbol_ids() {
+        use doctown_common::SymbolId;
+
+        let mut request = AssembleRequest {
+            job_id: "job_ids".to_string(),
+            repo_url: "https://github.com/test/repo".to_string(),
+            git_ref: "main".to_string(),
+            chunks: Vec::new(),
+            symbols: vec![
+                symbol("sym_given", "given"),
+                symbol("", "parse"),
+                symbol("", "parse"),
+                SymbolMetadata {
+                    qualified_name: Some("app::Parser".to_string()),
+                    kind: "struct".to_string(),
+                    ..symbol("", "Parser")
+                },
+            ],
+            test_handling: TestHandling::Include,
+            documents: Vec::new(),
+        };
+        request.assign_symbol_ids().unwrap();
+
+        let ids: Vec<&str> = request
+            .symbols
+            .iter()
+            .map(|s| s.symbol_id.as_str())
+            .collect();
+        let derived = |kind, path, n| SymbolId::derive(Language::Rust, kind, path, n).to_string();
+        assert_eq!(
+            ids,
+            vec![
+                "sym_given".to_string(),
+                derived(SymbolKind::Function, "src/lib.rs::parse", 0),
+                derived(SymbolKind::Function, "src/lib.rs::parse", 1),
+                derived(SymbolKind::Struct, "app::Parser", 0),
+            ]
+        );
+
+        request.symbols.push(SymbolMetadata {
+            kind: "widget".to_string(),
+            ..symbol("", "odd")
+        });
+        assert!(request.assign_symbol_ids().is_err());
+    }
+
+    #[test]
+    fn test_assign_symbol_ids_rejects_invalid_given_ids() {
+        let request = |symbols| AssembleRequest {
+            job_id: "job_ids".to_string(),
+            repo_url: "https://github.com/test/repo".to_string(),
+            git_ref: "main".to_string(),
+            chunks: Vec::new(),
+            symbols,
+            test_handling: TestHandling::Include,
+            documents: Vec::new(),
+        };
+
+        let error = request(vec![symbol("parse", "parse")])
+            .assign_symbol_ids()
+            .unwrap_err();
+        assert!(error.contains("Invalid ID `parse`"));
+
+        let error = request(vec![symbol("sym_given", "a"), symbol("sym_given", "b")])
+            .assign_symbol_ids()
+            .unwrap_err();
+        assert!(error.contains("Duplicate ID `sym_given` for symbol `b`"));
+    }
+}
@@ -6,6 +6,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use doctown_common::{Attribute, SymbolMetrics};
+
 use crate::graph::Graph;
 
 /// Structured context about a symbol for LLM documentation generation.
@@ -41,6 +43,14 @@ pub struct SymbolContext {
     /// List of imports used by this symbol (max 10).
     pub imports: Vec<String>,
 
+    /// List of type names this symbol uses in its signature or body (max 10).
+    #[serde(default)]
+    pub uses_types: Vec<String>,
+
+    /// Standard library and third-party symbols this symbol uses (e.g., "reqwest::Client") (max 10).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+
     /// Top 3 related symbol names (based on semantic similarity).
     pub related_symbols: Vec<String>,
 
@@ -49,6 +59,66 @@ pub struct SymbolContext {
 
     /// Centrality score (0.0-1.0) indicating importance in the codebase.
     pub centrality: f64,
+
+    /// Size and complexity metrics (if computed during ingest).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<SymbolMetrics>,
+
+    /// Attributes and decorators as written in source (e.g., "#[derive(Debug)]", "@property").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+
+    /// Traits derived with `#[derive(...)]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derives: Vec<String>,
+
+    /// Conditions from `#[cfg(...)]` attributes gating this symbol (e.g., "feature = \"serde\"").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cfg: Vec<String>,
+
+    /// Whether the symbol is marked deprecated.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+
+    /// Excerpts of human-written documentation that mention this symbol (max 3).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub documentation: Vec<DocumentExcerpt>,
+}
+
+/// An excerpt of a documentation section (README, docs/, ADRs) mentioning a symbol.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DocumentExcerpt {
+    /// File path of the document.
+    pub file_path: String,
+
+    /// Heading of the section (None for text before the first heading).
+    pub heading: Option<String>,
+
+    /// Start of the section text (max 300 characters).
+    pub excerpt: String,
+}
+
+impl DocumentExcerpt {
+    /// Maximum length of an excerpt, in characters.
+    const MAX_CHARS: usize = 300;
+
+    /// Create an excerpt from the full section text.
+    pub fn new(file_path: String, heading: Option<String>, text: &str) -> Self {
+        let text = text.trim();
+        let excerpt = match text.char_indices().nth(Self::MAX_CHARS) {
+            Some((end, _)) => format!("{}...", text[..end].trim_end()),
+            None => text.to_string(),
+        };
+        Self {
+            file_path,
+            heading,
+            excerpt,
+        }
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
 
 impl SymbolContext {
@@ -71,9 +141,17 @@ impl SymbolContext {
             calls: Vec::new(),
             called_by: Vec::new(),
             imports: Vec::new(),
+            uses_types: Vec::new(),
+            dependencies: Vec::new(),
             related_symbols: Vec::new(),
             cluster_label: None,
             centrality: 0.0,
+            metrics: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            cfg: Vec::new(),
+            deprecated: false,
+            documentation: Vec::new(),
         }
     }
 
@@ -95,6 +173,18 @@ impl SymbolContext {
         self
     }
 
+    /// Set the used types list (truncated to max 10 items).
+    pub fn with_uses_types(mut self, uses_types: Vec<String>) -> Self {
+        self.uses_types = Self::truncate_list(uses_types, 10);
+        self
+    }
+
+    /// Set the external dependencies list (truncated to max 10 items).
+    pub fn with_dependencies(mut self, dependencies: Vec<String>) -> Self {
+        self.dependencies = Self::truncate_list(dependencies, 10);
+        self
+    }
+
     /// Set the related symbols list (max 3 items).
     pub fn with_related_symbols(mut self, related: Vec<String>) -> Self {
         self.related_symbols = Self::truncate_list(related, 3);
@@ -113,6 +203,36 @@ impl SymbolContext {
         self
     }
 
+    /// Set the code metrics.
+    pub fn with_metrics(mut self, metrics: Option<SymbolMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Set the attributes, deriving the derive list, cfg conditions and deprecation from them.
+    pub fn with_attributes(mut self, attributes: &[Attribute]) -> Self {
+        self.attributes = attributes.iter().map(|a| a.text.clone()).collect();
+        self.derives = attributes.iter().flat_map(Attribute::derives).collect();
+        self.cfg = attributes
+            .iter()
+            .filter_map(|a| a.cfg_condition().map(str::to_string))
+            .collect();
+        self.deprecated = attributes.iter().any(Attribute::is_deprecated);
+        self
+    }
+
+    /// Set the documentation excerpts (truncated to max 3 items).
+    pub fn with_documentation(mut self, mut documentation: Vec<DocumentExcerpt>) -> Self {
+        documentation.truncate(3);
+        self.documentation = documentation;
+        self
+    }
+
+    /// Returns true if the symbol's metrics mark it as complex code.
+    pub fn is_complex(&self) -> bool {
+        self.metrics.is_some_and(|m| m.is_complex())
+    }
+
     /// Truncate a list to the specified maximum length.
     fn truncate_list(mut list: Vec<String>, max_len: usize) -> Vec<String> {
         list.truncate(max_len);
@@ -128,6 +248,12 @@ pub struct ContextGenerator {
     languages: HashMap<String, String>,
     /// Map from symbol_id to imports.
     imports: HashMap<String, Vec<String>>,
+    /// Map from symbol_id to code metrics.
+    metrics: HashMap<String, SymbolMetrics>,
+    /// Map from symbol_id to attributes and decorators.
+    attributes: HashMap<String, Vec<Attribute>>,
+    /// Map from symbol_id to documentation excerpts mentioning it.
+    documents: HashMap<String, Vec<DocumentExcerpt>>,
 }
 
 impl ContextGenerator {
@@ -137,6 +263,9 @@ impl ContextGenerator {
             cluster_labels: HashMap::new(),
             languages: HashMap::new(),
             imports: HashMap::new(),
+            metrics: HashMap::new(),
+            attributes: HashMap::new(),
+            documents: HashMap::new(),
         }
     }
 
@@ -158,6 +287,24 @@ impl ContextGenerator {
         self
     }
 
+    /// Set code metrics for symbols.
+    pub fn with_metrics(mut self, metrics: HashMap<String, SymbolMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Set attributes and decorators for symbols.
+    pub fn with_attributes(mut self, attributes: HashMap<String, Vec<Attribute>>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Set documentation excerpts for symbols.
+    pub fn with_documents(mut self, documents: HashMap<String, Vec<DocumentExcerpt>>) -> Self {
+        self.documents = documents;
+        self
+    }
+
     /// Generate contexts for all symbols in the graph.
     pub fn generate(&self, graph: &Graph) -> Vec<SymbolContext> {
         let centralities = graph.all_degree_centralities();
@@ -166,6 +313,11 @@ impl ContextGenerator {
         for node in &graph.nodes {
             let symbol_id = &node.id;
 
+            // External symbols are documented by their packages, not here
+            if graph.is_external(symbol_id) {
+                continue;
+            }
+
             // Extract metadata from the node
             let name = node.metadata.get("name").cloned().unwrap_or_default();
             let kind = node.metadata.get("kind").cloned().unwrap_or_default();
@@ -179,6 +331,12 @@ impl ContextGenerator {
             let calls = self.get_calls(graph, symbol_id);
             let called_by = self.get_called_by(graph, symbol_id);
 
+            // Build list of types this symbol uses
+            let uses_types = self.get_used_types(graph, symbol_id);
+
+            // Build list of stdlib and third-party symbols this symbol uses
+            let dependencies = self.get_dependencies(graph, symbol_id);
+
             // Get related symbols (top 3 by similarity)
             let related_symbols = self.get_related_symbols(graph, symbol_id, 3);
 
@@ -203,9 +361,19 @@ impl ContextGenerator {
             .with_calls(calls)
             .with_called_by(called_by)
             .with_imports(imports)
+            .with_uses_types(uses_types)
+            .with_dependencies(dependencies)
             .with_related_symbols(related_symbols)
             .with_cluster_label(cluster_label)
-            .with_centrality(centrality);
+            .with_centrality(centrality)
+            .with_metrics(self.metrics.get(symbol_id).copied())
+            .with_attributes(
+                self.attributes
+                    .get(symbol_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+            )
+            .with_documentation(self.documents.get(symbol_id).cloned().unwrap_or_default());
 
             contexts.push(context);
         }
@@ -219,6 +387,7 @@ impl ContextGenerator {
             .edges
             .iter()
             .filter(|e| e.source == symbol_id && e.kind == crate::EdgeKind::Calls)
+            .filter(|e| !graph.is_external(&e.target))
             .filter_map(|e| {
                 // Get the target node's name
                 graph
@@ -245,6 +414,42 @@ impl ContextGenerator {
             .collect()
     }
 
+    /// Get the list of types this symbol references.
+    fn get_used_types(&self, graph: &Graph, symbol_id: &str) -> Vec<String> {
+        graph
+            .edges
+            .iter()
+            .filter(|e| e.source == symbol_id && e.kind == crate::EdgeKind::References)
+            .filter_map(|e| {
+                // Get the target type's name
+                graph
+                    .get_node(&e.target)
+                    .and_then(|n| n.metadata.get("name"))
+                    .cloned()
+            })
+            .collect()
+    }
+
+    /// Get the external symbols this symbol calls or imports, without duplicates.
+    fn get_dependencies(&self, graph: &Graph, symbol_id: &str) -> Vec<String> {
+        let mut dependencies: Vec<String> = Vec::new();
+        for edge in graph.edges.iter().filter(|e| {
+            e.source == symbol_id
+                && matches!(e.kind, crate::EdgeKind::Calls | crate::EdgeKind::Imports)
+                && graph.is_external(&e.target)
+        }) {
+            if let Some(name) = graph
+                .get_node(&edge.target)
+                .and_then(|n| n.metadata.get("name"))
+            {
+                if !dependencies.contains(name) {
+                    dependencies.push(name.clone());
+                }
+            }
+        }
+        dependencies
+    }
+
     /// Get the top N related symbols based on semantic similarity.
     fn get_related_symbols(&self, graph: &Graph, symbol_id: &str, top_n: usize) -> Vec<String> {
         let mut related: Vec<_> = graph
@@ -312,6 +517,7 @@ mod tests {
         assert!(context.calls.is_empty());
         assert!(context.called_by.is_empty());
         assert!(context.imports.is_empty());
+        assert!(context.uses_types.is_empty());
         assert!(context.related_symbols.is_empty());
         assert_eq!(context.cluster_label, None);
         assert_eq!(context.centrality, 0.0);
@@ -480,6 +686,201 @@ mod tests {
         assert!(bar_ctx.calls.is_empty());
     }
 
+    #[test]
+    fn test_context_generator_uses_types() {
+        use crate::graph::{Edge, EdgeKind, Graph, Node};
+        use std::collections::HashMap;
+
+        let mut graph = Graph::new();
+
+        for (id, name, kind) in [
+            ("sym1", "create_order", "function"),
+            ("sym2", "User", "struct"),
+            ("sym3", "Order", "struct"),
+        ] {
+            let mut metadata = HashMap::new();
+            metadata.insert("name".to_string(), name.to_string());
+            metadata.insert("kind".to_string(), kind.to_string());
+            metadata.insert("file_path".to_string(), "src/orders.rs".to_string());
+            graph.add_node(Node::new(id.to_string(), metadata));
+        }
+
+        for target in ["sym2", "sym3"] {
+            graph.add_edge(Edge {
+                source: "sym1".to_string(),
+                target: target.to_string(),
+                kind: EdgeKind::References,
+                weight: None,
+            });
+        }
+
+        let contexts = ContextGenerator::new().generate(&graph);
+
+        let ctx = contexts.iter().find(|c| c.symbol_id == "sym1").unwrap();
+        assert_eq!(ctx.uses_types, vec!["User", "Order"]);
+        // References are not calls
+        assert!(ctx.calls.is_empty());
+
+        let user_ctx = contexts.iter().find(|c| c.symbol_id == "sym2").unwrap();
+        assert!(user_ctx.uses_types.is_empty());
+    }
+
+    #[test]
+    fn test_context_generator_dependencies() {
+        use crate::graph::{Edge, EdgeKind, Graph, Node};
+        use std::collections::HashMap;
+
+        let mut graph = Graph::new();
+
+        for (id, name, kind) in [
+            ("sym1", "fetch", "function"),
+            ("sym2", "parse", "function"),
+            ("ext_reqwest::Client", "reqwest::Client", "external"),
+            (
+                "ext_std::collections::HashMap",
+                "std::collections::HashMap",
+                "external",
+            ),
+        ] {
+            let mut metadata = HashMap::new();
+            metadata.insert("name".to_string(), name.to_string());
+            metadata.insert("kind".to_string(), kind.to_string());
+            graph.add_node(Node::new(id.to_string(), metadata));
+        }
+
+        for (target, kind) in [
+            ("sym2", EdgeKind::Calls),
+            ("ext_reqwest::Client", EdgeKind::Imports),
+            ("ext_reqwest::Client", EdgeKind::Calls),
+            ("ext_std::collections::HashMap", EdgeKind::Calls),
+        ] {
+            graph.add_edge(Edge {
+                source: "sym1".to_string(),
+                target: target.to_string(),
+                kind,
+                weight: None,
+            });
+        }
+
+        let contexts = ContextGenerator::new().generate(&graph);
+
+        // External nodes get no context of their own
+        assert_eq!(contexts.len(), 2);
+
+        let ctx = contexts.iter().find(|c| c.symbol_id == "sym1").unwrap();
+        assert_eq!(ctx.calls, vec!["parse"]);
+        assert_eq!(
+            ctx.dependencies,
+            vec!["reqwest::Client", "std::collections::HashMap"]
+        );
+    }
+
+    #[test]
+    fn test_context_generator_metrics() {
+        use crate::graph::{Graph, Node};
+        use std::collections::HashMap;
+
+        let mut graph = Graph::new();
+        for id in ["sym1", "sym2"] {
+            let mut metadata = HashMap::new();
+            metadata.insert("name".to_string(), id.to_string());
+            graph.add_node(Node::new(id.to_string(), metadata));
+        }
+
+        let metrics = SymbolMetrics {
+            lines_of_code: 120,
+            cyclomatic_complexity: 18,
+            max_nesting_depth: 5,
+            parameter_count: 4,
+        };
+        let contexts = ContextGenerator::new()
+            .with_metrics(HashMap::from([("sym1".to_string(), metrics)]))
+            .generate(&graph);
+
+        let ctx = contexts.iter().find(|c| c.symbol_id == "sym1").unwrap();
+        assert_eq!(ctx.metrics, Some(metrics));
+        assert!(ctx.is_complex());
+
+        let other = contexts.iter().find(|c| c.symbol_id == "sym2").unwrap();
+        assert_eq!(other.metrics, None);
+        assert!(!other.is_complex());
+        assert!(serde_json::to_value(other)
+            .unwrap()
+            .get("metrics")
+            .is_none());
+    }
+
+    #[test]
+    fn test_context_generator_attributes() {
+        use crate::graph::{Graph, Node};
+        use std::collections::HashMap;
+
+        let mut graph = Graph::new();
+        for id in ["sym1", "sym2"] {
+            let mut metadata = HashMap::new();
+            metadata.insert("name".to_string(), id.to_string());
+            graph.add_node(Node::new(id.to_string(), metadata));
+        }
+
+        let attributes = vec![
+            Attribute::parse("#[derive(Debug, Clone)]"),
+            Attribute::parse("#[cfg(feature = \"serde\")]"),
+            Attribute::parse("#[deprecated(note = \"use Config\")]"),
+        ];
+        let contexts = ContextGenerator::new()
+            .with_attributes(HashMap::from([("sym1".to_string(), attributes)]))
+            .generate(&graph);
+
+        let ctx = contexts.iter().find(|c| c.symbol_id == "sym1").unwrap();
+        assert_eq!(ctx.attributes.len(), 3);
+        assert_eq!(ctx.derives, vec!["Debug", "Clone"]);
+        assert_eq!(ctx.cfg, vec!["feature = \"serde\""]);
+        assert!(ctx.deprecated);
+
+        let other = contexts.iter().find(|c| c.symbol_id == "sym2").unwrap();
+        assert!(!other.deprecated);
+        let json = serde_json::to_value(other).unwrap();
+        for field in ["attributes", "derives", "cfg", "deprecated"] {
+            assert!(json.get(field).is_none());
+        }
+    }
+
+    #[test]
+    fn test_context_generator_documents() {
+        use crate::graph::{Graph, Node};
+        use std::collections::HashMap;
+
+        let mut graph = Graph::new();
+        for id in ["sym1", "sym2"] {
+            let mut metadata = HashMap::new();
+            metadata.insert("name".to_string(), id.to_string());
+            graph.add_node(Node::new(id.to_string(), metadata));
+        }
+
+        let long_text = "é".repeat(400);
+        let excerpts: Vec<_> = (0..4)
+            .map(|i| {
+                DocumentExcerpt::new(
+                    "README.md".to_string(),
+                    Some(format!("Section {}", i)),
+                    &long_text,
+                )
+            })
+            .collect();
+        let contexts = ContextGenerator::new()
+            .with_documents(HashMap::from([("sym1".to_string(), excerpts)]))
+            .generate(&graph);
+
+        let ctx = contexts.iter().find(|c| c.symbol_id == "sym1").unwrap();
+        assert_eq!(ctx.documentation.len(), 3);
+        assert_eq!(ctx.documentation[0].excerpt.chars().count(), 303);
+        assert!(ctx.documentation[0].excerpt.ends_with("..."));
+
+        let other = contexts.iter().find(|c| c.symbol_id == "sym2").unwrap();
+        let json = serde_json::to_value(other).unwrap();
+        assert!(json.get("documentation").is_none());
+    }
+
     #[test]
     fn test_context_generator_related_symbols() {
         use crate::graph::{Edge, EdgeKind, Graph, Node};
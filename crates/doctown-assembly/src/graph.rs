@@ -1,7 +1,9 @@
 //! Graph construction and metrics for code understanding.
 
-use doctown_common::types::{Call, Import};
-use std::collections::HashMap;
+use doctown_common::types::{
+    Call, ExternalSymbol, Import, RelationKind, TypeReference, TypeRelation,
+};
+use std::collections::{HashMap, HashSet};
 
 /// A node in the code graph representing a symbol.
 #[derive(Debug, Clone)]
@@ -28,6 +30,12 @@ pub enum EdgeKind {
     Imports,
     /// Semantic similarity relationship.
     Related,
+    /// Subtype extends a base class, interface, or supertrait.
+    Inherits,
+    /// Type implements a trait or interface.
+    Implements,
+    /// Symbol uses a type in its signature, fields, or annotations.
+    References,
 }
 
 /// An edge between two nodes in the graph.
@@ -73,6 +81,55 @@ impl Graph {
         self.nodes.iter().find(|n| n.id == id)
     }
 
+    /// Returns true if the node is marked as test code.
+    pub fn is_test(&self, node_id: &str) -> bool {
+        self.get_node(node_id)
+            .is_some_and(|n| n.metadata.get("is_test").is_some_and(|v| v == "true"))
+    }
+
+    /// Returns true if the node is an external (stdlib or third-party) symbol.
+    pub fn is_external(&self, node_id: &str) -> bool {
+        self.get_node(node_id)
+            .is_some_and(|n| n.metadata.get("kind").is_some_and(|v| v == "external"))
+    }
+
+    /// Returns a copy of the graph without test nodes or any edges touching them.
+    pub fn without_tests(&self) -> Graph {
+        self.without(|id| self.is_test(id))
+    }
+
+    /// Returns a copy of the graph without external nodes or any edges touching them.
+    pub fn without_externals(&self) -> Graph {
+        self.without(|id| self.is_external(id))
+    }
+
+    /// Returns a copy of the graph without the matching nodes or any edges touching them.
+    fn without(&self, exclude: impl Fn(&str) -> bool) -> Graph {
+        let excluded: HashSet<&str> = self
+            .nodes
+            .iter()
+            .filter(|n| exclude(&n.id))
+            .map(|n| n.id.as_str())
+            .collect();
+
+        Graph {
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|n| !excluded.contains(n.id.as_str()))
+                .cloned()
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|e| {
+                    !excluded.contains(e.source.as_str()) && !excluded.contains(e.target.as_str())
+                })
+                .cloned()
+                .collect(),
+        }
+    }
+
     /// Compute in-degree for a node.
     pub fn in_degree(&self, node_id: &str) -> usize {
         self.edges.iter().filter(|e| e.target == node_id).count()
@@ -238,6 +295,74 @@ impl GraphBuilder {
         }
     }
 
+    /// Build "inherits" and "implements" edges from type relation data.
+    ///
+    /// Creates edges from a subtype to its supertype when both symbols
+    /// exist in the graph.
+    ///
+    /// # Arguments
+    /// * `relations` - List of relations with (subtype_symbol_id, TypeRelation) tuples
+    pub fn build_type_relation_edges(&mut self, relations: &[(String, TypeRelation)]) {
+        for (source_id, relation) in relations {
+            // As with calls, the relation target is assumed to be the target symbol ID
+            let target_id = &relation.target;
+
+            if self.symbol_index.contains_key(source_id)
+                && self.symbol_index.contains_key(target_id)
+            {
+                let kind = match relation.kind {
+                    RelationKind::Inherits => EdgeKind::Inherits,
+                    RelationKind::Implements => EdgeKind::Implements,
+                };
+                let edge = Edge {
+                    source: source_id.clone(),
+                    target: target_id.clone(),
+                    kind,
+                    weight: None,
+                };
+                self.graph.add_edge(edge);
+            }
+        }
+    }
+
+    /// Build "references" edges from type reference data.
+    ///
+    /// Creates one edge per (symbol, type) pair for resolved references
+    /// when both symbols exist in the graph.
+    ///
+    /// # Arguments
+    /// * `references` - List of references with (referencing_symbol_id, TypeReference) tuples
+    pub fn build_references_edges(&mut self, references: &[(String, TypeReference)]) {
+        let mut seen = HashSet::new();
+
+        for (source_id, reference) in references {
+            if !reference.is_resolved {
+                continue;
+            }
+
+            // As with calls, the reference name is assumed to be the target symbol ID
+            let target_id = &reference.name;
+
+            // A symbol mentioning itself (e.g., a constructor returning `Self`) is not an edge
+            if source_id == target_id {
+                continue;
+            }
+
+            if self.symbol_index.contains_key(source_id)
+                && self.symbol_index.contains_key(target_id)
+                && seen.insert((source_id.clone(), target_id.clone()))
+            {
+                let edge = Edge {
+                    source: source_id.clone(),
+                    target: target_id.clone(),
+                    kind: EdgeKind::References,
+                    weight: None,
+                };
+                self.graph.add_edge(edge);
+            }
+        }
+    }
+
     /// Build "related" edges based on semantic similarity.
     ///
     /// Computes pairwise cosine similarity between all nodes and creates
@@ -310,6 +435,60 @@ impl GraphBuilder {
         }
     }
 
+    /// Build edges to external (stdlib and third-party) symbols.
+    ///
+    /// Adds one node of kind `external` per external symbol, keyed by
+    /// [`ExternalSymbol::node_id`], and one edge of the given kind per
+    /// (symbol, external) pair when the source symbol exists in the graph.
+    ///
+    /// # Arguments
+    /// * `externals` - List of (symbol_id, ExternalSymbol) tuples
+    /// * `kind` - Edge kind, [`EdgeKind::Calls`] or [`EdgeKind::Imports`]
+    pub fn build_external_edges(&mut self, externals: &[(String, ExternalSymbol)], kind: EdgeKind) {
+        let mut seen = HashSet::new();
+
+        for (source_id, external) in externals {
+            if !self.symbol_index.contains_key(source_id) {
+                continue;
+            }
+
+            let target_id = external.node_id();
+            if !self.symbol_index.contains_key(&target_id) {
+                let mut metadata = HashMap::new();
+                metadata.insert("name".to_string(), external.name.clone());
+                metadata.insert("kind".to_string(), "external".to_string());
+                metadata.insert("package".to_string(), external.package.clone());
+                metadata.insert("origin".to_string(), external.origin.to_string());
+
+                let node_idx = self.graph.nodes.len();
+                self.graph.add_node(Node::new(target_id.clone(), metadata));
+                self.symbol_index.insert(target_id.clone(), node_idx);
+            }
+
+            if seen.insert((source_id.clone(), target_id.clone())) {
+                self.graph.add_edge(Edge {
+                    source: source_id.clone(),
+                    target: target_id,
+                    kind: kind.clone(),
+                    weight: None,
+                });
+            }
+        }
+    }
+
+    /// Mark the given symbols as test code.
+    ///
+    /// Sets `is_test` in the node metadata; unknown symbol IDs are ignored.
+    pub fn mark_test_nodes<'a>(&mut self, symbol_ids: impl IntoIterator<Item = &'a str>) {
+        for symbol_id in symbol_ids {
+            if let Some(&idx) = self.symbol_index.get(symbol_id) {
+                self.graph.nodes[idx]
+                    .metadata
+                    .insert("is_test".to_string(), "true".to_string());
+            }
+        }
+    }
+
     /// Consume the builder and return the constructed graph.
     pub fn build(self) -> Graph {
         self.graph
@@ -442,6 +621,7 @@ mod tests {
                 range: ByteRange::new(100, 110),
                 kind: CallKind::Function,
                 is_resolved: true,
+                receiver_type: None,
             },
         )];
         builder.build_calls_edges(&calls);
@@ -477,6 +657,7 @@ mod tests {
                 range: ByteRange::new(100, 110),
                 kind: CallKind::Function,
                 is_resolved: false,
+                receiver_type: None,
             },
         )];
         builder.build_calls_edges(&calls);
@@ -485,6 +666,52 @@ mod tests {
         assert_eq!(builder.graph().edges.len(), 0);
     }
 
+    #[test]
+    fn test_graph_builder_external_edges() {
+        use doctown_common::types::{DependencyOrigin, ExternalSymbol};
+
+        let mut builder = GraphBuilder::new();
+        let symbols = vec![
+            SymbolData {
+                symbol_id: "fn_fetch".to_string(),
+                name: "fetch".to_string(),
+                kind: "function".to_string(),
+                file_path: "src/net.rs".to_string(),
+                signature: None,
+            },
+            SymbolData {
+                symbol_id: "fn_post".to_string(),
+                name: "post".to_string(),
+                kind: "function".to_string(),
+                file_path: "src/net.rs".to_string(),
+                signature: None,
+            },
+        ];
+        builder.build_nodes(&symbols);
+
+        let client =
+            ExternalSymbol::new("reqwest::Client", "reqwest", DependencyOrigin::ThirdParty);
+        let externals = vec![
+            ("fn_fetch".to_string(), client.clone()),
+            ("fn_fetch".to_string(), client.clone()),
+            ("fn_post".to_string(), client.clone()),
+            ("fn_unknown".to_string(), client.clone()),
+        ];
+        builder.build_external_edges(&externals, EdgeKind::Calls);
+
+        let graph = builder.build();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph.edges.iter().all(|e| e.target == client.node_id()));
+
+        let node = graph.get_node(&client.node_id()).unwrap();
+        assert_eq!(node.metadata.get("name").unwrap(), "reqwest::Client");
+        assert_eq!(node.metadata.get("package").unwrap(), "reqwest");
+        assert_eq!(node.metadata.get("origin").unwrap(), "third_party");
+        assert!(graph.is_external(&client.node_id()));
+        assert!(!graph.is_external("fn_fetch"));
+    }
+
     #[test]
     fn test_graph_builder_imports_edges() {
         use doctown_common::types::{ByteRange, Import};
@@ -564,6 +791,7 @@ mod tests {
                 range: ByteRange::new(10, 20),
                 kind: CallKind::Function,
                 is_resolved: true,
+                receiver_type: None,
             },
         )];
         builder.build_calls_edges(&calls);
@@ -601,6 +829,134 @@ mod tests {
         assert_eq!(imports_edges.len(), 1);
     }
 
+    #[test]
+    fn test_graph_builder_type_relation_edges() {
+        use doctown_common::types::{ByteRange, RelationKind, TypeRelation};
+
+        let mut builder = GraphBuilder::new();
+
+        let symbols = vec![
+            SymbolData {
+                symbol_id: "Dog".to_string(),
+                name: "Dog".to_string(),
+                kind: "class".to_string(),
+                file_path: "src/dog.py".to_string(),
+                signature: None,
+            },
+            SymbolData {
+                symbol_id: "Animal".to_string(),
+                name: "Animal".to_string(),
+                kind: "class".to_string(),
+                file_path: "src/animal.py".to_string(),
+                signature: None,
+            },
+            SymbolData {
+                symbol_id: "Pet".to_string(),
+                name: "Pet".to_string(),
+                kind: "interface".to_string(),
+                file_path: "src/pet.py".to_string(),
+                signature: None,
+            },
+        ];
+        builder.build_nodes(&symbols);
+
+        let relations = vec![
+            (
+                "Dog".to_string(),
+                TypeRelation {
+                    source: "Dog".to_string(),
+                    target: "Animal".to_string(),
+                    kind: RelationKind::Inherits,
+                    range: ByteRange::new(10, 16),
+                },
+            ),
+            (
+                "Dog".to_string(),
+                TypeRelation {
+                    source: "Dog".to_string(),
+                    target: "Pet".to_string(),
+                    kind: RelationKind::Implements,
+                    range: ByteRange::new(18, 21),
+                },
+            ),
+            (
+                "Dog".to_string(),
+                TypeRelation {
+                    source: "Dog".to_string(),
+                    target: "object".to_string(),
+                    kind: RelationKind::Inherits,
+                    range: ByteRange::new(23, 29),
+                },
+            ),
+        ];
+        builder.build_type_relation_edges(&relations);
+
+        // The edge to "object" is skipped because the target is not in the graph
+        let edges = &builder.graph().edges;
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].target, "Animal");
+        assert_eq!(edges[0].kind, EdgeKind::Inherits);
+        assert_eq!(edges[1].target, "Pet");
+        assert_eq!(edges[1].kind, EdgeKind::Implements);
+    }
+
+    #[test]
+    fn test_graph_builder_references_edges() {
+        use doctown_common::types::{ByteRange, TypeReference};
+
+        let mut builder = GraphBuilder::new();
+
+        let symbols = vec![
+            SymbolData {
+                symbol_id: "create_order".to_string(),
+                name: "create_order".to_string(),
+                kind: "function".to_string(),
+                file_path: "src/orders.rs".to_string(),
+                signature: Some("fn create_order(user: &User) -> Order".to_string()),
+            },
+            SymbolData {
+                symbol_id: "User".to_string(),
+                name: "User".to_string(),
+                kind: "struct".to_string(),
+                file_path: "src/models.rs".to_string(),
+                signature: None,
+            },
+            SymbolData {
+                symbol_id: "Order".to_string(),
+                name: "Order".to_string(),
+                kind: "struct".to_string(),
+                file_path: "src/models.rs".to_string(),
+                signature: None,
+            },
+        ];
+        builder.build_nodes(&symbols);
+
+        let reference = |name: &str, is_resolved: bool| {
+            (
+                "create_order".to_string(),
+                TypeReference {
+                    name: name.to_string(),
+                    range: ByteRange::new(0, 0),
+                    is_resolved,
+                },
+            )
+        };
+        let references = vec![
+            reference("User", true),
+            reference("Order", true),
+            reference("Order", true),
+            reference("String", false),
+        ];
+        builder.build_references_edges(&references);
+
+        // Duplicate references collapse into one edge; unresolved ones are skipped
+        let edges = &builder.graph().edges;
+        assert_eq!(edges.len(), 2);
+        assert!(edges.iter().all(|e| e.kind == EdgeKind::References));
+        assert_eq!(edges[0].target, "User");
+        assert_eq!(edges[1].target, "Order");
+    }
+
     #[test]
     fn test_cosine_similarity() {
         use ndarray::Array1;
@@ -751,6 +1107,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_without_tests() {
+        let mut builder = GraphBuilder::new();
+        builder.build_nodes(&[
+            SymbolData {
+                symbol_id: "parse".to_string(),
+                name: "parse".to_string(),
+                kind: "function".to_string(),
+                file_path: "src/lib.rs".to_string(),
+                signature: None,
+            },
+            SymbolData {
+                symbol_id: "validate".to_string(),
+                name: "validate".to_string(),
+                kind: "function".to_string(),
+                file_path: "src/lib.rs".to_string(),
+                signature: None,
+            },
+            SymbolData {
+                symbol_id: "test_parse".to_string(),
+                name: "test_parse".to_string(),
+                kind: "function".to_string(),
+                file_path: "src/lib.rs".to_string(),
+                signature: None,
+            },
+        ]);
+        builder.mark_test_nodes(["test_parse", "missing"]);
+
+        let mut graph = builder.build();
+        for (source, target) in [("parse", "validate"), ("test_parse", "parse")] {
+            graph.add_edge(Edge {
+                source: source.to_string(),
+                target: target.to_string(),
+                kind: EdgeKind::Calls,
+                weight: None,
+            });
+        }
+
+        assert!(graph.is_test("test_parse"));
+        assert!(!graph.is_test("parse"));
+
+        let production = graph.without_tests();
+        assert_eq!(production.nodes.len(), 2);
+        assert_eq!(production.edges.len(), 1);
+        assert_eq!(production.degree("parse"), 1);
+        assert_eq!(graph.degree("parse"), 2);
+    }
+
     #[test]
     fn test_graph_density() {
         let mut graph = Graph::new();
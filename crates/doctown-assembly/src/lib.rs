@@ -17,7 +17,7 @@ pub mod packer;
 
 pub use api::{start_server, AssembleRequest, AssembleResponse};
 pub use cluster::Clusterer;
-pub use context::{ContextGenerator, SymbolContext};
+pub use context::{ContextGenerator, DocumentExcerpt, SymbolContext};
 pub use graph::{Edge, EdgeKind, Graph, GraphBuilder, Node, SymbolData};
 pub use label::ClusterLabeler;
 pub use packer::{ChunkInfo, EmbeddingData, PackRequest, PackResponse, Packer, SourceFileInfo};
@@ -4,11 +4,11 @@
 //! embeddings, symbol contexts) and packages them into a reproducible .docpack file.
 
 use doctown_docpack::{
-    Cluster, Clusters, DocpackContent, DocpackWriter, Edge, Graph, Manifest, Nodes, SourceMap,
-    SourceMapChunk, SourceMapFile, Symbol,
+    Cluster, Clusters, DocpackContent, DocpackWriter, Document, Edge, External, Graph, Manifest,
+    MetricsStatistics, Nodes, SourceMap, SourceMapChunk, SourceMapFile, Symbol, SymbolMetrics,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use crate::context::SymbolContext;
 
@@ -36,6 +36,14 @@ pub struct PackRequest {
     pub nodes: Vec<NodeInfo>,
     pub edges: Vec<EdgeInfo>,
 
+    /// Documentation sections linked to the symbols they mention
+    #[serde(default)]
+    pub documents: Vec<DocumentNodeInfo>,
+
+    /// Standard library and third-party symbols used by the repository
+    #[serde(default)]
+    pub externals: Vec<ExternalNodeInfo>,
+
     /// Optional: embeddings data
     pub embeddings: Option<EmbeddingData>,
 
@@ -61,6 +69,9 @@ pub struct ChunkInfo {
     pub chunk_id: String,
     pub byte_range: (usize, usize),
     pub symbol_ids: Vec<String>,
+    /// Parent chunk in a hierarchical chunking
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_chunk_id: Option<String>,
 }
 
 /// Node information from assembly
@@ -79,6 +90,41 @@ pub struct NodeInfo {
     pub centrality: f64,
     pub documentation_summary: String,
     pub documentation_details: Option<String>,
+    #[serde(default)]
+    pub is_test: bool,
+    #[serde(default)]
+    pub metrics: Option<SymbolMetrics>,
+    #[serde(default)]
+    pub attributes: Vec<String>,
+}
+
+/// Documentation section information from assembly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentNodeInfo {
+    pub document_id: String,
+    pub file_path: String,
+    pub byte_range: (usize, usize),
+    #[serde(default)]
+    pub heading: Option<String>,
+    #[serde(default)]
+    pub heading_path: Vec<String>,
+    pub cluster_id: String,
+    /// IDs of the symbols the section mentions
+    #[serde(default)]
+    pub symbols: Vec<String>,
+}
+
+/// External (stdlib or third-party) symbol information from assembly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalNodeInfo {
+    pub id: String,
+    pub name: String,
+    pub package: String,
+    /// "stdlib" or "third_party"
+    pub origin: String,
+    /// IDs of the symbols calling or importing it
+    #[serde(default)]
+    pub used_by: Vec<String>,
 }
 
 /// Edge information from assembly
@@ -93,6 +139,9 @@ pub struct EdgeInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingData {
     pub dimensions: usize,
+    /// Model that made the vectors (e.g. "all-MiniLM-L6-v2")
+    #[serde(default)]
+    pub model: Option<String>,
     pub vectors: HashMap<String, Vec<f32>>, // chunk_id -> vector
 }
 
@@ -111,6 +160,8 @@ pub struct PackStatistics {
     pub symbol_count: usize,
     pub cluster_count: usize,
     pub embedding_dimensions: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
     pub has_embeddings: bool,
     pub has_symbol_contexts: bool,
 }
@@ -134,6 +185,21 @@ impl Packer {
         let symbol_count = request.nodes.len();
         let cluster_count = request.cluster_labels.len();
         let embedding_dimensions = request.embeddings.as_ref().map(|e| e.dimensions);
+        let embedding_model = request.embeddings.as_ref().and_then(|e| e.model.clone());
+        if let Some(embeddings) = &request.embeddings {
+            if let Some((chunk_id, vector)) = embeddings
+                .vectors
+                .iter()
+                .find(|(_, vector)| vector.len() != embeddings.dimensions)
+            {
+                return Err(format!(
+                    "Embedding of {} has {} dimensions, expected {}",
+                    chunk_id,
+                    vector.len(),
+                    embeddings.dimensions
+                ));
+            }
+        }
         let has_embeddings = request.embeddings.is_some();
         let has_symbol_contexts = request.symbol_contexts.is_some();
 
@@ -157,6 +223,11 @@ impl Packer {
                 symbol_count,
                 cluster_count,
             )
+        }
+        .with_metrics(MetricsStatistics::from_symbols(&nodes.symbols));
+        let manifest = match embedding_dimensions {
+            Some(dimensions) => manifest.with_embedding_model(embedding_model.clone(), dimensions),
+            None => manifest,
         };
 
         // M4.2.3: Write docpack (reproducible)
@@ -177,6 +248,7 @@ impl Packer {
                 symbol_count,
                 cluster_count,
                 embedding_dimensions,
+                embedding_model,
                 has_embeddings,
                 has_symbol_contexts,
             },
@@ -191,15 +263,31 @@ impl Packer {
             *member_counts.entry(cluster_id.clone()).or_insert(0) += 1;
         }
 
+        // Packages used by each cluster's members
+        let mut dependencies: HashMap<&str, BTreeSet<String>> = HashMap::new();
+        for external in &request.externals {
+            for symbol_id in &external.used_by {
+                if let Some(cluster_id) = request.cluster_assignments.get(symbol_id) {
+                    dependencies
+                        .entry(cluster_id.as_str())
+                        .or_default()
+                        .insert(external.package.clone());
+                }
+            }
+        }
+
         // Build cluster objects
         let mut clusters_vec = Vec::new();
         for (cluster_id, label) in &request.cluster_labels {
             let member_count = member_counts.get(cluster_id).copied().unwrap_or(0);
-            clusters_vec.push(Cluster::new(
-                cluster_id.clone(),
-                label.clone(),
-                member_count,
-            ));
+            clusters_vec.push(
+                Cluster::new(cluster_id.clone(), label.clone(), member_count).with_dependencies(
+                    dependencies
+                        .remove(cluster_id.as_str())
+                        .map(|packages| packages.into_iter().collect())
+                        .unwrap_or_default(),
+                ),
+            );
         }
 
         // Sort for reproducibility
@@ -217,7 +305,12 @@ impl Packer {
                 .chunks
                 .iter()
                 .map(|c| {
-                    SourceMapChunk::new(c.chunk_id.clone(), c.byte_range, c.symbol_ids.clone())
+                    let chunk =
+                        SourceMapChunk::new(c.chunk_id.clone(), c.byte_range, c.symbol_ids.clone());
+                    match &c.parent_chunk_id {
+                        Some(parent) => chunk.with_parent(parent.clone()),
+                        None => chunk,
+                    }
                 })
                 .collect();
 
@@ -237,10 +330,16 @@ impl Packer {
     /// Build graph from request
     fn build_graph(&self, request: &PackRequest) -> Result<Graph, String> {
         // Collect all node IDs
-        let node_ids: Vec<String> = request.nodes.iter().map(|n| n.symbol_id.clone()).collect();
+        let node_ids: Vec<String> = request
+            .nodes
+            .iter()
+            .map(|n| n.symbol_id.clone())
+            .chain(request.documents.iter().map(|d| d.document_id.clone()))
+            .chain(request.externals.iter().map(|e| e.id.clone()))
+            .collect();
 
         // Build edges
-        let edges: Vec<Edge> = request
+        let mut edges: Vec<Edge> = request
             .edges
             .iter()
             .map(|e| Edge {
@@ -250,6 +349,16 @@ impl Packer {
             })
             .collect();
 
+        // Documents point at the symbols they mention
+        for document in &request.documents {
+            for symbol_id in &document.symbols {
+                edges.push(Edge::mentions(
+                    document.document_id.clone(),
+                    symbol_id.clone(),
+                ));
+            }
+        }
+
         Ok(Graph::new(node_ids, edges))
     }
 
@@ -284,7 +393,13 @@ impl Packer {
                 .with_calls(node.calls.clone())
                 .with_called_by(node.called_by.clone())
                 .with_imports(node.imports.clone())
-                .with_centrality(node.centrality);
+                .with_centrality(node.centrality)
+                .with_test(node.is_test)
+                .with_attributes(node.attributes.clone());
+
+            if let Some(metrics) = node.metrics {
+                symbol = symbol.with_metrics(metrics);
+            }
 
             symbols.push(symbol);
         }
@@ -292,7 +407,46 @@ impl Packer {
         // Sort for reproducibility
         symbols.sort_by(|a, b| a.id.cmp(&b.id));
 
-        Ok(Nodes::new(symbols))
+        let mut documents: Vec<Document> = request
+            .documents
+            .iter()
+            .map(|d| {
+                let mut document = Document::new(
+                    d.document_id.clone(),
+                    d.file_path.clone(),
+                    d.byte_range,
+                    d.cluster_id.clone(),
+                )
+                .with_mentions(d.symbols.clone());
+                if let Some(heading) = &d.heading {
+                    document = document.with_heading(heading.clone(), d.heading_path.clone());
+                }
+                document
+            })
+            .collect();
+        documents.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut externals: Vec<External> = request
+            .externals
+            .iter()
+            .map(|e| {
+                let mut used_by = e.used_by.clone();
+                used_by.sort();
+                used_by.dedup();
+                External::new(
+                    e.id.clone(),
+                    e.name.clone(),
+                    e.package.clone(),
+                    e.origin.clone(),
+                )
+                .with_used_by(used_by)
+            })
+            .collect();
+        externals.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(Nodes::new(symbols)
+            .with_documents(documents)
+            .with_externals(externals))
     }
 
     /// Compute content-addressed docpack_id
@@ -332,6 +486,7 @@ mod tests {
                     chunk_id: "chunk_1".to_string(),
                     byte_range: (0, 100),
                     symbol_ids: vec!["sym_1".to_string()],
+                    parent_chunk_id: None,
                 }],
             }],
             cluster_assignments: {
@@ -358,8 +513,13 @@ mod tests {
                 centrality: 0.8,
                 documentation_summary: "Main entry point".to_string(),
                 documentation_details: None,
+                is_test: false,
+                metrics: None,
+                attributes: Vec::new(),
             }],
             edges: vec![],
+            documents: Vec::new(),
+            externals: Vec::new(),
             embeddings: None,
             symbol_contexts: None,
             deterministic_timestamp: None,
@@ -383,6 +543,47 @@ mod tests {
         let graph = packer.build_graph(&request).unwrap();
         assert_eq!(graph.nodes.len(), 1);
         assert_eq!(graph.edges.len(), 0);
+
+        // Documents become nodes with "mentions" edges to their symbols
+        let mut request = request;
+        request.documents = vec![DocumentNodeInfo {
+            document_id: "doc_readme".to_string(),
+            file_path: "README.md".to_string(),
+            byte_range: (0, 42),
+            heading: Some("Usage".to_string()),
+            heading_path: vec!["Usage".to_string()],
+            cluster_id: "cluster_1".to_string(),
+            symbols: vec!["sym_1".to_string()],
+        }];
+
+        let nodes = packer.build_nodes(&request).unwrap();
+        assert_eq!(nodes.documents.len(), 1);
+        assert_eq!(nodes.documents[0].mentions, vec!["sym_1"]);
+
+        let graph = packer.build_graph(&request).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].kind, "mentions");
+
+        // External symbols become nodes, and their packages cluster dependencies
+        request.externals = vec![ExternalNodeInfo {
+            id: "ext_reqwest::Client".to_string(),
+            name: "reqwest::Client".to_string(),
+            package: "reqwest".to_string(),
+            origin: "third_party".to_string(),
+            used_by: vec!["sym_1".to_string(), "sym_1".to_string()],
+        }];
+
+        let nodes = packer.build_nodes(&request).unwrap();
+        assert_eq!(nodes.externals.len(), 1);
+        assert_eq!(nodes.externals[0].kind, "external");
+        assert_eq!(nodes.externals[0].used_by, vec!["sym_1"]);
+
+        let clusters = packer.build_clusters(&request).unwrap();
+        assert_eq!(clusters.clusters[0].dependencies, vec!["reqwest"]);
+
+        let graph = packer.build_graph(&request).unwrap();
+        assert_eq!(graph.nodes.len(), 3);
     }
 
     /// M4.2.2: Test full docpack assembly
@@ -401,6 +602,7 @@ mod tests {
                     chunk_id: "chunk_abc".to_string(),
                     byte_range: (0, 200),
                     symbol_ids: vec!["sym_helper".to_string()],
+                    parent_chunk_id: None,
                 }],
             }],
             cluster_assignments: {
@@ -427,10 +629,16 @@ mod tests {
                 centrality: 0.5,
                 documentation_summary: "A helper function for testing".to_string(),
                 documentation_details: Some("Returns a fixed value".to_string()),
+                is_test: false,
+                metrics: None,
+                attributes: Vec::new(),
             }],
             edges: vec![],
+            documents: Vec::new(),
+            externals: Vec::new(),
             embeddings: Some(EmbeddingData {
                 dimensions: 384,
+                model: Some("all-MiniLM-L6-v2".to_string()),
                 vectors: {
                     let mut map = HashMap::new();
                     map.insert("chunk_abc".to_string(), vec![0.1; 384]);
@@ -449,6 +657,10 @@ mod tests {
         assert_eq!(response.statistics.symbol_count, 1);
         assert_eq!(response.statistics.cluster_count, 1);
         assert_eq!(response.statistics.embedding_dimensions, Some(384));
+        assert_eq!(
+            response.statistics.embedding_model.as_deref(),
+            Some("all-MiniLM-L6-v2")
+        );
         assert!(response.statistics.has_embeddings);
         assert!(!response.statistics.has_symbol_contexts);
 
@@ -457,6 +669,14 @@ mod tests {
 
         // Check bytes are not empty
         assert!(!response.docpack_bytes.is_empty());
+
+        // The manifest records the model that made the embeddings
+        let docpack = doctown_docpack::DocpackReader::read(&response.docpack_bytes).unwrap();
+        assert_eq!(docpack.manifest().statistics.embedding_dimensions, 384);
+        assert_eq!(
+            docpack.manifest().statistics.embedding_model.as_deref(),
+            Some("all-MiniLM-L6-v2")
+        );
     }
 
     /// M4.2.3: Test reproducibility
@@ -475,6 +695,7 @@ mod tests {
                     chunk_id: "chunk_1".to_string(),
                     byte_range: (0, 50),
                     symbol_ids: vec!["sym_test".to_string()],
+                    parent_chunk_id: None,
                 }],
             }],
             cluster_assignments: {
@@ -501,8 +722,13 @@ mod tests {
                 centrality: 0.3,
                 documentation_summary: "Test function".to_string(),
                 documentation_details: None,
+                is_test: false,
+                metrics: None,
+                attributes: Vec::new(),
             }],
             edges: vec![],
+            documents: Vec::new(),
+            externals: Vec::new(),
             embeddings: None,
             symbol_contexts: None,
             deterministic_timestamp: Some("2025-01-01T00:00:00Z".to_string()),
@@ -1,6 +1,6 @@
 //! Integration tests for the assembly worker.
 
-use doctown_assembly::api::{AssembleRequest, ChunkWithEmbedding, SymbolMetadata};
+use doctown_assembly::api::{AssembleRequest, ChunkWithEmbedding, SymbolMetadata, TestHandling};
 use doctown_events::EventType;
 
 /// Test the full assembly pipeline with sample data.
@@ -32,10 +32,19 @@ fn test_assembly_pipeline() {
             kind: "function".to_string(),
             language: "rust".to_string(),
             file_path: "src/math.rs".to_string(),
+            qualified_name: None,
             signature: "fn calculate_total() -> i32".to_string(),
             chunk_ids: vec!["chunk_1".to_string()],
             calls: vec!["sum".to_string()],
             imports: vec!["std::collections".to_string()],
+            inherits: vec![],
+            implements: vec![],
+            references: vec![],
+            is_test: false,
+            metrics: None,
+            attributes: Vec::new(),
+            external_calls: Vec::new(),
+            external_imports: Vec::new(),
         },
         SymbolMetadata {
             symbol_id: "sym_2".to_string(),
@@ -43,10 +52,19 @@ fn test_assembly_pipeline() {
             kind: "function".to_string(),
             language: "rust".to_string(),
             file_path: "src/math.rs".to_string(),
+            qualified_name: None,
             signature: "fn calculate_average() -> f64".to_string(),
             chunk_ids: vec!["chunk_2".to_string()],
             calls: vec!["mean".to_string()],
             imports: vec![],
+            inherits: vec![],
+            implements: vec![],
+            references: vec![],
+            is_test: false,
+            metrics: None,
+            attributes: Vec::new(),
+            external_calls: Vec::new(),
+            external_imports: Vec::new(),
         },
         SymbolMetadata {
             symbol_id: "sym_3".to_string(),
@@ -54,10 +72,19 @@ fn test_assembly_pipeline() {
             kind: "class".to_string(),
             language: "rust".to_string(),
             file_path: "src/loader.rs".to_string(),
+            qualified_name: None,
             signature: "class DataLoader".to_string(),
             chunk_ids: vec!["chunk_3".to_string()],
             calls: vec![],
             imports: vec![],
+            inherits: vec![],
+            implements: vec![],
+            references: vec![],
+            is_test: false,
+            metrics: None,
+            attributes: Vec::new(),
+            external_calls: Vec::new(),
+            external_imports: Vec::new(),
         },
     ];
 
@@ -67,6 +94,8 @@ fn test_assembly_pipeline() {
         git_ref: "main".to_string(),
         chunks,
         symbols,
+        test_handling: TestHandling::Include,
+        documents: Vec::new(),
     };
 
     // For now, just verify the request can be serialized/deserialized
@@ -77,6 +106,34 @@ fn test_assembly_pipeline() {
     // For now, this test validates the types and structure
 }
 
+/// Test that test handling defaults to including tests and parses from snake_case.
+#[test]
+fn test_assemble_request_test_handling() {
+    let json = r#"{
+        "job_id": "job_test_123",
+        "repo_url": "https://github.com/test/repo",
+        "git_ref": "main",
+        "chunks": [],
+        "symbols": [{
+            "symbol_id": "sym_1",
+            "name": "test_parse",
+            "kind": "function",
+            "file_path": "tests/parse.rs",
+            "signature": "fn test_parse()",
+            "chunk_ids": ["chunk_1"],
+            "is_test": true
+        }]
+    }"#;
+
+    let request: AssembleRequest = serde_json::from_str(json).unwrap();
+    assert_eq!(request.test_handling, TestHandling::Include);
+    assert!(request.symbols[0].is_test);
+
+    let json = json.replacen('{', r#"{"test_handling": "separate","#, 1);
+    let request: AssembleRequest = serde_json::from_str(&json).unwrap();
+    assert_eq!(request.test_handling, TestHandling::Separate);
+}
+
 /// Test event payload serialization.
 #[test]
 fn test_event_serialization() {
@@ -112,6 +169,9 @@ fn test_event_serialization() {
             calls: 80,
             imports: 30,
             related: 10,
+            inherits: 0,
+            implements: 0,
+            references: 0,
         },
     };
     let json = serde_json::to_string(&graph).unwrap();
@@ -207,6 +267,7 @@ fn test_symbol_contexts_in_response() {
         clusters: vec![],
         nodes: vec![],
         edges: vec![],
+        documents: vec![],
         symbol_contexts: contexts.clone(),
         stats: doctown_assembly::api::AssemblyStats {
             cluster_count: 1,
@@ -25,6 +25,10 @@ pub enum DocError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// A service behaves in a way its configuration rules out.
+    #[error("Configuration error: {0}")]
+    Config(String),
+
     /// An operation timed out.
     #[error("Operation timed out: {0}")]
     Timeout(String),
@@ -1,6 +1,9 @@
 //! Identifier types for Doctown entities.
 
+use crate::types::{Language, SymbolKind};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fmt;
 use uuid::Uuid;
 
@@ -111,6 +114,43 @@ impl SymbolId {
         Self(format!("sym_{}", &uuid[..16]))
     }
 
+    /// Derives the canonical SymbolId of a symbol.
+    ///
+    /// The ID hashes the language, kind and qualified path (e.g.
+    /// "my_crate::net::Client::connect", or "src/app.py::main" where the
+    /// language has no module paths) with a disambiguator numbering symbols
+    /// that share all three, such as overloads, in source order. The same
+    /// symbol gets the same ID in every run and every crate, so docpacks built
+    /// from different commits can be diffed by ID.
+    ///
+    /// Canonical IDs are "sym_" followed by 24 lowercase hex digits (96 bits).
+    pub fn derive(language: Language, kind: SymbolKind, path: &str, disambiguator: usize) -> Self {
+        let key = format!(
+            "{}:{}:{}#{}",
+            language.extension(),
+            kind.short_name(),
+            path,
+            disambiguator
+        );
+        let digest = Sha256::digest(key.as_bytes());
+        let hex: String = digest[..Self::CANONICAL_BYTES]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Self(format!("sym_{}", hex))
+    }
+
+    /// Returns true if the ID has the canonical form produced by [`SymbolId::derive`].
+    pub fn is_canonical(&self) -> bool {
+        self.0.strip_prefix("sym_").is_some_and(|hex| {
+            hex.len() == Self::CANONICAL_BYTES * 2
+                && hex.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+        })
+    }
+
+    /// Number of hash bytes in a canonical ID.
+    const CANONICAL_BYTES: usize = 12;
+
     fn validate(id: &str) -> Result<(), &'static str> {
         if !id.starts_with("sym_") {
             return Err("SymbolId must start with 'sym_'");
@@ -132,6 +172,32 @@ impl fmt::Display for SymbolId {
     }
 }
 
+/// Assigns canonical SymbolIds, numbering symbols that share a language,
+/// kind and qualified path in the order they are added.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIdAllocator {
+    /// Number of IDs handed out per (language, kind, path)
+    counts: HashMap<(Language, SymbolKind, String), usize>,
+}
+
+impl SymbolIdAllocator {
+    /// Creates an allocator with no IDs handed out.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the canonical ID of the next symbol with this language, kind and path.
+    pub fn allocate(&mut self, language: Language, kind: SymbolKind, path: &str) -> SymbolId {
+        let count = self
+            .counts
+            .entry((language, kind, path.to_string()))
+            .or_default();
+        let id = SymbolId::derive(language, kind, path, *count);
+        *count += 1;
+        id
+    }
+}
+
 /// A unique identifier for an event.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -250,6 +316,50 @@ mod tests {
         assert_eq!(id.as_str(), "sym_abc123def");
     }
 
+    #[test]
+    fn test_symbol_id_derive() {
+        let id = SymbolId::derive(Language::Rust, SymbolKind::Method, "app::Client::new", 0);
+        assert_eq!(
+            id,
+            SymbolId::derive(Language::Rust, SymbolKind::Method, "app::Client::new", 0)
+        );
+        assert!(id.is_canonical());
+        assert_eq!(id.as_str().len(), 28);
+        assert_eq!(SymbolId::new(id.as_str()).unwrap(), id);
+
+        // Every part of the key matters
+        let others = [
+            SymbolId::derive(Language::Rust, SymbolKind::Method, "app::Client::new", 1),
+            SymbolId::derive(Language::Rust, SymbolKind::Function, "app::Client::new", 0),
+            SymbolId::derive(Language::Go, SymbolKind::Method, "app::Client::new", 0),
+            SymbolId::derive(Language::Rust, SymbolKind::Method, "app::Server::new", 0),
+        ];
+        assert!(others.iter().all(|other| *other != id));
+
+        assert!(!SymbolId::new("sym_main_fn").unwrap().is_canonical());
+    }
+
+    #[test]
+    fn test_symbol_id_allocator() {
+        let mut allocator = SymbolIdAllocator::new();
+        let first = allocator.allocate(Language::TypeScript, SymbolKind::Method, "api.ts::get");
+        let second = allocator.allocate(Language::TypeScript, SymbolKind::Method, "api.ts::get");
+        let other = allocator.allocate(Language::TypeScript, SymbolKind::Method, "api.ts::put");
+
+        assert_eq!(
+            first,
+            SymbolId::derive(Language::TypeScript, SymbolKind::Method, "api.ts::get", 0)
+        );
+        assert_eq!(
+            second,
+            SymbolId::derive(Language::TypeScript, SymbolKind::Method, "api.ts::get", 1)
+        );
+        assert_eq!(
+            other,
+            SymbolId::derive(Language::TypeScript, SymbolKind::Method, "api.ts::put", 0)
+        );
+    }
+
     #[test]
     fn test_event_id_generate() {
         let id = EventId::generate();
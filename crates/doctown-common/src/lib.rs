@@ -7,5 +7,8 @@ pub mod ids;
 pub mod types;
 
 pub use error::DocError;
-pub use ids::{ChunkId, EventId, JobId, SymbolId, TraceId};
-pub use types::{ByteRange, Language, SymbolKind, Visibility};
+pub use ids::{ChunkId, EventId, JobId, SymbolId, SymbolIdAllocator, TraceId};
+pub use types::{
+    Attribute, ByteRange, ChunkLevel, DependencyOrigin, ExternalSymbol, Language, SymbolKind,
+    SymbolMetrics, Visibility,
+};
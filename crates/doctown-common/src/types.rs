@@ -197,6 +197,9 @@ pub struct Call {
     pub kind: CallKind,
     /// Whether the target is resolved (true) or external/unknown (false)
     pub is_resolved: bool,
+    /// Inferred type of the receiver for method calls (e.g., "Store" in `self.store.save()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receiver_type: Option<String>,
 }
 
 /// An import statement extracted from source code.
@@ -239,11 +242,279 @@ impl fmt::Display for CallKind {
     }
 }
 
+/// A type relationship (inheritance or implementation) extracted from source code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeRelation {
+    /// The name of the subtype (e.g., "Dog" in "class Dog(Animal)")
+    pub source: String,
+    /// The name of the supertype, without type arguments (e.g., "Animal", "std::fmt::Debug")
+    pub target: String,
+    /// The type of relationship
+    pub kind: RelationKind,
+    /// Byte range of the supertype reference
+    pub range: ByteRange,
+}
+
+/// The kind of type relationship.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    /// Subtype extends a base class or interface: `class Dog(Animal)`, `trait A: B`
+    Inherits,
+    /// Type implements a trait or interface: `impl Trait for Type`, `implements I`
+    Implements,
+}
+
+impl fmt::Display for RelationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelationKind::Inherits => write!(f, "inherits"),
+            RelationKind::Implements => write!(f, "implements"),
+        }
+    }
+}
+
+/// Where a dependency outside the repository comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyOrigin {
+    /// The language's standard library: `std`, `os`, `node:fs`, `net/http`
+    Stdlib,
+    /// A package declared in a manifest or installed from a registry
+    ThirdParty,
+}
+
+impl fmt::Display for DependencyOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyOrigin::Stdlib => write!(f, "stdlib"),
+            DependencyOrigin::ThirdParty => write!(f, "third_party"),
+        }
+    }
+}
+
+/// Granularity of a chunk in a hierarchical chunking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkLevel {
+    /// A summary of a whole file, or the file itself when it has no symbols.
+    ///
+    /// A summary is an outline rather than a span of the source, so its
+    /// byte range is empty.
+    File,
+    /// A top-level symbol (a class, impl block or free function).
+    Symbol,
+    /// A symbol nested in another one (e.g. a method).
+    SubSymbol,
+}
+
+impl ChunkLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChunkLevel::File => "file",
+            ChunkLevel::Symbol => "symbol",
+            ChunkLevel::SubSymbol => "sub_symbol",
+        }
+    }
+}
+
+/// A symbol outside the repository that code calls or imports.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExternalSymbol {
+    /// The symbol's path as imported (e.g., "reqwest::Client", "numpy.array")
+    pub name: String,
+    /// The package providing it (e.g., "reqwest", "numpy", "net/http")
+    pub package: String,
+    /// Whether the package is part of the standard library
+    pub origin: DependencyOrigin,
+}
+
+impl ExternalSymbol {
+    /// Create a new external symbol.
+    pub fn new(
+        name: impl Into<String>,
+        package: impl Into<String>,
+        origin: DependencyOrigin,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            package: package.into(),
+            origin,
+        }
+    }
+
+    /// The ID of the symbol's node in the code graph.
+    pub fn node_id(&self) -> String {
+        format!("ext_{}", self.name)
+    }
+}
+
+/// A reference to a named type in a signature, field, or annotation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeReference {
+    /// The referenced type name, without type arguments (e.g., "User", "models.Order")
+    pub name: String,
+    /// Byte range of the type reference
+    pub range: ByteRange,
+    /// Whether the type is resolved (true) or external/unknown (false)
+    pub is_resolved: bool,
+}
+
+/// An attribute or decorator attached to a symbol, such as `#[derive(Debug)]`
+/// or `@app.route("/")`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attribute {
+    /// The attribute path without arguments (e.g., "derive", "tokio::test", "app.route")
+    pub name: String,
+    /// The text between the outer parentheses, if any (e.g., "Debug, Clone")
+    pub arguments: Option<String>,
+    /// The attribute as written in source (e.g., "#[derive(Debug, Clone)]")
+    pub text: String,
+}
+
+impl Attribute {
+    /// Parse a Rust attribute (`#[...]`) or a Python/TypeScript decorator (`@...`).
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let inner = if let Some(rest) = text.strip_prefix('@') {
+            rest
+        } else {
+            text.trim_start_matches("#!")
+                .trim_start_matches('#')
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+        }
+        .trim();
+
+        let (name, arguments) = match inner.find(['(', '=']) {
+            Some(i) if inner[i..].starts_with('(') && inner.ends_with(')') => (
+                inner[..i].trim(),
+                Some(inner[i + 1..inner.len() - 1].trim().to_string()),
+            ),
+            // `#[doc = "..."]`, `#[path = "..."]`
+            Some(i) => (inner[..i].trim(), Some(inner[i + 1..].trim().to_string())),
+            None => (inner, None),
+        };
+
+        Self {
+            name: name.chars().filter(|c| !c.is_whitespace()).collect(),
+            arguments,
+            text: text.to_string(),
+        }
+    }
+
+    /// Returns the traits listed in a `#[derive(...)]` attribute.
+    pub fn derives(&self) -> Vec<String> {
+        if self.name != "derive" {
+            return Vec::new();
+        }
+        self.arguments
+            .iter()
+            .flat_map(|args| args.split(','))
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Returns the condition of a `#[cfg(...)]` or `#[cfg_attr(...)]` attribute.
+    pub fn cfg_condition(&self) -> Option<&str> {
+        match self.name.as_str() {
+            "cfg" => self.arguments.as_deref(),
+            // `cfg_attr(condition, attribute)`: keep the condition only
+            "cfg_attr" => self
+                .arguments
+                .as_deref()
+                .and_then(|args| args.split(',').next())
+                .map(str::trim),
+            _ => None,
+        }
+    }
+
+    /// Returns true for `#[deprecated]` and `@deprecated`-style decorators.
+    pub fn is_deprecated(&self) -> bool {
+        self.name
+            .rsplit(['.', ':'])
+            .next()
+            .is_some_and(|last| last == "deprecated")
+    }
+}
+
+/// Cyclomatic complexity above which a symbol is considered complex.
+pub const COMPLEXITY_THRESHOLD: usize = 10;
+
+/// Nesting depth above which a symbol is considered complex.
+pub const NESTING_THRESHOLD: usize = 4;
+
+/// Code metrics for a symbol, computed from its syntax tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolMetrics {
+    /// Non-blank lines in the symbol's source
+    pub lines_of_code: usize,
+    /// McCabe cyclomatic complexity: 1 + the number of branch points
+    pub cyclomatic_complexity: usize,
+    /// Deepest nesting of control-flow blocks (0 for straight-line code)
+    pub max_nesting_depth: usize,
+    /// Number of declared parameters, excluding `self`
+    pub parameter_count: usize,
+}
+
+impl SymbolMetrics {
+    /// Returns true if the complexity or nesting depth exceeds the thresholds.
+    pub fn is_complex(&self) -> bool {
+        self.cyclomatic_complexity > COMPLEXITY_THRESHOLD
+            || self.max_nesting_depth > NESTING_THRESHOLD
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_attribute_parse() {
+        let derive = Attribute::parse("#[derive(Debug, Clone, PartialEq)]");
+        assert_eq!(derive.name, "derive");
+        assert_eq!(derive.derives(), vec!["Debug", "Clone", "PartialEq"]);
+
+        let cfg = Attribute::parse("#[cfg(feature = \"serde\")]");
+        assert_eq!(cfg.name, "cfg");
+        assert_eq!(cfg.cfg_condition(), Some("feature = \"serde\""));
+        assert!(cfg.derives().is_empty());
+
+        let cfg_attr = Attribute::parse("#[cfg_attr(test, derive(Default))]");
+        assert_eq!(cfg_attr.cfg_condition(), Some("test"));
+
+        let route = Attribute::parse("#[get(\"/users/{id}\")]");
+        assert_eq!(route.name, "get");
+        assert_eq!(route.arguments.as_deref(), Some("\"/users/{id}\""));
+
+        let doc = Attribute::parse("#[doc = \"Hidden\"]");
+        assert_eq!(doc.name, "doc");
+        assert_eq!(doc.arguments.as_deref(), Some("\"Hidden\""));
+
+        let async_trait = Attribute::parse("#[async_trait::async_trait]");
+        assert_eq!(async_trait.name, "async_trait::async_trait");
+        assert_eq!(async_trait.arguments, None);
+    }
+
+    #[test]
+    fn test_attribute_deprecated() {
+        assert!(Attribute::parse("#[deprecated]").is_deprecated());
+        assert!(Attribute::parse("#[deprecated(since = \"1.2\")]").is_deprecated());
+        assert!(Attribute::parse("@typing_extensions.deprecated(\"use g\")").is_deprecated());
+        assert!(!Attribute::parse("#[inline]").is_deprecated());
+
+        let decorator = Attribute::parse("@app.route(\"/\", methods=[\"GET\"])");
+        assert_eq!(decorator.name, "app.route");
+        assert_eq!(
+            decorator.arguments.as_deref(),
+            Some("\"/\", methods=[\"GET\"]")
+        );
+        assert_eq!(decorator.text, "@app.route(\"/\", methods=[\"GET\"])");
+    }
+
     #[test]
     fn test_byte_range_len() {
         let range = ByteRange::new(10, 20);
@@ -300,6 +571,27 @@ mod tests {
         assert_eq!(SymbolKind::Struct.to_string(), "struct");
     }
 
+    #[test]
+    fn test_symbol_metrics_is_complex() {
+        let simple = SymbolMetrics {
+            lines_of_code: 20,
+            cyclomatic_complexity: 3,
+            max_nesting_depth: 2,
+            parameter_count: 1,
+        };
+        assert!(!simple.is_complex());
+        assert!(SymbolMetrics {
+            cyclomatic_complexity: 11,
+            ..simple
+        }
+        .is_complex());
+        assert!(SymbolMetrics {
+            max_nesting_depth: 5,
+            ..simple
+        }
+        .is_complex());
+    }
+
     #[test]
     fn test_language_display() {
         assert_eq!(Language::Rust.to_string(), "Rust");
@@ -12,6 +12,9 @@ pub struct Cluster {
     pub cluster_id: String,
     pub label: String,
     pub member_count: usize,
+    /// Packages the cluster's symbols depend on (e.g. `reqwest`, `std`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
 }
 
 impl Clusters {
@@ -70,8 +73,15 @@ impl Cluster {
             cluster_id,
             label,
             member_count,
+            dependencies: Vec::new(),
         }
     }
+
+    /// Set the packages the cluster depends on
+    pub fn with_dependencies(mut self, dependencies: Vec<String>) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -138,5 +148,22 @@ mod tests {
         assert!(json.contains("\"cluster_id\": \"cluster_auth\""));
         assert!(json.contains("\"label\": \"authentication\""));
         assert!(json.contains("\"member_count\": 12"));
+        assert!(!json.contains("dependencies"));
+    }
+
+    #[test]
+    fn test_cluster_dependencies() {
+        let cluster = Cluster::new("cluster_http".to_string(), "http".to_string(), 4)
+            .with_dependencies(vec!["reqwest".to_string(), "std".to_string()]);
+        let clusters = Clusters::new(vec![cluster.clone()]);
+
+        let parsed = Clusters::from_json(&clusters.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.clusters[0], cluster);
+
+        let legacy = Clusters::from_json(
+            r#"{"clusters": [{"cluster_id": "c", "label": "l", "member_count": 1}]}"#,
+        )
+        .unwrap();
+        assert!(legacy.clusters[0].dependencies.is_empty());
     }
 }
@@ -142,6 +142,26 @@ impl Edge {
     pub fn contains(from: String, to: String) -> Self {
         Self::new(from, to, "contains".to_string())
     }
+
+    /// Create an "inherits" edge
+    pub fn inherits(from: String, to: String) -> Self {
+        Self::new(from, to, "inherits".to_string())
+    }
+
+    /// Create an "implements" edge
+    pub fn implements(from: String, to: String) -> Self {
+        Self::new(from, to, "implements".to_string())
+    }
+
+    /// Create a "references" edge
+    pub fn references(from: String, to: String) -> Self {
+        Self::new(from, to, "references".to_string())
+    }
+
+    /// Create a "mentions" edge from a document to a symbol
+    pub fn mentions(from: String, to: String) -> Self {
+        Self::new(from, to, "mentions".to_string())
+    }
 }
 
 #[cfg(test)]
@@ -171,6 +191,18 @@ mod tests {
 
         let contains = Edge::contains("sym_a".to_string(), "sym_b".to_string());
         assert_eq!(contains.kind, "contains");
+
+        let inherits = Edge::inherits("sym_a".to_string(), "sym_b".to_string());
+        assert_eq!(inherits.kind, "inherits");
+
+        let implements = Edge::implements("sym_a".to_string(), "sym_b".to_string());
+        assert_eq!(implements.kind, "implements");
+
+        let references = Edge::references("sym_a".to_string(), "sym_b".to_string());
+        assert_eq!(references.kind, "references");
+
+        let mentions = Edge::mentions("doc_readme".to_string(), "sym_b".to_string());
+        assert_eq!(mentions.kind, "mentions");
     }
 
     #[test]
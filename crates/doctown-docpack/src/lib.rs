@@ -109,8 +109,11 @@ mod writer;
 pub use clusters::{Cluster, Clusters};
 pub use embeddings::{EmbeddingsError, EmbeddingsHeader, EmbeddingsReader, EmbeddingsWriter};
 pub use graph::{Edge, Graph, GraphMetrics};
-pub use manifest::{Checksum, Generator, Manifest, OptionalFeatures, Source, Statistics};
-pub use nodes::{Documentation, Nodes, Symbol};
+pub use manifest::{
+    Checksum, Generator, Manifest, MetricsAggregate, MetricsStatistics, OptionalFeatures, Source,
+    Statistics, DEFAULT_EMBEDDING_DIMENSIONS,
+};
+pub use nodes::{Document, Documentation, External, Nodes, Symbol, SymbolMetrics};
 pub use reader::{DocpackReader, ReadError};
 pub use source_map::{SourceMap, SourceMapChunk, SourceMapFile};
 pub use symbol_contexts::{SymbolContext, SymbolContexts};
@@ -1,4 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::nodes::{Symbol, SymbolMetrics};
+
+/// Embedding dimensions recorded when the model is not known (all-MiniLM-L6-v2).
+pub const DEFAULT_EMBEDDING_DIMENSIONS: usize = 384;
 
 /// Root metadata for a docpack
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -33,6 +39,28 @@ pub struct Statistics {
     pub symbol_count: usize,
     pub cluster_count: usize,
     pub embedding_dimensions: usize,
+    /// Model that made the embeddings, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<MetricsStatistics>,
+}
+
+/// Code metrics aggregated per file and per cluster
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricsStatistics {
+    pub by_file: BTreeMap<String, MetricsAggregate>,
+    pub by_cluster: BTreeMap<String, MetricsAggregate>,
+}
+
+/// Aggregate of the metrics of a group of symbols
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MetricsAggregate {
+    pub symbol_count: usize,
+    pub lines_of_code: usize,
+    pub avg_cyclomatic_complexity: f64,
+    pub max_cyclomatic_complexity: usize,
+    pub max_nesting_depth: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -76,7 +104,9 @@ impl Manifest {
                 file_count,
                 symbol_count,
                 cluster_count,
-                embedding_dimensions: 384,
+                embedding_dimensions: DEFAULT_EMBEDDING_DIMENSIONS,
+                embedding_model: None,
+                metrics: None,
             },
             checksum: Checksum {
                 algorithm: "sha256".to_string(),
@@ -116,7 +146,9 @@ impl Manifest {
                 file_count,
                 symbol_count,
                 cluster_count,
-                embedding_dimensions: 384,
+                embedding_dimensions: DEFAULT_EMBEDDING_DIMENSIONS,
+                embedding_model: None,
+                metrics: None,
             },
             checksum: Checksum {
                 algorithm: "sha256".to_string(),
@@ -129,6 +161,19 @@ impl Manifest {
         }
     }
 
+    /// Set the model and dimensions of the embeddings
+    pub fn with_embedding_model(mut self, model: Option<String>, dimensions: usize) -> Self {
+        self.statistics.embedding_model = model;
+        self.statistics.embedding_dimensions = dimensions;
+        self
+    }
+
+    /// Set the aggregated code metrics
+    pub fn with_metrics(mut self, metrics: Option<MetricsStatistics>) -> Self {
+        self.statistics.metrics = metrics;
+        self
+    }
+
     /// Serialize to JSON string
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
@@ -150,6 +195,89 @@ impl Manifest {
     }
 }
 
+impl MetricsStatistics {
+    /// Aggregate symbol metrics per file and per cluster.
+    ///
+    /// Returns `None` if no symbol has metrics.
+    pub fn from_symbols(symbols: &[Symbol]) -> Option<Self> {
+        let mut ranges: BTreeMap<&str, Vec<(usize, usize)>> = BTreeMap::new();
+        for symbol in symbols {
+            ranges
+                .entry(symbol.file_path.as_str())
+                .or_default()
+                .push(symbol.byte_range);
+        }
+
+        let mut by_file: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        let mut by_cluster: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        for symbol in symbols {
+            if let Some(metrics) = symbol.metrics {
+                let (start, end) = symbol.byte_range;
+                let is_leaf = !ranges[symbol.file_path.as_str()]
+                    .iter()
+                    .any(|&range| range != symbol.byte_range && start <= range.0 && range.1 <= end);
+                by_file
+                    .entry(symbol.file_path.clone())
+                    .or_default()
+                    .push((metrics, is_leaf));
+                by_cluster
+                    .entry(symbol.cluster_id.clone())
+                    .or_default()
+                    .push((metrics, is_leaf));
+            }
+        }
+        if by_file.is_empty() {
+            return None;
+        }
+
+        let aggregate = |groups: BTreeMap<String, Vec<_>>| {
+            groups
+                .into_iter()
+                .map(|(key, metrics)| (key, MetricsAggregate::from_metrics(&metrics)))
+                .collect()
+        };
+        Some(Self {
+            by_file: aggregate(by_file),
+            by_cluster: aggregate(by_cluster),
+        })
+    }
+}
+
+impl MetricsAggregate {
+    /// Aggregate a group of symbol metrics, each paired with whether its
+    /// symbol is a leaf, enclosing no other symbol.
+    ///
+    /// Lines of code are summed over leaves only, since an impl or class
+    /// already counts the lines of its methods.
+    pub fn from_metrics(metrics: &[(SymbolMetrics, bool)]) -> Self {
+        if metrics.is_empty() {
+            return Self::default();
+        }
+        let lines_of_code = metrics
+            .iter()
+            .filter(|(_, is_leaf)| *is_leaf)
+            .map(|(m, _)| m.lines_of_code)
+            .sum();
+        let metrics: Vec<_> = metrics.iter().map(|(m, _)| m).collect();
+        let total_complexity: usize = metrics.iter().map(|m| m.cyclomatic_complexity).sum();
+        Self {
+            symbol_count: metrics.len(),
+            lines_of_code,
+            avg_cyclomatic_complexity: total_complexity as f64 / metrics.len() as f64,
+            max_cyclomatic_complexity: metrics
+                .iter()
+                .map(|m| m.cyclomatic_complexity)
+                .max()
+                .unwrap_or(0),
+            max_nesting_depth: metrics
+                .iter()
+                .map(|m| m.max_nesting_depth)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,5 +344,112 @@ mod tests {
         assert!(json.contains("\"symbol_count\": 128"));
         assert!(json.contains("\"cluster_count\": 12"));
         assert!(json.contains("\"embedding_dimensions\": 384"));
+        assert!(!json.contains("\"metrics\""));
+        assert!(!json.contains("\"embedding_model\""));
+    }
+
+    #[test]
+    fn test_manifest_embedding_model() {
+        let manifest = Manifest::new(
+            "https://github.com/test/repo".to_string(),
+            "main".to_string(),
+            None,
+            1,
+            1,
+            1,
+        )
+        .with_embedding_model(Some("text-embedding-3-small".to_string()), 1536);
+
+        let json = manifest.to_json().unwrap();
+        assert!(json.contains("\"embedding_dimensions\": 1536"));
+        assert!(json.contains("\"embedding_model\": \"text-embedding-3-small\""));
+        assert_eq!(Manifest::from_json(&json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_metrics_statistics() {
+        let symbol = |id: &str, file: &str, cluster: &str, complexity: usize, depth: usize| {
+            Symbol::new(
+                id.to_string(),
+                id.to_string(),
+                "function".to_string(),
+                "rust".to_string(),
+                file.to_string(),
+                (0, 10),
+                cluster.to_string(),
+                String::new(),
+            )
+            .with_metrics(SymbolMetrics {
+                lines_of_code: 10,
+                cyclomatic_complexity: complexity,
+                max_nesting_depth: depth,
+                parameter_count: 1,
+            })
+        };
+        let symbols = vec![
+            symbol("a", "src/a.rs", "cluster_0", 2, 1),
+            symbol("b", "src/a.rs", "cluster_1", 6, 3),
+            symbol("c", "src/b.rs", "cluster_1", 1, 0),
+        ];
+
+        let stats = MetricsStatistics::from_symbols(&symbols).unwrap();
+
+        let file_a = &stats.by_file["src/a.rs"];
+        assert_eq!(file_a.symbol_count, 2);
+        assert_eq!(file_a.lines_of_code, 20);
+        assert_eq!(file_a.avg_cyclomatic_complexity, 4.0);
+        assert_eq!(file_a.max_cyclomatic_complexity, 6);
+        assert_eq!(file_a.max_nesting_depth, 3);
+
+        let cluster_1 = &stats.by_cluster["cluster_1"];
+        assert_eq!(cluster_1.symbol_count, 2);
+        assert_eq!(cluster_1.avg_cyclomatic_complexity, 3.5);
+
+        let without_metrics = Symbol::new(
+            "d".to_string(),
+            "d".to_string(),
+            "function".to_string(),
+            "rust".to_string(),
+            "src/d.rs".to_string(),
+            (0, 10),
+            "cluster_0".to_string(),
+            String::new(),
+        );
+        assert!(MetricsStatistics::from_symbols(&[without_metrics]).is_none());
+    }
+
+    #[test]
+    fn test_metrics_statistics_counts_nested_lines_once() {
+        let symbol = |id: &str, kind: &str, byte_range, lines_of_code| {
+            Symbol::new(
+                id.to_string(),
+                id.to_string(),
+                kind.to_string(),
+                "rust".to_string(),
+                "src/a.rs".to_string(),
+                byte_range,
+                "cluster_0".to_string(),
+                String::new(),
+            )
+            .with_metrics(SymbolMetrics {
+                lines_of_code,
+                cyclomatic_complexity: 1,
+                max_nesting_depth: 0,
+                parameter_count: 0,
+            })
+        };
+        let symbols = vec![
+            symbol("Parser", "impl", (0, 200), 12),
+            symbol("new", "method", (20, 80), 4),
+            symbol("parse", "method", (90, 190), 6),
+            symbol("helper", "function", (210, 260), 3),
+        ];
+
+        let stats = MetricsStatistics::from_symbols(&symbols).unwrap();
+
+        let file = &stats.by_file["src/a.rs"];
+        assert_eq!(file.symbol_count, 4);
+        assert_eq!(file.lines_of_code, 13);
+        assert_eq!(stats.by_cluster["cluster_0"].lines_of_code, 13);
     }
 }
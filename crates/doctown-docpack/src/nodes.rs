@@ -4,11 +4,18 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Nodes {
     pub symbols: Vec<Symbol>,
+    /// Documentation sections (README, docs/, ADRs) linked to the symbols they mention
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<Document>,
+    /// Standard library and third-party symbols the repository uses
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub externals: Vec<External>,
 }
 
 /// A symbol with its metadata and documentation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Symbol {
+    /// Canonical symbol ID, stable across runs and docpack versions
     pub id: String,
     pub name: String,
     pub kind: String,
@@ -26,9 +33,34 @@ pub struct Symbol {
     pub cluster_id: String,
     pub centrality: f64,
 
+    /// Whether the symbol is test code
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_test: bool,
+
+    /// Size and complexity metrics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<SymbolMetrics>,
+
+    /// Attributes and decorators as written in source (e.g. `#[derive(Debug)]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+
     pub documentation: Documentation,
 }
 
+/// Code metrics for a symbol
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SymbolMetrics {
+    pub lines_of_code: usize,
+    pub cyclomatic_complexity: usize,
+    pub max_nesting_depth: usize,
+    pub parameter_count: usize,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
 /// Documentation for a symbol
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Documentation {
@@ -37,19 +69,70 @@ pub struct Documentation {
     pub details: Option<String>,
 }
 
+/// A section of human-written documentation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Document {
+    pub id: String,
+    pub file_path: String,
+    pub byte_range: (usize, usize),
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub heading_path: Vec<String>,
+    pub cluster_id: String,
+    /// IDs of the symbols this section mentions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mentions: Vec<String>,
+}
+
+/// A symbol outside the repository (standard library or third-party package)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct External {
+    pub id: String,
+    /// Qualified name as written at the use site (e.g. `reqwest::Client`)
+    pub name: String,
+    /// Always `external`
+    pub kind: String,
+    /// Package providing the symbol (e.g. `reqwest`, `std`)
+    pub package: String,
+    /// `stdlib` or `third_party`
+    pub origin: String,
+    /// IDs of the repository symbols using it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub used_by: Vec<String>,
+}
+
 impl Nodes {
     /// Create a new Nodes container
     pub fn new(symbols: Vec<Symbol>) -> Self {
-        Self { symbols }
+        Self {
+            symbols,
+            documents: Vec::new(),
+            externals: Vec::new(),
+        }
     }
 
     /// Create an empty Nodes container
     pub fn empty() -> Self {
         Self {
             symbols: Vec::new(),
+            documents: Vec::new(),
+            externals: Vec::new(),
         }
     }
 
+    /// Set the documentation nodes
+    pub fn with_documents(mut self, documents: Vec<Document>) -> Self {
+        self.documents = documents;
+        self
+    }
+
+    /// Set the external symbol nodes
+    pub fn with_externals(mut self, externals: Vec<External>) -> Self {
+        self.externals = externals;
+        self
+    }
+
     /// Add a symbol to the collection
     pub fn add_symbol(&mut self, symbol: Symbol) {
         self.symbols.push(symbol);
@@ -112,6 +195,9 @@ impl Symbol {
             imports: Vec::new(),
             cluster_id,
             centrality: 0.0,
+            is_test: false,
+            metrics: None,
+            attributes: Vec::new(),
             documentation: Documentation {
                 summary: documentation_summary,
                 details: None,
@@ -149,6 +235,24 @@ impl Symbol {
         self
     }
 
+    /// Mark the symbol as test code
+    pub fn with_test(mut self, is_test: bool) -> Self {
+        self.is_test = is_test;
+        self
+    }
+
+    /// Set the code metrics
+    pub fn with_metrics(mut self, metrics: SymbolMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Set the attributes and decorators
+    pub fn with_attributes(mut self, attributes: Vec<String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
     /// Set the documentation details
     pub fn with_documentation_details(mut self, details: String) -> Self {
         self.documentation.details = Some(details);
@@ -156,6 +260,59 @@ impl Symbol {
     }
 }
 
+impl Document {
+    /// Create a new document node
+    pub fn new(
+        id: String,
+        file_path: String,
+        byte_range: (usize, usize),
+        cluster_id: String,
+    ) -> Self {
+        Self {
+            id,
+            file_path,
+            byte_range,
+            heading: None,
+            heading_path: Vec::new(),
+            cluster_id,
+            mentions: Vec::new(),
+        }
+    }
+
+    /// Set the section heading and its breadcrumb
+    pub fn with_heading(mut self, heading: String, heading_path: Vec<String>) -> Self {
+        self.heading = Some(heading);
+        self.heading_path = heading_path;
+        self
+    }
+
+    /// Set the mentioned symbol IDs
+    pub fn with_mentions(mut self, mentions: Vec<String>) -> Self {
+        self.mentions = mentions;
+        self
+    }
+}
+
+impl External {
+    /// Create a new external symbol node
+    pub fn new(id: String, name: String, package: String, origin: String) -> Self {
+        Self {
+            id,
+            name,
+            kind: "external".to_string(),
+            package,
+            origin,
+            used_by: Vec::new(),
+        }
+    }
+
+    /// Set the IDs of the symbols using it
+    pub fn with_used_by(mut self, used_by: Vec<String>) -> Self {
+        self.used_by = used_by;
+        self
+    }
+}
+
 impl Documentation {
     /// Create new documentation with just a summary
     pub fn new(summary: String) -> Self {
@@ -223,6 +380,79 @@ mod tests {
         assert_eq!(symbol.centrality, 0.84);
     }
 
+    #[test]
+    fn test_symbol_test_flag() {
+        let symbol = Symbol::new(
+            "sym_adds".to_string(),
+            "adds".to_string(),
+            "function".to_string(),
+            "rust".to_string(),
+            "src/math.rs".to_string(),
+            (0, 50),
+            "cluster_tests".to_string(),
+            "Tests addition".to_string(),
+        );
+        let json = serde_json::to_value(&symbol).unwrap();
+        assert!(json.get("is_test").is_none());
+
+        let symbol = symbol.with_test(true);
+        let json = serde_json::to_value(&symbol).unwrap();
+        assert_eq!(json["is_test"], true);
+    }
+
+    #[test]
+    fn test_symbol_metrics() {
+        let symbol = Symbol::new(
+            "sym_parse".to_string(),
+            "parse".to_string(),
+            "function".to_string(),
+            "rust".to_string(),
+            "src/parser.rs".to_string(),
+            (0, 400),
+            "cluster_0".to_string(),
+            "Parses input".to_string(),
+        );
+        let json = serde_json::to_value(&symbol).unwrap();
+        assert!(json.get("metrics").is_none());
+
+        let symbol = symbol.with_metrics(SymbolMetrics {
+            lines_of_code: 20,
+            cyclomatic_complexity: 5,
+            max_nesting_depth: 2,
+            parameter_count: 1,
+        });
+        let json = serde_json::to_value(&symbol).unwrap();
+        assert_eq!(json["metrics"]["cyclomatic_complexity"], 5);
+        assert_eq!(json["metrics"]["max_nesting_depth"], 2);
+
+        let restored: Symbol = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.metrics, symbol.metrics);
+    }
+
+    #[test]
+    fn test_symbol_attributes() {
+        let symbol = Symbol::new(
+            "sym_config".to_string(),
+            "Config".to_string(),
+            "struct".to_string(),
+            "rust".to_string(),
+            "src/config.rs".to_string(),
+            (0, 120),
+            "cluster_0".to_string(),
+            "Runtime configuration".to_string(),
+        );
+        let json = serde_json::to_value(&symbol).unwrap();
+        assert!(json.get("attributes").is_none());
+
+        let symbol = symbol.with_attributes(vec![
+            "#[derive(Debug, Clone)]".to_string(),
+            "#[deprecated]".to_string(),
+        ]);
+        let json = serde_json::to_value(&symbol).unwrap();
+        assert_eq!(json["attributes"][0], "#[derive(Debug, Clone)]");
+        assert_eq!(json["attributes"][1], "#[deprecated]");
+    }
+
     #[test]
     fn test_nodes_creation() {
         let symbol1 = Symbol::new(
@@ -309,4 +539,48 @@ mod tests {
         assert!(json.contains("\"centrality\": 0.84"));
         assert!(json.contains("\"summary\": \"This function initializes the application...\""));
     }
+
+    #[test]
+    fn test_nodes_documents() {
+        let document = Document::new(
+            "doc_readme_usage".to_string(),
+            "README.md".to_string(),
+            (120, 480),
+            "cluster_auth".to_string(),
+        )
+        .with_heading(
+            "Usage".to_string(),
+            vec!["Demo".to_string(), "Usage".to_string()],
+        )
+        .with_mentions(vec!["sym_main_fn".to_string()]);
+
+        let nodes = Nodes::empty().with_documents(vec![document.clone()]);
+        let parsed = Nodes::from_json(&nodes.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.documents, vec![document]);
+
+        // Docpacks without documentation keep the original format
+        let json = serde_json::to_value(Nodes::empty()).unwrap();
+        assert!(json.get("documents").is_none());
+        let legacy = Nodes::from_json(r#"{"symbols": []}"#).unwrap();
+        assert!(legacy.documents.is_empty());
+    }
+
+    #[test]
+    fn test_nodes_externals() {
+        let external = External::new(
+            "ext_reqwest::Client".to_string(),
+            "reqwest::Client".to_string(),
+            "reqwest".to_string(),
+            "third_party".to_string(),
+        )
+        .with_used_by(vec!["sym_fetch".to_string()]);
+        assert_eq!(external.kind, "external");
+
+        let nodes = Nodes::empty().with_externals(vec![external.clone()]);
+        let parsed = Nodes::from_json(&nodes.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.externals, vec![external]);
+
+        let json = serde_json::to_value(Nodes::empty()).unwrap();
+        assert!(json.get("externals").is_none());
+    }
 }
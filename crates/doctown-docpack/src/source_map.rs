@@ -20,6 +20,10 @@ pub struct SourceMapChunk {
     pub chunk_id: String,
     pub byte_range: (usize, usize),
     pub symbol_ids: Vec<String>,
+    /// The chunk one level up in a hierarchical chunking (a file summary
+    /// chunk, or the chunk of the enclosing class)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_chunk_id: Option<String>,
 }
 
 impl SourceMap {
@@ -97,8 +101,15 @@ impl SourceMapChunk {
             chunk_id,
             byte_range,
             symbol_ids,
+            parent_chunk_id: None,
         }
     }
+
+    /// Set the parent chunk in a hierarchical chunking
+    pub fn with_parent(mut self, parent_chunk_id: impl Into<String>) -> Self {
+        self.parent_chunk_id = Some(parent_chunk_id.into());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -197,5 +208,34 @@ mod tests {
         assert!(json.contains("\"language\": \"rust\""));
         assert!(json.contains("\"chunk_id\": \"chunk_abc\""));
         assert!(json.contains("\"symbol_ids\""));
+        assert!(!json.contains("parent_chunk_id"));
+    }
+
+    #[test]
+    fn test_chunk_parent() {
+        let summary = SourceMapChunk::new("chunk_file".to_string(), (0, 500), vec![]);
+        let method = SourceMapChunk::new(
+            "chunk_method".to_string(),
+            (120, 200),
+            vec!["sym_connect".to_string()],
+        )
+        .with_parent("chunk_file");
+
+        let file = SourceMapFile::new(
+            "src/client.rs".to_string(),
+            "rust".to_string(),
+            vec![summary, method.clone()],
+        );
+        let source_map = SourceMap::new(vec![file]);
+        let parsed = SourceMap::from_json(&source_map.to_json().unwrap()).unwrap();
+
+        assert_eq!(parsed.files[0].chunks[0].parent_chunk_id, None);
+        assert_eq!(parsed.files[0].chunks[1], method);
+
+        let legacy = SourceMap::from_json(
+            r#"{"files": [{"file_path": "a.rs", "language": "rust", "chunks": [{"chunk_id": "c", "byte_range": [0, 1], "symbol_ids": []}]}]}"#,
+        )
+        .unwrap();
+        assert!(legacy.files[0].chunks[0].parent_chunk_id.is_none());
     }
 }
@@ -50,6 +50,15 @@ pub struct EdgeTypeBreakdown {
     pub imports: usize,
     /// Number of "related" (similarity) edges.
     pub related: usize,
+    /// Number of "inherits" edges.
+    #[serde(default)]
+    pub inherits: usize,
+    /// Number of "implements" edges.
+    #[serde(default)]
+    pub implements: usize,
+    /// Number of "references" (type usage) edges.
+    #[serde(default)]
+    pub references: usize,
 }
 
 /// Payload for `assembly.completed.v1` event.
@@ -103,6 +112,9 @@ mod tests {
                 calls: 80,
                 imports: 30,
                 related: 10,
+                inherits: 0,
+                implements: 0,
+                references: 0,
             },
         };
         let json = serde_json::to_string(&payload).unwrap();
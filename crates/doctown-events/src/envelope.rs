@@ -29,10 +29,22 @@ pub enum EventType {
     #[serde(rename = "ingest.file_skipped.v1")]
     IngestFileSkipped,
 
+    /// Emitted when a file parses with syntax errors.
+    #[serde(rename = "ingest.file_parse_warning.v1")]
+    IngestFileParseWarning,
+
     /// Emitted when a chunk is created.
     #[serde(rename = "ingest.chunk_created.v1")]
     IngestChunkCreated,
 
+    /// Emitted when a documentation (Markdown) chunk is created.
+    #[serde(rename = "ingest.document_chunk_created.v1")]
+    IngestDocumentChunkCreated,
+
+    /// Emitted when a batch of chunks has been embedded.
+    #[serde(rename = "ingest.chunks_embedded.v1")]
+    IngestChunksEmbedded,
+
     /// Emitted when ingest pipeline completes.
     #[serde(rename = "ingest.completed.v1")]
     IngestCompleted,
@@ -62,7 +74,10 @@ impl EventType {
             Self::IngestStarted => "ingest.started.v1",
             Self::IngestFileDetected => "ingest.file_detected.v1",
             Self::IngestFileSkipped => "ingest.file_skipped.v1",
+            Self::IngestFileParseWarning => "ingest.file_parse_warning.v1",
             Self::IngestChunkCreated => "ingest.chunk_created.v1",
+            Self::IngestDocumentChunkCreated => "ingest.document_chunk_created.v1",
+            Self::IngestChunksEmbedded => "ingest.chunks_embedded.v1",
             Self::IngestCompleted => "ingest.completed.v1",
             Self::AssemblyStarted => "assembly.started.v1",
             Self::AssemblyClusterCreated => "assembly.cluster_created.v1",
@@ -82,7 +97,10 @@ impl EventType {
             "ingest.started.v1" => Some(Self::IngestStarted),
             "ingest.file_detected.v1" => Some(Self::IngestFileDetected),
             "ingest.file_skipped.v1" => Some(Self::IngestFileSkipped),
+            "ingest.file_parse_warning.v1" => Some(Self::IngestFileParseWarning),
             "ingest.chunk_created.v1" => Some(Self::IngestChunkCreated),
+            "ingest.document_chunk_created.v1" => Some(Self::IngestDocumentChunkCreated),
+            "ingest.chunks_embedded.v1" => Some(Self::IngestChunksEmbedded),
             "ingest.completed.v1" => Some(Self::IngestCompleted),
             "assembly.started.v1" => Some(Self::AssemblyStarted),
             "assembly.cluster_created.v1" => Some(Self::AssemblyClusterCreated),
@@ -411,10 +429,22 @@ mod tests {
             EventType::IngestFileSkipped.as_str(),
             "ingest.file_skipped.v1"
         );
+        assert_eq!(
+            EventType::IngestFileParseWarning.as_str(),
+            "ingest.file_parse_warning.v1"
+        );
         assert_eq!(
             EventType::IngestChunkCreated.as_str(),
             "ingest.chunk_created.v1"
         );
+        assert_eq!(
+            EventType::IngestDocumentChunkCreated.as_str(),
+            "ingest.document_chunk_created.v1"
+        );
+        assert_eq!(
+            EventType::IngestChunksEmbedded.as_str(),
+            "ingest.chunks_embedded.v1"
+        );
         assert_eq!(EventType::IngestCompleted.as_str(), "ingest.completed.v1");
     }
 
@@ -424,6 +454,18 @@ mod tests {
             EventType::try_from_str("ingest.started.v1"),
             Some(EventType::IngestStarted)
         );
+        assert_eq!(
+            EventType::try_from_str("ingest.file_parse_warning.v1"),
+            Some(EventType::IngestFileParseWarning)
+        );
+        assert_eq!(
+            EventType::try_from_str("ingest.document_chunk_created.v1"),
+            Some(EventType::IngestDocumentChunkCreated)
+        );
+        assert_eq!(
+            EventType::try_from_str("ingest.chunks_embedded.v1"),
+            Some(EventType::IngestChunksEmbedded)
+        );
         assert_eq!(
             EventType::try_from_str("ingest.completed.v1"),
             Some(EventType::IngestCompleted)
@@ -436,7 +478,10 @@ mod tests {
         assert!(!EventType::IngestStarted.is_terminal());
         assert!(!EventType::IngestFileDetected.is_terminal());
         assert!(!EventType::IngestFileSkipped.is_terminal());
+        assert!(!EventType::IngestFileParseWarning.is_terminal());
         assert!(!EventType::IngestChunkCreated.is_terminal());
+        assert!(!EventType::IngestDocumentChunkCreated.is_terminal());
+        assert!(!EventType::IngestChunksEmbedded.is_terminal());
         assert!(EventType::IngestCompleted.is_terminal());
     }
 
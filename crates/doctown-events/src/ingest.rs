@@ -1,6 +1,8 @@
 //! Ingest event types for Milestone 1.
 
-use doctown_common::{ByteRange, ChunkId, Language, SymbolKind};
+use doctown_common::{
+    Attribute, ByteRange, ChunkId, ChunkLevel, Language, SymbolId, SymbolKind, SymbolMetrics,
+};
 use serde::{Deserialize, Serialize};
 
 /// Payload for `ingest.started.v1` event.
@@ -94,6 +96,80 @@ pub enum SkipReason {
     ParseError,
 }
 
+/// Payload for `ingest.file_parse_warning.v1` event.
+///
+/// Tree-sitter recovers from syntax errors, so a file with errors still produces
+/// symbols and chunks; this event reports how much of the file was affected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFileParseWarningPayload {
+    /// Path to the file relative to repo root.
+    pub file_path: String,
+
+    /// The language of the file.
+    pub language: Language,
+
+    /// Number of ERROR nodes in the syntax tree.
+    pub error_nodes: usize,
+
+    /// Number of MISSING nodes (tokens the parser inserted to recover).
+    pub missing_nodes: usize,
+
+    /// Fraction of the file's bytes covered by ERROR nodes (0.0 - 1.0).
+    pub error_ratio: f64,
+
+    /// Lines containing errors.
+    pub error_lines: Vec<LineRange>,
+
+    /// Whether the file was chunked as a whole instead of per symbol.
+    pub fell_back_to_file_chunk: bool,
+}
+
+impl IngestFileParseWarningPayload {
+    pub fn new(
+        file_path: impl Into<String>,
+        language: Language,
+        error_nodes: usize,
+        missing_nodes: usize,
+        error_ratio: f64,
+    ) -> Self {
+        Self {
+            file_path: file_path.into(),
+            language,
+            error_nodes,
+            missing_nodes,
+            error_ratio,
+            error_lines: Vec::new(),
+            fell_back_to_file_chunk: false,
+        }
+    }
+
+    pub fn with_error_lines(mut self, error_lines: Vec<LineRange>) -> Self {
+        self.error_lines = error_lines;
+        self
+    }
+
+    pub fn with_file_chunk_fallback(mut self, fell_back: bool) -> Self {
+        self.fell_back_to_file_chunk = fell_back;
+        self
+    }
+}
+
+/// An inclusive, 1-based range of source lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl LineRange {
+    pub fn new(start_line: usize, end_line: usize) -> Self {
+        Self {
+            start_line,
+            end_line,
+        }
+    }
+}
+
 /// Payload for `ingest.chunk_created.v1` event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IngestChunkCreatedPayload {
@@ -117,8 +193,41 @@ pub struct IngestChunkCreatedPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol_name: Option<String>,
 
+    /// The canonical ID of the symbol (if applicable), see [`SymbolId::derive`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<SymbolId>,
+
     /// The content of the chunk.
     pub content: String,
+
+    /// Hash of the chunk's embedding input (`sha256:<hex>`), shared by
+    /// chunks deduplicated into one embedding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+
+    /// Whether the chunk is test code.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_test: bool,
+
+    /// Code metrics of the symbol (if applicable).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<SymbolMetrics>,
+
+    /// Attributes and decorators attached to the symbol (if applicable).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<Attribute>,
+
+    /// Full path of the symbol in its crate's module tree (Rust only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qualified_name: Option<String>,
+
+    /// Granularity of the chunk, when chunked hierarchically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<ChunkLevel>,
+
+    /// The chunk one level up, when chunked hierarchically (None at file level).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_chunk_id: Option<ChunkId>,
 }
 
 impl IngestChunkCreatedPayload {
@@ -136,7 +245,15 @@ impl IngestChunkCreatedPayload {
             byte_range,
             symbol_kind: None,
             symbol_name: None,
+            symbol_id: None,
             content: content.into(),
+            content_hash: None,
+            is_test: false,
+            metrics: None,
+            attributes: Vec::new(),
+            qualified_name: None,
+            level: None,
+            parent_chunk_id: None,
         }
     }
 
@@ -145,6 +262,167 @@ impl IngestChunkCreatedPayload {
         self.symbol_name = Some(name.into());
         self
     }
+
+    pub fn with_symbol_id(mut self, symbol_id: SymbolId) -> Self {
+        self.symbol_id = Some(symbol_id);
+        self
+    }
+
+    pub fn with_content_hash(mut self, content_hash: impl Into<String>) -> Self {
+        self.content_hash = Some(content_hash.into());
+        self
+    }
+
+    pub fn with_test(mut self, is_test: bool) -> Self {
+        self.is_test = is_test;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: SymbolMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Vec<Attribute>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    pub fn with_qualified_name(mut self, qualified_name: impl Into<String>) -> Self {
+        self.qualified_name = Some(qualified_name.into());
+        self
+    }
+
+    pub fn with_hierarchy(mut self, level: ChunkLevel, parent_chunk_id: Option<ChunkId>) -> Self {
+        self.level = Some(level);
+        self.parent_chunk_id = parent_chunk_id;
+        self
+    }
+}
+
+/// A fenced code block inside a documentation chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeBlock {
+    /// Language from the fence info string (e.g., "rust" in ```` ```rust ````).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Byte range of the block, including the fences, in the source file.
+    pub byte_range: ByteRange,
+}
+
+impl CodeBlock {
+    pub fn new(language: Option<String>, byte_range: ByteRange) -> Self {
+        Self {
+            language,
+            byte_range,
+        }
+    }
+}
+
+/// Payload for `ingest.document_chunk_created.v1` event.
+///
+/// Documentation files (README, docs/, ADRs) are chunked by heading section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestDocumentChunkCreatedPayload {
+    /// Unique identifier for this chunk.
+    pub chunk_id: ChunkId,
+
+    /// Path to the documentation file.
+    pub file_path: String,
+
+    /// Byte range of the section in the file.
+    pub byte_range: ByteRange,
+
+    /// The section heading (None for text before the first heading).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+
+    /// Headings of the enclosing sections, outermost first, ending with this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub heading_path: Vec<String>,
+
+    /// The content of the section.
+    pub content: String,
+
+    /// Fenced code blocks in the section.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_blocks: Vec<CodeBlock>,
+
+    /// Code identifiers mentioned in inline code spans (e.g., "ModuleTree::build").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mentions: Vec<String>,
+}
+
+impl IngestDocumentChunkCreatedPayload {
+    pub fn new(
+        chunk_id: ChunkId,
+        file_path: impl Into<String>,
+        byte_range: ByteRange,
+        content: impl Into<String>,
+    ) -> Self {
+        Self {
+            chunk_id,
+            file_path: file_path.into(),
+            byte_range,
+            heading: None,
+            heading_path: Vec::new(),
+            content: content.into(),
+            code_blocks: Vec::new(),
+            mentions: Vec::new(),
+        }
+    }
+
+    pub fn with_heading(mut self, heading: impl Into<String>, heading_path: Vec<String>) -> Self {
+        self.heading = Some(heading.into());
+        self.heading_path = heading_path;
+        self
+    }
+
+    pub fn with_code_blocks(mut self, code_blocks: Vec<CodeBlock>) -> Self {
+        self.code_blocks = code_blocks;
+        self
+    }
+
+    pub fn with_mentions(mut self, mentions: Vec<String>) -> Self {
+        self.mentions = mentions;
+        self
+    }
+}
+
+/// Payload for `ingest.chunks_embedded.v1` event.
+///
+/// Carries the vectors of one embedded batch, including the copies given to
+/// chunks identical to an embedded one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestChunksEmbeddedPayload {
+    /// Identifier of the batch.
+    pub batch_id: String,
+
+    /// The embedded chunks with their vectors.
+    pub vectors: Vec<ChunkEmbedding>,
+}
+
+impl IngestChunksEmbeddedPayload {
+    pub fn new(batch_id: impl Into<String>, vectors: Vec<ChunkEmbedding>) -> Self {
+        Self {
+            batch_id: batch_id.into(),
+            vectors,
+        }
+    }
+}
+
+/// A chunk's embedding vector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEmbedding {
+    pub chunk_id: ChunkId,
+    pub vector: Vec<f32>,
+}
+
+impl ChunkEmbedding {
+    pub fn new(chunk_id: ChunkId, vector: Vec<f32>) -> Self {
+        Self { chunk_id, vector }
+    }
 }
 
 /// Payload for `ingest.completed.v1` event.
@@ -170,6 +448,55 @@ pub struct IngestCompletedPayload {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub language_breakdown: Vec<LanguageCount>,
 
+    /// Number of files that parsed with syntax errors.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub files_with_parse_errors: usize,
+
+    /// Total ERROR and MISSING nodes across all files.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub parse_error_nodes: usize,
+
+    /// Number of files chunked as a whole because of too many parse errors.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub files_fallback_chunked: usize,
+
+    /// Number of distinct embedding inputs among the chunks.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub unique_chunks: usize,
+
+    /// Number of chunks whose vector was copied from an identical chunk
+    /// instead of being embedded again.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub duplicate_chunks: usize,
+
+    /// Number of calls that matched several equally close symbols.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub ambiguous_calls: usize,
+
+    /// Number of distinct inputs whose vector was found in the embedding cache.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub cache_hits: usize,
+
+    /// Number of distinct inputs looked up in the embedding cache and not found.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub cache_misses: usize,
+
+    /// Model that made the vectors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+
+    /// Dimensions of the vectors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_dimensions: Option<usize>,
+
+    /// Path the ingest output (chunks, vectors and symbols) was written to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+
+    /// Chunks that could not be embedded, after retries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_chunk_ids: Vec<ChunkId>,
+
     /// Error message if failed.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
@@ -189,6 +516,18 @@ impl IngestCompletedPayload {
             chunks_embedded: None,
             duration_ms,
             language_breakdown: Vec::new(),
+            files_with_parse_errors: 0,
+            parse_error_nodes: 0,
+            files_fallback_chunked: 0,
+            unique_chunks: 0,
+            duplicate_chunks: 0,
+            ambiguous_calls: 0,
+            cache_hits: 0,
+            cache_misses: 0,
+            embedding_model: None,
+            embedding_dimensions: None,
+            output_path: None,
+            failed_chunk_ids: Vec::new(),
             error: None,
         }
     }
@@ -206,6 +545,18 @@ impl IngestCompletedPayload {
             chunks_embedded: None,
             duration_ms,
             language_breakdown: Vec::new(),
+            files_with_parse_errors: 0,
+            parse_error_nodes: 0,
+            files_fallback_chunked: 0,
+            unique_chunks: 0,
+            duplicate_chunks: 0,
+            ambiguous_calls: 0,
+            cache_hits: 0,
+            cache_misses: 0,
+            embedding_model: None,
+            embedding_dimensions: None,
+            output_path: None,
+            failed_chunk_ids: Vec::new(),
             error: Some(error.into()),
         }
     }
@@ -214,6 +565,65 @@ impl IngestCompletedPayload {
         self.language_breakdown = breakdown;
         self
     }
+
+    pub fn with_parse_diagnostics(
+        mut self,
+        files_with_parse_errors: usize,
+        parse_error_nodes: usize,
+        files_fallback_chunked: usize,
+    ) -> Self {
+        self.files_with_parse_errors = files_with_parse_errors;
+        self.parse_error_nodes = parse_error_nodes;
+        self.files_fallback_chunked = files_fallback_chunked;
+        self
+    }
+
+    pub fn with_dedup(mut self, unique_chunks: usize, duplicate_chunks: usize) -> Self {
+        self.unique_chunks = unique_chunks;
+        self.duplicate_chunks = duplicate_chunks;
+        self
+    }
+
+    pub fn with_ambiguous_calls(mut self, ambiguous_calls: usize) -> Self {
+        self.ambiguous_calls = ambiguous_calls;
+        self
+    }
+
+    pub fn with_cache(mut self, cache_hits: usize, cache_misses: usize) -> Self {
+        self.cache_hits = cache_hits;
+        self.cache_misses = cache_misses;
+        self
+    }
+
+    /// Fraction of embedding cache lookups that found a vector, if the cache was used.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+        (lookups > 0).then(|| self.cache_hits as f64 / lookups as f64)
+    }
+
+    pub fn with_embedding_model(mut self, model: impl Into<String>, dimensions: usize) -> Self {
+        self.embedding_model = Some(model.into());
+        self.embedding_dimensions = Some(dimensions);
+        self
+    }
+
+    pub fn with_output_path(mut self, output_path: impl Into<String>) -> Self {
+        self.output_path = Some(output_path.into());
+        self
+    }
+
+    pub fn with_failed_chunks(mut self, failed_chunk_ids: Vec<ChunkId>) -> Self {
+        self.failed_chunk_ids = failed_chunk_ids;
+        self
+    }
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
 }
 
 /// Count of files/chunks per language.
@@ -274,6 +684,133 @@ mod tests {
         let json = serde_json::to_value(&payload).unwrap();
         assert_eq!(json["symbol_kind"], "function");
         assert_eq!(json["symbol_name"], "main");
+        assert!(json.get("is_test").is_none());
+    }
+
+    #[test]
+    fn test_chunk_created_with_hierarchy() {
+        let parent = ChunkId::generate();
+        let payload = IngestChunkCreatedPayload::new(
+            ChunkId::generate(),
+            "src/lib.rs",
+            Language::Rust,
+            ByteRange::new(20, 60),
+            "fn parse(&self) {}",
+        )
+        .with_hierarchy(ChunkLevel::SubSymbol, Some(parent.clone()));
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["level"], "sub_symbol");
+        assert_eq!(json["parent_chunk_id"], parent.as_str());
+
+        let flat = IngestChunkCreatedPayload::new(
+            ChunkId::generate(),
+            "src/lib.rs",
+            Language::Rust,
+            ByteRange::new(0, 12),
+            "fn main() {}",
+        );
+        let json = serde_json::to_value(&flat).unwrap();
+        assert!(json.get("level").is_none());
+        assert!(json.get("parent_chunk_id").is_none());
+    }
+
+    #[test]
+    fn test_chunk_created_test_code() {
+        let payload = IngestChunkCreatedPayload::new(
+            ChunkId::generate(),
+            "tests/api.rs",
+            Language::Rust,
+            ByteRange::new(0, 20),
+            "fn it_works() {}",
+        )
+        .with_test(true);
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["is_test"], true);
+        assert!(json.get("metrics").is_none());
+    }
+
+    #[test]
+    fn test_chunk_created_with_metrics() {
+        let payload = IngestChunkCreatedPayload::new(
+            ChunkId::generate(),
+            "src/lib.rs",
+            Language::Rust,
+            ByteRange::new(0, 40),
+            "fn f(a: i32) -> i32 { if a > 0 { a } else { 0 } }",
+        )
+        .with_symbol(SymbolKind::Function, "f")
+        .with_metrics(SymbolMetrics {
+            lines_of_code: 1,
+            cyclomatic_complexity: 2,
+            max_nesting_depth: 1,
+            parameter_count: 1,
+        });
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["metrics"]["cyclomatic_complexity"], 2);
+        assert_eq!(json["metrics"]["parameter_count"], 1);
+        assert!(json.get("attributes").is_none());
+    }
+
+    #[test]
+    fn test_chunk_created_with_attributes() {
+        let payload = IngestChunkCreatedPayload::new(
+            ChunkId::generate(),
+            "src/lib.rs",
+            Language::Rust,
+            ByteRange::new(0, 45),
+            "#[derive(Debug, Clone)]\npub struct User {}",
+        )
+        .with_symbol(SymbolKind::Struct, "User")
+        .with_attributes(vec![Attribute::parse("#[derive(Debug, Clone)]")]);
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["attributes"][0]["name"], "derive");
+        assert_eq!(json["attributes"][0]["arguments"], "Debug, Clone");
+        assert_eq!(json["attributes"][0]["text"], "#[derive(Debug, Clone)]");
+        assert!(json.get("qualified_name").is_none());
+
+        let payload = payload.with_qualified_name("my_crate::models::User");
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["qualified_name"], "my_crate::models::User");
+    }
+
+    #[test]
+    fn test_document_chunk_created() {
+        let payload = IngestDocumentChunkCreatedPayload::new(
+            ChunkId::generate(),
+            "docs/architecture.md",
+            ByteRange::new(120, 480),
+            "## Storage\n\nThe `DocpackWriter` writes...\n```rust\nlet w = DocpackWriter::new();\n```\n",
+        )
+        .with_heading(
+            "Storage",
+            vec!["Architecture".to_string(), "Storage".to_string()],
+        )
+        .with_code_blocks(vec![CodeBlock::new(
+            Some("rust".to_string()),
+            ByteRange::new(170, 220),
+        )])
+        .with_mentions(vec!["DocpackWriter".to_string()]);
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["heading"], "Storage");
+        assert_eq!(json["heading_path"][0], "Architecture");
+        assert_eq!(json["code_blocks"][0]["language"], "rust");
+        assert_eq!(json["mentions"][0], "DocpackWriter");
+
+        let untitled = IngestDocumentChunkCreatedPayload::new(
+            ChunkId::generate(),
+            "README.md",
+            ByteRange::new(0, 10),
+            "Intro text",
+        );
+        let json = serde_json::to_value(&untitled).unwrap();
+        for field in ["heading", "heading_path", "code_blocks", "mentions"] {
+            assert!(json.get(field).is_none());
+        }
     }
 
     #[test]
@@ -287,6 +824,122 @@ mod tests {
         assert!(json.get("error").is_none());
     }
 
+    #[test]
+    fn test_file_parse_warning_serialization() {
+        let payload =
+            IngestFileParseWarningPayload::new("src/broken.py", Language::Python, 2, 1, 0.25)
+                .with_error_lines(vec![LineRange::new(3, 4), LineRange::new(10, 10)])
+                .with_file_chunk_fallback(false);
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["file_path"], "src/broken.py");
+        assert_eq!(json["error_nodes"], 2);
+        assert_eq!(json["missing_nodes"], 1);
+        assert_eq!(json["error_ratio"], 0.25);
+        assert_eq!(json["error_lines"][0]["start_line"], 3);
+        assert_eq!(json["error_lines"][1]["end_line"], 10);
+        assert_eq!(json["fell_back_to_file_chunk"], false);
+    }
+
+    #[test]
+    fn test_ingest_completed_parse_diagnostics() {
+        let payload = IngestCompletedPayload::success(10, 0, 40, 100);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("files_with_parse_errors").is_none());
+
+        let payload = payload.with_parse_diagnostics(2, 7, 1);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["files_with_parse_errors"], 2);
+        assert_eq!(json["parse_error_nodes"], 7);
+        assert_eq!(json["files_fallback_chunked"], 1);
+    }
+
+    #[test]
+    fn test_ingest_completed_dedup() {
+        let payload = IngestCompletedPayload::success(10, 0, 40, 100);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("duplicate_chunks").is_none());
+
+        let payload = payload.with_dedup(32, 8);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["unique_chunks"], 32);
+        assert_eq!(json["duplicate_chunks"], 8);
+    }
+
+    #[test]
+    fn test_ingest_completed_ambiguous_calls() {
+        let payload = IngestCompletedPayload::success(10, 0, 40, 100);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("ambiguous_calls").is_none());
+
+        let json = serde_json::to_value(payload.with_ambiguous_calls(3)).unwrap();
+        assert_eq!(json["ambiguous_calls"], 3);
+    }
+
+    #[test]
+    fn test_ingest_completed_cache() {
+        let payload = IngestCompletedPayload::success(10, 0, 40, 100);
+        assert_eq!(payload.cache_hit_rate(), None);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("cache_hits").is_none());
+
+        let payload = payload.with_cache(30, 10);
+        assert_eq!(payload.cache_hit_rate(), Some(0.75));
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["cache_hits"], 30);
+        assert_eq!(json["cache_misses"], 10);
+    }
+
+    #[test]
+    fn test_ingest_completed_embedding_model() {
+        let payload = IngestCompletedPayload::success(10, 0, 40, 100);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("embedding_model").is_none());
+
+        let payload = payload.with_embedding_model("text-embedding-3-small", 1536);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["embedding_model"], "text-embedding-3-small");
+        assert_eq!(json["embedding_dimensions"], 1536);
+    }
+
+    #[test]
+    fn test_ingest_completed_output_path() {
+        let payload = IngestCompletedPayload::success(10, 0, 40, 100);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("output_path").is_none());
+
+        let payload = payload.with_output_path("/tmp/ingest.json");
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["output_path"], "/tmp/ingest.json");
+    }
+
+    #[test]
+    fn test_ingest_completed_failed_chunks() {
+        let payload = IngestCompletedPayload::success(10, 0, 40, 100);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("failed_chunk_ids").is_none());
+
+        let payload = payload.with_failed_chunks(vec![ChunkId::new("chunk_poisoned").unwrap()]);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["failed_chunk_ids"][0], "chunk_poisoned");
+    }
+
+    #[test]
+    fn test_chunks_embedded_serialization() {
+        let payload = IngestChunksEmbeddedPayload::new(
+            "job_abc123_batch_0",
+            vec![ChunkEmbedding::new(
+                ChunkId::new("chunk_abc123").unwrap(),
+                vec![0.5, -0.25],
+            )],
+        );
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["batch_id"], "job_abc123_batch_0");
+        assert_eq!(json["vectors"][0]["chunk_id"], "chunk_abc123");
+        assert_eq!(json["vectors"][0]["vector"][1], -0.25);
+    }
+
     #[test]
     fn test_ingest_completed_failed() {
         let payload = IngestCompletedPayload::failed("Download failed", 500);
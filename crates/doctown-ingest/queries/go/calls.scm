@@ -0,0 +1,7 @@
+; Go calls.
+
+(call_expression
+  function: (identifier) @name) @call.function
+
+(call_expression
+  function: (selector_expression) @name) @call.method
@@ -0,0 +1,14 @@
+; Go import specs.
+
+(import_spec
+  path: (_) @module) @import
+
+; import f "fmt"
+(import_spec
+  name: (package_identifier) @alias
+  path: (_) @module) @import
+
+; import . "fmt"
+(import_spec
+  name: (dot) @wildcard
+  path: (_) @module) @import
@@ -0,0 +1,21 @@
+; Go symbol definitions.
+; The catch-all type_spec pattern is listed last so struct and interface kinds win.
+
+(function_declaration
+  name: (identifier) @name
+  body: (_) @body) @definition.function
+
+(method_declaration
+  name: (field_identifier) @name
+  body: (_) @body) @definition.method
+
+(type_spec
+  name: (type_identifier) @name
+  type: (struct_type)) @definition.struct
+
+(type_spec
+  name: (type_identifier) @name
+  type: (interface_type)) @definition.interface
+
+(type_spec
+  name: (type_identifier) @name) @definition.type_alias
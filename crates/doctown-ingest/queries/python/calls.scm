@@ -0,0 +1,7 @@
+; Python calls.
+
+(call
+  function: (identifier) @name) @call.function
+
+(call
+  function: (attribute) @name) @call.method
@@ -0,0 +1,27 @@
+; Python import statements.
+
+; import os.path
+(import_statement
+  name: (dotted_name) @module) @import
+
+; import numpy as np
+(import_statement
+  name: (aliased_import
+    name: (dotted_name) @module
+    alias: (identifier) @alias)) @import
+
+; from os.path import join, exists
+(import_from_statement
+  module_name: (_) @module
+  name: (dotted_name) @item) @import
+
+; from typing import List as L
+(import_from_statement
+  module_name: (_) @module
+  name: (aliased_import
+    name: (dotted_name) @item)) @import
+
+; from os import *
+(import_from_statement
+  module_name: (_) @module
+  (wildcard_import) @wildcard) @import
@@ -0,0 +1,29 @@
+; Python symbol definitions.
+
+(module
+  (function_definition
+    name: (identifier) @name
+    body: (_) @body) @definition.function)
+
+(module
+  (decorated_definition
+    definition: (function_definition
+      name: (identifier) @name
+      body: (_) @body) @definition.function))
+
+(class_definition
+  body: (block
+    (function_definition
+      name: (identifier) @name
+      body: (_) @body) @definition.method))
+
+(class_definition
+  body: (block
+    (decorated_definition
+      definition: (function_definition
+        name: (identifier) @name
+        body: (_) @body) @definition.method)))
+
+(class_definition
+  name: (identifier) @name
+  body: (_) @body) @definition.class
@@ -0,0 +1,14 @@
+; Rust calls.
+
+(call_expression
+  function: (identifier) @name) @call.function
+
+(call_expression
+  function: (generic_function
+    function: (identifier) @name)) @call.function
+
+(call_expression
+  function: (scoped_identifier) @name) @call.associated
+
+(call_expression
+  function: (field_expression) @name) @call.method
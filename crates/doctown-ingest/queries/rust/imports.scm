@@ -0,0 +1,29 @@
+; Rust use declarations.
+
+; use foo;
+(use_declaration
+  argument: (identifier) @module) @import
+
+; use std::collections::HashMap;
+(use_declaration
+  argument: (scoped_identifier
+    path: (_) @module
+    name: (identifier) @item)) @import
+
+; use std::collections::{HashMap, HashSet};
+(use_declaration
+  argument: (scoped_use_list
+    path: (_) @module
+    list: (use_list
+      (identifier) @item))) @import
+
+; use std::io::Result as IoResult;
+(use_declaration
+  argument: (use_as_clause
+    path: (_) @module
+    alias: (identifier) @alias)) @import
+
+; use std::prelude::*;
+(use_declaration
+  argument: (use_wildcard
+    (_) @module) @wildcard) @import
@@ -0,0 +1,52 @@
+; Rust symbol definitions.
+; Functions inside impl and trait blocks are part of their block and not listed separately.
+
+(source_file
+  (function_item
+    (visibility_modifier)? @visibility
+    name: (identifier) @name
+    body: (_) @body) @definition.function)
+
+(mod_item
+  body: (declaration_list
+    (function_item
+      (visibility_modifier)? @visibility
+      name: (identifier) @name
+      body: (_) @body) @definition.function))
+
+(struct_item
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name) @definition.struct
+
+(enum_item
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name
+  body: (_) @body) @definition.enum
+
+(trait_item
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name
+  body: (_) @body) @definition.trait
+
+(impl_item
+  type: (_) @name
+  body: (_) @body) @definition.impl
+
+(mod_item
+  (visibility_modifier)? @visibility
+  name: (identifier) @name) @definition.module
+
+(const_item
+  (visibility_modifier)? @visibility
+  name: (identifier) @name) @definition.const
+
+(static_item
+  (visibility_modifier)? @visibility
+  name: (identifier) @name) @definition.static
+
+(type_item
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name) @definition.type_alias
+
+(macro_definition
+  name: (identifier) @name) @definition.macro
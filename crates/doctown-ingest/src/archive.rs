@@ -1,15 +1,22 @@
 //! Archive extraction.
+use crate::chunk::{create_chunks, create_chunks_with_tree, ChunkHierarchy, ChunkingConfig};
+use crate::diagnostics::{ParseDiagnostics, DEFAULT_MAX_ERROR_RATIO};
 use crate::filter::{
     normalize_archive_path, FileFilter, FilterResult, SkipReason as FilterSkipReason,
 };
 use crate::language::detect_language;
+use crate::linking::Linker;
+use crate::markdown::{chunk_markdown, is_markdown_file};
+use crate::module_tree::ModuleTree;
+use crate::output::{DocumentRecord, SymbolRecord};
 use crate::parsing::parse;
 use crate::pipeline::EventSender;
-use crate::symbol::extract_symbols;
-use doctown_common::{ChunkId, DocError};
+use crate::queries::QueryRegistry;
+use crate::test_code::is_test_file;
+use doctown_common::{ChunkId, DocError, Language, SymbolIdAllocator};
 use doctown_events::{
-    Context, Envelope, IngestChunkCreatedPayload, IngestFileDetectedPayload,
-    IngestFileSkippedPayload, SkipReason,
+    Context, Envelope, IngestChunkCreatedPayload, IngestDocumentChunkCreatedPayload,
+    IngestFileDetectedPayload, IngestFileParseWarningPayload, IngestFileSkippedPayload, SkipReason,
 };
 use std::fs;
 use std::io;
@@ -54,19 +61,128 @@ fn filter_reason_to_event_reason(reason: &FilterSkipReason) -> SkipReason {
     }
 }
 
+/// Options controlling how extracted files are processed.
+pub struct ProcessingOptions {
+    /// Query files used for symbol extraction.
+    pub queries: QueryRegistry,
+    /// Parse error ratio above which a file is chunked as a whole.
+    pub max_parse_error_ratio: f64,
+    /// How code is split into chunks.
+    pub chunking: ChunkingConfig,
+}
+
+impl Default for ProcessingOptions {
+    fn default() -> Self {
+        Self {
+            queries: QueryRegistry::new(),
+            max_parse_error_ratio: DEFAULT_MAX_ERROR_RATIO,
+            chunking: ChunkingConfig::default(),
+        }
+    }
+}
+
+impl ProcessingOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_queries(mut self, queries: QueryRegistry) -> Self {
+        self.queries = queries;
+        self
+    }
+
+    pub fn with_max_parse_error_ratio(mut self, ratio: f64) -> Self {
+        self.max_parse_error_ratio = ratio;
+        self
+    }
+
+    /// Prefixes the embedding input of code chunks with their file path,
+    /// enclosing scope, signature and doc comment. Chunk content is unchanged.
+    pub fn with_context_headers(mut self, enabled: bool) -> Self {
+        self.chunking.context_headers = enabled;
+        self
+    }
+
+    /// Chunks code hierarchically, recording each chunk's level and parent
+    /// (see [`ChunkingConfig::with_hierarchy`]).
+    pub fn with_hierarchy(mut self, enabled: bool) -> Self {
+        self.chunking.hierarchical = enabled;
+        self
+    }
+
+    /// Sizes, splits and links code chunks as `chunking` says. Symbols too
+    /// large for a chunk are split at syntax node boundaries.
+    pub fn with_chunking(mut self, chunking: ChunkingConfig) -> Self {
+        self.chunking = chunking;
+        self
+    }
+}
+
+/// Totals from processing a directory of extracted files.
+#[derive(Debug, Default)]
+pub struct ProcessingSummary {
+    pub files_processed: usize,
+    pub files_skipped: usize,
+    pub chunks_created: usize,
+    /// Chunks collected for embedding, with the text to embed.
+    pub chunks: Vec<(ChunkId, String)>,
+    /// Content of each chunk, without its context header.
+    pub chunk_contents: Vec<(ChunkId, String)>,
+    /// Level and parent of each code chunk, when chunked hierarchically.
+    pub chunk_hierarchy: ChunkHierarchy,
+    /// Symbols, with the IDs of their chunks and the symbols they link to.
+    pub symbols: Vec<SymbolRecord>,
+    /// Documentation sections, with the IDs of their chunks.
+    pub documents: Vec<DocumentRecord>,
+    /// Files that parsed with syntax errors.
+    pub files_with_parse_errors: usize,
+    /// ERROR and MISSING nodes across all files.
+    pub parse_error_nodes: usize,
+    /// Files chunked as a whole because of too many parse errors.
+    pub files_fallback_chunked: usize,
+    /// Calls that matched several equally close symbols.
+    pub ambiguous_calls: usize,
+}
+
 pub async fn process_extracted_files(
     repo_path: &Path,
     context: Context,
     sender: EventSender,
 ) -> Result<(usize, usize, usize, Vec<(ChunkId, String)>), DocError> {
-    let mut files_processed = 0;
-    let mut files_skipped = 0;
-    let mut chunks_created = 0;
-    let mut collected_chunks = Vec::new();
+    let summary = process_files(repo_path, context, sender, &ProcessingOptions::new()).await?;
+    Ok((
+        summary.files_processed,
+        summary.files_skipped,
+        summary.chunks_created,
+        summary.chunks,
+    ))
+}
+
+/// Like [`process_extracted_files`], but with explicit options and the full
+/// summary, including the symbols found, what they link to and parse
+/// diagnostics totals.
+pub async fn process_files(
+    repo_path: &Path,
+    context: Context,
+    sender: EventSender,
+    options: &ProcessingOptions,
+) -> Result<ProcessingSummary, DocError> {
+    let mut summary = ProcessingSummary::default();
 
     let filter = FileFilter::new();
+    // Module paths of Rust files, keyed by their path under `repo_path`
+    let module_tree = ModuleTree::build(repo_path);
+    // Canonical symbol IDs; files are walked in sorted order so symbols sharing
+    // a path are numbered the same way every run
+    let mut symbol_ids = SymbolIdAllocator::new();
+    // Names symbols refer to, resolved to IDs once every file is known
+    let mut linker = Linker::new(repo_path);
 
-    for entry in WalkDir::new(repo_path).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(repo_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
         if entry.file_type().is_file() {
             let path = entry.path();
             let raw_relative_path = path.strip_prefix(repo_path).unwrap_or(path);
@@ -95,7 +211,7 @@ pub async fn process_extracted_files(
                         ))
                         .await
                         .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
-                    files_skipped += 1;
+                    summary.files_skipped += 1;
                     continue;
                 }
                 FilterResult::Accept => {}
@@ -116,7 +232,7 @@ pub async fn process_extracted_files(
                         ))
                         .await
                         .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
-                    files_skipped += 1;
+                    summary.files_skipped += 1;
                     continue;
                 }
             };
@@ -135,7 +251,7 @@ pub async fn process_extracted_files(
                         ))
                         .await
                         .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
-                    files_skipped += 1;
+                    summary.files_skipped += 1;
                     continue;
                 }
                 FilterResult::Accept => {}
@@ -156,11 +272,56 @@ pub async fn process_extracted_files(
                         ))
                         .await
                         .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
-                    files_skipped += 1;
+                    summary.files_skipped += 1;
                     continue;
                 }
             };
 
+            // Documentation is chunked by heading section rather than parsed
+            if is_markdown_file(relative_path) {
+                for section in chunk_markdown(&content) {
+                    let chunk_id = ChunkId::generate();
+                    let chunk_content = content[section.range.start..section.range.end].to_string();
+
+                    // Collect chunk for embedding
+                    summary
+                        .chunks
+                        .push((chunk_id.clone(), chunk_content.clone()));
+                    summary
+                        .chunk_contents
+                        .push((chunk_id.clone(), chunk_content.clone()));
+                    summary.documents.push(
+                        DocumentRecord::new(&chunk_id, relative_path.to_string_lossy())
+                            .with_heading(section.heading.clone(), section.heading_path.clone())
+                            .with_mentions(section.mentions.clone()),
+                    );
+
+                    let mut payload = IngestDocumentChunkCreatedPayload::new(
+                        chunk_id,
+                        relative_path.to_string_lossy(),
+                        section.range,
+                        chunk_content,
+                    )
+                    .with_code_blocks(section.code_blocks)
+                    .with_mentions(section.mentions);
+                    if let Some(heading) = section.heading {
+                        payload = payload.with_heading(heading, section.heading_path);
+                    }
+
+                    sender
+                        .send(Envelope::new(
+                            "ingest.document_chunk_created.v1",
+                            context.clone(),
+                            serde_json::to_value(payload)?,
+                        ))
+                        .await
+                        .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
+                    summary.chunks_created += 1;
+                }
+                summary.files_processed += 1;
+                continue;
+            }
+
             if let Some(language) = detect_language(relative_path, Some(&content)) {
                 sender
                     .send(Envelope::new(
@@ -176,23 +337,164 @@ pub async fn process_extracted_files(
                     .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
 
                 if let Some(tree) = parse(&content, language) {
-                    let symbols = extract_symbols(&tree, &content, language);
-                    for symbol in symbols {
-                        let chunk_id = ChunkId::generate();
-                        let chunk_content =
-                            content[symbol.range.start..symbol.range.end].to_string();
+                    let diagnostics = ParseDiagnostics::from_tree(&tree, &content);
+                    let fall_back = diagnostics.exceeds(options.max_parse_error_ratio);
+                    let test_file = is_test_file(relative_path, language);
+                    if diagnostics.has_errors() {
+                        let payload = IngestFileParseWarningPayload::new(
+                            relative_path.to_string_lossy(),
+                            language,
+                            diagnostics.error_nodes,
+                            diagnostics.missing_nodes,
+                            diagnostics.error_ratio(),
+                        )
+                        .with_error_lines(diagnostics.error_lines.clone())
+                        .with_file_chunk_fallback(fall_back);
 
-                        // Collect chunk for embedding
-                        collected_chunks.push((chunk_id.clone(), chunk_content.clone()));
+                        sender
+                            .send(Envelope::new(
+                                "ingest.file_parse_warning.v1",
+                                context.clone(),
+                                serde_json::to_value(payload)?,
+                            ))
+                            .await
+                            .map_err(|e| {
+                                DocError::Internal(format!("Failed to send event: {}", e))
+                            })?;
+                        summary.files_with_parse_errors += 1;
+                        summary.parse_error_nodes += diagnostics.total_nodes();
+                    }
 
-                        let payload = IngestChunkCreatedPayload::new(
-                            chunk_id,
-                            relative_path.to_string_lossy(),
+                    // Symbols from a mostly broken tree are unreliable; chunk the whole file
+                    let file_path = relative_path.to_string_lossy();
+                    let (chunks, symbols) = if fall_back {
+                        summary.files_fallback_chunked += 1;
+                        let chunks =
+                            create_chunks(&file_path, &content, language, &[], &options.chunking);
+                        (chunks, Vec::new())
+                    } else {
+                        let mut symbols =
+                            options.queries.extract_symbols(&tree, &content, language);
+                        if language == Language::Rust {
+                            module_tree.qualify_symbols(
+                                raw_relative_path,
+                                &mut symbols,
+                                &tree,
+                                &content,
+                            );
+                        }
+                        let chunks = create_chunks_with_tree(
+                            &file_path,
+                            &content,
+                            language,
+                            &tree,
+                            &symbols,
+                            &options.chunking,
+                        );
+                        (chunks, symbols)
+                    };
+
+                    // The innermost symbol each chunk was cut from
+                    let owners: Vec<Option<usize>> = chunks
+                        .iter()
+                        .map(|chunk| {
+                            symbols
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, symbol)| {
+                                    chunk.metadata.symbol_kind == Some(symbol.kind)
+                                        && chunk.metadata.symbol_name.as_deref()
+                                            == Some(symbol.name.as_str())
+                                        && symbol.range.contains(&chunk.byte_range)
+                                })
+                                .min_by_key(|(_, symbol)| symbol.range.end - symbol.range.start)
+                                .map(|(i, _)| i)
+                        })
+                        .collect();
+
+                    // One record per symbol, listing all of its chunks
+                    let mut symbol_ids_of = vec![None; symbols.len()];
+                    let mut file_symbols = Vec::new();
+                    for (i, symbol) in symbols.iter().enumerate() {
+                        let mut chunk_ids = chunks
+                            .iter()
+                            .zip(&owners)
+                            .filter(|(_, owner)| **owner == Some(i))
+                            .map(|(chunk, _)| &chunk.id);
+                        let Some(first_chunk_id) = chunk_ids.next() else {
+                            continue;
+                        };
+                        let symbol_id = symbol_ids.allocate(
+                            language,
+                            symbol.kind,
+                            &symbol.canonical_path(&file_path),
+                        );
+                        let mut record = SymbolRecord::new(
+                            symbol_id.clone(),
+                            symbol,
                             language,
-                            symbol.range,
-                            &chunk_content,
+                            file_path.as_ref(),
+                            first_chunk_id,
+                        );
+                        record
+                            .chunk_ids
+                            .extend(chunk_ids.map(|chunk_id| chunk_id.to_string()));
+                        file_symbols.push((
+                            summary.symbols.len(),
+                            symbol_id.clone(),
+                            symbol.clone(),
+                        ));
+                        summary.symbols.push(record);
+                        symbol_ids_of[i] = Some(symbol_id);
+                    }
+
+                    for (chunk, owner) in chunks.into_iter().zip(owners) {
+                        // Collect chunk for embedding, behind its context header if enabled
+                        summary
+                            .chunks
+                            .push((chunk.id.clone(), chunk.embedding_input()));
+                        summary
+                            .chunk_contents
+                            .push((chunk.id.clone(), chunk.content.clone()));
+
+                        let symbol = owner.map(|i| &symbols[i]);
+                        let is_test = test_file || symbol.is_some_and(|s| s.is_test);
+                        let mut payload = IngestChunkCreatedPayload::new(
+                            chunk.id.clone(),
+                            file_path.as_ref(),
+                            language,
+                            chunk.byte_range,
+                            &chunk.content,
                         )
-                        .with_symbol(symbol.kind, symbol.name);
+                        .with_content_hash(chunk.content_hash())
+                        .with_test(is_test);
+                        if let Some(level) = chunk.metadata.level {
+                            let parent_id = chunk.metadata.parent_id.clone();
+                            summary
+                                .chunk_hierarchy
+                                .insert(chunk.id.clone(), (level, parent_id.clone()));
+                            payload = payload.with_hierarchy(level, parent_id);
+                        }
+                        match (symbol, owner.and_then(|i| symbol_ids_of[i].clone())) {
+                            (Some(symbol), Some(symbol_id)) => {
+                                payload = payload
+                                    .with_symbol_id(symbol_id)
+                                    .with_symbol(symbol.kind, symbol.name.clone())
+                                    .with_metrics(symbol.metrics)
+                                    .with_attributes(symbol.attributes.clone());
+                                if let Some(qualified_name) = &symbol.qualified_name {
+                                    payload = payload.with_qualified_name(qualified_name.clone());
+                                }
+                            }
+                            // Methods chunked on their own in hierarchical mode
+                            _ => {
+                                if let (Some(kind), Some(name)) =
+                                    (chunk.metadata.symbol_kind, chunk.metadata.symbol_name)
+                                {
+                                    payload = payload.with_symbol(kind, name);
+                                }
+                            }
+                        }
 
                         sender
                             .send(Envelope::new(
@@ -204,9 +506,19 @@ pub async fn process_extracted_files(
                             .map_err(|e| {
                                 DocError::Internal(format!("Failed to send event: {}", e))
                             })?;
-                        chunks_created += 1;
+                        summary.chunks_created += 1;
                     }
-                    files_processed += 1;
+                    if !fall_back {
+                        linker.add_file(
+                            &raw_relative_path.to_string_lossy().replace('\\', "/"),
+                            language,
+                            &tree,
+                            &content,
+                            &options.queries,
+                            &file_symbols,
+                        );
+                    }
+                    summary.files_processed += 1;
                 } else {
                     // Failed to parse, emit skipped event
                     sender
@@ -220,7 +532,7 @@ pub async fn process_extracted_files(
                         ))
                         .await
                         .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
-                    files_skipped += 1;
+                    summary.files_skipped += 1;
                 }
             } else {
                 // Unsupported language, emit skipped event
@@ -235,16 +547,12 @@ pub async fn process_extracted_files(
                     ))
                     .await
                     .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
-                files_skipped += 1;
+                summary.files_skipped += 1;
             }
         }
     }
-    Ok((
-        files_processed,
-        files_skipped,
-        chunks_created,
-        collected_chunks,
-    ))
+    summary.ambiguous_calls = linker.link(&mut summary.symbols);
+    Ok(summary)
 }
 
 #[cfg(test)]
@@ -0,0 +1,196 @@
+//! Attribute and decorator extraction.
+//!
+//! Collects the Rust attributes (`#[derive(...)]`, `#[cfg(...)]`, `#[deprecated]`,
+//! proc-macro attributes) and Python/TypeScript decorators attached to each symbol.
+
+use doctown_common::types::Attribute;
+use doctown_common::Language;
+use tree_sitter::{Node, Tree};
+
+use crate::symbol::{symbol_node, Symbol};
+use crate::traversal::node_text;
+
+/// Returns the attributes attached to a Rust item (the preceding `#[...]` siblings),
+/// in source order.
+pub(crate) fn rust_attributes<'a>(node: Node<'_>, source: &'a str) -> Vec<&'a str> {
+    let mut attributes = Vec::new();
+    let mut current = node.prev_named_sibling();
+    while let Some(sibling) = current {
+        match sibling.kind() {
+            "attribute_item" => attributes.push(node_text(sibling, source)),
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        current = sibling.prev_named_sibling();
+    }
+    attributes.reverse();
+    attributes
+}
+
+/// Extract decorators from a Python function or class.
+fn python_decorators<'a>(node: Node<'_>, source: &'a str) -> Vec<&'a str> {
+    // In tree-sitter-python, decorators are children of a decorated_definition node
+    match node.parent() {
+        Some(parent) if parent.kind() == "decorated_definition" => parent
+            .named_children(&mut parent.walk())
+            .filter(|child| child.kind() == "decorator")
+            .map(|child| node_text(child, source))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Extract decorators from a TypeScript/JavaScript class declaration.
+fn ts_decorators<'a>(node: Node<'_>, source: &'a str) -> Vec<&'a str> {
+    // `@Component export class X {}` attaches the decorator to the export statement
+    let export = node
+        .parent()
+        .filter(|parent| parent.kind() == "export_statement");
+    export
+        .into_iter()
+        .chain(std::iter::once(node))
+        .flat_map(|n| {
+            n.named_children(&mut n.walk())
+                .filter(|child| child.kind() == "decorator")
+                .collect::<Vec<_>>()
+        })
+        .map(|child| node_text(child, source))
+        .collect()
+}
+
+/// Returns the attributes and decorators attached to the syntax node of a symbol.
+pub fn node_attributes(node: Node<'_>, source_code: &str, language: Language) -> Vec<Attribute> {
+    let texts = match language {
+        Language::Rust => rust_attributes(node, source_code),
+        Language::Python => python_decorators(node, source_code),
+        Language::TypeScript | Language::JavaScript => ts_decorators(node, source_code),
+        Language::Go => Vec::new(),
+    };
+    texts.into_iter().map(Attribute::parse).collect()
+}
+
+/// Set `attributes` on each symbol from the syntax tree.
+pub fn attach_attributes(
+    symbols: &mut [Symbol],
+    tree: &Tree,
+    source_code: &str,
+    language: Language,
+) {
+    if language == Language::Go {
+        return;
+    }
+    for symbol in symbols {
+        // The outermost node spanning the symbol, whose siblings are the attributes
+        if let Some(node) = symbol_node(tree, symbol) {
+            symbol.attributes = node_attributes(node, source_code, language);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use crate::symbol::extract_symbols;
+
+    fn attributes_for(code: &str, language: Language, name: &str) -> Vec<Attribute> {
+        let tree = parse(code, language).unwrap();
+        extract_symbols(&tree, code, language)
+            .into_iter()
+            .find(|s| s.name == name)
+            .map(|s| s.attributes)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rust_attributes() {
+        let code = r#"
+/// A user record.
+#[derive(Debug, Clone)]
+#[cfg(feature = "users")]
+pub struct User {
+    name: String,
+}
+
+#[deprecated(since = "0.2.0", note = "use fetch")]
+#[get("/users")]
+pub async fn list_users() {}
+
+pub fn plain() {}
+"#;
+        let user = attributes_for(code, Language::Rust, "User");
+        let texts: Vec<_> = user.iter().map(|a| a.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["#[derive(Debug, Clone)]", "#[cfg(feature = \"users\")]"]
+        );
+        assert_eq!(user[0].derives(), vec!["Debug", "Clone"]);
+        assert_eq!(user[1].cfg_condition(), Some("feature = \"users\""));
+
+        let list_users = attributes_for(code, Language::Rust, "list_users");
+        assert!(list_users[0].is_deprecated());
+        assert_eq!(list_users[1].name, "get");
+
+        assert!(attributes_for(code, Language::Rust, "plain").is_empty());
+    }
+
+    #[test]
+    fn test_rust_impl_and_trait_attributes() {
+        let code = r#"
+#[async_trait]
+pub trait Store {
+    async fn get(&self);
+}
+
+#[async_trait]
+impl Store for Memory {
+    async fn get(&self) {}
+}
+"#;
+        let store = attributes_for(code, Language::Rust, "Store");
+        assert_eq!(store[0].name, "async_trait");
+    }
+
+    #[test]
+    fn test_python_decorators() {
+        let code = r#"
+@app.route("/users")
+@login_required
+def list_users():
+    pass
+
+@dataclass(frozen=True)
+class User:
+    name: str
+
+def plain():
+    pass
+"#;
+        let route = attributes_for(code, Language::Python, "list_users");
+        let names: Vec<_> = route.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["app.route", "login_required"]);
+        assert_eq!(route[0].arguments.as_deref(), Some("\"/users\""));
+
+        let user = attributes_for(code, Language::Python, "User");
+        assert_eq!(user[0].text, "@dataclass(frozen=True)");
+
+        assert!(attributes_for(code, Language::Python, "plain").is_empty());
+    }
+
+    #[test]
+    fn test_typescript_class_decorators() {
+        let code = r#"
+@Component({ selector: "app-root" })
+export class AppComponent {}
+
+@Injectable()
+class Service {}
+"#;
+        let app = attributes_for(code, Language::TypeScript, "AppComponent");
+        assert_eq!(app.len(), 1);
+        assert_eq!(app[0].name, "Component");
+
+        let service = attributes_for(code, Language::TypeScript, "Service");
+        assert_eq!(service[0].name, "Injectable");
+    }
+}
@@ -0,0 +1,441 @@
+//! Adaptive batching of embedding requests.
+//!
+//! Chunks are packed into batches by token count rather than by number, so a
+//! batch of short helpers costs about as much as a batch of long functions.
+//! How many batches are in flight is tuned as they complete: concurrency
+//! grows while batches finish within the target latency for their size,
+//! shrinks by one when they are slow or needed retries and halves when they
+//! fail or are rate limited. A fast GPU worker ends up with many large
+//! batches in flight, a CPU worker with few.
+
+use std::time::Duration;
+
+use doctown_common::ChunkId;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use tracing::debug;
+
+use crate::embedding::{BatchOutcome, ResilientEmbedder};
+
+/// Default token budget of a batch.
+pub const DEFAULT_MAX_BATCH_TOKENS: usize = 2048;
+
+/// Default maximum number of chunks in a batch.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 64;
+
+/// Default number of batches in flight when embedding starts.
+pub const DEFAULT_INITIAL_CONCURRENCY: usize = 4;
+
+/// Default upper bound on batches in flight.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 16;
+
+/// Default latency of a full batch above which concurrency is reduced.
+pub const DEFAULT_TARGET_LATENCY: Duration = Duration::from_secs(5);
+
+/// Smallest fraction of the target latency a batch is held to, however few
+/// its tokens, since every request has some fixed cost.
+const MIN_TARGET_FRACTION: f64 = 0.1;
+
+/// Chunks packed into a batch: the `(chunk_id, input)` pairs and their tokens.
+pub type PackedBatch = (Vec<(ChunkId, String)>, usize);
+
+/// A batch to embed: its number, ID, `(chunk_id, input)` pairs and tokens.
+pub type NumberedBatch = (usize, String, Vec<(ChunkId, String)>, usize);
+
+/// Limits of adaptive batching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchingConfig {
+    /// Maximum tokens in a batch. A chunk over the budget is a batch of its own.
+    pub max_batch_tokens: usize,
+    /// Maximum chunks in a batch.
+    pub max_batch_size: usize,
+    /// Batches in flight when embedding starts.
+    pub initial_concurrency: usize,
+    /// Fewest batches kept in flight.
+    pub min_concurrency: usize,
+    /// Most batches kept in flight.
+    pub max_concurrency: usize,
+    /// Latency of a batch of `max_batch_tokens` above which concurrency is
+    /// reduced. Smaller batches are held to a proportionally lower target.
+    pub target_latency: Duration,
+}
+
+impl Default for BatchingConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_tokens: DEFAULT_MAX_BATCH_TOKENS,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            initial_concurrency: DEFAULT_INITIAL_CONCURRENCY,
+            min_concurrency: 1,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            target_latency: DEFAULT_TARGET_LATENCY,
+        }
+    }
+}
+
+impl BatchingConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_batch_tokens(mut self, max_batch_tokens: usize) -> Self {
+        self.max_batch_tokens = max_batch_tokens;
+        self
+    }
+
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Sets the concurrency bounds and where concurrency starts between them.
+    pub fn with_concurrency(mut self, min: usize, initial: usize, max: usize) -> Self {
+        self.min_concurrency = min;
+        self.initial_concurrency = initial;
+        self.max_concurrency = max;
+        self
+    }
+
+    pub fn with_target_latency(mut self, target_latency: Duration) -> Self {
+        self.target_latency = target_latency;
+        self
+    }
+}
+
+/// Packs chunks into batches of at most `max_batch_tokens` tokens and
+/// `max_batch_size` chunks, keeping their order.
+///
+/// `count_tokens` counts the tokens of a chunk's embedding input. Each batch
+/// comes with its total tokens.
+pub fn pack_batches(
+    chunks: &[(ChunkId, String)],
+    config: &BatchingConfig,
+    count_tokens: impl Fn(&str) -> usize,
+) -> Vec<PackedBatch> {
+    let max_batch_size = config.max_batch_size.max(1);
+    let mut batches = Vec::new();
+    let mut batch: Vec<(ChunkId, String)> = Vec::new();
+    let mut batch_tokens = 0;
+
+    for chunk in chunks {
+        let tokens = count_tokens(&chunk.1);
+        let full = batch.len() >= max_batch_size || batch_tokens + tokens > config.max_batch_tokens;
+        if full && !batch.is_empty() {
+            batches.push((std::mem::take(&mut batch), batch_tokens));
+            batch_tokens = 0;
+        }
+        batch.push(chunk.clone());
+        batch_tokens += tokens;
+    }
+    if !batch.is_empty() {
+        batches.push((batch, batch_tokens));
+    }
+    batches
+}
+
+/// Rough token count for when the model's tokenizer is not available.
+pub fn estimate_tokens(text: &str) -> usize {
+    // Code averages about four bytes per token
+    text.len().div_ceil(4)
+}
+
+/// Tunes the number of batches in flight from their latency and errors.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyController {
+    limit: usize,
+    min: usize,
+    max: usize,
+    target_latency: Duration,
+    max_batch_tokens: usize,
+    /// Fast, successful batches since the limit last changed.
+    streak: usize,
+    completed: usize,
+    errored: usize,
+}
+
+impl ConcurrencyController {
+    pub fn new(config: &BatchingConfig) -> Self {
+        let min = config.min_concurrency.max(1);
+        let max = config.max_concurrency.max(min);
+        Self {
+            limit: config.initial_concurrency.clamp(min, max),
+            min,
+            max,
+            target_latency: config.target_latency,
+            max_batch_tokens: config.max_batch_tokens.max(1),
+            streak: 0,
+            completed: 0,
+            errored: 0,
+        }
+    }
+
+    /// Number of batches to keep in flight.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Fraction of completed batches that had failed chunks or were rate limited.
+    pub fn error_rate(&self) -> f64 {
+        if self.completed == 0 {
+            0.0
+        } else {
+            self.errored as f64 / self.completed as f64
+        }
+    }
+
+    /// Latency a batch of `tokens` should finish within: the target for a
+    /// full batch, scaled by its share of the token budget.
+    pub fn target_latency(&self, tokens: usize) -> Duration {
+        let fraction = tokens as f64 / self.max_batch_tokens as f64;
+        self.target_latency
+            .mul_f64(fraction.max(MIN_TARGET_FRACTION))
+    }
+
+    /// Adjusts the limit after a batch of `tokens` completes.
+    ///
+    /// Errors halve it and batches slower than their target reduce it by
+    /// one. After as many fast, successful batches in a row as the limit, it
+    /// grows by one.
+    pub fn record(&mut self, latency: Duration, tokens: usize, errored: bool) {
+        let slow = latency > self.target_latency(tokens);
+        self.adjust(slow, errored);
+    }
+
+    fn adjust(&mut self, slow: bool, errored: bool) {
+        self.completed += 1;
+        if errored {
+            self.errored += 1;
+            self.limit = (self.limit / 2).max(self.min);
+            self.streak = 0;
+        } else if slow {
+            self.limit = self.limit.saturating_sub(1).max(self.min);
+            self.streak = 0;
+        } else {
+            self.streak += 1;
+            if self.streak >= self.limit {
+                self.limit = (self.limit + 1).min(self.max);
+                self.streak = 0;
+            }
+        }
+    }
+
+    /// Records a batch of `tokens` embedded by [`ResilientEmbedder::embed_batch`].
+    ///
+    /// It errored if it left chunks without vectors or was rate limited. A
+    /// batch that needed retries but succeeded counts as slow, not errored:
+    /// the provider was struggling, not refusing.
+    pub fn record_outcome(&mut self, outcome: &BatchOutcome, tokens: usize) {
+        let errored = !outcome.failed.is_empty() || outcome.throttled;
+        let latency = Duration::from_millis(outcome.duration_ms);
+        let slow = outcome.requests > 1 || latency > self.target_latency(tokens);
+        self.adjust(slow, errored);
+    }
+}
+
+/// Embeds `batches`, keeping as many in flight as `controller` allows.
+///
+/// Outcomes are returned in completion order. No more batches are started
+/// once one has an [`BatchOutcome::error`].
+pub async fn embed_batches(
+    embedder: &ResilientEmbedder,
+    batches: Vec<NumberedBatch>,
+    controller: &mut ConcurrencyController,
+) -> Vec<(usize, String, BatchOutcome)> {
+    let mut pending = batches.into_iter();
+    let mut in_flight = FuturesUnordered::new();
+    let mut results = Vec::new();
+    let mut stopped = false;
+
+    loop {
+        while !stopped && in_flight.len() < controller.limit() {
+            let Some((batch_num, batch_id, chunks, tokens)) = pending.next() else {
+                break;
+            };
+            let embedder = embedder.clone();
+            in_flight.push(async move {
+                let outcome = embedder.embed_batch(batch_id.clone(), chunks).await;
+                (batch_num, batch_id, outcome, tokens)
+            });
+        }
+
+        let Some((batch_num, batch_id, outcome, tokens)) = in_flight.next().await else {
+            break;
+        };
+        controller.record_outcome(&outcome, tokens);
+        stopped |= outcome.error.is_some();
+        debug!(
+            "Batch {} done in {}ms; concurrency now {}",
+            batch_num + 1,
+            outcome.duration_ms,
+            controller.limit()
+        );
+        results.push((batch_num, batch_id, outcome));
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding_provider::HashEmbedder;
+
+    fn chunks(sizes: &[usize]) -> Vec<(ChunkId, String)> {
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, size)| {
+                (
+                    ChunkId::new(format!("chunk_test{:04}", i)).unwrap(),
+                    "x".repeat(*size),
+                )
+            })
+            .collect()
+    }
+
+    fn sizes(batches: &[PackedBatch]) -> Vec<Vec<usize>> {
+        batches
+            .iter()
+            .map(|(batch, tokens)| {
+                let sizes: Vec<usize> = batch.iter().map(|(_, input)| input.len()).collect();
+                assert_eq!(sizes.iter().sum::<usize>(), *tokens);
+                sizes
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pack_batches_by_tokens() {
+        let config = BatchingConfig::new().with_max_batch_tokens(10);
+        let batches = pack_batches(&chunks(&[4, 4, 4, 10, 2]), &config, str::len);
+
+        assert_eq!(
+            sizes(&batches),
+            vec![vec![4, 4], vec![4], vec![10], vec![2]]
+        );
+    }
+
+    #[test]
+    fn test_pack_batches_oversized_chunk_alone() {
+        let config = BatchingConfig::new().with_max_batch_tokens(10);
+        let batches = pack_batches(&chunks(&[2, 50, 2]), &config, str::len);
+
+        assert_eq!(sizes(&batches), vec![vec![2], vec![50], vec![2]]);
+    }
+
+    #[test]
+    fn test_pack_batches_by_size() {
+        let config = BatchingConfig::new().with_max_batch_size(2);
+        let batches = pack_batches(&chunks(&[1, 1, 1, 1, 1]), &config, str::len);
+
+        assert_eq!(sizes(&batches), vec![vec![1, 1], vec![1, 1], vec![1]]);
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("fn main() {}"), 3);
+        assert_eq!(estimate_tokens("fn a"), 1);
+    }
+
+    #[test]
+    fn test_concurrency_grows_while_fast() {
+        let config = BatchingConfig::new().with_concurrency(1, 2, 3);
+        let mut controller = ConcurrencyController::new(&config);
+        let fast = Duration::from_millis(10);
+
+        controller.record(fast, DEFAULT_MAX_BATCH_TOKENS, false);
+        assert_eq!(controller.limit(), 2);
+        controller.record(fast, DEFAULT_MAX_BATCH_TOKENS, false);
+        assert_eq!(controller.limit(), 3);
+        for _ in 0..10 {
+            controller.record(fast, DEFAULT_MAX_BATCH_TOKENS, false);
+        }
+        assert_eq!(controller.limit(), 3);
+    }
+
+    #[test]
+    fn test_concurrency_shrinks_when_slow_or_failing() {
+        let config = BatchingConfig::new()
+            .with_concurrency(1, 8, 16)
+            .with_target_latency(Duration::from_secs(1));
+        let mut controller = ConcurrencyController::new(&config);
+
+        let full = DEFAULT_MAX_BATCH_TOKENS;
+        controller.record(Duration::from_secs(2), full, false);
+        assert_eq!(controller.limit(), 7);
+        controller.record(Duration::from_millis(10), full, true);
+        assert_eq!(controller.limit(), 3);
+        controller.record(Duration::from_millis(10), full, true);
+        controller.record(Duration::from_millis(10), full, true);
+        assert_eq!(controller.limit(), 1);
+        assert!((controller.error_rate() - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_target_latency_scales_with_tokens() {
+        let config = BatchingConfig::new()
+            .with_max_batch_tokens(1000)
+            .with_concurrency(1, 8, 16)
+            .with_target_latency(Duration::from_secs(4));
+        let mut controller = ConcurrencyController::new(&config);
+
+        assert_eq!(controller.target_latency(1000), Duration::from_secs(4));
+        assert_eq!(controller.target_latency(250), Duration::from_secs(1));
+        assert_eq!(controller.target_latency(1), Duration::from_millis(400));
+
+        // 2s is fast for a full batch but slow for a quarter of one
+        controller.record(Duration::from_secs(2), 1000, false);
+        assert_eq!(controller.limit(), 8);
+        controller.record(Duration::from_secs(2), 250, false);
+        assert_eq!(controller.limit(), 7);
+    }
+
+    #[test]
+    fn test_record_outcome_halves_only_on_failures_or_throttling() {
+        let config = BatchingConfig::new().with_concurrency(1, 8, 16);
+        let mut controller = ConcurrencyController::new(&config);
+        let outcome = |requests, failed: usize, throttled| BatchOutcome {
+            failed: chunks(&vec![1; failed])
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect(),
+            requests,
+            duration_ms: 10,
+            throttled,
+            ..Default::default()
+        };
+
+        // A retry that succeeded is only slow
+        controller.record_outcome(&outcome(2, 0, false), 100);
+        assert_eq!(controller.limit(), 7);
+        controller.record_outcome(&outcome(2, 0, true), 100);
+        assert_eq!(controller.limit(), 3);
+        controller.record_outcome(&outcome(1, 1, false), 100);
+        assert_eq!(controller.limit(), 1);
+        assert!((controller.error_rate() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_embed_batches() {
+        let embedder = ResilientEmbedder::new(HashEmbedder::new(8));
+        let config = BatchingConfig::new().with_max_batch_tokens(8);
+        let batches: Vec<_> = pack_batches(&chunks(&[4, 4, 4, 4, 4]), &config, str::len)
+            .into_iter()
+            .enumerate()
+            .map(|(batch_num, (batch, tokens))| {
+                (batch_num, format!("batch_{}", batch_num), batch, tokens)
+            })
+            .collect();
+        let mut controller = ConcurrencyController::new(&config);
+
+        let results = embed_batches(&embedder, batches, &mut controller).await;
+
+        assert_eq!(results.len(), 3);
+        let embedded: usize = results
+            .iter()
+            .map(|(_, _, outcome)| outcome.vectors.len())
+            .sum();
+        assert_eq!(embedded, 5);
+        assert_eq!(controller.error_rate(), 0.0);
+    }
+}
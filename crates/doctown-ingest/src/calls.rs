@@ -3,6 +3,7 @@
 use doctown_common::types::{Call, CallKind};
 use tree_sitter::{Node, Tree};
 
+use crate::receiver_types::attach_receiver_types;
 use crate::traversal::{find_nodes_by_kind, node_byte_range, node_text};
 
 /// Extract all function/method calls from a parsed syntax tree.
@@ -11,13 +12,15 @@ pub fn extract_calls(
     source_code: &str,
     language: doctown_common::Language,
 ) -> Vec<Call> {
-    match language {
+    let mut calls = match language {
         doctown_common::Language::Rust => extract_rust_calls(tree, source_code),
         doctown_common::Language::Python => extract_python_calls(tree, source_code),
         doctown_common::Language::TypeScript => extract_typescript_calls(tree, source_code),
         doctown_common::Language::JavaScript => extract_javascript_calls(tree, source_code),
         doctown_common::Language::Go => extract_go_calls(tree, source_code),
-    }
+    };
+    attach_receiver_types(&mut calls, tree, source_code, language);
+    calls
 }
 
 /// Extract calls from Rust source code.
@@ -66,6 +69,7 @@ fn extract_rust_call_expression(node: Node<'_>, source_code: &str) -> Option<Cal
         range,
         kind,
         is_resolved: false, // Simple heuristic: mark all as unresolved for now
+        receiver_type: None,
     })
 }
 
@@ -79,6 +83,7 @@ fn extract_rust_method_call(node: Node<'_>, source_code: &str) -> Option<Call> {
         range,
         kind: CallKind::Method,
         is_resolved: false,
+        receiver_type: None,
     })
 }
 
@@ -117,6 +122,7 @@ fn extract_python_call(node: Node<'_>, source_code: &str) -> Option<Call> {
         range,
         kind,
         is_resolved: false,
+        receiver_type: None,
     })
 }
 
@@ -159,6 +165,7 @@ fn extract_ts_call(node: Node<'_>, source_code: &str) -> Option<Call> {
         range,
         kind,
         is_resolved: false,
+        receiver_type: None,
     })
 }
 
@@ -172,6 +179,7 @@ fn extract_ts_new_expression(node: Node<'_>, source_code: &str) -> Option<Call>
         range,
         kind: CallKind::Constructor,
         is_resolved: false,
+        receiver_type: None,
     })
 }
 
@@ -214,6 +222,7 @@ fn extract_go_call(node: Node<'_>, source_code: &str) -> Option<Call> {
         range,
         kind,
         is_resolved: false,
+        receiver_type: None,
     })
 }
 
@@ -2,14 +2,26 @@
 //!
 //! This module handles:
 //! - Creating chunks from extracted symbols
-//! - Splitting large symbols with overlap
+//! - Splitting large symbols with overlap, in bytes or model tokens
+//! - Splitting large symbols at syntax node boundaries when a tree is available
+//! - Contextual headers for the embedding input of each chunk
 //! - File-level fallback for files with no extractable symbols
-//! - Deterministic chunk ID generation
+//! - Hierarchical file, symbol and sub-symbol chunks linked to their parents
+//! - Deterministic chunk ID generation and content hashing
 
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+pub use doctown_common::types::ChunkLevel;
+use doctown_common::types::{SymbolMetrics, Visibility};
 use doctown_common::{ByteRange, ChunkId, Language, SymbolKind};
 use sha2::{Digest, Sha256};
+use tree_sitter::{Node, Tree};
 
+use crate::chunk_context::ChunkContext;
 use crate::symbol::Symbol;
+use crate::tokenizer::{TokenCounter, MODEL_MAX_TOKENS};
+use crate::traversal::{child_by_field, find_nodes_by_kinds, node_byte_range, node_text};
 
 /// Default maximum chunk size in bytes (4KB).
 pub const DEFAULT_MAX_CHUNK_SIZE: usize = 4096;
@@ -17,6 +29,9 @@ pub const DEFAULT_MAX_CHUNK_SIZE: usize = 4096;
 /// Default overlap size when splitting large chunks (256 bytes).
 pub const DEFAULT_OVERLAP_SIZE: usize = 256;
 
+/// Default overlap when splitting by tokens (32 tokens).
+pub const DEFAULT_OVERLAP_TOKENS: usize = 32;
+
 /// A chunk of source code extracted from a file.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Chunk {
@@ -49,6 +64,33 @@ pub struct ChunkMetadata {
     pub split_index: Option<usize>,
     /// Total number of splits, if split.
     pub split_total: Option<usize>,
+    /// The child nodes covered by this split, if split at node boundaries.
+    pub sub_nodes: Option<SubNodeRange>,
+    /// Context prepended to the content for embedding, if enabled.
+    pub context: Option<ChunkContext>,
+    /// Level of the chunk, when chunked hierarchically.
+    pub level: Option<ChunkLevel>,
+    /// The chunk one level up (the file summary, or the enclosing symbol),
+    /// when chunked hierarchically.
+    pub parent_id: Option<ChunkId>,
+}
+
+/// Level and parent of chunks in a hierarchical chunking, by chunk ID.
+pub type ChunkHierarchy = HashMap<ChunkId, (ChunkLevel, Option<ChunkId>)>;
+
+/// The child nodes of a symbol that a split chunk covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubNodeRange {
+    /// Kind of the first node in the chunk (e.g. `let_declaration`).
+    pub first_kind: String,
+    /// Kind of the last node in the chunk.
+    pub last_kind: String,
+    /// Number of nodes starting in the chunk.
+    pub node_count: usize,
+    /// Byte range in the source file spanned by the nodes, clipped to the chunk.
+    pub range: ByteRange,
+    /// Whether the chunk holds only part of a single node, cut at line boundaries.
+    pub by_lines: bool,
 }
 
 impl Chunk {
@@ -88,6 +130,46 @@ impl Chunk {
         self.metadata.split_total = Some(total);
         self
     }
+
+    /// Records the child nodes a split chunk covers.
+    pub fn with_sub_nodes(mut self, sub_nodes: SubNodeRange) -> Self {
+        self.metadata.sub_nodes = Some(sub_nodes);
+        self
+    }
+
+    /// Places the chunk in a hierarchy, under `parent_id` if it has a parent.
+    pub fn with_level(mut self, level: ChunkLevel, parent_id: Option<ChunkId>) -> Self {
+        self.metadata.level = Some(level);
+        self.metadata.parent_id = parent_id;
+        self
+    }
+
+    /// Sets the context used for the chunk's embedding input.
+    pub fn with_context(mut self, context: ChunkContext) -> Self {
+        self.metadata.context = Some(context);
+        self
+    }
+
+    /// Hash of the chunk's embedding input, equal for chunks that embed to
+    /// the same vector anywhere in the repository. See [`content_hash`].
+    pub fn content_hash(&self) -> String {
+        content_hash(&self.embedding_input())
+    }
+
+    /// Whether the chunk is a file summary, whose content is generated
+    /// rather than taken from the source.
+    pub fn is_summary(&self) -> bool {
+        self.metadata.level == Some(ChunkLevel::File)
+            && self.byte_range.start == self.byte_range.end
+    }
+
+    /// The text to embed: the context header, if any, followed by the content.
+    pub fn embedding_input(&self) -> String {
+        match &self.metadata.context {
+            Some(context) => context.apply(&self.content),
+            None => self.content.clone(),
+        }
+    }
 }
 
 /// Configuration for the chunking process.
@@ -97,6 +179,12 @@ pub struct ChunkingConfig {
     pub max_chunk_size: usize,
     /// Overlap size when splitting large chunks.
     pub overlap_size: usize,
+    /// Token budget; when set, chunks are sized in tokens instead of bytes.
+    pub token_budget: Option<TokenBudget>,
+    /// Whether chunks carry a [`ChunkContext`] for their embedding input.
+    pub context_headers: bool,
+    /// Whether chunks form a file, symbol and sub-symbol hierarchy.
+    pub hierarchical: bool,
 }
 
 impl Default for ChunkingConfig {
@@ -104,7 +192,176 @@ impl Default for ChunkingConfig {
         Self {
             max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
             overlap_size: DEFAULT_OVERLAP_SIZE,
+            token_budget: None,
+            context_headers: false,
+            hierarchical: false,
+        }
+    }
+}
+
+impl ChunkingConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `text` fits in a single chunk.
+    fn fits(&self, text: &str) -> bool {
+        match &self.token_budget {
+            Some(budget) => budget.counter.count(text) <= budget.content_tokens(),
+            None => text.len() <= self.max_chunk_size,
+        }
+    }
+
+    /// Adds a [`ChunkContext`] to each chunk, for [`Chunk::embedding_input`].
+    ///
+    /// Room for the header is left within the size limits, so the embedding
+    /// input still fits the model's window.
+    pub fn with_context_headers(mut self, enabled: bool) -> Self {
+        self.context_headers = enabled;
+        self
+    }
+
+    /// Chunks hierarchically: a summary chunk per file, listing its symbols,
+    /// is the parent of each top-level symbol's chunks, which are in turn the
+    /// parents of their nested symbols' chunks. See [`ChunkLevel`].
+    pub fn with_hierarchy(mut self, enabled: bool) -> Self {
+        self.hierarchical = enabled;
+        self
+    }
+
+    /// The limits for content embedded after `context`'s header.
+    fn reserving(&self, context: Option<&ChunkContext>) -> Cow<'_, ChunkingConfig> {
+        let Some(context) = context else {
+            return Cow::Borrowed(self);
+        };
+        let header = context.header();
+        let mut config = self.clone();
+        match &mut config.token_budget {
+            Some(budget) => {
+                budget.max_tokens = budget
+                    .max_tokens
+                    .saturating_sub(budget.counter.count(&header));
+            }
+            None => {
+                config.max_chunk_size = config
+                    .max_chunk_size
+                    .saturating_sub(header.len())
+                    .max(config.overlap_size + 1);
+            }
+        }
+        Cow::Owned(config)
+    }
+
+    /// Sizes chunks in tokens so each fits the embedding model's window.
+    ///
+    /// The byte limits are ignored while a token budget is set.
+    pub fn with_token_budget(mut self, budget: TokenBudget) -> Self {
+        self.token_budget = Some(budget);
+        self
+    }
+}
+
+/// Chunk size limits measured in embedding model tokens.
+#[derive(Debug, Clone)]
+pub struct TokenBudget {
+    /// Tokenizer of the embedding model.
+    pub counter: TokenCounter,
+    /// Maximum tokens per chunk, including the model's special tokens.
+    pub max_tokens: usize,
+    /// Tokens repeated at the start of each following split.
+    pub overlap_tokens: usize,
+}
+
+impl TokenBudget {
+    /// Creates a budget matching the embedding model's window.
+    pub fn new(counter: TokenCounter) -> Self {
+        Self {
+            counter,
+            max_tokens: MODEL_MAX_TOKENS,
+            overlap_tokens: DEFAULT_OVERLAP_TOKENS,
+        }
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_overlap_tokens(mut self, overlap_tokens: usize) -> Self {
+        self.overlap_tokens = overlap_tokens;
+        self
+    }
+
+    /// Tokens available for chunk content once special tokens are added.
+    pub fn content_tokens(&self) -> usize {
+        self.max_tokens
+            .saturating_sub(self.counter.special_tokens())
+            .max(1)
+    }
+
+    /// Splits `content` into byte ranges of at most [`Self::content_tokens`]
+    /// tokens, consecutive ranges sharing `overlap_tokens` tokens.
+    ///
+    /// Ranges start and end between words where possible. A single word
+    /// longer than the budget is cut between its tokens; since part of a word
+    /// may tokenize differently, each range is re-counted before it is used.
+    fn split(&self, content: &str) -> Vec<(usize, usize)> {
+        let tokens = self.counter.tokens(content);
+        let budget = self.content_tokens();
+        if tokens.len() <= budget {
+            return vec![(0, content.len())];
+        }
+
+        // A token index starts a new word if its word differs from the previous one
+        let starts_word =
+            |i: usize| i == 0 || i >= tokens.len() || tokens[i].word != tokens[i - 1].word;
+        let byte_range = |start: usize, end: usize| {
+            let byte_start = if start == 0 { 0 } else { tokens[start].start };
+            let byte_end = if end == tokens.len() {
+                content.len()
+            } else {
+                tokens[end - 1].end
+            };
+            (byte_start, byte_end)
+        };
+        let overlap = self.overlap_tokens.min(budget - 1);
+
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        loop {
+            let mut end = (start + budget).min(tokens.len());
+            // Back off to a word boundary unless the window is a single word
+            let mut boundary = end;
+            while boundary > start + 1 && !starts_word(boundary) {
+                boundary -= 1;
+            }
+            if starts_word(boundary) {
+                end = boundary;
+            }
+            // Text cut inside a word is re-tokenized on its own; shrink until it fits
+            while end > start + 1 {
+                let (byte_start, byte_end) = byte_range(start, end);
+                if self.counter.count(&content[byte_start..byte_end]) <= budget {
+                    break;
+                }
+                end -= 1;
+            }
+
+            let (byte_start, byte_end) = byte_range(start, end);
+            ranges.push((byte_start, byte_end));
+            if end == tokens.len() {
+                break;
+            }
+
+            // Step back by the overlap, moving forward to the next word start
+            let mut next = end.saturating_sub(overlap).max(start + 1);
+            while next < end && !starts_word(next) {
+                next += 1;
+            }
+            start = next;
         }
+
+        ranges
     }
 }
 
@@ -114,6 +371,9 @@ impl Default for ChunkingConfig {
 /// 1. Creates one chunk per symbol
 /// 2. Splits large symbols into multiple chunks with overlap
 /// 3. Falls back to a file-level chunk if no symbols are extracted
+///
+/// In hierarchical mode, a file summary chunk comes first and every chunk
+/// records its level and parent.
 pub fn create_chunks(
     file_path: &str,
     source_code: &str,
@@ -123,19 +383,542 @@ pub fn create_chunks(
 ) -> Vec<Chunk> {
     // If no symbols, create a file-level chunk
     if symbols.is_empty() {
-        return create_file_chunk(file_path, source_code, language, config);
+        let context = config.context_headers.then(|| ChunkContext::new(file_path));
+        let file_config = config.reserving(context.as_ref());
+        let chunks = create_file_chunk(file_path, source_code, language, &file_config);
+        return file_level(attach_context(chunks, context), config);
     }
 
-    let mut chunks = Vec::new();
+    let mut symbol_chunks = Vec::new();
 
     for symbol in symbols {
+        let context = config
+            .context_headers
+            .then(|| ChunkContext::for_symbol(file_path, symbol, symbols));
+        let symbol_config = config.reserving(context.as_ref());
         let symbol_content = &source_code[symbol.range.start..symbol.range.end];
-        let symbol_chunks =
-            create_symbol_chunks(file_path, language, symbol, symbol_content, config);
-        chunks.extend(symbol_chunks);
+        let chunks =
+            create_symbol_chunks(file_path, language, symbol, symbol_content, &symbol_config);
+        symbol_chunks.push(attach_context(chunks, context));
+    }
+
+    link_hierarchy(
+        file_path,
+        source_code,
+        language,
+        symbols,
+        symbol_chunks,
+        config,
+    )
+}
+
+/// Like [`create_chunks`], but splits large symbols at syntax node boundaries.
+///
+/// A symbol that doesn't fit in one chunk is cut between its child nodes
+/// (statements, methods inside a class, match arms), descending into a child
+/// that is itself too large. Only a single node that has no children small
+/// enough is cut at line boundaries, and a single line that is too large falls
+/// back to the windowed splitting of [`create_chunks`]. Comments stay with the
+/// node that follows them. Node splits don't overlap.
+///
+/// Without symbols, the file is split between its top-level nodes. Context
+/// headers include the symbol's doc comment. Hierarchical mode links chunks
+/// as in [`create_chunks`], adding sub-symbol chunks for the methods of
+/// classes, impl blocks and traits that are not symbols themselves.
+pub fn create_chunks_with_tree(
+    file_path: &str,
+    source_code: &str,
+    language: Language,
+    tree: &Tree,
+    symbols: &[Symbol],
+    config: &ChunkingConfig,
+) -> Vec<Chunk> {
+    let root = tree.root_node();
+
+    let with_members;
+    let symbols = if config.hierarchical {
+        with_members = [symbols, &member_symbols(tree, source_code, symbols)[..]].concat();
+        &with_members[..]
+    } else {
+        symbols
+    };
+
+    if symbols.is_empty() {
+        let context = config.context_headers.then(|| ChunkContext::new(file_path));
+        let file_config = config.reserving(context.as_ref());
+        let range = ByteRange::new(0, source_code.len());
+        let segments = node_segments(source_code, root, range, &file_config);
+        let num_splits = segments.len();
+        let chunks = segments
+            .into_iter()
+            .enumerate()
+            .map(|(i, (range, sub_nodes))| {
+                let chunk = Chunk::new(
+                    file_path,
+                    language,
+                    range,
+                    &source_code[range.start..range.end],
+                );
+                split_chunk(chunk, i, num_splits, sub_nodes)
+            })
+            .collect();
+        return file_level(attach_context(chunks, context), config);
+    }
+
+    let mut symbol_chunks = Vec::new();
+
+    for symbol in symbols {
+        let context = config.context_headers.then(|| {
+            ChunkContext::for_symbol_in_tree(
+                file_path,
+                symbol,
+                symbols,
+                tree,
+                source_code,
+                language,
+            )
+        });
+        let symbol_config = config.reserving(context.as_ref());
+        let node = root
+            .descendant_for_byte_range(symbol.range.start, symbol.range.end)
+            .unwrap_or(root);
+        let segments = node_segments(source_code, node, symbol.range, &symbol_config);
+        let num_splits = segments.len();
+
+        let chunks = segments
+            .into_iter()
+            .enumerate()
+            .map(|(i, (range, sub_nodes))| {
+                let chunk = Chunk::new(
+                    file_path,
+                    language,
+                    range,
+                    &source_code[range.start..range.end],
+                )
+                .with_symbol(symbol);
+                split_chunk(chunk, i, num_splits, sub_nodes)
+            })
+            .collect();
+        symbol_chunks.push(attach_context(chunks, context));
+    }
+
+    link_hierarchy(
+        file_path,
+        source_code,
+        language,
+        symbols,
+        symbol_chunks,
+        config,
+    )
+}
+
+/// Node kinds of methods inside a class, impl block or trait.
+const MEMBER_KINDS: &[&str] = &[
+    "function_item",
+    "function_signature_item",
+    "function_definition",
+    "method_definition",
+];
+
+/// Methods of the class-like symbols that are not among `symbols`.
+fn member_symbols(tree: &Tree, source_code: &str, symbols: &[Symbol]) -> Vec<Symbol> {
+    let root = tree.root_node();
+    let mut seen: HashSet<usize> = symbols.iter().map(|s| s.range.start).collect();
+    let mut members = Vec::new();
+
+    for symbol in symbols {
+        if !matches!(
+            symbol.kind,
+            SymbolKind::Class | SymbolKind::Impl | SymbolKind::Trait | SymbolKind::Interface
+        ) {
+            continue;
+        }
+        let Some(node) = root.descendant_for_byte_range(symbol.range.start, symbol.range.end)
+        else {
+            continue;
+        };
+        for member in find_nodes_by_kinds(node, MEMBER_KINDS) {
+            let range = node_byte_range(member);
+            if range == symbol.range || !seen.insert(range.start) {
+                continue;
+            }
+            let Some(name_node) = child_by_field(member, "name") else {
+                continue;
+            };
+            members.push(Symbol {
+                kind: SymbolKind::Method,
+                name: node_text(name_node, source_code).to_string(),
+                range,
+                name_range: node_byte_range(name_node),
+                signature: None,
+                visibility: Visibility::default(),
+                is_async: false,
+                is_test: symbol.is_test,
+                metrics: SymbolMetrics::default(),
+                attributes: Vec::new(),
+                qualified_name: None,
+            });
+        }
     }
 
+    members
+}
+
+/// Marks the chunks of a file without symbols as file level, in hierarchical mode.
+fn file_level(chunks: Vec<Chunk>, config: &ChunkingConfig) -> Vec<Chunk> {
+    if !config.hierarchical {
+        return chunks;
+    }
     chunks
+        .into_iter()
+        .map(|chunk| chunk.with_level(ChunkLevel::File, None))
+        .collect()
+}
+
+/// Flattens the chunks of each symbol, in hierarchical mode preceded by a
+/// file summary chunk and linked to their parents.
+///
+/// A split symbol's parent is the first chunk of the enclosing symbol.
+fn link_hierarchy(
+    file_path: &str,
+    source_code: &str,
+    language: Language,
+    symbols: &[Symbol],
+    symbol_chunks: Vec<Vec<Chunk>>,
+    config: &ChunkingConfig,
+) -> Vec<Chunk> {
+    if !config.hierarchical {
+        return symbol_chunks.into_iter().flatten().collect();
+    }
+
+    let context = config.context_headers.then(|| ChunkContext::new(file_path));
+    let summary_config = config.reserving(context.as_ref());
+    // The outline isn't text of the file, so it claims none of its bytes
+    let mut summary = Chunk::new(
+        file_path,
+        language,
+        ByteRange::new(0, 0),
+        file_outline(file_path, source_code, symbols, &summary_config),
+    )
+    .with_level(ChunkLevel::File, None);
+    if let Some(context) = context {
+        summary = summary.with_context(context);
+    }
+
+    let parents = parent_symbols(symbols);
+    let first_ids: Vec<_> = symbol_chunks
+        .iter()
+        .map(|chunks| chunks.first().map(|chunk| chunk.id.clone()))
+        .collect();
+
+    let mut chunks = Vec::with_capacity(1 + symbol_chunks.iter().map(Vec::len).sum::<usize>());
+    let summary_id = summary.id.clone();
+    chunks.push(summary);
+    for (chunks_of_symbol, parent) in symbol_chunks.into_iter().zip(parents) {
+        let (level, parent_id) = match parent {
+            Some(parent) => (ChunkLevel::SubSymbol, first_ids[parent].clone()),
+            None => (ChunkLevel::Symbol, Some(summary_id.clone())),
+        };
+        chunks.extend(
+            chunks_of_symbol
+                .into_iter()
+                .map(|chunk| chunk.with_level(level, parent_id.clone())),
+        );
+    }
+
+    chunks
+}
+
+/// The index of the innermost other symbol enclosing each symbol.
+///
+/// Symbols with identical ranges don't enclose each other.
+pub fn parent_symbols(symbols: &[Symbol]) -> Vec<Option<usize>> {
+    symbols
+        .iter()
+        .map(|symbol| {
+            symbols
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| {
+                    other.range != symbol.range
+                        && other.range.start <= symbol.range.start
+                        && other.range.end >= symbol.range.end
+                })
+                .min_by_key(|(_, other)| other.range.end - other.range.start)
+                .map(|(i, _)| i)
+        })
+        .collect()
+}
+
+/// Outline of a file for its summary chunk: the path, then the first line of
+/// each symbol in source order, indented by nesting depth.
+///
+/// Lines that would not fit the configured limits are left out.
+pub fn file_outline(
+    file_path: &str,
+    source_code: &str,
+    symbols: &[Symbol],
+    config: &ChunkingConfig,
+) -> String {
+    let parents = parent_symbols(symbols);
+    let depth = |mut i: usize| {
+        let mut depth = 0;
+        while let Some(parent) = parents[i] {
+            depth += 1;
+            i = parent;
+        }
+        depth
+    };
+
+    let mut order: Vec<_> = (0..symbols.len()).collect();
+    order.sort_by_key(|&i| {
+        let range = symbols[i].range;
+        (range.start, std::cmp::Reverse(range.end))
+    });
+
+    let mut outline = format!("{}\n", file_path);
+    for i in order {
+        let symbol = &symbols[i];
+        let first_line = source_code[symbol.range.start..symbol.range.end]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches('{')
+            .trim_end();
+        let line = format!("{}{}\n", "  ".repeat(depth(i)), first_line);
+        if !config.fits(&format!("{}{}", outline, line)) {
+            break;
+        }
+        outline.push_str(&line);
+    }
+    outline
+}
+
+/// Sets `context` on each chunk, if there is one.
+fn attach_context(chunks: Vec<Chunk>, context: Option<ChunkContext>) -> Vec<Chunk> {
+    match context {
+        Some(context) => chunks
+            .into_iter()
+            .map(|chunk| chunk.with_context(context.clone()))
+            .collect(),
+        None => chunks,
+    }
+}
+
+/// Adds split metadata to a chunk that is one of several.
+fn split_chunk(chunk: Chunk, index: usize, total: usize, sub_nodes: Option<SubNodeRange>) -> Chunk {
+    if total == 1 {
+        return chunk;
+    }
+    let chunk = chunk.with_split_info(index, total);
+    match sub_nodes {
+        Some(sub_nodes) => chunk.with_sub_nodes(sub_nodes),
+        None => chunk,
+    }
+}
+
+/// A node that a symbol may be split before.
+struct SplitUnit {
+    range: ByteRange,
+    kind: &'static str,
+    /// Too large for a chunk, with no children to split at instead.
+    oversized: bool,
+}
+
+/// Splits `range` of `node` into consecutive ranges that each fit in a chunk,
+/// cutting between child nodes where possible.
+///
+/// The returned ranges cover `range` exactly; content that already fits is
+/// returned as a single range.
+fn node_segments(
+    source: &str,
+    node: Node<'_>,
+    range: ByteRange,
+    config: &ChunkingConfig,
+) -> Vec<(ByteRange, Option<SubNodeRange>)> {
+    if config.fits(&source[range.start..range.end]) {
+        return vec![(range, None)];
+    }
+
+    let sizer = SpanSizer::new(source, range, config);
+    let mut units = Vec::new();
+    collect_units(node, range, &sizer, &mut units);
+
+    // Candidate cut points: the start of the line of each node that begins a line
+    let mut cuts = Vec::new();
+    for (i, unit) in units.iter().enumerate() {
+        let follows_comment = i > 0 && units[i - 1].kind.contains("comment");
+        if !follows_comment {
+            cuts.extend(line_start_before(source, unit.range.start));
+        }
+        if unit.oversized {
+            cuts.extend(line_starts(source, unit.range));
+        }
+    }
+    cuts.retain(|&cut| cut > range.start && cut < range.end);
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    // Greedily take the largest segment that fits
+    let mut segments = Vec::new();
+    let mut start = range.start;
+    while start < range.end {
+        let next_cuts = &cuts[cuts.partition_point(|&cut| cut <= start)..];
+        let mut end = None;
+        for &cut in next_cuts {
+            if !sizer.fits(start, cut) {
+                break;
+            }
+            end = Some(cut);
+        }
+        if sizer.fits(start, range.end) {
+            end = Some(range.end);
+        }
+        // Counts from offsets may be off where a span cuts the tokenization;
+        // confirm the segment, stepping back a cut if it doesn't fit
+        while let Some(candidate) = end {
+            if config.fits(&source[start..candidate]) {
+                break;
+            }
+            end = next_cuts.iter().rev().copied().find(|&cut| cut < candidate);
+        }
+
+        match end {
+            Some(end) => {
+                segments.push(ByteRange::new(start, end));
+                start = end;
+            }
+            None => {
+                // A single line too large for a chunk; fall back to windows
+                let end = cuts
+                    .iter()
+                    .copied()
+                    .find(|&cut| cut > start)
+                    .unwrap_or(range.end);
+                segments.extend(
+                    split_ranges(&source[start..end], config)
+                        .into_iter()
+                        .map(|(s, e)| ByteRange::new(start + s, start + e)),
+                );
+                start = end;
+            }
+        }
+    }
+
+    segments
+        .into_iter()
+        .map(|segment| (segment, sub_node_range(&units, segment)))
+        .collect()
+}
+
+/// Sizes spans of a range against the chunk limits, tokenizing the range
+/// only once.
+struct SpanSizer<'a> {
+    config: &'a ChunkingConfig,
+    /// Source offsets where the range's tokens start, when sized in tokens
+    token_starts: Option<Vec<usize>>,
+}
+
+impl<'a> SpanSizer<'a> {
+    fn new(source: &str, range: ByteRange, config: &'a ChunkingConfig) -> Self {
+        let token_starts = config.token_budget.as_ref().map(|budget| {
+            budget
+                .counter
+                .tokens(&source[range.start..range.end])
+                .iter()
+                .map(|token| range.start + token.start)
+                .collect()
+        });
+        Self {
+            config,
+            token_starts,
+        }
+    }
+
+    /// Whether the source between `start` and `end` fits in a chunk.
+    fn fits(&self, start: usize, end: usize) -> bool {
+        match (&self.token_starts, &self.config.token_budget) {
+            (Some(starts), Some(budget)) => {
+                let tokens =
+                    starts.partition_point(|&s| s < end) - starts.partition_point(|&s| s < start);
+                tokens <= budget.content_tokens()
+            }
+            _ => end - start <= self.config.max_chunk_size,
+        }
+    }
+}
+
+/// Collects the nodes to split `range` at, descending into children too
+/// large for a chunk.
+fn collect_units(
+    node: Node<'_>,
+    range: ByteRange,
+    sizer: &SpanSizer<'_>,
+    units: &mut Vec<SplitUnit>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let start = child.start_byte().max(range.start);
+        let end = child.end_byte().min(range.end);
+        if start >= end {
+            continue;
+        }
+
+        let fits = sizer.fits(start, end);
+        if !fits && child.named_child_count() > 0 {
+            let before = units.len();
+            collect_units(child, range, sizer, units);
+            if units.len() > before {
+                continue;
+            }
+        }
+        units.push(SplitUnit {
+            range: ByteRange::new(start, end),
+            kind: child.kind(),
+            oversized: !fits,
+        });
+    }
+}
+
+/// Describes the nodes a segment covers.
+fn sub_node_range(units: &[SplitUnit], segment: ByteRange) -> Option<SubNodeRange> {
+    let covered: Vec<_> = units
+        .iter()
+        .filter(|unit| unit.range.start < segment.end && unit.range.end > segment.start)
+        .collect();
+    let (first, last) = (covered.first()?, covered.last()?);
+    let node_count = covered
+        .iter()
+        .filter(|unit| unit.range.start >= segment.start)
+        .count();
+
+    Some(SubNodeRange {
+        first_kind: first.kind.to_string(),
+        last_kind: last.kind.to_string(),
+        node_count,
+        range: ByteRange::new(
+            first.range.start.max(segment.start),
+            last.range.end.min(segment.end),
+        ),
+        by_lines: node_count == 0,
+    })
+}
+
+/// The start of the line containing `pos`, if only whitespace precedes it.
+fn line_start_before(source: &str, pos: usize) -> Option<usize> {
+    let line_start = source[..pos].rfind('\n').map_or(0, |i| i + 1);
+    source[line_start..pos]
+        .trim()
+        .is_empty()
+        .then_some(line_start)
+}
+
+/// Starts of the lines after the first within `range`.
+fn line_starts(source: &str, range: ByteRange) -> impl Iterator<Item = usize> + '_ {
+    source[range.start..range.end]
+        .match_indices('\n')
+        .map(move |(i, _)| range.start + i + 1)
 }
 
 /// Creates chunks for a single symbol, splitting if necessary.
@@ -146,29 +929,40 @@ fn create_symbol_chunks(
     content: &str,
     config: &ChunkingConfig,
 ) -> Vec<Chunk> {
-    let content_size = content.len();
+    let ranges = split_ranges(content, config);
 
     // If small enough, create a single chunk
-    if content_size <= config.max_chunk_size {
+    if ranges.len() == 1 {
         let chunk = Chunk::new(file_path, language, symbol.range, content).with_symbol(symbol);
         return vec![chunk];
     }
 
     // Split the symbol into multiple chunks with overlap
-    split_symbol(file_path, language, symbol, content, config)
+    let num_splits = ranges.len();
+    ranges
+        .into_iter()
+        .enumerate()
+        .map(|(i, (start, end))| {
+            let byte_range = ByteRange::new(symbol.range.start + start, symbol.range.start + end);
+            Chunk::new(file_path, language, byte_range, &content[start..end])
+                .with_symbol(symbol)
+                .with_split_info(i, num_splits)
+        })
+        .collect()
 }
 
-/// Splits a large symbol into multiple chunks with overlap.
-fn split_symbol(
-    file_path: &str,
-    language: Language,
-    symbol: &Symbol,
-    content: &str,
-    config: &ChunkingConfig,
-) -> Vec<Chunk> {
-    let mut chunks = Vec::new();
-    let content_bytes = content.as_bytes();
-    let content_len = content_bytes.len();
+/// Splits content into overlapping byte ranges that fit the configured limits.
+///
+/// Content within the limits yields a single range covering all of it.
+fn split_ranges(content: &str, config: &ChunkingConfig) -> Vec<(usize, usize)> {
+    if let Some(budget) = &config.token_budget {
+        return budget.split(content);
+    }
+
+    let content_len = content.len();
+    if content_len <= config.max_chunk_size {
+        return vec![(0, content_len)];
+    }
 
     // Calculate effective chunk size (accounting for overlap)
     let effective_size = config.max_chunk_size - config.overlap_size;
@@ -176,28 +970,15 @@ fn split_symbol(
     // Calculate number of splits needed
     let num_splits = content_len.div_ceil(effective_size);
 
-    for i in 0..num_splits {
-        let start_offset = i * effective_size;
-        let end_offset = std::cmp::min(start_offset + config.max_chunk_size, content_len);
-
-        // Adjust to avoid splitting mid-character (UTF-8 safety)
-        let (adjusted_start, adjusted_end) =
-            find_safe_boundaries(content, start_offset, end_offset);
-
-        let chunk_content = &content[adjusted_start..adjusted_end];
-        let byte_range = ByteRange::new(
-            symbol.range.start + adjusted_start,
-            symbol.range.start + adjusted_end,
-        );
-
-        let chunk = Chunk::new(file_path, language, byte_range, chunk_content)
-            .with_symbol(symbol)
-            .with_split_info(i, num_splits);
-
-        chunks.push(chunk);
-    }
+    (0..num_splits)
+        .map(|i| {
+            let start_offset = i * effective_size;
+            let end_offset = std::cmp::min(start_offset + config.max_chunk_size, content_len);
 
-    chunks
+            // Adjust to avoid splitting mid-character (UTF-8 safety)
+            find_safe_boundaries(content, start_offset, end_offset)
+        })
+        .collect()
 }
 
 /// Finds safe UTF-8 boundaries for splitting.
@@ -235,39 +1016,23 @@ fn create_file_chunk(
     language: Language,
     config: &ChunkingConfig,
 ) -> Vec<Chunk> {
-    let content_len = source_code.len();
-
-    // If the file is small enough, create a single chunk
-    if content_len <= config.max_chunk_size {
-        let byte_range = ByteRange::new(0, content_len);
-        let chunk = Chunk::new(file_path, language, byte_range, source_code);
-        return vec![chunk];
-    }
-
-    // Split the file into multiple chunks
-    let mut chunks = Vec::new();
-    let effective_size = config.max_chunk_size - config.overlap_size;
-    let num_splits = content_len.div_ceil(effective_size);
-
-    for i in 0..num_splits {
-        let start_offset = i * effective_size;
-        let end_offset = std::cmp::min(start_offset + config.max_chunk_size, content_len);
-
-        let (adjusted_start, adjusted_end) =
-            find_safe_boundaries(source_code, start_offset, end_offset);
-
-        let chunk_content = &source_code[adjusted_start..adjusted_end];
-        let byte_range = ByteRange::new(adjusted_start, adjusted_end);
-
-        let mut chunk = Chunk::new(file_path, language, byte_range, chunk_content);
-        if num_splits > 1 {
-            chunk = chunk.with_split_info(i, num_splits);
-        }
-
-        chunks.push(chunk);
-    }
-
-    chunks
+    // Split the file into multiple chunks if it exceeds the limits
+    let ranges = split_ranges(source_code, config);
+    let num_splits = ranges.len();
+
+    ranges
+        .into_iter()
+        .enumerate()
+        .map(|(i, (start, end))| {
+            let byte_range = ByteRange::new(start, end);
+            let chunk = Chunk::new(file_path, language, byte_range, &source_code[start..end]);
+            if num_splits > 1 {
+                chunk.with_split_info(i, num_splits)
+            } else {
+                chunk
+            }
+        })
+        .collect()
 }
 
 /// Generates a deterministic chunk ID based on content and location.
@@ -289,12 +1054,20 @@ fn generate_chunk_id(file_path: &str, byte_range: &ByteRange, content: &str) ->
     ChunkId::new(format!("chunk_{}", hex)).expect("Generated chunk ID should be valid")
 }
 
+/// Hashes content alone, without path or range, as `sha256:<hex>`.
+///
+/// Unlike chunk IDs, identical content in different files or places hashes
+/// the same, so duplicates can share one embedding.
+pub fn content_hash(content: &str) -> String {
+    let hash = Sha256::digest(content.as_bytes());
+    format!("sha256:{}", hex::encode(hash))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::parsing::parse;
     use crate::symbol::extract_symbols;
-    use doctown_common::types::Visibility;
 
     // ============================================
     // Chunk Creation Tests
@@ -327,6 +1100,10 @@ mod tests {
             signature: Some("main()".to_string()),
             visibility: Visibility::Public,
             is_async: false,
+            is_test: false,
+            metrics: SymbolMetrics::default(),
+            attributes: Vec::new(),
+            qualified_name: None,
         };
 
         let chunk = Chunk::new(
@@ -430,6 +1207,7 @@ impl Point {
         let config = ChunkingConfig {
             max_chunk_size: 1024,
             overlap_size: 128,
+            ..Default::default()
         };
 
         let chunks = create_chunks("src/large.rs", &code, Language::Rust, &symbols, &config);
@@ -464,6 +1242,7 @@ impl Point {
         let config = ChunkingConfig {
             max_chunk_size: 512,
             overlap_size: 64,
+            ..Default::default()
         };
 
         let chunks = create_chunks("src/overlap.rs", &code, Language::Rust, &symbols, &config);
@@ -517,6 +1296,7 @@ impl Point {
         let config = ChunkingConfig {
             max_chunk_size: 1024,
             overlap_size: 128,
+            ..Default::default()
         };
 
         let chunks = create_chunks(
@@ -614,6 +1394,52 @@ impl Point {
         assert_ne!(chunk1.id, chunk2.id);
     }
 
+    #[test]
+    fn test_content_hash_ignores_location() {
+        let a = Chunk::new(
+            "src/a.rs",
+            Language::Rust,
+            ByteRange::new(0, 12),
+            "fn main() {}",
+        );
+        let b = Chunk::new(
+            "vendor/b.rs",
+            Language::Rust,
+            ByteRange::new(40, 52),
+            "fn main() {}",
+        );
+        let c = Chunk::new(
+            "src/a.rs",
+            Language::Rust,
+            ByteRange::new(0, 12),
+            "fn other() {}",
+        );
+
+        assert_ne!(a.id, b.id);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_ne!(a.content_hash(), c.content_hash());
+        assert!(a.content_hash().starts_with("sha256:"));
+        assert_eq!(a.content_hash().len(), "sha256:".len() + 64);
+    }
+
+    #[test]
+    fn test_content_hash_covers_embedding_input() {
+        let plain = Chunk::new(
+            "src/a.rs",
+            Language::Rust,
+            ByteRange::new(0, 12),
+            "fn main() {}",
+        );
+        let with_context = plain.clone().with_context(ChunkContext::new("src/a.rs"));
+
+        assert_eq!(with_context.content, plain.content);
+        assert_ne!(with_context.content_hash(), plain.content_hash());
+        assert_eq!(
+            with_context.content_hash(),
+            content_hash(&with_context.embedding_input())
+        );
+    }
+
     #[test]
     fn test_chunk_id_format() {
         let chunk = Chunk::new(
@@ -639,6 +1465,7 @@ impl Point {
         let config = ChunkingConfig {
             max_chunk_size: 20,
             overlap_size: 5,
+            ..Default::default()
         };
 
         // Should not panic when splitting
@@ -649,4 +1476,695 @@ impl Point {
             assert!(chunk.content.is_ascii() || !chunk.content.is_empty());
         }
     }
+
+    // ============================================
+    // Token Budget Tests
+    // ============================================
+
+    fn token_config(max_tokens: usize, overlap_tokens: usize) -> ChunkingConfig {
+        let budget = TokenBudget::new(crate::tokenizer::bundled_counter())
+            .with_max_tokens(max_tokens)
+            .with_overlap_tokens(overlap_tokens);
+        ChunkingConfig::new().with_token_budget(budget)
+    }
+
+    fn large_function() -> String {
+        let mut source = String::from("fn process_records(input: &[Record]) -> Summary {\n");
+        for i in 0..200 {
+            source.push_str(&format!(
+                "    let value_{i} = input[{i}].normalized_weight() * FACTOR;\n"
+            ));
+        }
+        source.push_str("    Summary::default()\n}\n");
+        source
+    }
+
+    #[test]
+    fn test_span_sizer_counts_tokens_between_lines() {
+        let source = large_function();
+        let config = token_config(64, 0);
+        let budget = config.token_budget.as_ref().unwrap();
+        let sizer = SpanSizer::new(&source, ByteRange::new(0, source.len()), &config);
+
+        let starts: Vec<usize> = line_starts(&source, ByteRange::new(0, source.len()))
+            .take(30)
+            .collect();
+        let mut outcomes = HashSet::new();
+        for (i, &start) in starts.iter().enumerate() {
+            for &end in &starts[i + 1..(i + 7).min(starts.len())] {
+                let fits = budget.counter.count(&source[start..end]) <= budget.content_tokens();
+                assert_eq!(sizer.fits(start, end), fits, "span {}..{}", start, end);
+                outcomes.insert(fits);
+            }
+        }
+        assert_eq!(outcomes.len(), 2, "spans both fit and overflow");
+    }
+
+    #[test]
+    fn test_token_budget_small_symbol_single_chunk() {
+        let source = "fn add(a: i32, b: i32) -> i32 { a + b }";
+        let tree = parse(source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, source, Language::Rust);
+
+        let chunks = create_chunks(
+            "src/lib.rs",
+            source,
+            Language::Rust,
+            &symbols,
+            &token_config(256, 32),
+        );
+
+        assert_eq!(chunks.len(), 1);
+        assert!(!chunks[0].metadata.is_split);
+    }
+
+    #[test]
+    fn test_token_budget_chunks_fit_model_window() {
+        let source = large_function();
+        // Well under the byte limit, but far over the token window
+        assert!(source.len() < DEFAULT_MAX_CHUNK_SIZE * 4);
+        let tree = parse(&source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Rust);
+        let config = token_config(256, 32);
+        let budget = config.token_budget.as_ref().unwrap();
+
+        let chunks = create_chunks("src/lib.rs", &source, Language::Rust, &symbols, &config);
+
+        assert!(chunks.len() > 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert!(budget.counter.count(&chunk.content) <= budget.content_tokens());
+            assert_eq!(chunk.metadata.split_index, Some(i));
+            assert_eq!(chunk.metadata.split_total, Some(chunks.len()));
+            assert_eq!(
+                chunk.metadata.symbol_name.as_deref(),
+                Some("process_records")
+            );
+            assert_eq!(
+                &source[chunk.byte_range.start..chunk.byte_range.end],
+                chunk.content
+            );
+        }
+        assert_eq!(
+            chunks.first().unwrap().byte_range.start,
+            symbols[0].range.start
+        );
+        assert_eq!(chunks.last().unwrap().byte_range.end, symbols[0].range.end);
+    }
+
+    #[test]
+    fn test_token_budget_overlap() {
+        let source = large_function();
+        let config = token_config(64, 16);
+        let budget = config.token_budget.as_ref().unwrap();
+
+        let chunks = create_chunks("src/lib.rs", &source, Language::Rust, &[], &config);
+
+        assert!(chunks.len() > 2);
+        for pair in chunks.windows(2) {
+            let (prev, next) = (&pair[0].byte_range, &pair[1].byte_range);
+            assert!(next.start < prev.end, "consecutive chunks should overlap");
+            let shared = &source[next.start..prev.end];
+            let shared_tokens = budget.counter.count(shared);
+            assert!(shared_tokens > 0 && shared_tokens <= 16);
+        }
+    }
+
+    #[test]
+    fn test_token_budget_without_overlap() {
+        let source = large_function();
+        let config = token_config(64, 0);
+
+        let chunks = create_chunks("src/lib.rs", &source, Language::Rust, &[], &config);
+
+        for pair in chunks.windows(2) {
+            assert!(pair[1].byte_range.start >= pair[0].byte_range.end);
+        }
+    }
+
+    #[test]
+    fn test_token_budget_ignores_byte_limit() {
+        let source = "fn a() {}\n".repeat(100);
+        let mut config = token_config(1024, 0);
+        config.max_chunk_size = 64;
+
+        let chunks = create_chunks("src/lib.rs", &source, Language::Rust, &[], &config);
+
+        assert_eq!(chunks.len(), 1);
+    }
+
+    #[test]
+    fn test_token_budget_single_long_word() {
+        let source = "x".repeat(2000);
+        let config = token_config(32, 4);
+        let budget = config.token_budget.as_ref().unwrap();
+
+        let chunks = create_chunks("src/lib.rs", &source, Language::Rust, &[], &config);
+
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.last().unwrap().byte_range.end, source.len());
+        for chunk in &chunks {
+            assert!(budget.counter.count(&chunk.content) <= budget.content_tokens());
+        }
+    }
+
+    // ============================================
+    // Node Boundary Split Tests
+    // ============================================
+
+    fn byte_config(max_chunk_size: usize) -> ChunkingConfig {
+        ChunkingConfig {
+            max_chunk_size,
+            overlap_size: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Asserts the chunks are contiguous and cover `range` exactly.
+    fn assert_tiles(chunks: &[Chunk], range: ByteRange, source: &str) {
+        assert_eq!(chunks.first().unwrap().byte_range.start, range.start);
+        assert_eq!(chunks.last().unwrap().byte_range.end, range.end);
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].byte_range.end, pair[1].byte_range.start);
+        }
+        for chunk in chunks {
+            assert_eq!(
+                &source[chunk.byte_range.start..chunk.byte_range.end],
+                chunk.content
+            );
+        }
+    }
+
+    #[test]
+    fn test_tree_split_at_statements() {
+        let mut source = String::from("fn process(items: &[Item]) -> u64 {\n");
+        for i in 0..40 {
+            source.push_str(&format!("    let total_{i} = items[{i}].weight() * {i};\n"));
+        }
+        source.push_str("    0\n}\n");
+        let tree = parse(&source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Rust);
+        let config = byte_config(512);
+
+        let chunks = create_chunks_with_tree(
+            "src/lib.rs",
+            &source,
+            Language::Rust,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        assert!(chunks.len() > 1);
+        assert_tiles(&chunks, symbols[0].range, &source);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert!(chunk.content.len() <= 512);
+            assert_eq!(chunk.metadata.split_index, Some(i));
+            assert_eq!(chunk.metadata.symbol_name.as_deref(), Some("process"));
+            let sub_nodes = chunk.metadata.sub_nodes.as_ref().unwrap();
+            assert!(!sub_nodes.by_lines);
+            assert!(sub_nodes.node_count > 0);
+            if i > 0 {
+                // Every later chunk starts with a whole statement
+                assert!(chunk.content.starts_with("    let total_"));
+                assert_eq!(sub_nodes.first_kind, "let_declaration");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tree_split_class_at_methods() {
+        let mut source = String::from("class Service:\n");
+        for i in 0..12 {
+            source.push_str(&format!(
+                "    # Handles request {i}\n    def handle_{i}(self, request):\n        return self.backend.call({i}, request)\n\n"
+            ));
+        }
+        let tree = parse(&source, Language::Python).unwrap();
+        let symbols: Vec<_> = extract_symbols(&tree, &source, Language::Python)
+            .into_iter()
+            .filter(|s| s.name == "Service")
+            .collect();
+        let config = byte_config(300);
+
+        let chunks = create_chunks_with_tree(
+            "app/service.py",
+            &source,
+            Language::Python,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        assert!(chunks.len() > 1);
+        assert_tiles(&chunks, symbols[0].range, &source);
+        for chunk in &chunks[1..] {
+            // Comments stay attached to the method that follows them
+            assert!(chunk.content.starts_with("    # Handles request"));
+            assert!(chunk.content.len() <= 300);
+            let sub_nodes = chunk.metadata.sub_nodes.as_ref().unwrap();
+            assert_eq!(sub_nodes.first_kind, "comment");
+            assert_eq!(sub_nodes.last_kind, "function_definition");
+        }
+    }
+
+    #[test]
+    fn test_tree_split_match_arms() {
+        let mut source = String::from("fn apply(op: Op, x: i64) -> i64 {\n    match op {\n");
+        for i in 0..30 {
+            source.push_str(&format!(
+                "        Op::Variant{i} => x.wrapping_mul({i}) + {i},\n"
+            ));
+        }
+        source.push_str("    }\n}\n");
+        let tree = parse(&source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Rust);
+        let config = byte_config(400);
+
+        let chunks = create_chunks_with_tree(
+            "src/ops.rs",
+            &source,
+            Language::Rust,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        assert!(chunks.len() > 1);
+        assert_tiles(&chunks, symbols[0].range, &source);
+        for chunk in &chunks[1..] {
+            assert!(chunk.content.starts_with("        Op::Variant"));
+            let sub_nodes = chunk.metadata.sub_nodes.as_ref().unwrap();
+            assert_eq!(sub_nodes.first_kind, "match_arm");
+        }
+    }
+
+    #[test]
+    fn test_tree_split_falls_back_to_lines() {
+        let mut source = String::from("fn banner() -> &'static str {\n    r#\"\n");
+        for i in 0..40 {
+            source.push_str(&format!("banner line number {i} of the startup text\n"));
+        }
+        source.push_str("\"#\n}\n");
+        let tree = parse(&source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Rust);
+        let config = byte_config(256);
+
+        let chunks = create_chunks_with_tree(
+            "src/banner.rs",
+            &source,
+            Language::Rust,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        assert!(chunks.len() > 1);
+        assert_tiles(&chunks, symbols[0].range, &source);
+        for chunk in &chunks {
+            assert!(chunk.content.len() <= 256);
+        }
+        // Middle chunks hold only part of the string, cut between lines
+        let middle = &chunks[1];
+        assert!(middle.content.starts_with("banner line number"));
+        assert!(middle.content.ends_with('\n'));
+        assert!(middle.metadata.sub_nodes.as_ref().unwrap().by_lines);
+    }
+
+    #[test]
+    fn test_tree_split_long_line_uses_windows() {
+        let source = format!(
+            "fn data() -> u8 {{\n    let x = {};\n    0\n}}\n",
+            "1 + ".repeat(200) + "1"
+        );
+        let tree = parse(&source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Rust);
+        let config = byte_config(128);
+
+        let chunks = create_chunks_with_tree(
+            "src/data.rs",
+            &source,
+            Language::Rust,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        assert_tiles(&chunks, symbols[0].range, &source);
+        for chunk in &chunks {
+            assert!(chunk.content.len() <= 128);
+        }
+    }
+
+    #[test]
+    fn test_tree_split_small_symbol_unchanged() {
+        let source = "fn add(a: i32, b: i32) -> i32 { a + b }";
+        let tree = parse(source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, source, Language::Rust);
+
+        let chunks = create_chunks_with_tree(
+            "src/lib.rs",
+            source,
+            Language::Rust,
+            &tree,
+            &symbols,
+            &ChunkingConfig::default(),
+        );
+
+        assert_eq!(chunks.len(), 1);
+        assert!(!chunks[0].metadata.is_split);
+        assert!(chunks[0].metadata.sub_nodes.is_none());
+    }
+
+    #[test]
+    fn test_tree_split_file_at_top_level_nodes() {
+        let source: String = (0..20)
+            .map(|i| format!("fn helper_{i}() -> u32 {{\n    {i}\n}}\n\n"))
+            .collect();
+        let tree = parse(&source, Language::Rust).unwrap();
+        let config = byte_config(128);
+
+        let chunks =
+            create_chunks_with_tree("src/lib.rs", &source, Language::Rust, &tree, &[], &config);
+
+        assert!(chunks.len() > 1);
+        assert_tiles(&chunks, ByteRange::new(0, source.len()), &source);
+        for chunk in &chunks {
+            assert!(chunk.content.starts_with("fn helper_"));
+            assert_eq!(
+                chunk.metadata.sub_nodes.as_ref().unwrap().first_kind,
+                "function_item"
+            );
+        }
+    }
+
+    #[test]
+    fn test_tree_split_with_token_budget() {
+        let source = large_function();
+        let tree = parse(&source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Rust);
+        let config = token_config(128, 16);
+        let budget = config.token_budget.as_ref().unwrap();
+
+        let chunks = create_chunks_with_tree(
+            "src/lib.rs",
+            &source,
+            Language::Rust,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        assert!(chunks.len() > 1);
+        assert_tiles(&chunks, symbols[0].range, &source);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert!(budget.counter.count(&chunk.content) <= budget.content_tokens());
+            if i > 0 {
+                assert!(chunk.content.starts_with("    let value_"));
+            }
+        }
+    }
+
+    // ============================================
+    // Context Header Tests
+    // ============================================
+
+    #[test]
+    fn test_context_headers_disabled_by_default() {
+        let source = "fn add(a: i32, b: i32) -> i32 { a + b }";
+        let tree = parse(source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, source, Language::Rust);
+
+        let chunks = create_chunks(
+            "src/lib.rs",
+            source,
+            Language::Rust,
+            &symbols,
+            &ChunkingConfig::default(),
+        );
+
+        assert!(chunks[0].metadata.context.is_none());
+        assert_eq!(chunks[0].embedding_input(), chunks[0].content);
+    }
+
+    #[test]
+    fn test_context_headers_on_every_split() {
+        let mut source = String::from("/// Saves all records.\npub fn save(store: &Store) {\n");
+        for i in 0..40 {
+            source.push_str(&format!("    store.write_record({i});\n"));
+        }
+        source.push_str("}\n");
+        let tree = parse(&source, Language::Rust).unwrap();
+        let save = extract_symbols(&tree, &source, Language::Rust);
+        let config = byte_config(512).with_context_headers(true);
+
+        let chunks = create_chunks_with_tree(
+            "src/store.rs",
+            &source,
+            Language::Rust,
+            &tree,
+            &save,
+            &config,
+        );
+
+        assert!(chunks.len() > 1);
+        assert_tiles(&chunks, save[0].range, &source);
+        for chunk in &chunks {
+            let context = chunk.metadata.context.as_ref().unwrap();
+            assert_eq!(context.file_path, "src/store.rs");
+            assert_eq!(context.doc_comment.as_deref(), Some("Saves all records."));
+
+            // The header is only in the embedding input, which still fits the limit
+            let input = chunk.embedding_input();
+            assert!(input.starts_with("File: src/store.rs\n"));
+            assert!(input.contains("Doc: Saves all records.\n"));
+            assert!(input.ends_with(&chunk.content));
+            assert!(!chunk.content.starts_with("File:"));
+            assert!(input.len() <= 512);
+        }
+    }
+
+    #[test]
+    fn test_context_headers_scope_from_enclosing_symbol() {
+        let source = "class Outer:\n    class Inner:\n        X = 1\n";
+        let tree = parse(source, Language::Python).unwrap();
+        let symbols = extract_symbols(&tree, source, Language::Python);
+        let config = ChunkingConfig::new().with_context_headers(true);
+
+        let chunks = create_chunks("app/models.py", source, Language::Python, &symbols, &config);
+
+        let inner = chunks
+            .iter()
+            .find(|c| c.metadata.symbol_name.as_deref() == Some("Inner"))
+            .unwrap();
+        assert_eq!(
+            inner.metadata.context.as_ref().unwrap().scope.as_deref(),
+            Some("Outer")
+        );
+    }
+
+    #[test]
+    fn test_context_headers_fit_token_budget() {
+        let source = large_function();
+        let tree = parse(&source, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Rust);
+        let config = token_config(128, 16).with_context_headers(true);
+        let budget = config.token_budget.as_ref().unwrap();
+
+        let chunks = create_chunks("src/lib.rs", &source, Language::Rust, &symbols, &config);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            let tokens = budget.counter.count(&chunk.embedding_input());
+            assert!(tokens <= budget.content_tokens());
+        }
+    }
+
+    #[test]
+    fn test_context_headers_file_chunk() {
+        let source = "just some text";
+        let config = ChunkingConfig::new().with_context_headers(true);
+
+        let chunks = create_chunks("notes.txt", source, Language::Rust, &[], &config);
+
+        assert_eq!(
+            chunks[0].embedding_input(),
+            "File: notes.txt\n\njust some text"
+        );
+    }
+
+    // ============================================
+    // Hierarchical Chunking Tests
+    // ============================================
+
+    const CLIENT_SOURCE: &str = r#"
+struct Client {
+    addr: String,
+}
+
+impl Client {
+    fn connect(&self) -> bool {
+        true
+    }
+
+    fn close(&self) {}
+}
+"#;
+
+    #[test]
+    fn test_hierarchy_disabled_by_default() {
+        let tree = parse(CLIENT_SOURCE, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, CLIENT_SOURCE, Language::Rust);
+
+        let chunks = create_chunks(
+            "src/client.rs",
+            CLIENT_SOURCE,
+            Language::Rust,
+            &symbols,
+            &ChunkingConfig::default(),
+        );
+
+        assert_eq!(chunks.len(), symbols.len());
+        for chunk in &chunks {
+            assert!(chunk.metadata.level.is_none());
+            assert!(chunk.metadata.parent_id.is_none());
+        }
+    }
+
+    #[test]
+    fn test_hierarchy_levels_and_parents() {
+        let tree = parse(CLIENT_SOURCE, Language::Rust).unwrap();
+        let symbols = extract_symbols(&tree, CLIENT_SOURCE, Language::Rust);
+        let config = ChunkingConfig::new().with_hierarchy(true);
+
+        let chunks = create_chunks_with_tree(
+            "src/client.rs",
+            CLIENT_SOURCE,
+            Language::Rust,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        let summary = &chunks[0];
+        assert_eq!(summary.metadata.level, Some(ChunkLevel::File));
+        assert!(summary.metadata.parent_id.is_none());
+        assert_eq!(summary.byte_range, ByteRange::new(0, 0));
+        assert!(summary.is_summary());
+        assert!(chunks[1..].iter().all(|c| !c.is_summary()));
+        assert_eq!(
+            summary.content,
+            "src/client.rs\nstruct Client\nimpl Client\n  fn connect(&self) -> bool\n  fn close(&self) {}\n"
+        );
+
+        let struct_chunk = chunks
+            .iter()
+            .find(|c| c.metadata.symbol_kind == Some(SymbolKind::Struct))
+            .unwrap();
+        let impl_chunk = chunks
+            .iter()
+            .find(|c| c.metadata.symbol_kind == Some(SymbolKind::Impl))
+            .unwrap();
+        for chunk in [struct_chunk, impl_chunk] {
+            assert_eq!(chunk.metadata.level, Some(ChunkLevel::Symbol));
+            assert_eq!(chunk.metadata.parent_id.as_ref(), Some(&summary.id));
+        }
+
+        // Methods aren't extracted as symbols, but get their own chunks
+        for method in ["connect", "close"] {
+            let chunk = chunks
+                .iter()
+                .find(|c| c.metadata.symbol_name.as_deref() == Some(method))
+                .unwrap();
+            assert_eq!(chunk.metadata.symbol_kind, Some(SymbolKind::Method));
+            assert_eq!(chunk.metadata.level, Some(ChunkLevel::SubSymbol));
+            assert_eq!(chunk.metadata.parent_id.as_ref(), Some(&impl_chunk.id));
+        }
+        assert_eq!(chunks.len(), 5);
+    }
+
+    #[test]
+    fn test_hierarchy_nested_symbols_without_tree() {
+        let source = "class Outer:\n    class Inner:\n        X = 1\n";
+        let tree = parse(source, Language::Python).unwrap();
+        let symbols = extract_symbols(&tree, source, Language::Python);
+        let config = ChunkingConfig::new().with_hierarchy(true);
+
+        let chunks = create_chunks("app/models.py", source, Language::Python, &symbols, &config);
+
+        let by_name = |name: &str| {
+            chunks
+                .iter()
+                .find(|c| c.metadata.symbol_name.as_deref() == Some(name))
+                .unwrap()
+        };
+        let outer = by_name("Outer");
+        let inner = by_name("Inner");
+        assert_eq!(outer.metadata.level, Some(ChunkLevel::Symbol));
+        assert_eq!(outer.metadata.parent_id.as_ref(), Some(&chunks[0].id));
+        assert_eq!(inner.metadata.level, Some(ChunkLevel::SubSymbol));
+        assert_eq!(inner.metadata.parent_id.as_ref(), Some(&outer.id));
+        assert_eq!(
+            chunks[0].content,
+            "app/models.py\nclass Outer:\n  class Inner:\n"
+        );
+    }
+
+    #[test]
+    fn test_hierarchy_split_parent_is_first_split() {
+        let mut source = String::from("class Store:\n");
+        for i in 0..40 {
+            source.push_str(&format!("    def write_{i}(self):\n        return {i}\n\n"));
+        }
+        let tree = parse(&source, Language::Python).unwrap();
+        let symbols = extract_symbols(&tree, &source, Language::Python);
+        let config = byte_config(512).with_hierarchy(true);
+
+        let chunks = create_chunks_with_tree(
+            "store.py",
+            &source,
+            Language::Python,
+            &tree,
+            &symbols,
+            &config,
+        );
+
+        let class_chunks: Vec<_> = chunks
+            .iter()
+            .filter(|c| c.metadata.symbol_name.as_deref() == Some("Store"))
+            .collect();
+        assert!(class_chunks.len() > 1);
+        for chunk in &class_chunks {
+            assert_eq!(chunk.metadata.level, Some(ChunkLevel::Symbol));
+            assert_eq!(chunk.metadata.parent_id.as_ref(), Some(&chunks[0].id));
+        }
+
+        let method = chunks
+            .iter()
+            .find(|c| c.metadata.symbol_name.as_deref() == Some("write_39"))
+            .unwrap();
+        assert_eq!(method.metadata.level, Some(ChunkLevel::SubSymbol));
+        assert_eq!(
+            method.metadata.parent_id.as_ref(),
+            Some(&class_chunks[0].id)
+        );
+
+        // The outline is cut to fit the chunk size
+        assert!(chunks[0].content.len() <= 512);
+        assert!(chunks[0]
+            .content
+            .starts_with("store.py\nclass Store:\n  def write_0(self):\n"));
+    }
+
+    #[test]
+    fn test_hierarchy_file_without_symbols() {
+        let config = ChunkingConfig::new().with_hierarchy(true);
+
+        let chunks = create_chunks("notes.txt", "just some text", Language::Rust, &[], &config);
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "just some text");
+        assert_eq!(chunks[0].metadata.level, Some(ChunkLevel::File));
+        assert!(!chunks[0].is_summary());
+        assert!(chunks[0].metadata.parent_id.is_none());
+    }
 }
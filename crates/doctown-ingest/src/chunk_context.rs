@@ -0,0 +1,214 @@
+//! Contextual headers for chunk embeddings.
+//!
+//! A chunk of code says little about where it came from. The header built here
+//! is prepended to the text sent to the embedding model (never to the stored
+//! chunk content) so every chunk, including the later parts of a split symbol,
+//! carries its file, enclosing scope, signature and doc comment.
+
+use doctown_common::Language;
+use tree_sitter::Tree;
+
+use crate::doc_comments::doc_comment;
+use crate::symbol::Symbol;
+
+/// Maximum length of the signature in a header, in characters.
+pub const MAX_SIGNATURE_LEN: usize = 200;
+
+/// Where a chunk comes from, for its embedding header.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChunkContext {
+    /// Path to the source file.
+    pub file_path: String,
+    /// Enclosing module or class (e.g. `my_crate::net::Client`).
+    pub scope: Option<String>,
+    /// Signature of the symbol.
+    pub signature: Option<String>,
+    /// Doc comment of the symbol.
+    pub doc_comment: Option<String>,
+}
+
+impl ChunkContext {
+    pub fn new(file_path: impl Into<String>) -> Self {
+        Self {
+            file_path: file_path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Context of a symbol, scoped by the other symbols of its file.
+    pub fn for_symbol(file_path: impl Into<String>, symbol: &Symbol, symbols: &[Symbol]) -> Self {
+        let mut context = Self::new(file_path);
+        context.scope = enclosing_scope(symbol, symbols);
+        context.signature = symbol.signature.clone();
+        context
+    }
+
+    /// Like [`Self::for_symbol`], with the symbol's doc comment from `tree`.
+    pub fn for_symbol_in_tree(
+        file_path: impl Into<String>,
+        symbol: &Symbol,
+        symbols: &[Symbol],
+        tree: &Tree,
+        source_code: &str,
+        language: Language,
+    ) -> Self {
+        let mut context = Self::for_symbol(file_path, symbol, symbols);
+        context.doc_comment = doc_comment(symbol, tree, source_code, language);
+        context
+    }
+
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    pub fn with_signature(mut self, signature: impl Into<String>) -> Self {
+        self.signature = Some(signature.into());
+        self
+    }
+
+    pub fn with_doc_comment(mut self, doc_comment: impl Into<String>) -> Self {
+        self.doc_comment = Some(doc_comment.into());
+        self
+    }
+
+    /// The header text, ending in a blank line.
+    ///
+    /// Signatures are collapsed to one line of at most [`MAX_SIGNATURE_LEN`]
+    /// characters, and only the first paragraph of the doc comment is kept.
+    pub fn header(&self) -> String {
+        let mut header = format!("File: {}\n", self.file_path);
+        if let Some(scope) = &self.scope {
+            header.push_str(&format!("Scope: {}\n", scope));
+        }
+        if let Some(signature) = &self.signature {
+            let signature = signature.split_whitespace().collect::<Vec<_>>().join(" ");
+            let signature = match signature.char_indices().nth(MAX_SIGNATURE_LEN) {
+                Some((end, _)) => format!("{}...", &signature[..end]),
+                None => signature,
+            };
+            header.push_str(&format!("Signature: {}\n", signature));
+        }
+        if let Some(doc_comment) = &self.doc_comment {
+            let summary = doc_comment
+                .split("\n\n")
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !summary.is_empty() {
+                header.push_str(&format!("Doc: {}\n", summary));
+            }
+        }
+        header.push('\n');
+        header
+    }
+
+    /// The embedding input for `content`: the header followed by the content.
+    pub fn apply(&self, content: &str) -> String {
+        let mut input = self.header();
+        input.push_str(content);
+        input
+    }
+}
+
+/// The module or class enclosing a symbol.
+///
+/// Uses the qualified name where known; otherwise the names of the symbols
+/// whose ranges contain it, outermost first (e.g. `Outer.Inner`).
+pub fn enclosing_scope(symbol: &Symbol, symbols: &[Symbol]) -> Option<String> {
+    if let Some(qualified_name) = &symbol.qualified_name {
+        return qualified_name
+            .rsplit_once("::")
+            .map(|(scope, _)| scope.to_string());
+    }
+
+    let mut enclosing: Vec<_> = symbols
+        .iter()
+        .filter(|other| {
+            other.range != symbol.range
+                && other.range.start <= symbol.range.start
+                && other.range.end >= symbol.range.end
+        })
+        .collect();
+    if enclosing.is_empty() {
+        return None;
+    }
+    enclosing.sort_by_key(|other| std::cmp::Reverse(other.range.end - other.range.start));
+    Some(
+        enclosing
+            .iter()
+            .map(|other| other.name.as_str())
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use crate::symbol::extract_symbols;
+
+    #[test]
+    fn test_header() {
+        let context = ChunkContext::new("src/net/client.rs")
+            .with_scope("my_crate::net::Client")
+            .with_signature("fn connect(\n    &self,\n    addr: &str,\n) -> Result<()>")
+            .with_doc_comment("Opens a connection.\n\nRetries twice.");
+
+        assert_eq!(
+            context.header(),
+            "File: src/net/client.rs\n\
+             Scope: my_crate::net::Client\n\
+             Signature: fn connect( &self, addr: &str, ) -> Result<()>\n\
+             Doc: Opens a connection.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_header_file_only() {
+        let context = ChunkContext::new("README.txt");
+        assert_eq!(context.header(), "File: README.txt\n\n");
+        assert_eq!(context.apply("body"), "File: README.txt\n\nbody");
+    }
+
+    #[test]
+    fn test_long_signature_truncated() {
+        let context = ChunkContext::new("a.rs").with_signature("x".repeat(500));
+        let header = context.header();
+        let line = header.lines().nth(1).unwrap();
+        assert!(line.ends_with("..."));
+        assert_eq!(line.len(), "Signature: ".len() + MAX_SIGNATURE_LEN + 3);
+    }
+
+    #[test]
+    fn test_enclosing_scope_from_ranges() {
+        let code = r#"
+class Outer:
+    class Inner:
+        X = 1
+"#;
+        let tree = parse(code, Language::Python).unwrap();
+        let symbols = extract_symbols(&tree, code, Language::Python);
+        let inner = symbols.iter().find(|s| s.name == "Inner").unwrap();
+        let outer = symbols.iter().find(|s| s.name == "Outer").unwrap();
+
+        assert_eq!(enclosing_scope(inner, &symbols).as_deref(), Some("Outer"));
+        assert_eq!(enclosing_scope(outer, &symbols), None);
+    }
+
+    #[test]
+    fn test_enclosing_scope_from_qualified_name() {
+        let code = "fn connect() {}";
+        let tree = parse(code, Language::Rust).unwrap();
+        let mut symbols = extract_symbols(&tree, code, Language::Rust);
+        symbols[0].qualified_name = Some("my_crate::net::connect".to_string());
+
+        assert_eq!(
+            enclosing_scope(&symbols[0], &symbols).as_deref(),
+            Some("my_crate::net")
+        );
+    }
+}
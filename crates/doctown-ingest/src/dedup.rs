@@ -0,0 +1,179 @@
+//! Deduplication of chunks before embedding.
+//!
+//! Copy-pasted files and vendored code produce chunks with identical text.
+//! Each distinct embedding input is embedded once, and its vector is copied
+//! to every chunk that shares it.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use doctown_common::ChunkId;
+
+use crate::chunk::content_hash;
+
+/// Chunks grouped by identical embedding input.
+#[derive(Debug, Default)]
+pub struct DedupedChunks {
+    /// One chunk per distinct input, in first-seen order.
+    unique: Vec<(ChunkId, String)>,
+    /// Chunks sharing the input of a unique chunk, keyed by that chunk's ID.
+    duplicates: HashMap<ChunkId, Vec<ChunkId>>,
+}
+
+impl DedupedChunks {
+    /// Groups chunks by the content hash of their embedding input.
+    pub fn new(chunks: &[(ChunkId, String)]) -> Self {
+        let mut deduped = Self::default();
+        let mut first_by_hash: HashMap<String, ChunkId> = HashMap::new();
+
+        for (chunk_id, input) in chunks {
+            match first_by_hash.entry(content_hash(input)) {
+                Entry::Occupied(first) => deduped
+                    .duplicates
+                    .entry(first.get().clone())
+                    .or_default()
+                    .push(chunk_id.clone()),
+                Entry::Vacant(entry) => {
+                    entry.insert(chunk_id.clone());
+                    deduped.unique.push((chunk_id.clone(), input.clone()));
+                }
+            }
+        }
+
+        deduped
+    }
+
+    /// The chunks to embed, one per distinct input.
+    pub fn unique(&self) -> &[(ChunkId, String)] {
+        &self.unique
+    }
+
+    /// Number of distinct inputs.
+    pub fn unique_count(&self) -> usize {
+        self.unique.len()
+    }
+
+    /// Number of chunks that reuse the vector of an identical chunk.
+    pub fn duplicate_count(&self) -> usize {
+        self.duplicates.values().map(Vec::len).sum()
+    }
+
+    /// Copies each vector to the chunks sharing its input.
+    ///
+    /// Each vector is followed by its copies, in the order the duplicates were seen.
+    pub fn fan_out(&self, vectors: Vec<(ChunkId, Vec<f32>)>) -> Vec<(ChunkId, Vec<f32>)> {
+        let mut fanned = Vec::with_capacity(vectors.len());
+        for (chunk_id, vector) in vectors {
+            let copies: Vec<_> = self
+                .duplicates
+                .get(&chunk_id)
+                .into_iter()
+                .flatten()
+                .map(|duplicate| (duplicate.clone(), vector.clone()))
+                .collect();
+            fanned.push((chunk_id, vector));
+            fanned.extend(copies);
+        }
+        fanned
+    }
+
+    /// Expands unique chunks that could not be embedded to include the
+    /// duplicates that were waiting on their vectors.
+    pub fn fan_out_ids(&self, chunk_ids: Vec<ChunkId>) -> Vec<ChunkId> {
+        let mut fanned = Vec::with_capacity(chunk_ids.len());
+        for chunk_id in chunk_ids {
+            let copies = self.duplicates.get(&chunk_id).cloned().unwrap_or_default();
+            fanned.push(chunk_id);
+            fanned.extend(copies);
+        }
+        fanned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &str, content: &str) -> (ChunkId, String) {
+        (ChunkId::new(id).unwrap(), content.to_string())
+    }
+
+    fn ids(vectors: &[(ChunkId, Vec<f32>)]) -> Vec<&str> {
+        vectors.iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_unique_inputs_embedded_once() {
+        let chunks = vec![
+            chunk("chunk_first", "fn main() {}"),
+            chunk("chunk_second", "fn helper() {}"),
+            chunk("chunk_third", "fn main() {}"),
+            chunk("chunk_fourth", "fn main() {}"),
+        ];
+
+        let deduped = DedupedChunks::new(&chunks);
+
+        let unique: Vec<_> = deduped.unique().iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(unique, vec!["chunk_first", "chunk_second"]);
+        assert_eq!(deduped.unique_count(), 2);
+        assert_eq!(deduped.duplicate_count(), 2);
+    }
+
+    #[test]
+    fn test_fan_out_copies_vectors() {
+        let chunks = vec![
+            chunk("chunk_first", "fn main() {}"),
+            chunk("chunk_second", "fn helper() {}"),
+            chunk("chunk_third", "fn main() {}"),
+        ];
+        let deduped = DedupedChunks::new(&chunks);
+
+        let vectors = vec![
+            (ChunkId::new("chunk_second").unwrap(), vec![0.5, 0.5]),
+            (ChunkId::new("chunk_first").unwrap(), vec![1.0, 0.0]),
+        ];
+        let fanned = deduped.fan_out(vectors);
+
+        assert_eq!(
+            ids(&fanned),
+            vec!["chunk_second", "chunk_first", "chunk_third"]
+        );
+        assert_eq!(fanned[1].1, fanned[2].1);
+    }
+
+    #[test]
+    fn test_fan_out_skips_missing_vectors() {
+        let chunks = vec![
+            chunk("chunk_first", "fn main() {}"),
+            chunk("chunk_second", "fn main() {}"),
+        ];
+        let deduped = DedupedChunks::new(&chunks);
+
+        // A failed batch leaves the unique chunk, and so its duplicates, without vectors
+        assert!(deduped.fan_out(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_fan_out_ids_includes_duplicates() {
+        let chunks = vec![
+            chunk("chunk_first", "fn main() {}"),
+            chunk("chunk_second", "fn helper() {}"),
+            chunk("chunk_third", "fn main() {}"),
+        ];
+        let deduped = DedupedChunks::new(&chunks);
+
+        let failed = deduped.fan_out_ids(vec![ChunkId::new("chunk_first").unwrap()]);
+
+        let failed: Vec<_> = failed.iter().map(ChunkId::as_str).collect();
+        assert_eq!(failed, vec!["chunk_first", "chunk_third"]);
+    }
+
+    #[test]
+    fn test_no_duplicates() {
+        let chunks = vec![chunk("chunk_first", "a"), chunk("chunk_second", "b")];
+        let deduped = DedupedChunks::new(&chunks);
+
+        assert_eq!(deduped.unique_count(), 2);
+        assert_eq!(deduped.duplicate_count(), 0);
+    }
+}
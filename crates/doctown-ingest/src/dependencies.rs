@@ -0,0 +1,838 @@
+//! Classification of imports and calls that leave the repository.
+//!
+//! Targets that don't resolve to repository symbols are classified by their
+//! import path as standard library or third-party package symbols:
+//!
+//! - Rust: `std`/`core`/`alloc` are the standard library; crates declared in a
+//!   `Cargo.toml` are third-party; other paths are the crate's own modules
+//! - Python: stdlib modules are known by name; anything that isn't relative or
+//!   a module of the repository comes from an installed package
+//! - TypeScript/JavaScript: Node built-ins (`fs`, `node:path`) are the standard
+//!   library; bare specifiers other than the repository's own packages are
+//!   npm packages
+//! - Go: paths without a domain (`net/http`) are the standard library; paths
+//!   outside the `go.mod` module are third-party
+
+use doctown_common::types::{Call, CallKind, DependencyOrigin, ExternalSymbol, Import};
+use doctown_common::Language;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::manifests::{CargoManifest, GoMod, PyProject};
+
+/// Directories never searched for manifests or modules.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "__pycache__"];
+
+/// Rust standard library crates.
+const RUST_STDLIB: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// Path segments that refer to the current Rust crate or module.
+const RUST_RELATIVE: &[&str] = &["crate", "self", "super", "Self"];
+
+/// Top-level modules of the Python standard library.
+const PYTHON_STDLIB: &[&str] = &[
+    "__future__",
+    "abc",
+    "argparse",
+    "array",
+    "ast",
+    "asyncio",
+    "base64",
+    "binascii",
+    "bisect",
+    "builtins",
+    "bz2",
+    "calendar",
+    "cmath",
+    "codecs",
+    "collections",
+    "concurrent",
+    "configparser",
+    "contextlib",
+    "contextvars",
+    "copy",
+    "csv",
+    "ctypes",
+    "dataclasses",
+    "datetime",
+    "decimal",
+    "difflib",
+    "dis",
+    "email",
+    "enum",
+    "errno",
+    "fnmatch",
+    "fractions",
+    "ftplib",
+    "functools",
+    "gc",
+    "getpass",
+    "gettext",
+    "glob",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "hmac",
+    "html",
+    "http",
+    "importlib",
+    "inspect",
+    "io",
+    "ipaddress",
+    "itertools",
+    "json",
+    "keyword",
+    "locale",
+    "logging",
+    "lzma",
+    "math",
+    "mimetypes",
+    "multiprocessing",
+    "numbers",
+    "operator",
+    "os",
+    "pathlib",
+    "pickle",
+    "platform",
+    "pprint",
+    "queue",
+    "random",
+    "re",
+    "secrets",
+    "select",
+    "selectors",
+    "shlex",
+    "shutil",
+    "signal",
+    "socket",
+    "sqlite3",
+    "ssl",
+    "stat",
+    "statistics",
+    "string",
+    "struct",
+    "subprocess",
+    "sys",
+    "sysconfig",
+    "tarfile",
+    "tempfile",
+    "textwrap",
+    "threading",
+    "time",
+    "timeit",
+    "tkinter",
+    "token",
+    "tokenize",
+    "traceback",
+    "types",
+    "typing",
+    "unicodedata",
+    "unittest",
+    "urllib",
+    "uuid",
+    "venv",
+    "warnings",
+    "weakref",
+    "xml",
+    "zipfile",
+    "zlib",
+    "zoneinfo",
+];
+
+/// Node.js built-in modules.
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// Classifies import paths as standard library, third-party or local.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyClassifier {
+    /// Packages declared in manifests, by ecosystem
+    declared: HashMap<Language, BTreeSet<String>>,
+    /// The repository's own packages and top-level modules, by ecosystem
+    local: HashMap<Language, BTreeSet<String>>,
+}
+
+impl DependencyClassifier {
+    /// Create a classifier with no manifests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a package dependency (a `Cargo.toml`, `package.json`,
+    /// `pyproject.toml`/`requirements.txt` or `go.mod` entry).
+    pub fn with_dependency(mut self, language: Language, package: impl Into<String>) -> Self {
+        self.declared
+            .entry(ecosystem(language))
+            .or_default()
+            .insert(normalize_package(language, &package.into()));
+        self
+    }
+
+    /// Mark a package or top-level module as part of the repository.
+    pub fn with_local_package(mut self, language: Language, package: impl Into<String>) -> Self {
+        self.local
+            .entry(ecosystem(language))
+            .or_default()
+            .insert(normalize_package(language, &package.into()));
+        self
+    }
+
+    /// Build a classifier from a repository checkout, reading its package
+    /// manifests and top-level Python modules.
+    pub fn build(root: &Path) -> Self {
+        let mut classifier = Self::new();
+
+        let entries = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                e.depth() == 0 || !(name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file());
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy();
+            let read = || fs::read_to_string(entry.path()).unwrap_or_default();
+            let (language, manifest) = match name.as_ref() {
+                "Cargo.toml" => (Language::Rust, cargo_manifest(&read())),
+                "package.json" => (Language::JavaScript, package_json(&read())),
+                "pyproject.toml" => (Language::Python, pyproject_manifest(&read())),
+                "go.mod" => (Language::Go, go_mod(&read())),
+                _ if name.starts_with("requirements") && name.ends_with(".txt") => {
+                    (Language::Python, requirements_txt(&read()))
+                }
+                _ if name.ends_with(".py") => {
+                    // Python modules import each other by their top-level name
+                    if let Some(module) = python_module(root, entry.path()) {
+                        classifier = classifier.with_local_package(Language::Python, module);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            for package in manifest.local {
+                classifier = classifier.with_local_package(language, package);
+            }
+            for package in manifest.dependencies {
+                classifier = classifier.with_dependency(language, package);
+            }
+        }
+        classifier
+    }
+
+    /// Classify an import path, returning the package it comes from, or None
+    /// if it refers to the repository itself.
+    pub fn classify(
+        &self,
+        module_path: &str,
+        language: Language,
+    ) -> Option<(String, DependencyOrigin)> {
+        let module_path = module_path.trim();
+        match language {
+            Language::Rust => {
+                let head = module_path.split("::").next()?;
+                if RUST_RELATIVE.contains(&head) || self.is_local(language, head) {
+                    None
+                } else if RUST_STDLIB.contains(&head) {
+                    Some((head.to_string(), DependencyOrigin::Stdlib))
+                } else if self.is_declared(language, head) {
+                    Some((head.to_string(), DependencyOrigin::ThirdParty))
+                } else {
+                    // An undeclared crate name is a module of the crate itself
+                    None
+                }
+            }
+            Language::Python => {
+                let head = module_path.split('.').next()?;
+                if head.is_empty() || self.is_local(language, head) {
+                    // Relative imports start with a dot
+                    None
+                } else if PYTHON_STDLIB.contains(&head) {
+                    Some((head.to_string(), DependencyOrigin::Stdlib))
+                } else {
+                    Some((head.to_string(), DependencyOrigin::ThirdParty))
+                }
+            }
+            Language::TypeScript | Language::JavaScript => {
+                if module_path.is_empty()
+                    || module_path.starts_with(['.', '/', '~', '#'])
+                    || module_path.starts_with("@/")
+                {
+                    return None;
+                }
+                let builtin = module_path.strip_prefix("node:").unwrap_or(module_path);
+                let builtin = builtin.split('/').next()?;
+                if module_path.starts_with("node:") || NODE_BUILTINS.contains(&builtin) {
+                    return Some((builtin.to_string(), DependencyOrigin::Stdlib));
+                }
+                let segments = if module_path.starts_with('@') { 2 } else { 1 };
+                let package: Vec<&str> = module_path.split('/').take(segments).collect();
+                let package = package.join("/");
+                (!self.is_local(language, &package))
+                    .then_some((package, DependencyOrigin::ThirdParty))
+            }
+            Language::Go => {
+                let locals = self.local.get(&Language::Go);
+                if locals.is_some_and(|modules| {
+                    modules
+                        .iter()
+                        .any(|m| module_path == m || module_path.starts_with(&format!("{}/", m)))
+                }) {
+                    return None;
+                }
+                let head = module_path.split('/').next()?;
+                if !head.contains('.') {
+                    return Some((module_path.to_string(), DependencyOrigin::Stdlib));
+                }
+                // The declared module containing the path, or its repository
+                let package = self
+                    .declared
+                    .get(&Language::Go)
+                    .into_iter()
+                    .flatten()
+                    .filter(|m| module_path == *m || module_path.starts_with(&format!("{}/", m)))
+                    .max_by_key(|m| m.len())
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        module_path.split('/').take(3).collect::<Vec<_>>().join("/")
+                    });
+                Some((package, DependencyOrigin::ThirdParty))
+            }
+        }
+    }
+
+    /// Returns the external symbols an import brings in: one per imported item,
+    /// or the module itself.
+    pub fn import_externals(&self, import: &Import, language: Language) -> Vec<ExternalSymbol> {
+        let Some((package, origin)) = self.classify(&import.module_path, language) else {
+            return Vec::new();
+        };
+        match import.imported_items.as_deref() {
+            Some(items) if !items.is_empty() => items
+                .iter()
+                .map(|item| {
+                    ExternalSymbol::new(
+                        join_path(&import.module_path, item, language),
+                        package.clone(),
+                        origin,
+                    )
+                })
+                .collect(),
+            _ => vec![ExternalSymbol::new(
+                import.module_path.trim(),
+                package,
+                origin,
+            )],
+        }
+    }
+
+    /// Returns the external symbol an unresolved call targets, found through
+    /// the import binding the call's first segment (or its receiver type).
+    ///
+    /// Calls of methods on external types name the type: `Client::new()` after
+    /// `use reqwest::Client` and `client.get()` on a `Client` both become
+    /// `reqwest::Client`.
+    pub fn call_external(
+        &self,
+        call: &Call,
+        imports: &[Import],
+        language: Language,
+    ) -> Option<ExternalSymbol> {
+        if call.is_resolved {
+            return None;
+        }
+        let segments: Vec<&str> = call
+            .name
+            .split(['.', ':'])
+            .filter(|s| !s.is_empty())
+            .collect();
+        let (head, rest) = match (&call.kind, &call.receiver_type) {
+            (CallKind::Method, Some(receiver_type)) => (receiver_type.as_str(), &[][..]),
+            _ => {
+                let (head, rest) = segments.split_first()?;
+                (*head, rest)
+            }
+        };
+
+        let path = imports
+            .iter()
+            .find_map(|import| bound_path(import, head, language))
+            // Rust paths may name a crate directly: `reqwest::get()`, `std::fs::read()`
+            .or_else(|| (language == Language::Rust && !rest.is_empty()).then(|| head.to_string()))?;
+        let (package, origin) = self.classify(&path, language)?;
+
+        let mut name = path;
+        let mut rest = rest;
+        // Methods are reported as the type they belong to
+        if let [init @ .., _] = rest {
+            let owner = init.last().copied().unwrap_or(head);
+            if owner.starts_with(char::is_uppercase) {
+                rest = init;
+            }
+        }
+        for segment in rest {
+            name = join_path(&name, segment, language);
+        }
+        Some(ExternalSymbol::new(name, package, origin))
+    }
+
+    /// Returns the external symbol one of `imports` binds `name` to, if any:
+    /// `join` after `from os.path import join` is `os.path.join`.
+    pub fn bound_external(
+        &self,
+        name: &str,
+        imports: &[Import],
+        language: Language,
+    ) -> Option<ExternalSymbol> {
+        let path = imports
+            .iter()
+            .find_map(|import| bound_path(import, name, language))?;
+        let (package, origin) = self.classify(&path, language)?;
+        Some(ExternalSymbol::new(path, package, origin))
+    }
+
+    /// Returns the external symbols a file's unresolved calls and imports refer
+    /// to, sorted and without duplicates.
+    pub fn externals(
+        &self,
+        calls: &[Call],
+        imports: &[Import],
+        language: Language,
+    ) -> Vec<ExternalSymbol> {
+        let mut externals: Vec<ExternalSymbol> = imports
+            .iter()
+            .flat_map(|import| self.import_externals(import, language))
+            .chain(
+                calls
+                    .iter()
+                    .filter_map(|call| self.call_external(call, imports, language)),
+            )
+            .collect();
+        externals.sort_by(|a, b| a.name.cmp(&b.name));
+        externals.dedup();
+        externals
+    }
+
+    fn is_local(&self, language: Language, package: &str) -> bool {
+        self.local
+            .get(&ecosystem(language))
+            .is_some_and(|packages| packages.contains(&normalize_package(language, package)))
+    }
+
+    fn is_declared(&self, language: Language, package: &str) -> bool {
+        self.declared
+            .get(&ecosystem(language))
+            .is_some_and(|packages| packages.contains(&normalize_package(language, package)))
+    }
+}
+
+/// TypeScript and JavaScript share npm packages.
+fn ecosystem(language: Language) -> Language {
+    match language {
+        Language::TypeScript => Language::JavaScript,
+        other => other,
+    }
+}
+
+/// Normalize a package name to the form used in imports: Rust and Python
+/// names use underscores where manifests may use hyphens.
+fn normalize_package(language: Language, package: &str) -> String {
+    match language {
+        Language::Rust => package.replace('-', "_"),
+        Language::Python => package.to_lowercase().replace('-', "_"),
+        _ => package.to_string(),
+    }
+}
+
+/// Returns the path an import binds `name` to, if it binds it.
+fn bound_path(import: &Import, name: &str, language: Language) -> Option<String> {
+    let module = import.module_path.trim();
+    let items = import.imported_items.as_deref().unwrap_or_default();
+    if items.iter().any(|item| item == name) {
+        return Some(join_path(module, name, language));
+    }
+    if import.alias.as_deref() == Some(name) {
+        return Some(module.to_string());
+    }
+    if !items.is_empty() || import.is_wildcard {
+        return None;
+    }
+    match language {
+        Language::Rust => (module.rsplit("::").next() == Some(name)).then(|| module.to_string()),
+        // `import os.path` binds `os`
+        Language::Python => (module.split('.').next() == Some(name)).then(|| name.to_string()),
+        Language::Go => (module.rsplit('/').next() == Some(name)).then(|| module.to_string()),
+        Language::TypeScript | Language::JavaScript => None,
+    }
+}
+
+/// Append a member to a path with the language's separator.
+fn join_path(path: &str, member: &str, language: Language) -> String {
+    let separator = if language == Language::Rust {
+        "::"
+    } else {
+        "."
+    };
+    format!("{}{}{}", path.trim(), separator, member)
+}
+
+/// Local packages and dependencies declared by a manifest.
+#[derive(Debug, Default, PartialEq)]
+struct Manifest {
+    local: Vec<String>,
+    dependencies: Vec<String>,
+}
+
+/// The crate of a `Cargo.toml` and its path dependencies are local; its other
+/// dependencies are third-party crates.
+fn cargo_manifest(text: &str) -> Manifest {
+    let manifest = CargoManifest::parse(text);
+    let (paths, crates): (Vec<_>, Vec<_>) = manifest
+        .dependencies
+        .into_iter()
+        .partition(|dependency| dependency.is_path);
+    Manifest {
+        local: [manifest.package_name, manifest.lib_name]
+            .into_iter()
+            .flatten()
+            .chain(paths.into_iter().map(|dependency| dependency.name))
+            .collect(),
+        dependencies: crates
+            .into_iter()
+            .map(|dependency| dependency.name)
+            .collect(),
+    }
+}
+
+/// Parse a `package.json`: its name and the keys of its dependency objects.
+fn package_json(text: &str) -> Manifest {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(text) else {
+        return Manifest::default();
+    };
+    let dependencies = [
+        "dependencies",
+        "devDependencies",
+        "peerDependencies",
+        "optionalDependencies",
+    ]
+    .iter()
+    .filter_map(|key| json.get(key)?.as_object())
+    .flat_map(|deps| deps.keys().cloned())
+    .collect();
+    Manifest {
+        local: json
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+            .into_iter()
+            .collect(),
+        dependencies,
+    }
+}
+
+/// The project of a `pyproject.toml` is local; its requirements are packages.
+fn pyproject_manifest(text: &str) -> Manifest {
+    let pyproject = PyProject::parse(text);
+    Manifest {
+        local: pyproject.name.into_iter().collect(),
+        dependencies: pyproject
+            .dependencies
+            .iter()
+            .filter_map(|requirement| requirement_name(requirement))
+            .collect(),
+    }
+}
+
+/// Parse a `requirements.txt`.
+fn requirements_txt(text: &str) -> Manifest {
+    Manifest {
+        local: Vec::new(),
+        dependencies: text
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or(line).trim())
+            .filter(|line| !line.starts_with('-'))
+            .filter_map(requirement_name)
+            .collect(),
+    }
+}
+
+/// The distribution name of a requirement (`requests>=2.0`, `uvicorn[standard]`).
+fn requirement_name(requirement: &str) -> Option<String> {
+    let name: String = requirement
+        .trim()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// The module of a `go.mod` is local; the modules it requires are third-party.
+fn go_mod(text: &str) -> Manifest {
+    let go_mod = GoMod::parse(text);
+    Manifest {
+        local: go_mod.module.into_iter().collect(),
+        dependencies: go_mod.requires,
+    }
+}
+
+/// The top-level module name of a Python file, relative to its source root.
+///
+/// Packages are directories with an `__init__.py`; the module is the outermost
+/// package containing the file (or the file itself).
+fn python_module(root: &Path, file: &Path) -> Option<String> {
+    let mut module = file.file_stem()?.to_string_lossy().into_owned();
+    let mut dir = file.parent()?;
+    while dir != root && dir.join("__init__.py").is_file() {
+        module = dir.file_name()?.to_string_lossy().into_owned();
+        dir = dir.parent()?;
+    }
+    (module != "__init__").then_some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use doctown_common::types::ByteRange;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn import(module_path: &str, items: Option<&[&str]>, alias: Option<&str>) -> Import {
+        Import {
+            module_path: module_path.to_string(),
+            imported_items: items.map(|items| items.iter().map(|s| s.to_string()).collect()),
+            alias: alias.map(str::to_string),
+            range: ByteRange::new(0, 0),
+            is_wildcard: false,
+        }
+    }
+
+    fn call(name: &str, kind: CallKind, receiver_type: Option<&str>) -> Call {
+        Call {
+            name: name.to_string(),
+            range: ByteRange::new(0, 0),
+            kind,
+            is_resolved: false,
+            receiver_type: receiver_type.map(str::to_string),
+        }
+    }
+
+    fn names(externals: &[ExternalSymbol]) -> Vec<(&str, &str, DependencyOrigin)> {
+        externals
+            .iter()
+            .map(|e| (e.name.as_str(), e.package.as_str(), e.origin))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_externals() {
+        let classifier = DependencyClassifier::new()
+            .with_dependency(Language::Rust, "reqwest")
+            .with_dependency(Language::Rust, "serde-json")
+            .with_local_package(Language::Rust, "my-app");
+        let imports = vec![
+            import("reqwest::Client", None, None),
+            import("std::collections", Some(&["HashMap"]), None),
+            import("crate::net::connect", None, None),
+            import("my_app::config", None, None),
+            import("net::Client", None, None),
+        ];
+        let calls = vec![
+            call("Client::new", CallKind::Associated, None),
+            call("client.get", CallKind::Method, Some("Client")),
+            call("serde_json::to_string", CallKind::Associated, None),
+            call("HashMap::new", CallKind::Associated, None),
+            call("connect", CallKind::Function, None),
+        ];
+
+        assert_eq!(
+            names(&classifier.externals(&calls, &imports, Language::Rust)),
+            vec![
+                ("reqwest::Client", "reqwest", DependencyOrigin::ThirdParty),
+                (
+                    "serde_json::to_string",
+                    "serde_json",
+                    DependencyOrigin::ThirdParty
+                ),
+                ("std::collections::HashMap", "std", DependencyOrigin::Stdlib),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_externals() {
+        let classifier = DependencyClassifier::new().with_local_package(Language::Python, "myapp");
+        let imports = vec![
+            import("numpy", None, Some("np")),
+            import("os.path", None, None),
+            import("requests", Some(&["Session"]), None),
+            import("myapp.models", Some(&["User"]), None),
+            import(".util", Some(&["helper"]), None),
+        ];
+        let calls = vec![
+            call("np.array", CallKind::Method, None),
+            call("os.path.join", CallKind::Method, None),
+            call("Session", CallKind::Constructor, None),
+            call("User", CallKind::Constructor, None),
+            call("helper", CallKind::Function, None),
+        ];
+
+        let externals = classifier.externals(&calls, &imports, Language::Python);
+        assert_eq!(
+            names(&externals),
+            vec![
+                ("numpy", "numpy", DependencyOrigin::ThirdParty),
+                ("numpy.array", "numpy", DependencyOrigin::ThirdParty),
+                ("os.path", "os", DependencyOrigin::Stdlib),
+                ("os.path.join", "os", DependencyOrigin::Stdlib),
+                ("requests.Session", "requests", DependencyOrigin::ThirdParty),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_script_and_go_classification() {
+        let classifier = DependencyClassifier::new()
+            .with_local_package(Language::TypeScript, "@acme/shared")
+            .with_local_package(Language::Go, "github.com/acme/svc")
+            .with_dependency(Language::Go, "github.com/gorilla/mux");
+
+        let script = |path| classifier.classify(path, Language::TypeScript);
+        assert_eq!(script("./util"), None);
+        assert_eq!(script("@acme/shared/types"), None);
+        assert_eq!(
+            script("node:fs/promises"),
+            Some(("fs".to_string(), DependencyOrigin::Stdlib))
+        );
+        assert_eq!(
+            script("@tanstack/react-query"),
+            Some((
+                "@tanstack/react-query".to_string(),
+                DependencyOrigin::ThirdParty
+            ))
+        );
+        assert_eq!(
+            script("lodash/debounce"),
+            Some(("lodash".to_string(), DependencyOrigin::ThirdParty))
+        );
+
+        let go = |path| classifier.classify(path, Language::Go);
+        assert_eq!(go("github.com/acme/svc/db"), None);
+        assert_eq!(
+            go("net/http"),
+            Some(("net/http".to_string(), DependencyOrigin::Stdlib))
+        );
+        assert_eq!(
+            go("github.com/gorilla/mux/middleware"),
+            Some((
+                "github.com/gorilla/mux".to_string(),
+                DependencyOrigin::ThirdParty
+            ))
+        );
+
+        let calls = vec![call("http.Get", CallKind::Method, None)];
+        let imports = vec![import("net/http", None, None)];
+        assert_eq!(
+            names(&classifier.externals(&calls, &imports, Language::Go)),
+            vec![
+                ("net/http", "net/http", DependencyOrigin::Stdlib),
+                ("net/http.Get", "net/http", DependencyOrigin::Stdlib),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_reads_manifests() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[package]\nname = \"my-app\"\n\n[dependencies]\nreqwest.workspace = true\nsxd = { version = \"0.3\", features = [\"xpath\"] }\ncore-lib = { path = \"core\" }\n\n[dev-dependencies.tokio-test]\nversion = \"0.4\"\n",
+        );
+        write(
+            "package.json",
+            r#"{"name": "web", "dependencies": {"react": "^18"}, "devDependencies": {"vitest": "^1"}}"#,
+        );
+        write(
+            "pyproject.toml",
+            "[project]\nname = \"tools\"\ndependencies = [\n  \"requests>=2\",\n  \"PyYAML\",\n]\n",
+        );
+        write(
+            "go.mod",
+            "module github.com/acme/svc\n\nrequire (\n\tgithub.com/gorilla/mux v1.8.0\n)\n",
+        );
+        write("scripts/pkg/__init__.py", "");
+        write("scripts/pkg/jobs.py", "");
+
+        let classifier = DependencyClassifier::build(root);
+        assert!(classifier.is_declared(Language::Rust, "reqwest"));
+        assert!(classifier.is_declared(Language::Rust, "tokio_test"));
+        assert!(classifier.is_declared(Language::Rust, "sxd"));
+        assert!(!classifier.is_declared(Language::Rust, "core_lib"));
+        assert!(classifier.is_local(Language::Rust, "core_lib"));
+        assert!(classifier.is_local(Language::Rust, "my_app"));
+        assert!(classifier.is_declared(Language::TypeScript, "vitest"));
+        assert!(classifier.is_local(Language::JavaScript, "web"));
+        assert!(classifier.is_declared(Language::Python, "pyyaml"));
+        assert!(classifier.is_local(Language::Python, "pkg"));
+        assert_eq!(classifier.classify("pkg.jobs", Language::Python), None);
+        assert_eq!(
+            classifier.classify("github.com/gorilla/mux", Language::Go),
+            Some((
+                "github.com/gorilla/mux".to_string(),
+                DependencyOrigin::ThirdParty
+            ))
+        );
+        assert_eq!(
+            classifier.classify("github.com/acme/svc/api", Language::Go),
+            None
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Parse diagnostics: syntax error reporting for parsed files.
+//!
+//! Tree-sitter always produces a tree, recovering from syntax errors by wrapping
+//! unparseable input in ERROR nodes and inserting MISSING tokens. This module
+//! summarizes how much of a file was affected so the pipeline can report it and
+//! decide whether symbol-level chunking can be trusted.
+
+use doctown_events::LineRange;
+use tree_sitter::{Node, Tree};
+
+/// Error ratio above which a file is chunked as a whole instead of per symbol.
+pub const DEFAULT_MAX_ERROR_RATIO: f64 = 0.5;
+
+/// Summary of the syntax errors in a parsed file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseDiagnostics {
+    /// Number of outermost ERROR nodes.
+    pub error_nodes: usize,
+    /// Number of MISSING nodes.
+    pub missing_nodes: usize,
+    /// Bytes covered by ERROR nodes.
+    pub error_bytes: usize,
+    /// Total bytes in the file.
+    pub total_bytes: usize,
+    /// Merged, 1-based line ranges containing errors.
+    pub error_lines: Vec<LineRange>,
+}
+
+impl ParseDiagnostics {
+    /// Collects diagnostics from a parsed tree.
+    pub fn from_tree(tree: &Tree, source_code: &str) -> Self {
+        let mut diagnostics = Self {
+            total_bytes: source_code.len(),
+            ..Self::default()
+        };
+        let root = tree.root_node();
+        if root.has_error() {
+            diagnostics.collect(root);
+        }
+        diagnostics
+    }
+
+    fn collect(&mut self, node: Node<'_>) {
+        if node.is_error() {
+            // Nested errors are part of the outer one
+            self.error_nodes += 1;
+            self.error_bytes += node.end_byte() - node.start_byte();
+            self.push_lines(node);
+            return;
+        }
+        if node.is_missing() {
+            self.missing_nodes += 1;
+            self.push_lines(node);
+            return;
+        }
+        for child in node.children(&mut node.walk()) {
+            if child.has_error() || child.is_missing() {
+                self.collect(child);
+            }
+        }
+    }
+
+    fn push_lines(&mut self, node: Node<'_>) {
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        match self.error_lines.last_mut() {
+            Some(last) if start_line <= last.end_line + 1 => {
+                last.end_line = last.end_line.max(end_line);
+            }
+            _ => self.error_lines.push(LineRange::new(start_line, end_line)),
+        }
+    }
+
+    /// Returns true if the tree contains any ERROR or MISSING nodes.
+    pub fn has_errors(&self) -> bool {
+        self.error_nodes > 0 || self.missing_nodes > 0
+    }
+
+    /// Total number of ERROR and MISSING nodes.
+    pub fn total_nodes(&self) -> usize {
+        self.error_nodes + self.missing_nodes
+    }
+
+    /// Fraction of the file's bytes covered by ERROR nodes.
+    pub fn error_ratio(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.error_bytes as f64 / self.total_bytes as f64
+    }
+
+    /// Returns true if the error ratio is above `max_ratio`.
+    pub fn exceeds(&self, max_ratio: f64) -> bool {
+        self.error_ratio() > max_ratio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use doctown_common::Language;
+
+    #[test]
+    fn test_clean_file_has_no_errors() {
+        let code = "fn main() {}\nfn helper() {}";
+        let tree = parse(code, Language::Rust).unwrap();
+        let diagnostics = ParseDiagnostics::from_tree(&tree, code);
+
+        assert!(!diagnostics.has_errors());
+        assert_eq!(diagnostics.error_ratio(), 0.0);
+        assert!(diagnostics.error_lines.is_empty());
+    }
+
+    #[test]
+    fn test_error_nodes_and_lines() {
+        let code = "def ok():\n    pass\n\ndef broken():\n    x = $ 1\n";
+        let tree = parse(code, Language::Python).unwrap();
+        let diagnostics = ParseDiagnostics::from_tree(&tree, code);
+
+        assert!(diagnostics.has_errors());
+        assert!(diagnostics.error_nodes >= 1);
+        assert_eq!(diagnostics.error_lines, vec![LineRange::new(5, 5)]);
+        assert!(!diagnostics.exceeds(DEFAULT_MAX_ERROR_RATIO));
+    }
+
+    #[test]
+    fn test_missing_nodes() {
+        let code = "fn main() {\n    let x = 1\n}";
+        let tree = parse(code, Language::Rust).unwrap();
+        let diagnostics = ParseDiagnostics::from_tree(&tree, code);
+
+        assert_eq!(diagnostics.missing_nodes, 1);
+        assert_eq!(diagnostics.error_lines, vec![LineRange::new(2, 2)]);
+    }
+
+    #[test]
+    fn test_mostly_garbage_exceeds_threshold() {
+        let code = "@@@ ### $$$ %%% ^^^ &&& *** !!! ~~~ ``` @@@ ###\nfn f() {}";
+        let tree = parse(code, Language::Rust).unwrap();
+        let diagnostics = ParseDiagnostics::from_tree(&tree, code);
+
+        assert!(diagnostics.exceeds(DEFAULT_MAX_ERROR_RATIO));
+    }
+}
@@ -0,0 +1,266 @@
+//! Doc comment extraction.
+//!
+//! Finds the documentation attached to a symbol: Rust `///` and `/** */`
+//! comments, Python docstrings, JSDoc `/** */` blocks and Go comments directly
+//! above a declaration. Comment markers are stripped from the returned text.
+
+use doctown_common::Language;
+use tree_sitter::{Node, Tree};
+
+use crate::symbol::{symbol_node, Symbol};
+use crate::traversal::{child_by_field, node_text};
+
+/// Returns the doc comment of a Rust item (the preceding `///` or `/** */` siblings).
+fn rust_doc_comment(node: Node<'_>, source: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut current = node.prev_named_sibling();
+    while let Some(sibling) = current {
+        let text = node_text(sibling, source);
+        match sibling.kind() {
+            "attribute_item" => {}
+            "line_comment" if text.starts_with("///") && !text.starts_with("////") => {
+                lines.push(text.trim_start_matches("///").trim_end().to_string());
+            }
+            "block_comment" if text.starts_with("/**") && !text.starts_with("/***") => {
+                lines.push(strip_block_comment(text));
+            }
+            _ => break,
+        }
+        current = sibling.prev_named_sibling();
+    }
+    lines.reverse();
+    join_lines(lines)
+}
+
+/// Returns the docstring of a Python function or class.
+fn python_docstring(node: Node<'_>, source: &str) -> Option<String> {
+    // Decorated symbols span the decorated_definition wrapping them
+    let definition = match node.kind() {
+        "decorated_definition" => child_by_field(node, "definition")?,
+        _ => node,
+    };
+    let body = child_by_field(definition, "body")?;
+    let first = body.named_child(0)?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+    let string = first.named_child(0).filter(|n| n.kind() == "string")?;
+    let content: String = string
+        .named_children(&mut string.walk())
+        .filter(|child| child.kind() == "string_content")
+        .map(|child| node_text(child, source))
+        .collect();
+    join_lines(content.lines().map(str::to_string).collect())
+}
+
+/// Returns the JSDoc block directly before a TypeScript/JavaScript declaration.
+fn jsdoc_comment(node: Node<'_>, source: &str) -> Option<String> {
+    // `/** ... */ export function f() {}` documents the export statement
+    let node = node
+        .parent()
+        .filter(|parent| parent.kind() == "export_statement")
+        .unwrap_or(node);
+    let comment = node
+        .prev_named_sibling()
+        .filter(|sibling| sibling.kind() == "comment")?;
+    let text = node_text(comment, source);
+    if !text.starts_with("/**") {
+        return None;
+    }
+    join_lines(vec![strip_block_comment(text)])
+}
+
+/// Returns the `//` comments on the lines directly above a Go declaration.
+fn go_doc_comment(node: Node<'_>, source: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut next_row = node.start_position().row;
+    let mut current = node.prev_named_sibling();
+    while let Some(sibling) = current {
+        let text = node_text(sibling, source);
+        if sibling.kind() != "comment"
+            || !text.starts_with("//")
+            || sibling.end_position().row + 1 != next_row
+        {
+            break;
+        }
+        lines.push(text.trim_start_matches("//").trim_end().to_string());
+        next_row = sibling.start_position().row;
+        current = sibling.prev_named_sibling();
+    }
+    lines.reverse();
+    join_lines(lines)
+}
+
+/// Strips `/**`, `*/` and leading `*` from the lines of a block comment.
+fn strip_block_comment(text: &str) -> String {
+    let inner = text.trim_start_matches("/**").trim_end_matches("*/");
+    inner
+        .lines()
+        .map(|line| {
+            let line = line.trim_start();
+            line.strip_prefix('*').unwrap_or(line).trim_end()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Removes common indentation and surrounding blank lines.
+///
+/// The first line is left out of the common indentation, as in a docstring
+/// that starts right after the opening quotes.
+fn join_lines(lines: Vec<String>) -> Option<String> {
+    let lines: Vec<_> = lines.iter().flat_map(|l| l.split('\n')).collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let text = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line.trim(),
+            _ => line.get(indent..).unwrap_or("").trim_end(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Returns the doc comment attached to the syntax node of a symbol.
+pub fn node_doc_comment(node: Node<'_>, source_code: &str, language: Language) -> Option<String> {
+    match language {
+        Language::Rust => rust_doc_comment(node, source_code),
+        Language::Python => python_docstring(node, source_code),
+        Language::TypeScript | Language::JavaScript => jsdoc_comment(node, source_code),
+        Language::Go => go_doc_comment(node, source_code),
+    }
+}
+
+/// Returns the doc comment of a symbol, if it has one.
+pub fn doc_comment(
+    symbol: &Symbol,
+    tree: &Tree,
+    source_code: &str,
+    language: Language,
+) -> Option<String> {
+    node_doc_comment(symbol_node(tree, symbol)?, source_code, language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use crate::symbol::extract_symbols;
+
+    fn doc_for(code: &str, language: Language, name: &str) -> Option<String> {
+        let tree = parse(code, language).unwrap();
+        let symbol = extract_symbols(&tree, code, language)
+            .into_iter()
+            .find(|s| s.name == name)
+            .unwrap();
+        doc_comment(&symbol, &tree, code, language)
+    }
+
+    #[test]
+    fn test_rust_doc_comments() {
+        let code = r#"
+/// A user record.
+///
+/// Stored in the `users` table.
+#[derive(Debug)]
+pub struct User {
+    name: String,
+}
+
+/** Looks up a user. */
+pub fn find() {}
+
+// Not documentation.
+pub fn plain() {}
+
+//// Not documentation either.
+pub fn ruled() {}
+"#;
+        assert_eq!(
+            doc_for(code, Language::Rust, "User").as_deref(),
+            Some("A user record.\n\nStored in the `users` table.")
+        );
+        assert_eq!(
+            doc_for(code, Language::Rust, "find").as_deref(),
+            Some("Looks up a user.")
+        );
+        assert_eq!(doc_for(code, Language::Rust, "plain"), None);
+        assert_eq!(doc_for(code, Language::Rust, "ruled"), None);
+    }
+
+    #[test]
+    fn test_python_docstrings() {
+        let code = r#"
+class Service:
+    """Serves requests.
+
+    Thread safe.
+    """
+
+
+@cached
+def handle(request):
+    '''Handles one request.'''
+    return request
+
+def plain():
+    return 1
+"#;
+        assert_eq!(
+            doc_for(code, Language::Python, "Service").as_deref(),
+            Some("Serves requests.\n\nThread safe.")
+        );
+        assert_eq!(
+            doc_for(code, Language::Python, "handle").as_deref(),
+            Some("Handles one request.")
+        );
+        assert_eq!(doc_for(code, Language::Python, "plain"), None);
+    }
+
+    #[test]
+    fn test_jsdoc_comments() {
+        let code = r#"
+/**
+ * Formats a date.
+ * @param d the date
+ */
+export function format(d) {}
+
+// Not documentation.
+function plain() {}
+"#;
+        assert_eq!(
+            doc_for(code, Language::JavaScript, "format").as_deref(),
+            Some("Formats a date.\n@param d the date")
+        );
+        assert_eq!(doc_for(code, Language::JavaScript, "plain"), None);
+    }
+
+    #[test]
+    fn test_go_doc_comments() {
+        let code = r#"package main
+
+// Serve starts the server.
+// It blocks until shutdown.
+func Serve() {}
+
+// Detached comment.
+
+func Plain() {}
+"#;
+        assert_eq!(
+            doc_for(code, Language::Go, "Serve").as_deref(),
+            Some("Serve starts the server.\nIt blocks until shutdown.")
+        );
+        assert_eq!(doc_for(code, Language::Go, "Plain"), None);
+    }
+}
@@ -1,8 +1,43 @@
 //! Embedding client for calling the embedding worker.
+//!
+//! [`EmbeddingClient`] is the HTTP [`EmbeddingProvider`]. [`ResilientEmbedder`]
+//! wraps any provider: failed batches are retried with jittered exponential
+//! backoff. A batch the provider rejects or keeps failing on is split in half
+//! until the chunks that cannot be embedded are isolated, so one bad chunk
+//! doesn't cost the vectors of its whole batch. A circuit breaker stops
 
 use doctown_common::{ChunkId, DocError};
 use serde::{Deserialize, Serialize};
-use std::time::Instant;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::embedding_provider::{validate_vector, EmbedFuture, EmbeddingProvider};
+
+/// Model the embedding worker runs unless configured otherwise.
+pub const DEFAULT_MODEL_ID: &str = "all-MiniLM-L6-v2";
+
+/// Default number of retries after a failed request.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// Default upper bound on the delay between retries.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Default number of consecutive failed batches that opens the circuit.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Default time the circuit stays open before the worker is tried again.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Shortest time a probe of a half-open circuit keeps other calls out.
+const MIN_PROBE_TIME: Duration = Duration::from_secs(1);
 
 /// Request to embed a batch of chunks.
 #[derive(Debug, Clone, Serialize)]
@@ -36,15 +71,204 @@ pub struct EmbedResponse {
 pub struct ChunkVector {
     /// Unique identifier for the chunk.
     pub chunk_id: String,
-    /// 384-dimensional embedding vector.
+    /// Embedding vector.
     pub vector: Vec<f32>,
 }
 
+/// How failed requests are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following one.
+    pub base_delay: Duration,
+    /// Upper bound on the delay.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// The delay before retry number `retry` (0-based): the exponential
+    /// backoff, capped at `max_delay`, scaled by a random factor in [0.5, 1.0]
+    /// so concurrent batches don't retry in lockstep.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        backoff.mul_f64(0.5 + random_fraction() / 2.0)
+    }
+}
+
+/// A random number in [0.0, 1.0).
+fn random_fraction() -> f64 {
+    // RandomState is seeded randomly per instance; no RNG dependency needed
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Stops calls to a worker after repeated failures.
+///
+/// After `failure_threshold` consecutive batches fail because the worker could
+/// not be reached (connection refused, timeouts), the circuit opens
+/// and batches fail immediately for `cooldown`. After that the circuit is
+/// half-open: a single call is admitted as a probe while the others are still
+/// refused, and the probe's success closes the circuit while its failure
+/// opens it again. Clones share their state.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// When the probe of a half-open circuit was admitted, while it runs
+    probe_since: Option<Instant>,
+}
+
+/// Whether the circuit lets a call through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Admission {
+    /// The circuit is closed.
+    Closed,
+    /// The circuit is half-open and this call is its probe.
+    Probe,
+    /// The circuit is open, or half-open with a probe running.
+    Refused,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Arc::new(Mutex::new(BreakerState::default())),
+        }
+    }
+
+    /// Whether calls are currently being refused.
+    pub fn is_open(&self) -> bool {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        state.open_until.is_some_and(|open_until| now < open_until) || self.probing(&state, now)
+    }
+
+    /// Lets a call through if the circuit is closed, or as the probe of a
+    /// half-open circuit.
+    fn admit(&self) -> Admission {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match state.open_until {
+            None => Admission::Closed,
+            Some(open_until) if now < open_until => Admission::Refused,
+            Some(_) if self.probing(&state, now) => Admission::Refused,
+            Some(_) => {
+                state.probe_since = Some(now);
+                Admission::Probe
+            }
+        }
+    }
+
+    /// Whether a probe is running. A probe that hasn't reported back within
+    /// a cooldown (e.g. because its batch was dropped) no longer counts.
+    fn probing(&self, state: &BreakerState, now: Instant) -> bool {
+        state
+            .probe_since
+            .is_some_and(|since| now < since + self.cooldown.max(MIN_PROBE_TIME))
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures = 0;
+        state.open_until = None;
+        state.probe_since = None;
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.failure_threshold || state.probe_since.is_some() {
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+        state.probe_since = None;
+    }
+}
+
+/// The result of embedding a batch with [`ResilientEmbedder::embed_batch`].
+#[derive(Debug, Clone, Default)]
+pub struct BatchOutcome {
+    /// Vectors of the chunks that were embedded.
+    pub vectors: Vec<(ChunkId, Vec<f32>)>,
+    /// Chunks that could not be embedded.
+    pub failed: Vec<ChunkId>,
+    /// Requests made, including retries and bisected sub-batches.
+    pub requests: usize,
+    /// Whether the provider rate limited any request.
+    pub throttled: bool,
+    /// Total duration in milliseconds.
+    pub duration_ms: u64,
+    /// Why embedding stopped, if the provider returned vectors that can't be
+    /// right for any input (see `DocError::Config`).
+    pub error: Option<String>,
+}
+
+/// Why a batch could not be embedded.
+enum BatchFailure {
+    /// The circuit is open; the provider was not called.
+    CircuitOpen,
+    /// The provider rejected the batch, or answered it with errors until the
+    /// retries ran out; some of its inputs may be to blame.
+    Rejected(DocError),
+    /// The provider was unreachable or throttling after all retries.
+    Failed(DocError),
+    /// The provider is misconfigured; no batch will fare better.
+    Fatal(String),
+}
+
 /// Client for calling the embedding worker.
 #[derive(Clone)]
 pub struct EmbeddingClient {
     base_url: String,
     client: reqwest::Client,
+    model_id: String,
 }
 
 impl EmbeddingClient {
@@ -64,9 +288,16 @@ impl EmbeddingClient {
         Self {
             base_url: base_url.into(),
             client,
+            model_id: DEFAULT_MODEL_ID.to_string(),
         }
     }
 
+    /// Sets the ID of the model the worker runs.
+    pub fn with_model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = model_id.into();
+        self
+    }
+
     /// Check if the embedding worker is healthy.
     pub async fn health_check(&self) -> Result<bool, DocError> {
         let url = format!("{}/health", self.base_url);
@@ -113,12 +344,29 @@ impl EmbeddingClient {
             .json(&request)
             .send()
             .await
-            .map_err(|e| DocError::Internal(format!("Failed to call embedding worker: {}", e)))?;
+            .map_err(|e| DocError::Http(format!("Failed to call embedding worker: {}", e)))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if status.is_client_error()
+            && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+            && status != reqwest::StatusCode::REQUEST_TIMEOUT
+        {
+            // The batch itself was rejected; retrying it unchanged won't help
+            return Err(DocError::Validation(format!(
+                "Embedding worker rejected batch: {}",
+                status
+            )));
+        }
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(DocError::RateLimited(format!(
+                "Embedding worker returned {}",
+                status
+            )));
+        }
+        if !status.is_success() {
             return Err(DocError::Internal(format!(
                 "Embedding worker returned error: {}",
-                response.status()
+                status
             )));
         }
 
@@ -143,9 +391,554 @@ impl EmbeddingClient {
     }
 }
 
+impl EmbeddingProvider for EmbeddingClient {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embed<'a>(&'a self, batch_id: &'a str, chunks: Vec<(ChunkId, String)>) -> EmbedFuture<'a> {
+        Box::pin(async move {
+            let (vectors, _) = self.embed_batch(batch_id, chunks).await?;
+            Ok(vectors)
+        })
+    }
+}
+
+/// Embeds batches with any provider, recovering from failures.
+#[derive(Clone)]
+pub struct ResilientEmbedder {
+    provider: Arc<dyn EmbeddingProvider>,
+    retry_policy: RetryPolicy,
+    breaker: CircuitBreaker,
+    /// Dimensions of the first vectors received, or 0 before any. Clones
+    /// share them.
+    observed_dimensions: Arc<AtomicUsize>,
+}
+
+impl ResilientEmbedder {
+    pub fn new(provider: impl EmbeddingProvider + 'static) -> Self {
+        Self::from_arc(Arc::new(provider))
+    }
+
+    pub fn from_arc(provider: Arc<dyn EmbeddingProvider>) -> Self {
+        Self {
+            provider,
+            retry_policy: RetryPolicy::default(),
+            breaker: CircuitBreaker::default(),
+            observed_dimensions: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// The wrapped provider.
+    pub fn provider(&self) -> &dyn EmbeddingProvider {
+        self.provider.as_ref()
+    }
+
+    /// The circuit breaker guarding calls to the provider.
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Dimensions of the vectors: the provider's, or those of the vectors
+    /// received so far.
+    pub fn dimensions(&self) -> Option<usize> {
+        self.provider
+            .dimensions()
+            .or(match self.observed_dimensions.load(Ordering::Relaxed) {
+                0 => None,
+                observed => Some(observed),
+            })
+    }
+
+    /// Checks every vector of a batch against the dimensions of the earlier
+    /// ones, whichever provider made them.
+    fn validate(&self, vectors: &[(ChunkId, Vec<f32>)]) -> Result<(), DocError> {
+        let Some(dimensions) = self
+            .dimensions()
+            .or_else(|| vectors.first().map(|(_, vector)| vector.len()))
+        else {
+            return Ok(());
+        };
+        for (i, (_, vector)) in vectors.iter().enumerate() {
+            validate_vector(i, vector, dimensions)?;
+        }
+        let _ = self.observed_dimensions.compare_exchange(
+            0,
+            dimensions,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+        Ok(())
+    }
+
+    /// Embeds a batch, retrying failed requests and bisecting batches the
+    /// provider rejects or keeps failing on.
+    ///
+    /// Never returns an error: chunks that could not be embedded, because
+    /// they are rejected on their own, the provider kept failing or the
+    /// circuit is open, are listed in [`BatchOutcome::failed`], as are chunks
+    /// the provider returned no vector for. A misconfigured provider fails
+    /// the rest of the batch at once, with [`BatchOutcome::error`] set.
+    pub async fn embed_batch(
+        &self,
+        batch_id: impl Into<String>,
+        chunks: Vec<(ChunkId, String)>,
+    ) -> BatchOutcome {
+        let started = Instant::now();
+        let batch_id = batch_id.into();
+        let mut outcome = BatchOutcome::default();
+
+        // Sub-batches still to embed, with their IDs
+        let mut pending = vec![(batch_id, chunks)];
+        while let Some((sub_batch_id, sub_batch)) = pending.pop() {
+            let requested: Vec<ChunkId> = sub_batch.iter().map(|(id, _)| id.clone()).collect();
+            match self
+                .embed_with_retry(&sub_batch_id, &sub_batch, &mut outcome)
+                .await
+            {
+                Ok(vectors) => {
+                    let returned: HashSet<_> = vectors.iter().map(|(id, _)| id.clone()).collect();
+                    outcome.failed.extend(
+                        requested
+                            .into_iter()
+                            .filter(|chunk_id| !returned.contains(chunk_id)),
+                    );
+                    outcome.vectors.extend(vectors);
+                }
+                Err(BatchFailure::CircuitOpen) => {
+                    outcome.failed.extend(requested);
+                }
+                Err(BatchFailure::Fatal(message)) => {
+                    warn!("Embedding batch {} stopped: {}", sub_batch_id, message);
+                    outcome.failed.extend(requested);
+                    outcome.failed.extend(
+                        pending
+                            .drain(..)
+                            .flat_map(|(_, chunks)| chunks.into_iter().map(|(id, _)| id)),
+                    );
+                    outcome.error = Some(message);
+                }
+                // Smaller batches won't fare better against an unreachable or throttling provider
+                Err(BatchFailure::Failed(e)) => {
+                    warn!(
+                        "Batch {} of {} chunks could not be embedded: {}",
+                        sub_batch_id,
+                        sub_batch.len(),
+                        e
+                    );
+                    outcome.failed.extend(requested);
+                }
+                Err(BatchFailure::Rejected(e)) if sub_batch.len() > 1 => {
+                    warn!(
+                        "Batch {} of {} chunks was rejected ({}); splitting it",
+                        sub_batch_id,
+                        sub_batch.len(),
+                        e
+                    );
+                    let mut first = sub_batch;
+                    let second = first.split_off(first.len() / 2);
+                    // Pushed in reverse so the first half is embedded first
+                    pending.push((format!("{}_b", sub_batch_id), second));
+                    pending.push((format!("{}_a", sub_batch_id), first));
+                }
+                Err(BatchFailure::Rejected(e)) => {
+                    warn!("Chunk {} could not be embedded: {}", requested[0], e);
+                    outcome.failed.extend(requested);
+                }
+            }
+        }
+
+        outcome.duration_ms = started.elapsed().as_millis() as u64;
+        outcome
+    }
+
+    /// Calls the provider until it succeeds, the error is not transient, or
+    /// the retries run out.
+    ///
+    /// When the circuit is half-open, this call's first request is its probe,
+    /// and an unreachable provider fails it without retries.
+    async fn embed_with_retry(
+        &self,
+        batch_id: &str,
+        chunks: &[(ChunkId, String)],
+        outcome: &mut BatchOutcome,
+    ) -> Result<Vec<(ChunkId, Vec<f32>)>, BatchFailure> {
+        let mut retry = 0;
+        loop {
+            let admission = self.breaker.admit();
+            if admission == Admission::Refused {
+                return Err(BatchFailure::CircuitOpen);
+            }
+
+            outcome.requests += 1;
+            let result = self
+                .provider
+                .embed(batch_id, chunks.to_vec())
+                .await
+                .and_then(|vectors| self.validate(&vectors).map(|()| vectors));
+            outcome.throttled |= matches!(result, Err(DocError::RateLimited(_)));
+            match result {
+                Ok(vectors) => {
+                    self.breaker.record_success();
+                    return Ok(vectors);
+                }
+                Err(DocError::Config(message)) => {
+                    self.breaker.record_success();
+                    return Err(BatchFailure::Fatal(message));
+                }
+                // The provider answered, so it is up; the batch is the problem
+                Err(e @ DocError::Validation(_)) => {
+                    self.breaker.record_success();
+                    return Err(BatchFailure::Rejected(e));
+                }
+                Err(e @ DocError::Http(_)) if admission == Admission::Probe => {
+                    self.breaker.record_failure();
+                    return Err(BatchFailure::Failed(e));
+                }
+                Err(e) if retry >= self.retry_policy.max_retries => {
+                    return Err(match e {
+                        DocError::Http(_) => {
+                            self.breaker.record_failure();
+                            BatchFailure::Failed(e)
+                        }
+                        // The worker is up but choking on the batch, maybe on one of its inputs
+                        DocError::Internal(_) => {
+                            self.breaker.record_success();
+                            BatchFailure::Rejected(e)
+                        }
+                        _ => {
+                            self.breaker.record_success();
+                            BatchFailure::Failed(e)
+                        }
+                    });
+                }
+                Err(e) => {
+                    if admission == Admission::Probe {
+                        // An error response: the provider is reachable again
+                        self.breaker.record_success();
+                    }
+                    let delay = self.retry_policy.delay(retry);
+                    warn!(
+                        "Embedding batch {} failed ({}); retrying in {}ms",
+                        batch_id,
+                        e,
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves `/embed` on a local port, rejecting any batch with a chunk
+    /// containing "invalid", failing any with one containing "poison" like
+    /// the worker fails on a model error, and rate limiting any with one
+    /// containing "throttle", and returns the base URL.
+    async fn mock_worker() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body_start = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                let headers = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+                let content_length: usize = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|len| len.trim().parse().unwrap())
+                    .unwrap_or(0);
+                while request.len() < body_start + content_length {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let body: serde_json::Value =
+                    serde_json::from_slice(&request[body_start..]).unwrap();
+                let chunks = body["chunks"].as_array().unwrap();
+                let contains = |text: &str| {
+                    chunks
+                        .iter()
+                        .any(|chunk| chunk["content"].as_str().unwrap().contains(text))
+                };
+                let response = if contains("invalid") {
+                    "HTTP/1.1 422 Unprocessable Entity\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        .to_string()
+                } else if contains("throttle") {
+                    "HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        .to_string()
+                } else if contains("poison") {
+                    "HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        .to_string()
+                } else {
+                    let vectors: Vec<_> = chunks
+                        .iter()
+                        .map(|chunk| serde_json::json!({"chunk_id": chunk["chunk_id"], "vector": [1.0, 0.0]}))
+                        .collect();
+                    let json =
+                        serde_json::json!({"batch_id": body["batch_id"], "vectors": vectors})
+                            .to_string();
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        json.len(),
+                        json
+                    )
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy::new()
+            .with_max_retries(1)
+            .with_base_delay(Duration::from_millis(1))
+    }
+
+    fn chunks(contents: &[&str]) -> Vec<(ChunkId, String)> {
+        contents
+            .iter()
+            .enumerate()
+            .map(|(i, content)| {
+                (
+                    ChunkId::new(format!("chunk_test{:04}", i)).unwrap(),
+                    content.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_retry_delay_is_jittered_and_capped() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(1000));
+
+        for _ in 0..20 {
+            let first = policy.delay(0);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+            let third = policy.delay(2);
+            assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+            let capped = policy.delay(10);
+            assert!(capped >= Duration::from_millis(500) && capped <= Duration::from_millis(1000));
+        }
+    }
+
+    #[test]
+    fn test_circuit_breaker_opens_and_closes() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(!breaker.is_open());
+        breaker.record_failure();
+        assert!(breaker.is_open());
+
+        // Clones share state
+        let clone = breaker.clone();
+        clone.record_success();
+        assert!(!breaker.is_open());
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_open_admits_one_probe() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.record_failure();
+
+        assert_eq!(breaker.admit(), Admission::Probe);
+        assert_eq!(breaker.admit(), Admission::Refused);
+        assert!(breaker.is_open());
+
+        // A failed probe opens the circuit again, then admits a new probe
+        breaker.record_failure();
+        assert_eq!(breaker.admit(), Admission::Probe);
+        breaker.record_success();
+        assert!(!breaker.is_open());
+        assert_eq!(breaker.admit(), Admission::Closed);
+        assert_eq!(breaker.admit(), Admission::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_resilient_batch_isolates_poisoned_chunk() {
+        let client = ResilientEmbedder::new(EmbeddingClient::new(mock_worker().await))
+            .with_retry_policy(fast_retries());
+        let batch = chunks(&["fn a() {}", "fn b() {}", "poison", "fn d() {}", "fn e() {}"]);
+
+        let outcome = client.embed_batch("test_batch", batch.clone()).await;
+
+        assert_eq!(outcome.failed, vec![batch[2].0.clone()]);
+        assert_eq!(outcome.vectors.len(), 4);
+        assert!(outcome.error.is_none());
+        assert!(!client.circuit_breaker().is_open());
+    }
+
+    #[tokio::test]
+    async fn test_resilient_batch_fails_fast_when_circuit_open() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        breaker.record_failure();
+        let client = ResilientEmbedder::new(EmbeddingClient::new(mock_worker().await))
+            .with_circuit_breaker(breaker);
+        let batch = chunks(&["fn a() {}", "fn b() {}"]);
+
+        let outcome = client.embed_batch("test_batch", batch).await;
+
+        assert_eq!(outcome.failed.len(), 2);
+        assert!(outcome.vectors.is_empty());
+        assert_eq!(outcome.requests, 0);
+    }
+
+    #[tokio::test]
+    async fn test_resilient_batch_opens_circuit_when_worker_down() {
+        // Nothing listens on this port once the listener is dropped
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let client = ResilientEmbedder::new(EmbeddingClient::new(format!("http://{}", addr)))
+            .with_retry_policy(fast_retries())
+            .with_circuit_breaker(CircuitBreaker::new(2, Duration::from_secs(60)));
+        let batch = chunks(&["fn a() {}", "fn b() {}", "fn c() {}", "fn d() {}"]);
+
+        // The whole batch is retried, not split, then fails
+        let outcome = client.embed_batch("test_batch", batch.clone()).await;
+        assert_eq!(outcome.failed.len(), 4);
+        assert_eq!(outcome.requests, 2);
+        assert!(!client.circuit_breaker().is_open());
+
+        let outcome = client.embed_batch("test_batch", batch.clone()).await;
+        assert_eq!(outcome.requests, 2);
+        assert!(client.circuit_breaker().is_open());
+
+        let outcome = client.embed_batch("test_batch", batch).await;
+        assert_eq!(outcome.failed.len(), 4);
+        assert_eq!(outcome.requests, 0);
+    }
+
+    /// Returns one vector too few for every batch.
+    struct Miscounting;
+
+    impl EmbeddingProvider for Miscounting {
+        fn model_id(&self) -> &str {
+            "miscounting"
+        }
+
+        fn embed<'a>(&'a self, _: &'a str, chunks: Vec<(ChunkId, String)>) -> EmbedFuture<'a> {
+            let vectors = vec![vec![1.0]; chunks.len() - 1];
+            Box::pin(async move {
+                crate::embedding_provider::validate_vectors(&vectors, chunks.len(), None)?;
+                Ok(chunks.into_iter().map(|(id, _)| id).zip(vectors).collect())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resilient_batch_stops_on_misconfigured_provider() {
+        let client = ResilientEmbedder::new(Miscounting).with_retry_policy(fast_retries());
+        let batch = chunks(&["fn a() {}", "fn b() {}", "fn c() {}", "fn d() {}"]);
+
+        let outcome = client.embed_batch("test_batch", batch).await;
+
+        assert_eq!(outcome.failed.len(), 4);
+        assert_eq!(outcome.requests, 1);
+        assert!(outcome
+            .error
+            .unwrap()
+            .contains("Expected 4 embeddings, got 3"));
+        assert!(!client.circuit_breaker().is_open());
+    }
+
+    /// Embeds each input as a vector as long as the input, unchecked.
+    struct Unvalidated;
+
+    impl EmbeddingProvider for Unvalidated {
+        fn model_id(&self) -> &str {
+            "unvalidated"
+        }
+
+        fn embed<'a>(&'a self, _: &'a str, chunks: Vec<(ChunkId, String)>) -> EmbedFuture<'a> {
+            let vectors = chunks
+                .into_iter()
+                .map(|(id, input)| (id, vec![1.0; input.len()]))
+                .collect();
+            Box::pin(async move { Ok(vectors) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resilient_batch_checks_dimensions_across_batches() {
+        let client = ResilientEmbedder::new(Unvalidated).with_retry_policy(fast_retries());
+
+        let outcome = client.embed_batch("first", chunks(&["ab", "cd"])).await;
+        assert_eq!(outcome.vectors.len(), 2);
+        assert_eq!(client.dimensions(), Some(2));
+
+        let outcome = client.embed_batch("second", chunks(&["ef", "ghi"])).await;
+        assert!(outcome.vectors.is_empty());
+        assert_eq!(outcome.failed.len(), 2);
+        assert!(outcome
+            .error
+            .unwrap()
+            .contains("has 3 dimensions, expected 2"));
+    }
+
+    #[tokio::test]
+    async fn test_resilient_batch_isolates_rejected_chunk() {
+        let client = ResilientEmbedder::new(EmbeddingClient::new(mock_worker().await))
+            .with_retry_policy(fast_retries());
+        let batch = chunks(&["fn a() {}", "invalid", "fn c() {}", "fn d() {}"]);
+
+        let outcome = client.embed_batch("test_batch", batch.clone()).await;
+
+        // Rejections aren't retried: 1 + 2 halves + 2 quarters of the first half
+        assert_eq!(outcome.failed, vec![batch[1].0.clone()]);
+        assert_eq!(outcome.vectors.len(), 3);
+        assert_eq!(outcome.requests, 5);
+        assert!(!outcome.throttled);
+        assert!(!client.circuit_breaker().is_open());
+    }
+
+    #[tokio::test]
+    async fn test_resilient_batch_reports_throttling() {
+        let client = ResilientEmbedder::new(EmbeddingClient::new(mock_worker().await))
+            .with_retry_policy(fast_retries());
+
+        let outcome = client.embed_batch("fine", chunks(&["fn a() {}"])).await;
+        assert!(!outcome.throttled);
+
+        let outcome = client
+            .embed_batch("throttled", chunks(&["fn a() {}", "throttle"]))
+            .await;
+        assert!(outcome.throttled);
+        assert_eq!(outcome.failed.len(), 2);
+        assert_eq!(outcome.requests, 2);
+    }
 
     #[tokio::test]
     #[ignore] // Only run when embedding worker is running
@@ -0,0 +1,593 @@
+//! Persistent cache of embedding vectors.
+//!
+//! Re-ingesting a repository after a small change produces mostly the same
+//! chunks. Vectors are cached in a binary file, keyed by the model that made
+//! them and the content hash of the embedding input, so unchanged chunks are
+//! not embedded again. Least recently used entries are evicted when the cache
+//! grows past its size limit.
+//!
+//! The file is a log: saving appends the vectors added since the last save
+//! and records of the entries used, so a run that embeds little writes
+//! little. It is rewritten without stale records when they outgrow the live
+//! ones, or when entries are evicted.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use doctown_common::{ChunkId, DocError};
+use tracing::warn;
+
+use crate::chunk::content_hash;
+
+/// Default size limit of the cache, in bytes of vector data.
+pub const DEFAULT_MAX_BYTES: usize = 512 * 1024 * 1024;
+
+/// Start of the cache file: a magic number, then the format version.
+const CACHE_MAGIC: &[u8; 8] = b"DTEMBED\0";
+
+/// Version of the cache file format.
+const CACHE_VERSION: u32 = 2;
+
+const HEADER_LEN: u64 = CACHE_MAGIC.len() as u64 + 4;
+
+/// Record of a vector: the key, when it was last used and the vector.
+const VECTOR_RECORD: u8 = 1;
+
+/// Record of a use of an earlier vector: the key and when.
+const USE_RECORD: u8 = 2;
+
+/// Vectors found in the cache and the `(chunk_id, input)` pairs still to embed.
+pub type CachePartition = (Vec<(ChunkId, Vec<f32>)>, Vec<(ChunkId, String)>);
+
+/// `(model_id, content_hash)`
+type CacheKey = (String, String);
+
+/// Embedding vectors keyed by `(model_id, content_hash)`, stored in one file.
+#[derive(Debug)]
+pub struct EmbeddingCache {
+    path: PathBuf,
+    model_id: String,
+    max_bytes: usize,
+    entries: HashMap<CacheKey, CachedVector>,
+    /// Logical clock ordering accesses, for LRU eviction.
+    clock: u64,
+    /// Entries added since the last save.
+    added: HashSet<CacheKey>,
+    /// Entries used since the last save.
+    used: HashSet<CacheKey>,
+    /// Length of the file as last read or written, if it can be appended to.
+    file_len: Option<u64>,
+    hits: usize,
+    misses: usize,
+}
+
+#[derive(Debug, Clone)]
+struct CachedVector {
+    vector: Vec<f32>,
+    last_used: u64,
+}
+
+impl EmbeddingCache {
+    /// Opens the cache at `path` for vectors made by `model_id`.
+    ///
+    /// A missing file is an empty cache. So is an unreadable or outdated one,
+    /// which is overwritten on [`Self::save`]. Records cut short by an
+    /// interrupted save are ignored.
+    pub fn open(path: impl Into<PathBuf>, model_id: impl Into<String>) -> Result<Self, DocError> {
+        let path = path.into();
+        let mut cache = Self {
+            path,
+            model_id: model_id.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            entries: HashMap::new(),
+            clock: 0,
+            added: HashSet::new(),
+            used: HashSet::new(),
+            file_len: None,
+            hits: 0,
+            misses: 0,
+        };
+
+        let bytes = match fs::read(&cache.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(cache),
+            Err(e) => return Err(e.into()),
+        };
+        let mut reader = Reader::new(&bytes);
+        match (reader.take(CACHE_MAGIC.len()), reader.u32()) {
+            (Some(magic), Some(CACHE_VERSION)) if magic == CACHE_MAGIC => {}
+            (Some(magic), Some(_)) if magic == CACHE_MAGIC => {
+                warn!(
+                    "Ignoring embedding cache {} of an old version",
+                    cache.path.display()
+                );
+                return Ok(cache);
+            }
+            _ => {
+                warn!(
+                    "Ignoring unreadable embedding cache {}",
+                    cache.path.display()
+                );
+                return Ok(cache);
+            }
+        }
+
+        let mut valid_len = reader.pos;
+        while cache.read_record(&mut reader).is_some() {
+            valid_len = reader.pos;
+        }
+        if valid_len < bytes.len() {
+            warn!(
+                "Ignoring {} bytes of incomplete records in embedding cache {}",
+                bytes.len() - valid_len,
+                cache.path.display()
+            );
+        } else {
+            cache.file_len = Some(valid_len as u64);
+        }
+        Ok(cache)
+    }
+
+    /// Reads one record into the cache, or nothing at the end of the file or
+    /// of its valid records.
+    fn read_record(&mut self, reader: &mut Reader) -> Option<()> {
+        let tag = reader.u8()?;
+        let last_used = reader.u64()?;
+        let model_id = reader.string()?;
+        let content_hash = reader.string()?;
+        let key = (model_id, content_hash);
+        match tag {
+            VECTOR_RECORD => {
+                let dimensions = reader.u32()? as usize;
+                let bytes = reader.take(dimensions.checked_mul(4)?)?;
+                let vector = bytes
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                self.entries.insert(key, CachedVector { vector, last_used });
+            }
+            USE_RECORD => {
+                if let Some(cached) = self.entries.get_mut(&key) {
+                    cached.last_used = cached.last_used.max(last_used);
+                }
+            }
+            _ => return None,
+        }
+        self.clock = self.clock.max(last_used);
+        Some(())
+    }
+
+    /// Sets the size limit, in bytes of vector data, enforced on [`Self::save`].
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// The cached vector for an input with `content_hash`, if any.
+    pub fn get(&mut self, content_hash: &str) -> Option<Vec<f32>> {
+        self.clock += 1;
+        let key = (self.model_id.clone(), content_hash.to_string());
+        match self.entries.get_mut(&key) {
+            Some(cached) => {
+                cached.last_used = self.clock;
+                self.hits += 1;
+                let vector = cached.vector.clone();
+                self.used.insert(key);
+                Some(vector)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Caches the vector for an input with `content_hash`.
+    pub fn insert(&mut self, content_hash: impl Into<String>, vector: Vec<f32>) {
+        self.clock += 1;
+        let key = (self.model_id.clone(), content_hash.into());
+        self.entries.insert(
+            key.clone(),
+            CachedVector {
+                vector,
+                last_used: self.clock,
+            },
+        );
+        self.added.insert(key);
+    }
+
+    /// Splits chunks into the vectors found in the cache and the chunks
+    /// still to embed.
+    pub fn partition(&mut self, chunks: &[(ChunkId, String)]) -> CachePartition {
+        let mut cached = Vec::new();
+        let mut missing = Vec::new();
+        for (chunk_id, input) in chunks {
+            match self.get(&content_hash(input)) {
+                Some(vector) => cached.push((chunk_id.clone(), vector)),
+                None => missing.push((chunk_id.clone(), input.clone())),
+            }
+        }
+        (cached, missing)
+    }
+
+    /// Caches the vectors of embedded chunks, given the chunks' inputs.
+    pub fn store(&mut self, chunks: &[(ChunkId, String)], vectors: &[(ChunkId, Vec<f32>)]) {
+        let inputs: HashMap<_, _> = chunks.iter().map(|(id, input)| (id, input)).collect();
+        for (chunk_id, vector) in vectors {
+            if let Some(input) = inputs.get(chunk_id) {
+                self.insert(content_hash(input), vector.clone());
+            }
+        }
+    }
+
+    /// Number of lookups that found a vector.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of lookups that found nothing.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Fraction of lookups that found a vector, or 0 if there were none.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    /// Number of cached vectors, across all models.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Size of the cached vectors in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.entries.values().map(entry_size).sum()
+    }
+
+    /// Evicts least recently used entries until the cache fits its size
+    /// limit. Returns the number of entries evicted.
+    pub fn evict(&mut self) -> usize {
+        let mut size = self.size_bytes();
+        if size <= self.max_bytes {
+            return 0;
+        }
+
+        let mut by_age: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(key, cached)| (cached.last_used, key.clone()))
+            .collect();
+        by_age.sort();
+
+        let mut evicted = 0;
+        for (_, key) in by_age {
+            if size <= self.max_bytes {
+                break;
+            }
+            if let Some(cached) = self.entries.remove(&key) {
+                size -= entry_size(&cached);
+                self.added.remove(&key);
+                self.used.remove(&key);
+                evicted += 1;
+            }
+        }
+        // The file still holds the evicted vectors
+        if evicted > 0 {
+            self.file_len = None;
+        }
+        evicted
+    }
+
+    /// Evicts entries over the size limit and writes the changes since the
+    /// last save to the cache file.
+    pub fn save(&mut self) -> Result<(), DocError> {
+        self.evict();
+
+        let live_len: u64 = self
+            .entries
+            .iter()
+            .map(|(key, cached)| vector_record_len(key, &cached.vector))
+            .sum();
+        let appended = match self.file_len {
+            // Stale vectors and use records are dropped once they outweigh the live vectors
+            Some(file_len) if file_len - HEADER_LEN <= 2 * live_len => self.append()?,
+            _ => false,
+        };
+        if !appended {
+            self.rewrite()?;
+        }
+        self.added.clear();
+        self.used.clear();
+        Ok(())
+    }
+
+    /// Appends the records of the entries added or used since the last
+    /// save. Returns false, writing nothing, if the file changed since.
+    fn append(&mut self) -> Result<bool, DocError> {
+        let mut file = match OpenOptions::new().append(true).open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+        if Some(file.metadata()?.len()) != self.file_len {
+            return Ok(false);
+        }
+
+        let mut buf = Vec::new();
+        for key in &self.added {
+            if let Some(cached) = self.entries.get(key) {
+                write_vector_record(&mut buf, key, cached);
+            }
+        }
+        for key in self.used.difference(&self.added) {
+            if let Some(cached) = self.entries.get(key) {
+                write_key(&mut buf, USE_RECORD, key, cached.last_used);
+            }
+        }
+        file.write_all(&buf)?;
+        self.file_len = self.file_len.map(|len| len + buf.len() as u64);
+        Ok(true)
+    }
+
+    /// Writes every entry to a new cache file.
+    fn rewrite(&mut self) -> Result<(), DocError> {
+        let mut buf = Vec::with_capacity(HEADER_LEN as usize + self.size_bytes());
+        buf.extend_from_slice(CACHE_MAGIC);
+        buf.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+        for (key, cached) in &self.entries {
+            write_vector_record(&mut buf, key, cached);
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Written aside and renamed, so an interrupted save keeps the old cache
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, &buf)?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.file_len = Some(buf.len() as u64);
+        Ok(())
+    }
+
+    /// Path of the cache file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn entry_size(cached: &CachedVector) -> usize {
+    cached.vector.len() * std::mem::size_of::<f32>()
+}
+
+/// Writes the common start of a record: its tag, last use and key.
+fn write_key(buf: &mut Vec<u8>, tag: u8, (model_id, content_hash): &CacheKey, last_used: u64) {
+    buf.push(tag);
+    buf.extend_from_slice(&last_used.to_le_bytes());
+    for string in [model_id, content_hash] {
+        buf.extend_from_slice(&(string.len() as u32).to_le_bytes());
+        buf.extend_from_slice(string.as_bytes());
+    }
+}
+
+fn write_vector_record(buf: &mut Vec<u8>, key: &CacheKey, cached: &CachedVector) {
+    write_key(buf, VECTOR_RECORD, key, cached.last_used);
+    buf.extend_from_slice(&(cached.vector.len() as u32).to_le_bytes());
+    for x in &cached.vector {
+        buf.extend_from_slice(&x.to_le_bytes());
+    }
+}
+
+/// Length of the record [`write_vector_record`] writes.
+fn vector_record_len((model_id, content_hash): &CacheKey, vector: &[f32]) -> u64 {
+    (1 + 8 + 4 + model_id.len() + 4 + content_hash.len() + 4 + 4 * vector.len()) as u64
+}
+
+/// Reads little-endian values from the cache file.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn chunk(id: &str, input: &str) -> (ChunkId, String) {
+        (ChunkId::new(id).unwrap(), input.to_string())
+    }
+
+    #[test]
+    fn test_partition_and_store() {
+        let dir = tempdir().unwrap();
+        let mut cache =
+            EmbeddingCache::open(dir.path().join("embeddings.cache"), "minilm").unwrap();
+        let chunks = vec![
+            chunk("chunk_first", "fn a() {}"),
+            chunk("chunk_second", "fn b() {}"),
+        ];
+
+        let (cached, missing) = cache.partition(&chunks);
+        assert!(cached.is_empty());
+        assert_eq!(missing.len(), 2);
+
+        cache.store(
+            &missing,
+            &[(ChunkId::new("chunk_first").unwrap(), vec![1.0, 0.0])],
+        );
+
+        // Same input under a new chunk ID is a hit
+        let (cached, missing) = cache.partition(&[chunk("chunk_renamed", "fn a() {}")]);
+        assert_eq!(
+            cached,
+            vec![(ChunkId::new("chunk_renamed").unwrap(), vec![1.0, 0.0])]
+        );
+        assert!(missing.is_empty());
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 2);
+        assert!((cache.hit_rate() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_persists_across_opens() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("embeddings.cache");
+        let mut cache = EmbeddingCache::open(&path, "minilm").unwrap();
+        cache.insert("sha256:abc", vec![0.25, 0.5]);
+        cache.save().unwrap();
+
+        let mut reopened = EmbeddingCache::open(&path, "minilm").unwrap();
+        assert_eq!(reopened.get("sha256:abc"), Some(vec![0.25, 0.5]));
+    }
+
+    #[test]
+    fn test_keyed_by_model() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("embeddings.cache");
+        let mut cache = EmbeddingCache::open(&path, "minilm").unwrap();
+        cache.insert("sha256:abc", vec![0.25, 0.5]);
+        cache.save().unwrap();
+
+        let mut other_model = EmbeddingCache::open(&path, "bge-small").unwrap();
+        assert_eq!(other_model.get("sha256:abc"), None);
+        assert_eq!(other_model.len(), 1);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = tempdir().unwrap();
+        let mut cache = EmbeddingCache::open(dir.path().join("embeddings.cache"), "minilm")
+            .unwrap()
+            .with_max_bytes(2 * 2 * std::mem::size_of::<f32>());
+        cache.insert("sha256:old", vec![0.0, 1.0]);
+        cache.insert("sha256:used", vec![1.0, 0.0]);
+        cache.insert("sha256:new", vec![0.5, 0.5]);
+        cache.get("sha256:used");
+
+        assert_eq!(cache.evict(), 1);
+        assert_eq!(cache.get("sha256:old"), None);
+        assert!(cache.get("sha256:used").is_some());
+        assert!(cache.get("sha256:new").is_some());
+    }
+
+    #[test]
+    fn test_save_appends_changes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("embeddings.cache");
+        let mut cache = EmbeddingCache::open(&path, "minilm").unwrap();
+        cache.insert("sha256:abc", vec![0.25; 64]);
+        cache.save().unwrap();
+        let first = fs::read(&path).unwrap();
+
+        let mut cache = EmbeddingCache::open(&path, "minilm").unwrap();
+        cache.get("sha256:abc");
+        cache.insert("sha256:def", vec![0.5; 64]);
+        cache.save().unwrap();
+        let second = fs::read(&path).unwrap();
+
+        // The first save's records are kept as they were
+        assert_eq!(&second[..first.len()], &first[..]);
+        let mut reopened = EmbeddingCache::open(&path, "minilm").unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.get("sha256:def"), Some(vec![0.5; 64]));
+    }
+
+    #[test]
+    fn test_ignores_incomplete_record() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("embeddings.cache");
+        let mut cache = EmbeddingCache::open(&path, "minilm").unwrap();
+        cache.insert("sha256:abc", vec![0.25, 0.5]);
+        cache.save().unwrap();
+        cache.insert("sha256:def", vec![1.0, 0.0]);
+        cache.save().unwrap();
+
+        // A save interrupted in the middle of the last record
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+
+        let mut cache = EmbeddingCache::open(&path, "minilm").unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("sha256:abc"), Some(vec![0.25, 0.5]));
+        cache.insert("sha256:ghi", vec![0.0, 1.0]);
+        cache.save().unwrap();
+        assert_eq!(EmbeddingCache::open(&path, "minilm").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_eviction_rewrites_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("embeddings.cache");
+        let mut cache = EmbeddingCache::open(&path, "minilm")
+            .unwrap()
+            .with_max_bytes(2 * 2 * std::mem::size_of::<f32>());
+        cache.insert("sha256:aaa", vec![0.0, 1.0]);
+        cache.insert("sha256:bbb", vec![1.0, 0.0]);
+        cache.save().unwrap();
+        let size = fs::metadata(&path).unwrap().len();
+
+        cache.insert("sha256:ccc", vec![0.5, 0.5]);
+        cache.save().unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().len(), size);
+        let mut reopened = EmbeddingCache::open(&path, "minilm").unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.get("sha256:aaa"), None);
+    }
+
+    #[test]
+    fn test_unreadable_file_is_empty_cache() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("embeddings.cache");
+        fs::write(&path, "not json").unwrap();
+
+        let mut cache = EmbeddingCache::open(&path, "minilm").unwrap();
+        assert!(cache.is_empty());
+        cache.insert("sha256:abc", vec![1.0]);
+        cache.save().unwrap();
+        assert_eq!(EmbeddingCache::open(&path, "minilm").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,260 @@
+//! Embedding providers.
+//!
+//! An [`EmbeddingProvider`] turns chunk inputs into vectors:
+//! - [`EmbeddingClient`](crate::embedding::EmbeddingClient) calls the
+//!   embedding worker over HTTP
+//! - [`OpenAiEmbedder`](crate::openai::OpenAiEmbedder) calls any server with
+//!   an OpenAI-compatible `/v1/embeddings` API
+//! - [`OnnxEmbedder`](crate::onnx::OnnxEmbedder) runs the model in-process on
+//!   the CPU (with the `onnx` feature)
+//! - [`HashEmbedder`] needs no model at all, for tests and offline runs
+
+use doctown_common::{ChunkId, DocError};
+use futures_util::future::BoxFuture;
+use sha2::{Digest, Sha256};
+
+/// Default dimensions of [`HashEmbedder`] vectors, matching MiniLM.
+pub const DEFAULT_HASH_DIMENSIONS: usize = 384;
+
+/// Future of [`EmbeddingProvider::embed`]: the `(chunk_id, vector)` pairs of a batch.
+pub type EmbedFuture<'a> = BoxFuture<'a, Result<Vec<(ChunkId, Vec<f32>)>, DocError>>;
+
+/// Something that embeds batches of chunks.
+pub trait EmbeddingProvider: Send + Sync {
+    /// ID of the model making the vectors, such as `all-MiniLM-L6-v2`.
+    ///
+    /// Vectors of different models are not comparable, so this keys the
+    /// embedding cache.
+    fn model_id(&self) -> &str;
+
+    /// Dimensions of the vectors, if known before any are made.
+    fn dimensions(&self) -> Option<usize> {
+        None
+    }
+
+    /// Embeds a batch of `(chunk_id, input)` pairs.
+    ///
+    /// Chunks left out of the result are treated as failed. Errors are
+    /// classified as in [`ResilientEmbedder`](crate::embedding::ResilientEmbedder):
+    /// `DocError::Validation` for a rejected batch, `DocError::Http` when the
+    /// provider cannot be reached, `DocError::Config` when its output is wrong
+    /// whatever the inputs (e.g. vectors of the wrong size), anything else for
+    /// transient failures.
+    fn embed<'a>(&'a self, batch_id: &'a str, chunks: Vec<(ChunkId, String)>) -> EmbedFuture<'a>;
+}
+
+/// Checks the vectors returned for a batch of `expected_count` inputs.
+///
+/// Every vector must have the same number of dimensions, `expected_dimensions`
+/// when given, and finite values only. Returns the number of dimensions.
+///
+/// A wrong count or size is the provider's fault, not the inputs', and is a
+/// `DocError::Config`; non-finite values are a `DocError::Validation`.
+pub fn validate_vectors(
+    vectors: &[Vec<f32>],
+    expected_count: usize,
+    expected_dimensions: Option<usize>,
+) -> Result<usize, DocError> {
+    if vectors.len() != expected_count {
+        return Err(DocError::Config(format!(
+            "Expected {} embeddings, got {}",
+            expected_count,
+            vectors.len()
+        )));
+    }
+
+    let Some(dimensions) = expected_dimensions.or_else(|| vectors.first().map(Vec::len)) else {
+        return Ok(0);
+    };
+    for (i, vector) in vectors.iter().enumerate() {
+        validate_vector(i, vector, dimensions)?;
+    }
+    Ok(dimensions)
+}
+
+/// Checks that the vector at `index` has `dimensions` finite values, as
+/// [`validate_vectors`] does for each vector.
+pub fn validate_vector(index: usize, vector: &[f32], dimensions: usize) -> Result<(), DocError> {
+    if vector.len() != dimensions {
+        return Err(DocError::Config(format!(
+            "Embedding {} has {} dimensions, expected {}",
+            index,
+            vector.len(),
+            dimensions
+        )));
+    }
+    if vector.iter().any(|x| !x.is_finite()) {
+        return Err(DocError::Validation(format!(
+            "Embedding {} contains NaN or infinite values",
+            index
+        )));
+    }
+    Ok(())
+}
+
+/// Deterministic embeddings from hashed words, without a model.
+///
+/// Each lowercased word is hashed to a dimension and a sign, and the counts
+/// are normalized to unit length. Texts sharing words get similar vectors, so
+/// search still behaves sensibly, and the same text always embeds the same.
+#[derive(Debug, Clone)]
+pub struct HashEmbedder {
+    model_id: String,
+    dimensions: usize,
+}
+
+impl Default for HashEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASH_DIMENSIONS)
+    }
+}
+
+impl HashEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(1);
+        Self {
+            model_id: format!("hash-{}", dimensions),
+            dimensions,
+        }
+    }
+
+    /// Number of dimensions of the vectors.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// The vector of `text`.
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        let mut words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        if words.is_empty() {
+            // Punctuation-only or empty text still gets a non-zero vector
+            words.push(text.to_string());
+        }
+
+        for word in &words {
+            let hash = Sha256::digest(word.as_bytes());
+            let bucket = u64::from_le_bytes(hash[..8].try_into().unwrap_or_default());
+            let index = (bucket % self.dimensions as u64) as usize;
+            let sign = if hash[8] & 1 == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign;
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        vector
+    }
+}
+
+impl EmbeddingProvider for HashEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        Some(self.dimensions)
+    }
+
+    fn embed<'a>(&'a self, _batch_id: &'a str, chunks: Vec<(ChunkId, String)>) -> EmbedFuture<'a> {
+        let vectors = chunks
+            .into_iter()
+            .map(|(chunk_id, input)| {
+                let vector = self.embed_text(&input);
+                (chunk_id, vector)
+            })
+            .collect();
+        Box::pin(async move { Ok(vectors) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn test_validate_vectors() {
+        let vectors = vec![vec![0.5, 0.5], vec![1.0, 0.0]];
+        assert_eq!(validate_vectors(&vectors, 2, None).unwrap(), 2);
+        assert_eq!(validate_vectors(&vectors, 2, Some(2)).unwrap(), 2);
+        assert_eq!(validate_vectors(&[], 0, None).unwrap(), 0);
+
+        let error = |vectors: &[Vec<f32>], count, dimensions| {
+            validate_vectors(vectors, count, dimensions)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(error(&vectors, 3, None).contains("Expected 3 embeddings, got 2"));
+        assert!(error(&vectors, 2, Some(384)).contains("has 2 dimensions, expected 384"));
+        assert!(error(&[vec![0.5, 0.5], vec![1.0]], 2, None).contains("Embedding 1 has 1"));
+        assert!(error(&[vec![0.5, f32::NAN]], 1, None).contains("NaN"));
+        assert!(matches!(
+            validate_vectors(&vectors, 2, Some(384)),
+            Err(DocError::Config(_))
+        ));
+        assert!(matches!(
+            validate_vectors(&[vec![f32::INFINITY]], 1, None),
+            Err(DocError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_hash_embedding_is_deterministic_and_normalized() {
+        let embedder = HashEmbedder::new(64);
+        let vector = embedder.embed_text("fn connect(addr: &str)");
+
+        assert_eq!(vector.len(), 64);
+        assert_eq!(
+            vector,
+            HashEmbedder::new(64).embed_text("fn connect(addr: &str)")
+        );
+        assert!((cosine(&vector, &vector) - 1.0).abs() < 1e-5);
+        assert_eq!(embedder.model_id(), "hash-64");
+    }
+
+    #[test]
+    fn test_hash_embedding_similarity() {
+        let embedder = HashEmbedder::default();
+        let query = embedder.embed_text("parse the config file");
+        let related = embedder.embed_text("fn parse_config(file: &Path)  // parse the config");
+        let unrelated = embedder.embed_text("render a button widget");
+
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+    }
+
+    #[test]
+    fn test_hash_embedding_of_empty_text() {
+        let vector = HashEmbedder::default().embed_text("");
+        assert!(vector.iter().any(|x| *x != 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_hash_provider_embeds_batch() {
+        let embedder = HashEmbedder::new(8);
+        let chunks = vec![
+            (
+                ChunkId::new("chunk_first").unwrap(),
+                "fn a() {}".to_string(),
+            ),
+            (
+                ChunkId::new("chunk_second").unwrap(),
+                "fn b() {}".to_string(),
+            ),
+        ];
+
+        let vectors = embedder.embed("batch", chunks).await.unwrap();
+
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0].0.as_str(), "chunk_first");
+        assert_eq!(vectors[1].1, embedder.embed_text("fn b() {}"));
+    }
+}
@@ -0,0 +1,401 @@
+//! Mapping import statements to the repository files they load.
+//!
+//! Each language finds its imports differently:
+//! - Python: absolute (`pkg.util`) and relative (`..util`) modules, as `.py` files
+//!   or packages with an `__init__.py`
+//! - TypeScript/JavaScript: relative paths with implied extensions and `index` files
+//! - Go: package directories under the module declared in `go.mod`
+//! - Rust: `use` paths through the crate module trees
+
+use doctown_common::types::Import;
+use doctown_common::Language;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::manifests::GoMod;
+use crate::module_tree::ModuleTree;
+
+/// Directories never searched for import targets.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "__pycache__"];
+
+/// Extensions tried, in order, for extensionless TypeScript/JavaScript imports.
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Resolves imports to files, given the files of a repository.
+///
+/// File paths are relative to the repository root and use `/` separators.
+#[derive(Debug, Clone, Default)]
+pub struct ImportResolver {
+    files: BTreeSet<String>,
+    /// Go modules as (module path, directory of go.mod)
+    go_modules: Vec<(String, String)>,
+    module_tree: ModuleTree,
+}
+
+impl ImportResolver {
+    /// Create a resolver over a set of repository files.
+    pub fn new<I, S>(files: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            files: files.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Add a Go module (the `module` line of a `go.mod`) rooted at `dir`.
+    pub fn with_go_module(mut self, module: impl Into<String>, dir: impl Into<String>) -> Self {
+        self.go_modules.push((module.into(), dir.into()));
+        self
+    }
+
+    /// Set the Rust module trees used to resolve `use` paths.
+    pub fn with_module_tree(mut self, module_tree: ModuleTree) -> Self {
+        self.module_tree = module_tree;
+        self
+    }
+
+    /// Build a resolver from a repository checkout, reading its `go.mod` and
+    /// `Cargo.toml` files.
+    pub fn build(root: &Path) -> Self {
+        let mut resolver = Self::default().with_module_tree(ModuleTree::build(root));
+
+        let entries = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                e.depth() == 0 || !(name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file());
+        for entry in entries {
+            let Ok(relative) = entry.path().strip_prefix(root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if entry.file_name() == "go.mod" {
+                if let Some(module) = fs::read_to_string(entry.path())
+                    .ok()
+                    .and_then(|text| GoMod::parse(&text).module)
+                {
+                    resolver = resolver.with_go_module(module, parent_dir(&relative));
+                }
+            }
+            resolver.files.insert(relative);
+        }
+        resolver
+    }
+
+    /// Returns true if the file is part of the repository.
+    pub fn contains(&self, file: &str) -> bool {
+        self.files.contains(file)
+    }
+
+    /// Returns the repository files an import in `from_file` loads.
+    ///
+    /// Imports of third-party packages and the standard library resolve to nothing.
+    pub fn resolve(&self, from_file: &str, import: &Import) -> Vec<String> {
+        let Some(language) = Language::from_path(Path::new(from_file)) else {
+            return Vec::new();
+        };
+        match language {
+            Language::Python => self.resolve_python(from_file, import),
+            Language::TypeScript | Language::JavaScript => {
+                self.resolve_script(from_file, &import.module_path)
+            }
+            Language::Go => self.resolve_go(&import.module_path),
+            Language::Rust => {
+                let mut files: Vec<String> = self
+                    .module_tree
+                    .resolve_import(Path::new(from_file), import)
+                    .into_iter()
+                    .map(|resolved| resolved.file.to_string_lossy().replace('\\', "/"))
+                    .collect();
+                files.sort();
+                files.dedup();
+                files
+            }
+        }
+    }
+
+    fn resolve_python(&self, from_file: &str, import: &Import) -> Vec<String> {
+        let module = import.module_path.trim();
+        let dots = module.chars().take_while(|&c| c == '.').count();
+        let module_dir = module[dots..].replace('.', "/");
+
+        // Relative imports start from the importing package, one level up per extra dot
+        let mut bases = Vec::new();
+        if dots > 0 {
+            let mut base = parent_dir(from_file);
+            for _ in 1..dots {
+                base = parent_dir(&base);
+            }
+            bases.push(base);
+        }
+
+        let mut files = Vec::new();
+        // `from pkg import util` may name a submodule rather than an attribute
+        let items = import.imported_items.iter().flatten();
+        let targets =
+            std::iter::once(module_dir.clone()).chain(items.map(|item| join(&module_dir, item)));
+        for target in targets {
+            if target.is_empty() {
+                // `from . import x`: the package itself
+                for base in &bases {
+                    files.extend(self.existing([join(base, "__init__.py")]));
+                }
+                continue;
+            }
+            let candidates = [format!("{}.py", target), join(&target, "__init__.py")];
+            if dots > 0 {
+                for base in &bases {
+                    files.extend(self.existing(candidates.iter().map(|c| join(base, c))));
+                }
+            } else {
+                // Absolute imports are relative to a source root, which may be
+                // the repository root, `src/` or any other directory
+                files.extend(
+                    self.files
+                        .iter()
+                        .filter(|f| candidates.iter().any(|c| ends_with_path(f, c)))
+                        .cloned(),
+                );
+            }
+        }
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    fn resolve_script(&self, from_file: &str, specifier: &str) -> Vec<String> {
+        // Bare specifiers are packages
+        if !specifier.starts_with('.') {
+            return Vec::new();
+        }
+        let Some(target) = normalize(&join(&parent_dir(from_file), specifier)) else {
+            return Vec::new();
+        };
+
+        // The specifier may name the file exactly, omit its extension or name a directory
+        let mut candidates = vec![target.clone()];
+        // `./util.js` is how ESM TypeScript imports `./util.ts`
+        if let Some(stem) = target
+            .strip_suffix(".js")
+            .or_else(|| target.strip_suffix(".mjs"))
+        {
+            candidates.extend(["ts", "tsx"].iter().map(|ext| format!("{}.{}", stem, ext)));
+        }
+        candidates.extend(
+            SCRIPT_EXTENSIONS
+                .iter()
+                .map(|ext| format!("{}.{}", target, ext)),
+        );
+        candidates.extend(
+            SCRIPT_EXTENSIONS
+                .iter()
+                .map(|ext| join(&target, &format!("index.{}", ext))),
+        );
+
+        self.existing(candidates).into_iter().take(1).collect()
+    }
+
+    fn resolve_go(&self, import_path: &str) -> Vec<String> {
+        let Some(dir) = self.go_modules.iter().find_map(|(module, module_dir)| {
+            let rest = import_path.strip_prefix(module.as_str())?;
+            if !rest.is_empty() && !rest.starts_with('/') {
+                return None;
+            }
+            Some(join(module_dir, rest.trim_start_matches('/')))
+        }) else {
+            return Vec::new();
+        };
+
+        // A package is every non-test Go file directly in its directory
+        self.files
+            .iter()
+            .filter(|f| parent_dir(f) == dir)
+            .filter(|f| f.ends_with(".go") && !f.ends_with("_test.go"))
+            .cloned()
+            .collect()
+    }
+
+    fn existing<I: IntoIterator<Item = String>>(&self, candidates: I) -> Vec<String> {
+        candidates
+            .into_iter()
+            .filter(|c| self.files.contains(c))
+            .collect()
+    }
+}
+
+/// Returns the directory of a `/`-separated path ("" for top-level files).
+fn parent_dir(path: &str) -> String {
+    path.rsplit_once('/')
+        .map(|(dir, _)| dir.to_string())
+        .unwrap_or_default()
+}
+
+fn join(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        path.to_string()
+    } else if path.is_empty() {
+        dir.to_string()
+    } else {
+        format!("{}/{}", dir, path)
+    }
+}
+
+/// Resolve `.` and `..` segments; None if the path leaves the repository.
+fn normalize(path: &str) -> Option<String> {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Returns true if `file` is `suffix` or ends with `/suffix`.
+fn ends_with_path(file: &str, suffix: &str) -> bool {
+    file == suffix
+        || file
+            .strip_suffix(suffix)
+            .is_some_and(|rest| rest.ends_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use doctown_common::types::ByteRange;
+
+    fn import(module_path: &str, items: Option<&[&str]>) -> Import {
+        Import {
+            module_path: module_path.to_string(),
+            imported_items: items.map(|items| items.iter().map(|i| i.to_string()).collect()),
+            alias: None,
+            range: ByteRange::new(0, 0),
+            is_wildcard: false,
+        }
+    }
+
+    #[test]
+    fn test_python_imports() {
+        let resolver = ImportResolver::new([
+            "src/app/__init__.py",
+            "src/app/main.py",
+            "src/app/util.py",
+            "src/app/db/__init__.py",
+            "src/app/db/models.py",
+        ]);
+
+        assert_eq!(
+            resolver.resolve("src/app/main.py", &import("app.db.models", None)),
+            vec!["src/app/db/models.py"]
+        );
+        assert_eq!(
+            resolver.resolve("src/app/main.py", &import("app.db", None)),
+            vec!["src/app/db/__init__.py"]
+        );
+        assert_eq!(
+            resolver.resolve("src/app/db/models.py", &import("..util", Some(&["helper"]))),
+            vec!["src/app/util.py"]
+        );
+        // `from . import util` names a sibling module
+        assert_eq!(
+            resolver.resolve("src/app/main.py", &import(".", Some(&["util"]))),
+            vec!["src/app/__init__.py", "src/app/util.py"]
+        );
+        assert!(resolver
+            .resolve("src/app/main.py", &import("numpy", None))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_script_imports() {
+        let resolver = ImportResolver::new([
+            "web/src/app.ts",
+            "web/src/util.ts",
+            "web/src/components/index.tsx",
+            "web/src/legacy/helpers.js",
+        ]);
+
+        let resolve =
+            |specifier: &str| resolver.resolve("web/src/app.ts", &import(specifier, None));
+        assert_eq!(resolve("./util"), vec!["web/src/util.ts"]);
+        assert_eq!(resolve("./util.js"), vec!["web/src/util.ts"]);
+        assert_eq!(
+            resolve("./components"),
+            vec!["web/src/components/index.tsx"]
+        );
+        assert_eq!(
+            resolve("./legacy/helpers"),
+            vec!["web/src/legacy/helpers.js"]
+        );
+        assert!(resolve("react").is_empty());
+        assert!(resolve("../../../outside").is_empty());
+    }
+
+    #[test]
+    fn test_go_imports() {
+        let resolver = ImportResolver::new([
+            "svc/go.mod",
+            "svc/main.go",
+            "svc/db/db.go",
+            "svc/db/query.go",
+            "svc/db/db_test.go",
+        ])
+        .with_go_module("github.com/acme/svc", "svc");
+
+        assert_eq!(
+            resolver.resolve("svc/main.go", &import("github.com/acme/svc/db", None)),
+            vec!["svc/db/db.go", "svc/db/query.go"]
+        );
+        assert!(resolver
+            .resolve("svc/main.go", &import("github.com/acme/svcx", None))
+            .is_empty());
+        assert!(resolver
+            .resolve("svc/main.go", &import("fmt", None))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_build_from_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("svc/db")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod net;\n").unwrap();
+        fs::write(root.join("src/net.rs"), "pub fn connect() {}\n").unwrap();
+        fs::write(
+            root.join("svc/go.mod"),
+            "module example.com/svc\n\ngo 1.22\n",
+        )
+        .unwrap();
+        fs::write(root.join("svc/db/db.go"), "package db\n").unwrap();
+
+        let resolver = ImportResolver::build(root);
+
+        assert!(resolver.contains("src/net.rs"));
+        assert_eq!(
+            resolver.resolve("src/lib.rs", &import("crate::net::connect", None)),
+            vec!["src/net.rs"]
+        );
+        assert_eq!(
+            resolver.resolve("svc/main.go", &import("example.com/svc/db", None)),
+            vec!["svc/db/db.go"]
+        );
+    }
+}
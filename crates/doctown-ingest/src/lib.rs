@@ -8,33 +8,84 @@
 
 pub mod api;
 pub mod archive;
+pub mod attributes;
+pub mod batching;
 pub mod calls;
 pub mod chunk;
+pub mod chunk_context;
+pub mod dedup;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod doc_comments;
 pub mod embedding;
+pub mod embedding_cache;
+pub mod embedding_provider;
 pub mod filter;
 pub mod github;
+pub mod import_resolution;
 pub mod imports;
 pub mod language;
+pub mod linking;
+pub mod manifests;
+pub mod markdown;
+pub mod metrics;
+pub mod module_tree;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+pub mod openai;
+pub mod output;
 pub mod parsing;
 pub mod pipeline;
+pub mod queries;
+pub mod receiver_types;
+pub mod relations;
 pub mod resolution;
 pub mod symbol;
+pub mod test_code;
+pub mod tokenizer;
 pub mod traversal;
+pub mod type_refs;
 
-pub use archive::{extract_zip, process_extracted_files};
+pub use archive::{
+    extract_zip, process_extracted_files, process_files, ProcessingOptions, ProcessingSummary,
+};
+pub use attributes::{attach_attributes, node_attributes};
 pub use calls::extract_calls;
-pub use chunk::{create_chunks, Chunk, ChunkMetadata, ChunkingConfig};
+pub use chunk::{
+    content_hash, create_chunks, create_chunks_with_tree, Chunk, ChunkLevel, ChunkMetadata,
+    ChunkingConfig, SubNodeRange, TokenBudget,
+};
+pub use chunk_context::{enclosing_scope, ChunkContext};
+pub use dedup::DedupedChunks;
+pub use dependencies::DependencyClassifier;
+pub use diagnostics::{ParseDiagnostics, DEFAULT_MAX_ERROR_RATIO};
+pub use doc_comments::{doc_comment, node_doc_comment};
 pub use filter::{
     normalize_archive_path, FileFilter, FilterResult, SkipReason as FilterSkipReason,
     MAX_FILE_SIZE, MAX_REPO_SIZE,
 };
 pub use github::{GitHubClient, GitHubUrl, RateLimitInfo, RefInfo, RepoMetadata};
+pub use import_resolution::ImportResolver;
 pub use imports::extract_imports;
 pub use language::detect_language;
+pub use linking::Linker;
+pub use manifests::{CargoDependency, CargoManifest, GoMod, PyProject};
+pub use markdown::{chunk_markdown, is_markdown_file, MarkdownSection};
+pub use metrics::{attach_metrics, compute_metrics};
+pub use module_tree::{ModuleTree, ResolvedImport, RustCrate};
+pub use output::{DocumentRecord, EmbeddedChunk, IngestOutput, SymbolRecord};
 pub use parsing::{parse, Parser};
 pub use pipeline::run_pipeline;
-pub use resolution::{resolve_calls, SymbolTable};
+pub use queries::{QueryKind, QueryRegistry};
+pub use receiver_types::attach_receiver_types;
+pub use relations::extract_type_relations;
+pub use resolution::{
+    resolve_calls, resolve_calls_from, resolve_type_references, Candidate, Resolution,
+    ResolutionScope, SymbolTable,
+};
 pub use symbol::{extract_symbols, Symbol};
+pub use test_code::{is_test_file, mark_test_symbols};
+pub use tokenizer::{TokenCounter, DEFAULT_TOKENIZER_PATH, MODEL_MAX_TOKENS};
 pub use traversal::{
     ancestors, child_by_field, child_text, collect_named_children_text, find_ancestor_by_kind,
     find_child_by_kind, find_children_by_kind, find_nodes_by_kind, find_nodes_by_kinds, has_error,
@@ -42,3 +93,4 @@ pub use traversal::{
     node_end_position, node_line_count, node_start_position, node_text, node_text_owned,
     text_from_range, DfsIterator, TreeCursor,
 };
+pub use type_refs::{extract_type_references, type_references_in_range};
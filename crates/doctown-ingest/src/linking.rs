@@ -0,0 +1,267 @@
+//! Cross-file linking of the symbols found during ingest.
+//!
+//! Extractors report the functions a symbol calls and the types it inherits
+//! from, implements or mentions by name, as written in the source. Once every file has been processed, [`Linker`]
+//! resolves those names through a [`SymbolTable`] of the whole repository and
+//! records the canonical IDs they refer to on the [`SymbolRecord`]s, which is
+//! what assembly builds its edges from. Calls and imports that leave the
+//! repository are recorded as external symbols by a [`DependencyClassifier`].
+
+use doctown_common::types::{Call, CallKind, Import, RelationKind, TypeReference, TypeRelation};
+use doctown_common::{ByteRange, ExternalSymbol, Language, SymbolId, SymbolKind};
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::Tree;
+
+use crate::dependencies::DependencyClassifier;
+use crate::import_resolution::ImportResolver;
+use crate::output::SymbolRecord;
+use crate::queries::QueryRegistry;
+use crate::relations::extract_type_relations;
+use crate::resolution::{Resolution, ResolutionScope, SymbolTable};
+use crate::symbol::Symbol;
+use crate::type_refs::extract_type_references;
+
+/// Collects the symbols of each file and the names they refer to, then
+/// resolves the names to symbol IDs.
+#[derive(Debug)]
+pub struct Linker {
+    table: SymbolTable,
+    dependencies: DependencyClassifier,
+    /// Index of each symbol's record in the records passed to [`Linker::link`]
+    records: HashMap<SymbolId, usize>,
+    files: Vec<LinkedFile>,
+}
+
+/// What a file's symbols refer to, by name.
+#[derive(Debug)]
+struct LinkedFile {
+    /// Path under the repository root, as the import resolver knows it
+    path: String,
+    language: Language,
+    /// Each symbol's ID and range, for attributing names to symbols
+    symbols: Vec<(SymbolId, ByteRange)>,
+    imports: Vec<Import>,
+    calls: Vec<Call>,
+    relations: Vec<TypeRelation>,
+    references: Vec<TypeReference>,
+}
+
+impl Linker {
+    /// Creates a linker for the repository at `root`, resolving imports
+    /// between its files and classifying others by its package manifests.
+    pub fn new(root: &Path) -> Self {
+        Self {
+            table: SymbolTable::new().with_import_resolver(ImportResolver::build(root)),
+            dependencies: DependencyClassifier::build(root),
+            records: HashMap::new(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Adds a parsed file at `path` (relative to the root) and its symbols,
+    /// each with its ID and the index of its record.
+    pub fn add_file(
+        &mut self,
+        path: &str,
+        language: Language,
+        tree: &Tree,
+        source_code: &str,
+        queries: &QueryRegistry,
+        symbols: &[(usize, SymbolId, Symbol)],
+    ) {
+        for (record, symbol_id, symbol) in symbols {
+            self.records.insert(symbol_id.clone(), *record);
+            // Impl blocks are named after their type, and are never referred to
+            if symbol.kind != SymbolKind::Impl {
+                self.table
+                    .add_symbol_with_id(symbol, symbol_id.clone(), path);
+            }
+        }
+        let imports = queries.extract_imports(tree, source_code, language);
+        self.table.add_file_imports(path, imports.clone());
+
+        self.files.push(LinkedFile {
+            path: path.to_string(),
+            language,
+            imports,
+            calls: queries.extract_calls(tree, source_code, language),
+            symbols: symbols
+                .iter()
+                .map(|(_, symbol_id, symbol)| (symbol_id.clone(), symbol.range))
+                .collect(),
+            relations: extract_type_relations(tree, source_code, language),
+            references: extract_type_references(tree, source_code, language),
+        });
+    }
+
+    /// Records what each symbol calls, imports, inherits from, implements and
+    /// references on `records`, and the external symbols it calls and imports.
+    ///
+    /// A symbol imports the repository symbols it uses through one of its
+    /// file's imports, and each call resolved through an import records it. Names that match no repository symbol (e.g.
+    /// `std::fmt::Display`), or several equally close ones, are left out;
+    /// unresolved calls, and imports the symbol uses, are classified as
+    /// external where possible. Calls that match several equally close
+    /// symbols record each candidate with its confidence instead; their
+    /// number is returned.
+    pub fn link(self, records: &mut [SymbolRecord]) -> usize {
+        let mut ambiguous_calls = 0;
+        for file in &self.files {
+            for relation in &file.relations {
+                let (Some(source), Some(target)) = (
+                    self.resolve(&relation.source, &file.path),
+                    self.resolve(&relation.target, &file.path),
+                ) else {
+                    continue;
+                };
+                if source == target {
+                    continue;
+                }
+                let Some(&index) = self.records.get(&source) else {
+                    continue;
+                };
+                let targets = match relation.kind {
+                    RelationKind::Inherits => &mut records[index].inherits,
+                    RelationKind::Implements => &mut records[index].implements,
+                };
+                push_unique(targets, &target);
+            }
+
+            for reference in &file.references {
+                let Some(source) = file.innermost_symbol(reference.range) else {
+                    continue;
+                };
+                let resolution = self
+                    .table
+                    .resolve_type_reference_from(reference, &file.path);
+                match (resolution.unique(), self.records.get(source)) {
+                    (Some(target), Some(&index)) if target != source => {
+                        push_unique(&mut records[index].references, target);
+                        if is_imported(&resolution) {
+                            push_unique(&mut records[index].imports, target);
+                        }
+                    }
+                    _ if !resolution.is_resolved() => {
+                        self.add_bound_external(file, source, name_head(&reference.name), records);
+                    }
+                    _ => {}
+                }
+            }
+
+            for call in &file.calls {
+                let Some(source) = file.innermost_symbol(call.range) else {
+                    continue;
+                };
+                let Some(&index) = self.records.get(source) else {
+                    continue;
+                };
+                let resolution = self.table.resolve_call_from(call, &file.path);
+                if resolution.is_resolved() {
+                    if let Some(target) = resolution.unique().filter(|&target| target != source) {
+                        push_unique(&mut records[index].calls, target);
+                        if is_imported(&resolution) {
+                            push_unique(&mut records[index].imports, target);
+                        }
+                        if let Some(import) = &resolution.candidates[0].import {
+                            let call_import = (target.to_string(), import.module_path.clone());
+                            if !records[index].call_imports.contains(&call_import) {
+                                records[index].call_imports.push(call_import);
+                            }
+                        }
+                    }
+                    if resolution.is_ambiguous() {
+                        ambiguous_calls += 1;
+                        let candidate_calls = &mut records[index].candidate_calls;
+                        for candidate in &resolution.candidates {
+                            if candidate.symbol_id != *source
+                                && !candidate_calls
+                                    .iter()
+                                    .any(|(id, _)| id == candidate.symbol_id.as_str())
+                            {
+                                candidate_calls
+                                    .push((candidate.symbol_id.to_string(), candidate.confidence));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if let Some(external) =
+                    self.dependencies
+                        .call_external(call, &file.imports, file.language)
+                {
+                    push_unique_external(&mut records[index].external_calls, external);
+                }
+                let head = match (&call.kind, &call.receiver_type) {
+                    (CallKind::Method, Some(receiver_type)) => receiver_type.as_str(),
+                    _ => name_head(&call.name),
+                };
+                self.add_bound_external(file, source, head, records);
+            }
+        }
+        ambiguous_calls
+    }
+
+    /// Records the external symbol an import of `file` binds `name` to, if
+    /// any, as imported by `source`.
+    fn add_bound_external(
+        &self,
+        file: &LinkedFile,
+        source: &SymbolId,
+        name: &str,
+        records: &mut [SymbolRecord],
+    ) {
+        let Some(&index) = self.records.get(source) else {
+            return;
+        };
+        if let Some(external) = self
+            .dependencies
+            .bound_external(name, &file.imports, file.language)
+        {
+            push_unique_external(&mut records[index].external_imports, external);
+        }
+    }
+
+    /// The one symbol `name` refers to from `file`, if any.
+    fn resolve(&self, name: &str, file: &str) -> Option<SymbolId> {
+        self.table.resolve(name, Some(file)).unique().cloned()
+    }
+}
+
+impl LinkedFile {
+    /// The innermost symbol containing `range`.
+    fn innermost_symbol(&self, range: ByteRange) -> Option<&SymbolId> {
+        self.symbols
+            .iter()
+            .filter(|(_, symbol_range)| symbol_range.contains(&range))
+            .min_by_key(|(_, symbol_range)| symbol_range.end - symbol_range.start)
+            .map(|(symbol_id, _)| symbol_id)
+    }
+}
+
+/// Whether a name was resolved through an import of its file.
+fn is_imported(resolution: &Resolution) -> bool {
+    resolution
+        .candidates
+        .iter()
+        .all(|candidate| candidate.scope == ResolutionScope::Imported)
+}
+
+/// The first segment of a name: `np` in `np.array`, `Client` in `Client::new`.
+fn name_head(name: &str) -> &str {
+    name.split(['.', ':'])
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(name)
+}
+
+fn push_unique_external(externals: &mut Vec<ExternalSymbol>, external: ExternalSymbol) {
+    if !externals.contains(&external) {
+        externals.push(external);
+    }
+}
+
+fn push_unique(ids: &mut Vec<String>, id: &SymbolId) {
+    if !ids.iter().any(|existing| existing == id.as_str()) {
+        ids.push(id.to_string());
+    }
+}
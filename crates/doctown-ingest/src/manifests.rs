@@ -0,0 +1,323 @@
+//! Package manifests read by module resolution and dependency classification.
+//!
+//! `Cargo.toml` and `pyproject.toml` are parsed as TOML; `go.mod` has its own
+//! line-based format. A manifest that fails to parse reads as empty, so one
+//! broken file doesn't stop ingest.
+
+use toml::{Table, Value};
+
+/// Dependency tables of a `Cargo.toml`, also found under `[target.'cfg(..)']`.
+const CARGO_DEPENDENCY_TABLES: &[&str] =
+    &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A `Cargo.toml`: its crate, workspace members and dependencies.
+#[derive(Debug, Default, PartialEq)]
+pub struct CargoManifest {
+    pub package_name: Option<String>,
+    pub lib_name: Option<String>,
+    pub lib_path: Option<String>,
+    /// Workspace members, possibly ending in a `*` glob (`crates/*`).
+    pub members: Vec<String>,
+    /// Dependencies of all tables, including `[workspace.dependencies]`.
+    pub dependencies: Vec<CargoDependency>,
+}
+
+/// A dependency declared in a `Cargo.toml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CargoDependency {
+    /// The name the crate is used by: its key, even if renamed with `package`.
+    pub name: String,
+    /// Whether it is a `path` dependency, one of the repository's own crates.
+    pub is_path: bool,
+}
+
+impl CargoManifest {
+    /// Parse a `Cargo.toml`.
+    pub fn parse(text: &str) -> Self {
+        let Ok(table) = text.parse::<Table>() else {
+            return Self::default();
+        };
+
+        let mut dependency_tables: Vec<&Table> = CARGO_DEPENDENCY_TABLES
+            .iter()
+            .filter_map(|name| table_at(&table, &[name]))
+            .collect();
+        if let Some(targets) = table_at(&table, &["target"]) {
+            for target in targets.values().filter_map(Value::as_table) {
+                dependency_tables.extend(
+                    CARGO_DEPENDENCY_TABLES
+                        .iter()
+                        .filter_map(|name| table_at(target, &[name])),
+                );
+            }
+        }
+        dependency_tables.extend(table_at(&table, &["workspace", "dependencies"]));
+
+        Self {
+            package_name: string_at(&table, &["package", "name"]),
+            lib_name: string_at(&table, &["lib", "name"]),
+            lib_path: string_at(&table, &["lib", "path"]),
+            members: strings_at(&table, &["workspace", "members"]),
+            dependencies: dependency_tables
+                .into_iter()
+                .flatten()
+                .map(|(name, spec)| CargoDependency {
+                    name: name.clone(),
+                    is_path: spec
+                        .as_table()
+                        .is_some_and(|spec| spec.contains_key("path")),
+                })
+                .collect(),
+        }
+    }
+
+    /// The crate name used in paths: the library name, or the package name with
+    /// hyphens replaced by underscores.
+    pub fn crate_name(&self) -> Option<String> {
+        self.lib_name
+            .as_ref()
+            .or(self.package_name.as_ref())
+            .map(|name| name.replace('-', "_"))
+    }
+}
+
+/// A `pyproject.toml`: the project name and its dependencies.
+#[derive(Debug, Default, PartialEq)]
+pub struct PyProject {
+    pub name: Option<String>,
+    /// Requirements of PEP 621 dependency arrays (`requests>=2.0`) and names of
+    /// Poetry dependency tables.
+    pub dependencies: Vec<String>,
+}
+
+impl PyProject {
+    /// Parse a `pyproject.toml`.
+    pub fn parse(text: &str) -> Self {
+        let Ok(table) = text.parse::<Table>() else {
+            return Self::default();
+        };
+
+        let mut dependencies = strings_at(&table, &["project", "dependencies"]);
+        if let Some(optional) = table_at(&table, &["project", "optional-dependencies"]) {
+            dependencies.extend(optional.values().flat_map(strings));
+        }
+
+        // Poetry: `[tool.poetry.dependencies]`, `[tool.poetry.dev-dependencies]`
+        // and `[tool.poetry.group.<name>.dependencies]`
+        let mut poetry_tables: Vec<&Table> = ["dependencies", "dev-dependencies"]
+            .iter()
+            .filter_map(|name| table_at(&table, &["tool", "poetry", name]))
+            .collect();
+        if let Some(groups) = table_at(&table, &["tool", "poetry", "group"]) {
+            poetry_tables.extend(
+                groups
+                    .values()
+                    .filter_map(Value::as_table)
+                    .filter_map(|group| table_at(group, &["dependencies"])),
+            );
+        }
+        dependencies.extend(
+            poetry_tables
+                .into_iter()
+                .flat_map(Table::keys)
+                .filter(|name| *name != "python")
+                .cloned(),
+        );
+
+        Self {
+            name: string_at(&table, &["project", "name"]),
+            dependencies,
+        }
+    }
+}
+
+/// A `go.mod`: the module path and required modules.
+#[derive(Debug, Default, PartialEq)]
+pub struct GoMod {
+    pub module: Option<String>,
+    pub requires: Vec<String>,
+}
+
+impl GoMod {
+    /// Parse a `go.mod`.
+    pub fn parse(text: &str) -> Self {
+        let mut go_mod = Self::default();
+        let mut in_require = false;
+        for line in text.lines() {
+            let line = line.split("//").next().unwrap_or(line).trim();
+            if in_require {
+                if line == ")" {
+                    in_require = false;
+                } else if let Some(module) = line.split_whitespace().next() {
+                    go_mod.requires.push(module.to_string());
+                }
+            } else if let Some(module) = line.strip_prefix("module") {
+                let module = module.trim().trim_matches('"');
+                if go_mod.module.is_none() && !module.is_empty() {
+                    go_mod.module = Some(module.to_string());
+                }
+            } else if let Some(require) = line.strip_prefix("require") {
+                match require.trim() {
+                    "(" => in_require = true,
+                    single => go_mod
+                        .requires
+                        .extend(single.split_whitespace().next().map(str::to_string)),
+                }
+            }
+        }
+        go_mod
+    }
+}
+
+/// The table at a path of keys.
+fn table_at<'a>(table: &'a Table, path: &[&str]) -> Option<&'a Table> {
+    path.iter()
+        .try_fold(table, |table, key| table.get(*key)?.as_table())
+}
+
+/// The string at a path of keys.
+fn string_at(table: &Table, path: &[&str]) -> Option<String> {
+    let (key, tables) = path.split_last()?;
+    table_at(table, tables)?
+        .get(*key)?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// The strings of the array at a path of keys.
+fn strings_at(table: &Table, path: &[&str]) -> Vec<String> {
+    let Some((key, tables)) = path.split_last() else {
+        return Vec::new();
+    };
+    table_at(table, tables)
+        .and_then(|table| table.get(*key))
+        .map(strings)
+        .unwrap_or_default()
+}
+
+/// The strings of an array value.
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_manifest() {
+        let manifest = CargoManifest::parse(
+            r#"
+[package]
+name = "my-crate" # the package
+
+[lib]
+path = 'lib/mod.rs'
+
+[workspace]
+members = [
+    "a",
+    'b', # single-quoted
+]
+
+[workspace.dependencies]
+core-lib = { path = "core" }
+
+[dependencies]
+reqwest.workspace = true
+xml = { version = "1", features = ["xpath"] }
+fork = { git = "https://example.com/path/fork" }
+core-lib = { workspace = true }
+"local" = { path = "../local" }
+
+[dev-dependencies.tokio-test]
+version = "0.4"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#,
+        );
+
+        assert_eq!(manifest.crate_name(), Some("my_crate".to_string()));
+        assert_eq!(manifest.lib_path.as_deref(), Some("lib/mod.rs"));
+        assert_eq!(manifest.members, vec!["a", "b"]);
+
+        let dependency = |name: &str| {
+            manifest
+                .dependencies
+                .iter()
+                .find(|d| d.name == name)
+                .map(|d| d.is_path)
+        };
+        assert_eq!(dependency("reqwest"), Some(false));
+        assert_eq!(dependency("xml"), Some(false));
+        assert_eq!(dependency("fork"), Some(false));
+        assert_eq!(dependency("local"), Some(true));
+        assert_eq!(dependency("tokio-test"), Some(false));
+        assert_eq!(dependency("libc"), Some(false));
+        assert!(manifest
+            .dependencies
+            .iter()
+            .any(|d| d.name == "core-lib" && d.is_path));
+    }
+
+    #[test]
+    fn test_invalid_manifest_reads_as_empty() {
+        assert_eq!(
+            CargoManifest::parse("[package\nname = "),
+            CargoManifest::default()
+        );
+    }
+
+    #[test]
+    fn test_pyproject() {
+        let pyproject = PyProject::parse(
+            r#"
+[project]
+name = "tools"
+dependencies = [
+  "requests>=2", # HTTP
+  'PyYAML',
+]
+
+[project.optional-dependencies]
+dev = ["pytest"]
+
+[tool.poetry.dependencies]
+python = "^3.10"
+click = "^8"
+
+[tool.poetry.group.docs.dependencies]
+mkdocs = "*"
+"#,
+        );
+
+        assert_eq!(pyproject.name.as_deref(), Some("tools"));
+        assert_eq!(
+            pyproject.dependencies,
+            vec!["requests>=2", "PyYAML", "pytest", "click", "mkdocs"]
+        );
+    }
+
+    #[test]
+    fn test_go_mod() {
+        let go_mod = GoMod::parse(
+            "module github.com/acme/svc // the service\n\ngo 1.22\n\nrequire golang.org/x/sync v0.7.0\n\nrequire (\n\tgithub.com/gorilla/mux v1.8.0\n)\n",
+        );
+
+        assert_eq!(go_mod.module.as_deref(), Some("github.com/acme/svc"));
+        assert_eq!(
+            go_mod.requires,
+            vec!["golang.org/x/sync", "github.com/gorilla/mux"]
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Markdown documentation chunking.
+//!
+//! Splits Markdown files (README, docs/, ADRs) into heading sections. Each section
+//! records its heading breadcrumb, the fenced code blocks it contains and the code
+//! identifiers it mentions in inline code spans, so documentation chunks can be
+//! embedded alongside code and linked to the symbols they describe.
+
+use doctown_common::ByteRange;
+use doctown_events::CodeBlock;
+use std::path::Path;
+
+/// File extensions treated as Markdown.
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdx"];
+
+/// A heading section of a Markdown file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownSection {
+    /// The heading text (None for text before the first heading).
+    pub heading: Option<String>,
+    /// Heading level (1-6), or 0 for text before the first heading.
+    pub level: usize,
+    /// Headings of the enclosing sections, outermost first, ending with this one.
+    pub heading_path: Vec<String>,
+    /// Byte range of the section, from its heading to the next heading.
+    pub range: ByteRange,
+    /// Fenced code blocks in the section.
+    pub code_blocks: Vec<CodeBlock>,
+    /// Code identifiers from inline code spans, in order of first mention.
+    pub mentions: Vec<String>,
+}
+
+/// Returns true if the file is Markdown documentation.
+pub fn is_markdown_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MARKDOWN_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// An open code fence: its marker character, length and start byte.
+struct Fence {
+    marker: char,
+    len: usize,
+    start: usize,
+    language: Option<String>,
+}
+
+/// Split a Markdown document into heading sections.
+///
+/// ATX (`# Title`) and setext (`Title` underlined with `===` or `---`) headings
+/// start a new section; headings inside fenced code blocks are ignored. Sections
+/// with no content besides their heading are dropped.
+pub fn chunk_markdown(content: &str) -> Vec<MarkdownSection> {
+    let mut sections = Vec::new();
+    let mut current = MarkdownSection {
+        heading: None,
+        level: 0,
+        heading_path: Vec::new(),
+        range: ByteRange::new(0, 0),
+        code_blocks: Vec::new(),
+        mentions: Vec::new(),
+    };
+    // (level, heading) of the enclosing sections
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut fence: Option<Fence> = None;
+    let mut previous_line: Option<(usize, &str)> = None;
+
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let text = line.trim_end_matches(['\n', '\r']);
+
+        if let Some(open) = &fence {
+            if is_closing_fence(text, open) {
+                current.code_blocks.push(CodeBlock::new(
+                    open.language.clone(),
+                    ByteRange::new(open.start, offset),
+                ));
+                fence = None;
+            }
+            previous_line = None;
+            continue;
+        }
+        if let Some(open) = opening_fence(text, start) {
+            fence = Some(open);
+            previous_line = None;
+            continue;
+        }
+
+        let heading = atx_heading(text)
+            .map(|(level, title)| (level, title, start))
+            .or_else(|| {
+                // Setext: the previous paragraph line becomes the heading
+                let level = setext_level(text)?;
+                let (line_start, title) = previous_line?;
+                Some((level, title.trim().to_string(), line_start))
+            });
+
+        match heading {
+            Some((level, title, heading_start)) => {
+                current.range.end = heading_start;
+                push_section(&mut sections, current, content);
+
+                while stack.last().is_some_and(|(l, _)| *l >= level) {
+                    stack.pop();
+                }
+                stack.push((level, title.clone()));
+                current = MarkdownSection {
+                    heading: Some(title),
+                    level,
+                    heading_path: stack.iter().map(|(_, h)| h.clone()).collect(),
+                    range: ByteRange::new(heading_start, offset),
+                    code_blocks: Vec::new(),
+                    mentions: Vec::new(),
+                };
+                previous_line = None;
+            }
+            None => {
+                add_mentions(&mut current.mentions, text);
+                previous_line = (!text.trim().is_empty()).then_some((start, text));
+            }
+        }
+    }
+
+    // An unclosed fence runs to the end of the document
+    if let Some(open) = fence {
+        current.code_blocks.push(CodeBlock::new(
+            open.language,
+            ByteRange::new(open.start, content.len()),
+        ));
+    }
+    current.range.end = content.len();
+    push_section(&mut sections, current, content);
+    sections
+}
+
+fn push_section(sections: &mut Vec<MarkdownSection>, section: MarkdownSection, content: &str) {
+    let text = &content[section.range.start..section.range.end];
+    let body = match section.heading {
+        // Skip the heading line(s) themselves
+        Some(_) => text.split_once('\n').map(|(_, rest)| rest).unwrap_or(""),
+        None => text,
+    };
+    let has_body = body
+        .lines()
+        .any(|l| !l.trim().is_empty() && setext_level(l).is_none());
+    if has_body || !section.code_blocks.is_empty() {
+        sections.push(section);
+    }
+}
+
+/// Parse an ATX heading (`## Title ##`), returning its level and text.
+fn atx_heading(line: &str) -> Option<(usize, String)> {
+    let trimmed = line
+        .strip_prefix("   ")
+        .or(line.strip_prefix("  "))
+        .or(line.strip_prefix(' '))
+        .unwrap_or(line);
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim();
+    Some((level, title.to_string()))
+}
+
+/// Returns the level of a setext underline (`===` is 1, `---` is 2).
+fn setext_level(line: &str) -> Option<usize> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || line.starts_with("    ") {
+        return None;
+    }
+    if trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Parse an opening code fence (```` ```lang ```` or `~~~lang`).
+fn opening_fence(line: &str, start: usize) -> Option<Fence> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|&c| c == marker).count();
+    if len < 3 {
+        return None;
+    }
+    let info = trimmed[len..].trim();
+    // Backtick fences can't have backticks in their info string
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+    // `rust,ignore` and `{.python}` name the language first
+    let language = info
+        .split([' ', ',', '{', '}', '.'])
+        .find(|s| !s.is_empty())
+        .map(str::to_lowercase);
+    Some(Fence {
+        marker,
+        len,
+        start,
+        language,
+    })
+}
+
+fn is_closing_fence(line: &str, open: &Fence) -> bool {
+    let trimmed = line.trim();
+    trimmed.chars().take_while(|&c| c == open.marker).count() >= open.len
+        && trimmed.chars().all(|c| c == open.marker)
+}
+
+/// Collect code identifiers from the inline code spans of a line.
+fn add_mentions(mentions: &mut Vec<String>, line: &str) {
+    for (i, span) in line.split('`').enumerate() {
+        // Odd segments are inside backticks
+        if i % 2 == 0 {
+            continue;
+        }
+        if let Some(identifier) = code_identifier(span) {
+            if !mentions.contains(&identifier) {
+                mentions.push(identifier);
+            }
+        }
+    }
+}
+
+/// Normalize an inline code span to an identifier path, if it looks like one.
+///
+/// `Parser::parse()`, `parse(content)` and `Vec<Symbol>` become `Parser::parse`,
+/// `parse` and `Vec`; spans with spaces or operators (shell commands, expressions)
+/// are not identifiers.
+fn code_identifier(span: &str) -> Option<String> {
+    let span = span.trim();
+    let path = span
+        .split(['(', '<', '!'])
+        .next()
+        .unwrap_or(span)
+        .trim_end_matches("::");
+    let is_identifier_path = path.split("::").flat_map(|s| s.split('.')).all(|segment| {
+        segment
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+            && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+    });
+    // Single-letter spans are almost never symbol names
+    (is_identifier_path && path.len() > 1).then(|| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_markdown_file() {
+        assert!(is_markdown_file(Path::new("README.md")));
+        assert!(is_markdown_file(Path::new(
+            "docs/adr/0001-storage.markdown"
+        )));
+        assert!(is_markdown_file(Path::new("docs/guide.MDX")));
+        assert!(!is_markdown_file(Path::new("src/lib.rs")));
+        assert!(!is_markdown_file(Path::new("LICENSE")));
+    }
+
+    #[test]
+    fn test_sections_by_heading() {
+        let content = "Intro paragraph.\n\n# Project\n\nOverview.\n\n## Install\n\nRun it.\n\n## Usage\n\nCall `run()`.\n\n# Empty\n\n# License\n\nMIT\n";
+        let sections = chunk_markdown(content);
+
+        let headings: Vec<_> = sections.iter().map(|s| s.heading.as_deref()).collect();
+        assert_eq!(
+            headings,
+            vec![
+                None,
+                Some("Project"),
+                Some("Install"),
+                Some("Usage"),
+                Some("License")
+            ]
+        );
+        assert_eq!(sections[2].heading_path, vec!["Project", "Install"]);
+        assert_eq!(sections[4].heading_path, vec!["License"]);
+        assert_eq!(sections[2].level, 2);
+
+        let install = &content[sections[2].range.start..sections[2].range.end];
+        assert_eq!(install, "## Install\n\nRun it.\n\n");
+        assert_eq!(sections.last().unwrap().range.end, content.len());
+    }
+
+    #[test]
+    fn test_setext_headings() {
+        let content = "Title\n=====\n\nText.\n\nSection\n-------\n\nMore text.\n";
+        let sections = chunk_markdown(content);
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].heading.as_deref(), Some("Title"));
+        assert_eq!(sections[0].range.start, 0);
+        assert_eq!(sections[1].heading_path, vec!["Title", "Section"]);
+    }
+
+    #[test]
+    fn test_code_blocks_tagged_by_language() {
+        let content = "# Example\n\n```rust,ignore\n# not a heading\nfn main() {}\n```\n\n~~~\nplain\n~~~\n\n```python\nprint('unclosed')\n";
+        let sections = chunk_markdown(content);
+
+        assert_eq!(sections.len(), 1);
+        let blocks = &sections[0].code_blocks;
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(
+            &content[blocks[0].byte_range.start..blocks[0].byte_range.end],
+            "```rust,ignore\n# not a heading\nfn main() {}\n```\n"
+        );
+        assert_eq!(blocks[1].language, None);
+        assert_eq!(blocks[2].language.as_deref(), Some("python"));
+        assert_eq!(blocks[2].byte_range.end, content.len());
+    }
+
+    #[test]
+    fn test_inline_code_mentions() {
+        let content = "# API\n\nUse `Parser::parse()` with a `Vec<Symbol>`, then `graph.build(x)`.\nRun `cargo test --all` and check `x` and `Parser::parse`.\n";
+        let sections = chunk_markdown(content);
+
+        assert_eq!(
+            sections[0].mentions,
+            vec!["Parser::parse", "Vec", "graph.build"]
+        );
+    }
+}
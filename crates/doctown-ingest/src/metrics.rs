@@ -0,0 +1,322 @@
+//! Per-symbol code metrics: lines of code, cyclomatic complexity, nesting depth
+//! and parameter count.
+//!
+//! Metrics are computed from the syntax tree node that spans each symbol, so
+//! container symbols (classes, impls, modules) report the totals of their bodies.
+
+use doctown_common::types::SymbolMetrics;
+use doctown_common::Language;
+use tree_sitter::{Node, Tree};
+
+use crate::symbol::Symbol;
+use crate::traversal::{child_by_field, node_text};
+
+/// Node kinds that add a branch to the control flow.
+fn branch_kinds(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &[
+            "if_expression",
+            "match_arm",
+            "while_expression",
+            "for_expression",
+            "loop_expression",
+        ],
+        Language::Python => &[
+            "if_statement",
+            "elif_clause",
+            "for_statement",
+            "while_statement",
+            "except_clause",
+            "conditional_expression",
+            "case_clause",
+            "for_in_clause",
+            "if_clause",
+        ],
+        Language::TypeScript | Language::JavaScript => &[
+            "if_statement",
+            "for_statement",
+            "for_in_statement",
+            "while_statement",
+            "do_statement",
+            "switch_case",
+            "catch_clause",
+            "ternary_expression",
+        ],
+        Language::Go => &[
+            "if_statement",
+            "for_statement",
+            "expression_case",
+            "type_case",
+            "communication_case",
+        ],
+    }
+}
+
+/// Node kinds that open a nested control-flow block.
+fn nesting_kinds(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &[
+            "if_expression",
+            "match_expression",
+            "while_expression",
+            "for_expression",
+            "loop_expression",
+        ],
+        Language::Python => &[
+            "if_statement",
+            "for_statement",
+            "while_statement",
+            "try_statement",
+            "with_statement",
+            "match_statement",
+        ],
+        Language::TypeScript | Language::JavaScript => &[
+            "if_statement",
+            "for_statement",
+            "for_in_statement",
+            "while_statement",
+            "do_statement",
+            "switch_statement",
+            "try_statement",
+        ],
+        Language::Go => &[
+            "if_statement",
+            "for_statement",
+            "expression_switch_statement",
+            "type_switch_statement",
+            "select_statement",
+        ],
+    }
+}
+
+/// Returns true for short-circuit boolean operators, which add a branch each.
+fn is_boolean_operator(node: Node<'_>, source: &str, language: Language) -> bool {
+    let binary_kind = match language {
+        Language::Python => "boolean_operator",
+        _ => "binary_expression",
+    };
+    if node.kind() != binary_kind {
+        return false;
+    }
+    child_by_field(node, "operator")
+        .is_some_and(|op| matches!(node_text(op, source), "&&" | "||" | "??" | "and" | "or"))
+}
+
+/// Returns true if `node` is the `else if` continuation of an enclosing `if`.
+///
+/// Such chains are one level of nesting, not one level per branch.
+fn is_else_if(node: Node<'_>) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        // Rust, JavaScript and TypeScript wrap the alternative in an else clause
+        "else_clause" => true,
+        // Go attaches the alternative `if` directly
+        "if_statement" => parent.child_by_field_name("alternative") == Some(node),
+        _ => false,
+    }
+}
+
+/// Compute metrics for the syntax tree node spanning a symbol.
+pub fn compute_metrics(node: Node<'_>, source_code: &str, language: Language) -> SymbolMetrics {
+    let text = node_text(node, source_code);
+    let lines_of_code = text.lines().filter(|line| !line.trim().is_empty()).count();
+
+    let branches = branch_kinds(language);
+    let nesting = nesting_kinds(language);
+
+    let mut branch_points = 0;
+    let mut max_nesting_depth = 0;
+    let mut stack = vec![(node, 0)];
+    while let Some((current, depth)) = stack.pop() {
+        if branches.contains(&current.kind()) || is_boolean_operator(current, source_code, language)
+        {
+            branch_points += 1;
+        }
+
+        let depth = if nesting.contains(&current.kind()) && !is_else_if(current) {
+            depth + 1
+        } else {
+            depth
+        };
+        max_nesting_depth = max_nesting_depth.max(depth);
+
+        for child in current.named_children(&mut current.walk()) {
+            stack.push((child, depth));
+        }
+    }
+
+    SymbolMetrics {
+        lines_of_code,
+        cyclomatic_complexity: 1 + branch_points,
+        max_nesting_depth,
+        parameter_count: parameter_count(node, source_code),
+    }
+}
+
+/// Returns the parameter list of a function-like node.
+///
+/// Variable declarations holding a function (`const f = (a) => a`) are followed
+/// through to the function value.
+fn parameters_node(node: Node<'_>) -> Option<Node<'_>> {
+    if let Some(params) =
+        child_by_field(node, "parameters").or_else(|| child_by_field(node, "parameter"))
+    {
+        return Some(params);
+    }
+    match node.kind() {
+        "lexical_declaration" | "variable_declaration" => node
+            .named_children(&mut node.walk())
+            .find(|c| c.kind() == "variable_declarator")
+            .and_then(parameters_node),
+        "variable_declarator" => child_by_field(node, "value").and_then(parameters_node),
+        "decorated_definition" => child_by_field(node, "definition").and_then(parameters_node),
+        _ => None,
+    }
+}
+
+fn parameter_count(node: Node<'_>, source_code: &str) -> usize {
+    let Some(params) = parameters_node(node) else {
+        return 0;
+    };
+    // Arrow function with a single bare parameter: `x => x`
+    if params.kind() == "identifier" {
+        return 1;
+    }
+
+    params
+        .named_children(&mut params.walk())
+        .map(|param| match param.kind() {
+            "comment" | "line_comment" | "block_comment" | "self_parameter" => 0,
+            "identifier" if matches!(node_text(param, source_code), "self" | "cls") => 0,
+            // Go declares several names per type: `a, b int`
+            "parameter_declaration" => param
+                .children_by_field_name("name", &mut param.walk())
+                .count()
+                .max(1),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Compute and set `metrics` on each symbol from the syntax tree.
+pub fn attach_metrics(symbols: &mut [Symbol], tree: &Tree, source_code: &str, language: Language) {
+    let root = tree.root_node();
+    for symbol in symbols {
+        if let Some(node) = root.descendant_for_byte_range(symbol.range.start, symbol.range.end) {
+            symbol.metrics = compute_metrics(node, source_code, language);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use crate::symbol::extract_symbols;
+
+    fn metrics_for(code: &str, language: Language, name: &str) -> SymbolMetrics {
+        let tree = parse(code, language).unwrap();
+        extract_symbols(&tree, code, language)
+            .into_iter()
+            .find(|s| s.name == name)
+            .map(|s| s.metrics)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_straight_line_function() {
+        let code = "fn add(a: i32, b: i32) -> i32 {\n\n    a + b\n}";
+        let metrics = metrics_for(code, Language::Rust, "add");
+
+        assert_eq!(
+            metrics,
+            SymbolMetrics {
+                lines_of_code: 3,
+                cyclomatic_complexity: 1,
+                max_nesting_depth: 0,
+                parameter_count: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rust_branches_and_nesting() {
+        let code = r#"
+fn classify(n: i32, strict: bool) -> &'static str {
+    if n < 0 && strict {
+        return "negative";
+    } else if n == 0 {
+        return "zero";
+    }
+    for i in 0..n {
+        match i {
+            0 => {}
+            _ => {}
+        }
+    }
+    "positive"
+}
+"#;
+        let metrics = metrics_for(code, Language::Rust, "classify");
+
+        // if, &&, else if, for, two match arms
+        assert_eq!(metrics.cyclomatic_complexity, 7);
+        // for > match
+        assert_eq!(metrics.max_nesting_depth, 2);
+        assert_eq!(metrics.parameter_count, 2);
+    }
+
+    #[test]
+    fn test_python_metrics() {
+        let code = r#"
+def load(path, retries=3, *args):
+    for attempt in range(retries):
+        try:
+            if path and attempt:
+                return open(path)
+        except OSError:
+            continue
+    return [x for x in args if x]
+"#;
+        let metrics = metrics_for(code, Language::Python, "load");
+
+        // for, if, and, except, comprehension for + if
+        assert_eq!(metrics.cyclomatic_complexity, 7);
+        // for > try > if
+        assert_eq!(metrics.max_nesting_depth, 3);
+        assert_eq!(metrics.parameter_count, 3);
+        assert_eq!(metrics.lines_of_code, 8);
+    }
+
+    #[test]
+    fn test_typescript_arrow_function() {
+        let code = "const pick = (a: number, b?: number) => a > 0 ? a : b ?? 0;";
+        let metrics = metrics_for(code, Language::TypeScript, "pick");
+
+        assert_eq!(metrics.cyclomatic_complexity, 3);
+        assert_eq!(metrics.parameter_count, 2);
+    }
+
+    #[test]
+    fn test_go_grouped_parameters() {
+        let code = r#"
+package main
+
+func clamp(v, lo, hi int) int {
+    if v < lo {
+        return lo
+    } else if v > hi {
+        return hi
+    }
+    return v
+}
+"#;
+        let metrics = metrics_for(code, Language::Go, "clamp");
+
+        assert_eq!(metrics.parameter_count, 3);
+        assert_eq!(metrics.cyclomatic_complexity, 3);
+        assert_eq!(metrics.max_nesting_depth, 1);
+    }
+}
@@ -0,0 +1,625 @@
+//! Rust module tree resolution.
+//!
+//! Builds the module tree of each crate in a repository, starting from the
+//! `lib.rs`/`main.rs` roots of the packages found in `Cargo.toml` (including
+//! workspace members) and following `mod foo;` declarations to `foo.rs` or
+//! `foo/mod.rs`. The tree maps files to module paths like `my_crate::net::http`,
+//! so symbols can be given their full Rust path and `use` paths can be resolved
+//! to files and symbols.
+
+use doctown_common::types::{Import, SymbolKind};
+use doctown_common::Language;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Tree};
+use walkdir::WalkDir;
+
+use crate::attributes::rust_attributes;
+use crate::manifests::CargoManifest;
+use crate::parsing::parse;
+use crate::symbol::{symbol_node, Symbol};
+use crate::traversal::{ancestors, child_by_field, node_text};
+
+/// Item kinds that declare a name in their module.
+const ITEM_KINDS: &[&str] = &[
+    "function_item",
+    "function_signature_item",
+    "struct_item",
+    "enum_item",
+    "union_item",
+    "trait_item",
+    "const_item",
+    "static_item",
+    "type_item",
+    "macro_definition",
+];
+
+/// Directories never searched for `Cargo.toml` files.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+/// A crate found in the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustCrate {
+    /// Crate name as used in paths (hyphens replaced by underscores).
+    pub name: String,
+    /// Root file of the crate (`src/lib.rs`, `src/main.rs`), relative to the repository.
+    pub root: PathBuf,
+}
+
+/// A `use` path resolved to a module in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedImport {
+    /// The full path of the import (e.g., `my_crate::net::Client`).
+    pub path: String,
+    /// The module the path resolves into (e.g., `my_crate::net`).
+    pub module: String,
+    /// File defining the module, relative to the repository.
+    pub file: PathBuf,
+    /// The imported item, if it is declared in that module (e.g., `Client`).
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct ModuleInfo {
+    /// File defining the module (the enclosing file for inline modules)
+    file: PathBuf,
+    /// Names of the items and submodules declared in the module
+    items: HashSet<String>,
+}
+
+/// The module trees of all crates in a repository.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleTree {
+    root: PathBuf,
+    crates: Vec<RustCrate>,
+    /// Map from module path to module info
+    modules: HashMap<String, ModuleInfo>,
+    /// Map from relative file path to module path
+    files: HashMap<PathBuf, String>,
+}
+
+impl ModuleTree {
+    /// Build the module trees of the crates under `root`.
+    ///
+    /// The shallowest `Cargo.toml` files are the entry points: their package and
+    /// workspace members are the crates. Files that can't be read or parsed are skipped.
+    pub fn build(root: &Path) -> Self {
+        let mut tree = Self {
+            root: root.to_path_buf(),
+            ..Self::default()
+        };
+        for manifest_dir in find_manifest_roots(root) {
+            tree.add_manifest(&manifest_dir, true);
+        }
+        tree
+    }
+
+    /// Returns true if no crates were found.
+    pub fn is_empty(&self) -> bool {
+        self.crates.is_empty()
+    }
+
+    /// Returns the crates in the repository.
+    pub fn crates(&self) -> &[RustCrate] {
+        &self.crates
+    }
+
+    /// Returns the module path of a file, relative to the repository.
+    pub fn module_path(&self, file: &Path) -> Option<&str> {
+        self.files.get(file).map(String::as_str)
+    }
+
+    /// Returns the file defining a module, relative to the repository.
+    pub fn module_file(&self, module_path: &str) -> Option<&Path> {
+        self.modules.get(module_path).map(|m| m.file.as_path())
+    }
+
+    fn add_manifest(&mut self, dir: &Path, follow_members: bool) {
+        let Ok(text) = fs::read_to_string(dir.join("Cargo.toml")) else {
+            return;
+        };
+        let manifest = CargoManifest::parse(&text);
+
+        if let Some(name) = manifest.crate_name() {
+            let lib = dir.join(manifest.lib_path.as_deref().unwrap_or("src/lib.rs"));
+            let bin = dir.join("src/main.rs");
+            for root_file in [lib, bin] {
+                if !root_file.is_file() {
+                    continue;
+                }
+                self.crates.push(RustCrate {
+                    name: name.clone(),
+                    root: self.relative(&root_file),
+                });
+                let child_dir = root_file.parent().unwrap_or(dir).to_path_buf();
+                self.add_file(&root_file, name.clone(), &child_dir, &mut HashSet::new());
+            }
+        }
+
+        if follow_members {
+            for member in &manifest.members {
+                for member_dir in expand_member(dir, member) {
+                    self.add_manifest(&member_dir, false);
+                }
+            }
+        }
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf()
+    }
+
+    fn add_file(
+        &mut self,
+        file: &Path,
+        module_path: String,
+        child_dir: &Path,
+        visited: &mut HashSet<PathBuf>,
+    ) {
+        if !visited.insert(file.to_path_buf()) {
+            return;
+        }
+        let Ok(source) = fs::read_to_string(file) else {
+            return;
+        };
+        let Some(tree) = parse(&source, Language::Rust) else {
+            return;
+        };
+        let relative = self.relative(file);
+        // A binary and a library can share a crate name; the library wins
+        self.files
+            .entry(relative.clone())
+            .or_insert_with(|| module_path.clone());
+        self.visit_module(
+            tree.root_node(),
+            &source,
+            &module_path,
+            child_dir,
+            file,
+            visited,
+        );
+    }
+
+    fn visit_module(
+        &mut self,
+        node: Node<'_>,
+        source: &str,
+        module_path: &str,
+        child_dir: &Path,
+        file: &Path,
+        visited: &mut HashSet<PathBuf>,
+    ) {
+        let relative = self.relative(file);
+        self.modules
+            .entry(module_path.to_string())
+            .or_insert_with(|| ModuleInfo {
+                file: relative,
+                items: HashSet::new(),
+            });
+
+        for child in node.named_children(&mut node.walk()) {
+            let is_module = child.kind() == "mod_item";
+            if !is_module && !ITEM_KINDS.contains(&child.kind()) {
+                continue;
+            }
+            let Some(name) = child_by_field(child, "name").map(|n| item_name(n, source)) else {
+                continue;
+            };
+            if let Some(module) = self.modules.get_mut(module_path) {
+                module.items.insert(name.to_string());
+            }
+            if !is_module {
+                continue;
+            }
+
+            let submodule_path = format!("{}::{}", module_path, name);
+            if let Some(body) = child_by_field(child, "body") {
+                self.visit_module(
+                    body,
+                    source,
+                    &submodule_path,
+                    &child_dir.join(name),
+                    file,
+                    visited,
+                );
+            } else if let Some((target, target_child_dir)) =
+                module_file(child, source, name, child_dir, file)
+            {
+                self.add_file(&target, submodule_path, &target_child_dir, visited);
+            }
+        }
+    }
+
+    /// Resolve a Rust path (e.g., `crate::net::Client`, `super::util`) used in `from_file`.
+    ///
+    /// Returns `None` for paths outside the repository's crates, such as `std::...`.
+    pub fn resolve_path(&self, from_file: &Path, path: &str) -> Option<ResolvedImport> {
+        let from_module = self.module_path(from_file)?;
+        let mut segments: Vec<&str> = path
+            .trim_start_matches("::")
+            .split("::")
+            .map(|s| s.trim().trim_start_matches("r#"))
+            .filter(|s| !s.is_empty() && *s != "*")
+            .collect();
+        // `use a::b::{self}` imports the module itself
+        if segments.len() > 1 && segments.last() == Some(&"self") {
+            segments.pop();
+        }
+        let mut segments = segments.into_iter().peekable();
+
+        let mut full: Vec<&str> = from_module.split("::").collect();
+        match *segments.peek()? {
+            "crate" => {
+                full.truncate(1);
+                segments.next();
+            }
+            "self" => {
+                segments.next();
+            }
+            "super" => {
+                while segments.next_if_eq(&"super").is_some() {
+                    if full.len() > 1 {
+                        full.pop();
+                    }
+                }
+            }
+            first if self.crates.iter().any(|c| c.name == first) => {
+                full.clear();
+            }
+            // 2018-edition paths relative to the current module
+            first
+                if self
+                    .modules
+                    .contains_key(&format!("{}::{}", from_module, first)) => {}
+            _ => return None,
+        }
+        full.extend(segments);
+
+        (1..=full.len()).rev().find_map(|len| {
+            let module = full[..len].join("::");
+            let info = self.modules.get(&module)?;
+            let symbol = full
+                .get(len)
+                .filter(|item| info.items.contains(**item))
+                .map(|item| item.to_string());
+            Some(ResolvedImport {
+                path: full.join("::"),
+                module,
+                file: info.file.clone(),
+                symbol,
+            })
+        })
+    }
+
+    /// Resolve the paths of a `use` import in `from_file`, one per imported item.
+    pub fn resolve_import(&self, from_file: &Path, import: &Import) -> Vec<ResolvedImport> {
+        let base = import.module_path.trim_end_matches("::*");
+        match &import.imported_items {
+            Some(items) => items
+                .iter()
+                // Nested use lists are kept as raw text by the extractor
+                .filter(|item| !item.contains('{'))
+                .filter_map(|item| self.resolve_path(from_file, &format!("{}::{}", base, item)))
+                .collect(),
+            None => self.resolve_path(from_file, base).into_iter().collect(),
+        }
+    }
+
+    /// Set `qualified_name` on the symbols of `file` from its module path and
+    /// the inline modules enclosing each symbol.
+    pub fn qualify_symbols(
+        &self,
+        file: &Path,
+        symbols: &mut [Symbol],
+        tree: &Tree,
+        source_code: &str,
+    ) {
+        let Some(module_path) = self.module_path(file) else {
+            return;
+        };
+        for symbol in symbols {
+            // `impl Trait for Type` blocks have no path of their own
+            if symbol.kind == SymbolKind::Impl {
+                continue;
+            }
+            let Some(node) = symbol_node(tree, symbol) else {
+                continue;
+            };
+            let mut inline_modules: Vec<&str> = ancestors(node)
+                .filter(|n| n.kind() == "mod_item")
+                .filter_map(|n| child_by_field(n, "name"))
+                .map(|n| item_name(n, source_code))
+                .collect();
+            inline_modules.reverse();
+
+            let mut path = module_path.to_string();
+            for module in inline_modules {
+                path.push_str("::");
+                path.push_str(module);
+            }
+            path.push_str("::");
+            path.push_str(&symbol.name);
+            symbol.qualified_name = Some(path);
+        }
+    }
+}
+
+/// Returns an item's name without the raw identifier prefix (`r#type` -> `type`).
+fn item_name<'a>(name_node: Node<'_>, source: &'a str) -> &'a str {
+    node_text(name_node, source).trim_start_matches("r#")
+}
+
+/// Find the file of a `mod name;` declaration and the directory of its submodules.
+///
+/// Honors `#[path = "..."]`, which is relative to the declaring file's directory.
+fn module_file(
+    node: Node<'_>,
+    source: &str,
+    name: &str,
+    child_dir: &Path,
+    file: &Path,
+) -> Option<(PathBuf, PathBuf)> {
+    let path_attribute = rust_attributes(node, source)
+        .into_iter()
+        .map(doctown_common::Attribute::parse)
+        .find(|a| a.name == "path")
+        .and_then(|a| a.arguments)
+        .map(|p| p.trim_matches('"').to_string());
+
+    if let Some(path) = path_attribute {
+        let target = file.parent()?.join(path);
+        let dir = target.parent()?.to_path_buf();
+        return target.is_file().then_some((target, dir));
+    }
+
+    let file_module = child_dir.join(format!("{}.rs", name));
+    if file_module.is_file() {
+        return Some((file_module, child_dir.join(name)));
+    }
+    let mod_rs = child_dir.join(name).join("mod.rs");
+    mod_rs.is_file().then(|| (mod_rs, child_dir.join(name)))
+}
+
+/// Returns the directories of the shallowest `Cargo.toml` files under `root`.
+fn find_manifest_roots(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0 || !(name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.file_name() == "Cargo.toml")
+        .filter_map(|e| e.path().parent().map(Path::to_path_buf))
+        .collect();
+    dirs.sort_by_key(|d| d.components().count());
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        if !roots.iter().any(|r| dir.starts_with(r)) {
+            roots.push(dir);
+        }
+    }
+    roots
+}
+
+/// Expand a workspace member entry, which may end in a `*` glob (`crates/*`).
+fn expand_member(workspace_dir: &Path, member: &str) -> Vec<PathBuf> {
+    let Some((parent, pattern)) = member.rsplit_once('/').filter(|(_, p)| p.contains('*')) else {
+        if member.contains('*') {
+            return expand_member(workspace_dir, &format!("./{}", member));
+        }
+        return vec![workspace_dir.join(member)];
+    };
+    let (prefix, suffix) = pattern.split_once('*').unwrap_or((pattern, ""));
+    let Ok(entries) = fs::read_dir(workspace_dir.join(parent)) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            name.starts_with(prefix)
+                && name.ends_with(suffix)
+                && e.path().join("Cargo.toml").is_file()
+        })
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::imports::extract_imports;
+    use crate::symbol::extract_symbols;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\n    \"crates/*\", # all crates\n]\n",
+        );
+        write(
+            root,
+            "crates/net-core/Cargo.toml",
+            "[package]\nname = \"net-core\"\nversion = \"0.1.0\"\n",
+        );
+        write(
+            root,
+            "crates/net-core/src/lib.rs",
+            "pub mod client;\nmod util;\n#[path = \"platform/linux.rs\"]\nmod sys;\n\npub fn init() {}\n",
+        );
+        write(
+            root,
+            "crates/net-core/src/client.rs",
+            "mod pool;\n\npub struct Client;\n\npub mod retry {\n    pub fn backoff() {}\n}\n",
+        );
+        write(
+            root,
+            "crates/net-core/src/client/pool.rs",
+            "use super::Client;\nuse crate::util::{parse, Missing};\n\npub struct Pool;\n",
+        );
+        write(
+            root,
+            "crates/net-core/src/util/mod.rs",
+            "pub fn parse() {}\n",
+        );
+        write(
+            root,
+            "crates/net-core/src/platform/linux.rs",
+            "pub fn page_size() -> usize { 4096 }\n",
+        );
+        write(
+            root,
+            "crates/app/Cargo.toml",
+            "[package]\nname = \"app\"\n\n[dependencies]\nnet-core = { path = \"../net-core\" }\n",
+        );
+        write(
+            root,
+            "crates/app/src/main.rs",
+            "use net_core::client::Client;\nuse std::collections::HashMap;\nuse net_core::*;\n\nfn main() {}\n",
+        );
+        dir
+    }
+
+    #[test]
+    fn test_module_tree_files() {
+        let dir = workspace();
+        let tree = ModuleTree::build(dir.path());
+
+        let names: Vec<_> = tree.crates().iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "net_core"]);
+
+        let module = |path: &str| tree.module_path(Path::new(path));
+        assert_eq!(module("crates/net-core/src/lib.rs"), Some("net_core"));
+        assert_eq!(
+            module("crates/net-core/src/client.rs"),
+            Some("net_core::client")
+        );
+        assert_eq!(
+            module("crates/net-core/src/client/pool.rs"),
+            Some("net_core::client::pool")
+        );
+        assert_eq!(
+            module("crates/net-core/src/util/mod.rs"),
+            Some("net_core::util")
+        );
+        assert_eq!(
+            module("crates/net-core/src/platform/linux.rs"),
+            Some("net_core::sys")
+        );
+        assert_eq!(module("crates/app/src/main.rs"), Some("app"));
+
+        assert_eq!(
+            tree.module_file("net_core::client::retry"),
+            Some(Path::new("crates/net-core/src/client.rs"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_imports() {
+        let dir = workspace();
+        let tree = ModuleTree::build(dir.path());
+
+        let pool = Path::new("crates/net-core/src/client/pool.rs");
+        let source = fs::read_to_string(dir.path().join(pool)).unwrap();
+        let parsed = parse(&source, Language::Rust).unwrap();
+        let imports = extract_imports(&parsed, &source, Language::Rust);
+
+        let resolved = tree.resolve_import(pool, &imports[0]);
+        assert_eq!(
+            resolved,
+            vec![ResolvedImport {
+                path: "net_core::client::Client".to_string(),
+                module: "net_core::client".to_string(),
+                file: PathBuf::from("crates/net-core/src/client.rs"),
+                symbol: Some("Client".to_string()),
+            }]
+        );
+
+        let resolved = tree.resolve_import(pool, &imports[1]);
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].symbol.as_deref(), Some("parse"));
+        assert_eq!(
+            resolved[0].file,
+            PathBuf::from("crates/net-core/src/util/mod.rs")
+        );
+        // Not declared in the module (e.g. a re-export we can't see)
+        assert_eq!(resolved[1].module, "net_core::util");
+        assert_eq!(resolved[1].symbol, None);
+
+        let main = Path::new("crates/app/src/main.rs");
+        let source = fs::read_to_string(dir.path().join(main)).unwrap();
+        let parsed = parse(&source, Language::Rust).unwrap();
+        let imports = extract_imports(&parsed, &source, Language::Rust);
+
+        let across_crates = tree.resolve_import(main, &imports[0]);
+        assert_eq!(across_crates[0].path, "net_core::client::Client");
+        assert!(tree.resolve_import(main, &imports[1]).is_empty());
+        let wildcard = tree.resolve_import(main, &imports[2]);
+        assert_eq!(wildcard[0].module, "net_core");
+        assert_eq!(wildcard[0].symbol, None);
+
+        let relative = tree
+            .resolve_path(
+                Path::new("crates/net-core/src/lib.rs"),
+                "client::retry::backoff",
+            )
+            .unwrap();
+        assert_eq!(relative.module, "net_core::client::retry");
+        assert_eq!(relative.symbol.as_deref(), Some("backoff"));
+    }
+
+    #[test]
+    fn test_qualify_symbols() {
+        let dir = workspace();
+        let tree = ModuleTree::build(dir.path());
+
+        let file = Path::new("crates/net-core/src/client.rs");
+        let source = fs::read_to_string(dir.path().join(file)).unwrap();
+        let parsed = parse(&source, Language::Rust).unwrap();
+        let mut symbols = extract_symbols(&parsed, &source, Language::Rust);
+        tree.qualify_symbols(file, &mut symbols, &parsed, &source);
+
+        let qualified = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name == name)
+                .and_then(|s| s.qualified_name.clone())
+        };
+        assert_eq!(
+            qualified("Client").as_deref(),
+            Some("net_core::client::Client")
+        );
+        assert_eq!(
+            qualified("backoff").as_deref(),
+            Some("net_core::client::retry::backoff")
+        );
+        assert_eq!(
+            qualified("retry").as_deref(),
+            Some("net_core::client::retry")
+        );
+    }
+
+    #[test]
+    fn test_repository_without_cargo_manifest() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "src/lib.rs", "mod a;\n");
+        let tree = ModuleTree::build(dir.path());
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.module_path(Path::new("src/lib.rs")), None);
+    }
+}
@@ -0,0 +1,272 @@
+//! In-process CPU embeddings with ONNX Runtime.
+//!
+//! Runs an exported sentence-transformer model (such as MiniLM-L6) without
+//! the embedding worker: inputs are tokenized with the model's
+//! `tokenizer.json`, token vectors are mean-pooled over the attention mask and
+//! normalized to unit length, as sentence-transformers does.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use doctown_common::{ChunkId, DocError};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+use crate::embedding::DEFAULT_MODEL_ID;
+use crate::embedding_provider::{EmbedFuture, EmbeddingProvider};
+use crate::tokenizer::MODEL_MAX_TOKENS;
+
+/// Sentence-transformer model run in-process on the CPU.
+///
+/// Cheap to clone; the session and tokenizer are shared. Batches run one at a
+/// time on a blocking thread.
+#[derive(Clone)]
+pub struct OnnxEmbedder {
+    model_id: String,
+    model: Arc<OnnxModel>,
+}
+
+struct OnnxModel {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    /// Whether the model takes `token_type_ids` (BERT-style models do).
+    takes_token_type_ids: bool,
+}
+
+impl OnnxEmbedder {
+    /// Loads the model at `model_path` and its tokenizer at `tokenizer_path`.
+    ///
+    /// The model ID defaults to [`DEFAULT_MODEL_ID`], the model the embedding
+    /// worker runs, so both share cached vectors; set it with
+    /// [`with_model_id`](Self::with_model_id) for any other model.
+    pub fn from_files(
+        model_path: impl AsRef<Path>,
+        tokenizer_path: impl AsRef<Path>,
+    ) -> Result<Self, DocError> {
+        let model_path = model_path.as_ref();
+        let tokenizer_path = tokenizer_path.as_ref();
+
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.commit_from_file(model_path))
+            .map_err(|e| {
+                DocError::Internal(format!(
+                    "Failed to load ONNX model {}: {}",
+                    model_path.display(),
+                    e
+                ))
+            })?;
+        let takes_token_type_ids = session
+            .inputs
+            .iter()
+            .any(|input| input.name == "token_type_ids");
+
+        let mut tokenizer = Tokenizer::from_file(tokenizer_path).map_err(|e| {
+            DocError::Internal(format!(
+                "Failed to load tokenizer {}: {}",
+                tokenizer_path.display(),
+                e
+            ))
+        })?;
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MODEL_MAX_TOKENS,
+                ..Default::default()
+            }))
+            .map_err(|e| DocError::Internal(format!("Failed to configure tokenizer: {}", e)))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+
+        Ok(Self {
+            model_id: DEFAULT_MODEL_ID.to_string(),
+            model: Arc::new(OnnxModel {
+                session: Mutex::new(session),
+                tokenizer,
+                takes_token_type_ids,
+            }),
+        })
+    }
+
+    pub fn with_model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = model_id.into();
+        self
+    }
+}
+
+impl OnnxModel {
+    /// Embeds `inputs`, blocking the thread.
+    fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, DocError> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let encodings = self
+            .tokenizer
+            .encode_batch(inputs, true)
+            .map_err(|e| DocError::Validation(format!("Failed to tokenize batch: {}", e)))?;
+        let batch_size = encodings.len();
+        let seq_len = encodings[0].len();
+
+        let flatten = |values: fn(&tokenizers::Encoding) -> &[u32]| -> Vec<i64> {
+            encodings
+                .iter()
+                .flat_map(|encoding| values(encoding).iter().map(|&v| v as i64))
+                .collect()
+        };
+        let input_ids = flatten(tokenizers::Encoding::get_ids);
+        let attention_mask = flatten(tokenizers::Encoding::get_attention_mask);
+        let token_type_ids = flatten(tokenizers::Encoding::get_type_ids);
+
+        let shape = [batch_size, seq_len];
+        let to_tensor = |values: Vec<i64>| {
+            Tensor::from_array((shape, values))
+                .map_err(|e| DocError::Internal(format!("Failed to build input tensor: {}", e)))
+        };
+
+        let mut session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+        let outputs = if self.takes_token_type_ids {
+            session.run(ort::inputs![
+                "input_ids" => to_tensor(input_ids)?,
+                "attention_mask" => to_tensor(attention_mask.clone())?,
+                "token_type_ids" => to_tensor(token_type_ids)?,
+            ])
+        } else {
+            session.run(ort::inputs![
+                "input_ids" => to_tensor(input_ids)?,
+                "attention_mask" => to_tensor(attention_mask.clone())?,
+            ])
+        }
+        .map_err(|e| DocError::Internal(format!("ONNX inference failed: {}", e)))?;
+
+        // Token vectors of shape [batch, seq, hidden]
+        let (output_shape, hidden_states) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| DocError::Internal(format!("Unexpected ONNX output: {}", e)))?;
+        let hidden = hidden_size(&output_shape[..], batch_size, seq_len)?;
+
+        Ok((0..batch_size)
+            .map(|row| {
+                mean_pool(
+                    &hidden_states[row * seq_len * hidden..(row + 1) * seq_len * hidden],
+                    &attention_mask[row * seq_len..(row + 1) * seq_len],
+                    hidden,
+                )
+            })
+            .collect())
+    }
+}
+
+/// Size of the token vectors in an output of `shape`, which must be
+/// `[batch_size, seq_len, hidden]`.
+///
+/// Any other shape is a model this embedder can't pool, such as one that
+/// outputs pooled vectors already, and no batch will fare better.
+fn hidden_size(shape: &[i64], batch_size: usize, seq_len: usize) -> Result<usize, DocError> {
+    match shape {
+        &[batch, seq, hidden]
+            if batch == batch_size as i64 && seq == seq_len as i64 && hidden > 0 =>
+        {
+            Ok(hidden as usize)
+        }
+        _ => Err(DocError::Config(format!(
+            "Expected token embeddings of shape [{}, {}, hidden], got {:?}",
+            batch_size, seq_len, shape
+        ))),
+    }
+}
+
+/// Averages the token vectors under the attention mask and normalizes the
+/// result to unit length.
+fn mean_pool(token_vectors: &[f32], attention_mask: &[i64], hidden: usize) -> Vec<f32> {
+    let mut pooled = vec![0.0f32; hidden];
+    let mut tokens = 0.0f32;
+    for (token, &mask) in token_vectors.chunks(hidden).zip(attention_mask) {
+        if mask == 0 {
+            continue;
+        }
+        tokens += 1.0;
+        pooled.iter_mut().zip(token).for_each(|(p, t)| *p += t);
+    }
+    if tokens > 0.0 {
+        pooled.iter_mut().for_each(|p| *p /= tokens);
+    }
+
+    let norm = pooled.iter().map(|p| p * p).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        pooled.iter_mut().for_each(|p| *p /= norm);
+    }
+    pooled
+}
+
+impl EmbeddingProvider for OnnxEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embed<'a>(&'a self, _batch_id: &'a str, chunks: Vec<(ChunkId, String)>) -> EmbedFuture<'a> {
+        let model = Arc::clone(&self.model);
+        Box::pin(async move {
+            let (chunk_ids, inputs): (Vec<_>, Vec<_>) = chunks.into_iter().unzip();
+            let vectors = tokio::task::spawn_blocking(move || model.embed(inputs))
+                .await
+                .map_err(|e| DocError::Internal(format!("Embedding task failed: {}", e)))??;
+            Ok(chunk_ids.into_iter().zip(vectors).collect())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean_pool_ignores_padding() {
+        let token_vectors = [1.0, 0.0, 3.0, 0.0, 100.0, 100.0];
+        let pooled = mean_pool(&token_vectors, &[1, 1, 0], 2);
+        assert_eq!(pooled, vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_hidden_size_checks_batch_and_sequence() {
+        assert_eq!(hidden_size(&[2, 7, 384], 2, 7).unwrap(), 384);
+        assert!(matches!(
+            hidden_size(&[2, 384], 2, 7),
+            Err(DocError::Config(_))
+        ));
+        assert!(matches!(
+            hidden_size(&[1, 7, 384], 2, 7),
+            Err(DocError::Config(_))
+        ));
+        assert!(matches!(
+            hidden_size(&[2, 5, 384], 2, 7),
+            Err(DocError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[ignore] // Only run with an exported model at models/minilm-l6/model.onnx
+    fn test_embed_with_bundled_tokenizer() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../../models/minilm-l6");
+        let embedder = OnnxEmbedder::from_files(
+            format!("{}/model.onnx", dir),
+            format!("{}/tokenizer.json", dir),
+        )
+        .unwrap();
+
+        let vectors = embedder
+            .model
+            .embed(vec![
+                "fn main() {}".to_string(),
+                "class Parser:".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(embedder.model_id(), DEFAULT_MODEL_ID);
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0].len(), 384);
+    }
+}
@@ -0,0 +1,374 @@
+//! Embeddings over the OpenAI `/v1/embeddings` protocol.
+//!
+//! Besides OpenAI itself, many local servers speak this protocol, such as
+//! llama.cpp, text-embeddings-inference, vLLM and Ollama. Responses are
+//! validated before use: one vector per input, all of the same dimensions,
+//! with no NaN or infinite values. A wrong count or size is a misconfigured
+//! model or server, and fails the batch with `DocError::Config`.
+
+use std::time::Duration;
+
+use doctown_common::{ChunkId, DocError};
+use serde::{Deserialize, Serialize};
+
+use crate::embedding_provider::{validate_vectors, EmbedFuture, EmbeddingProvider};
+
+/// Request body of `POST /embeddings`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenAiEmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+    pub encoding_format: String,
+    /// Requested dimensions, for models that can shorten their vectors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<usize>,
+}
+
+/// Response body of `POST /embeddings`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAiEmbeddingResponse {
+    pub data: Vec<OpenAiEmbedding>,
+    /// The model that made the vectors, as reported by the server.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// One vector of an [`OpenAiEmbeddingResponse`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAiEmbedding {
+    /// Position of the input this vector belongs to.
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// Client for an OpenAI-compatible embeddings API.
+#[derive(Clone)]
+pub struct OpenAiEmbedder {
+    base_url: String,
+    model: String,
+    /// The model and, when requested, its dimensions: `model@dimensions`
+    model_id: String,
+    dimensions: Option<usize>,
+    auth_header: Option<(String, String)>,
+    client: reqwest::Client,
+}
+
+impl OpenAiEmbedder {
+    /// Creates a client for `model` at `base_url`, the API root such as
+    /// `https://api.openai.com/v1` or `http://localhost:8080/v1`.
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        let model = model.into();
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            model_id: model.clone(),
+            model,
+            dimensions: None,
+            auth_header: None,
+            client,
+        }
+    }
+
+    /// Requests vectors of `dimensions` and rejects vectors of any other size.
+    ///
+    /// Shortened vectors differ from full ones, so the dimensions become part
+    /// of the model ID, such as `text-embedding-3-small@512`.
+    pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = Some(dimensions);
+        self.model_id = format!("{}@{}", self.model, dimensions);
+        self
+    }
+
+    /// Sends `Authorization: Bearer <api_key>`.
+    pub fn with_api_key(self, api_key: impl AsRef<str>) -> Self {
+        self.with_auth_header("Authorization", format!("Bearer {}", api_key.as_ref()))
+    }
+
+    /// Sends a custom auth header, such as `api-key` for Azure OpenAI.
+    pub fn with_auth_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.auth_header = Some((name.into(), value.into()));
+        self
+    }
+
+    /// Embeds `inputs`, returning their vectors in order.
+    pub async fn embed_inputs(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, DocError> {
+        let expected_count = inputs.len();
+        let request = OpenAiEmbeddingRequest {
+            model: self.model.clone(),
+            input: inputs,
+            encoding_format: "float".to_string(),
+            dimensions: self.dimensions,
+        };
+
+        let url = format!("{}/embeddings", self.base_url);
+        let mut builder = self.client.post(&url).json(&request);
+        if let Some((name, value)) = &self.auth_header {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| DocError::Http(format!("Failed to call embeddings API: {}", e)))?;
+
+        let status = response.status();
+        if status.is_client_error()
+            && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+            && status != reqwest::StatusCode::REQUEST_TIMEOUT
+        {
+            let body = response.text().await.unwrap_or_default();
+            return Err(DocError::Validation(format!(
+                "Embeddings API rejected batch: {} {}",
+                status, body
+            )));
+        }
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(DocError::RateLimited(format!(
+                "Embeddings API returned {}",
+                status
+            )));
+        }
+        if !status.is_success() {
+            return Err(DocError::Internal(format!(
+                "Embeddings API returned error: {}",
+                status
+            )));
+        }
+
+        let response: OpenAiEmbeddingResponse = response.json().await.map_err(|e| {
+            DocError::Internal(format!("Failed to parse embeddings response: {}", e))
+        })?;
+        let vectors = self.vectors_in_order(response, expected_count)?;
+
+        validate_vectors(&vectors, expected_count, self.dimensions)?;
+        Ok(vectors)
+    }
+
+    /// Orders the vectors of a response by input index, checking that each
+    /// input has exactly one.
+    fn vectors_in_order(
+        &self,
+        response: OpenAiEmbeddingResponse,
+        expected_count: usize,
+    ) -> Result<Vec<Vec<f32>>, DocError> {
+        if response.data.len() != expected_count {
+            return Err(DocError::Config(format!(
+                "Expected {} embeddings, got {}",
+                expected_count,
+                response.data.len()
+            )));
+        }
+
+        let mut vectors: Vec<Option<Vec<f32>>> = vec![None; expected_count];
+        for embedding in response.data {
+            match vectors.get_mut(embedding.index) {
+                Some(slot @ None) => *slot = Some(embedding.embedding),
+                _ => {
+                    return Err(DocError::Config(format!(
+                        "Unexpected or duplicate embedding index {}",
+                        embedding.index
+                    )))
+                }
+            }
+        }
+        // Every slot is filled: there are as many embeddings as slots, each in its own
+        Ok(vectors.into_iter().flatten().collect())
+    }
+}
+
+impl EmbeddingProvider for OpenAiEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        self.dimensions
+    }
+
+    fn embed<'a>(&'a self, _batch_id: &'a str, chunks: Vec<(ChunkId, String)>) -> EmbedFuture<'a> {
+        Box::pin(async move {
+            let (chunk_ids, inputs): (Vec<_>, Vec<_>) = chunks.into_iter().unzip();
+            let vectors = self.embed_inputs(inputs).await?;
+            Ok(chunk_ids.into_iter().zip(vectors).collect())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding_cache::EmbeddingCache;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// Answers one request with `body` and returns the base URL and a handle
+    /// resolving to the raw request.
+    async fn serve_once(body: serde_json::Value) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(body_start) = text.find("\r\n\r\n") {
+                    let content_length: usize = text[..body_start]
+                        .to_lowercase()
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map(|len| len.trim().parse().unwrap())
+                        .unwrap_or(0);
+                    if request.len() >= body_start + 4 + content_length {
+                        break;
+                    }
+                }
+            }
+
+            let body = body.to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (format!("http://{}/v1", addr), handle)
+    }
+
+    fn inputs(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_embed_orders_by_index_and_sends_config() {
+        let (url, request) = serve_once(serde_json::json!({
+            "object": "list",
+            "model": "text-embedding-3-small",
+            "data": [
+                {"object": "embedding", "index": 1, "embedding": [0.0, 1.0]},
+                {"object": "embedding", "index": 0, "embedding": [1.0, 0.0]}
+            ]
+        }))
+        .await;
+        let embedder = OpenAiEmbedder::new(url, "text-embedding-3-small")
+            .with_dimensions(2)
+            .with_api_key("sk-test");
+
+        let vectors = embedder.embed_inputs(inputs(&["a", "b"])).await.unwrap();
+
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /v1/embeddings"));
+        assert!(request
+            .to_lowercase()
+            .contains("authorization: bearer sk-test"));
+        assert!(request.contains(r#""model":"text-embedding-3-small""#));
+        assert!(request.contains(r#""dimensions":2"#));
+    }
+
+    #[test]
+    fn test_model_id_includes_dimensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("embeddings.cache");
+        let full = OpenAiEmbedder::new("http://unused", "text-embedding-3-small");
+        let shortened = full.clone().with_dimensions(512);
+        assert_eq!(full.model_id(), "text-embedding-3-small");
+        assert_eq!(shortened.model_id(), "text-embedding-3-small@512");
+
+        let mut cache = EmbeddingCache::open(&path, full.model_id()).unwrap();
+        cache.insert("sha256:abc", vec![0.5; 1536]);
+        cache.save().unwrap();
+
+        // Changing the dimensions misses the vectors cached before
+        let mut cache = EmbeddingCache::open(&path, shortened.model_id()).unwrap();
+        assert_eq!(cache.get("sha256:abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_custom_auth_header() {
+        let (url, request) = serve_once(serde_json::json!({
+            "data": [{"index": 0, "embedding": [1.0]}]
+        }))
+        .await;
+        let embedder =
+            OpenAiEmbedder::new(url, "nomic-embed-text").with_auth_header("api-key", "secret");
+
+        embedder.embed_inputs(inputs(&["a"])).await.unwrap();
+
+        let request = request.await.unwrap().to_lowercase();
+        assert!(request.contains("api-key: secret"));
+        assert!(!request.contains("authorization"));
+        assert!(!request.contains("dimensions"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_count_mismatch() {
+        let (url, _) = serve_once(serde_json::json!({
+            "data": [{"index": 0, "embedding": [1.0, 0.0]}]
+        }))
+        .await;
+        let embedder = OpenAiEmbedder::new(url, "bge-small");
+
+        let error = embedder
+            .embed_inputs(inputs(&["a", "b"]))
+            .await
+            .unwrap_err();
+
+        // Fatal: bisecting the batch wouldn't make the server count right
+        assert!(matches!(error, DocError::Config(_)));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_null_values() {
+        let (url, _) = serve_once(serde_json::json!({
+            "data": [{"index": 0, "embedding": [1.0, null]}]
+        }))
+        .await;
+        let embedder = OpenAiEmbedder::new(url, "bge-small");
+
+        // JSON has no NaN, so servers write null (or invalid JSON) instead
+        assert!(embedder.embed_inputs(inputs(&["a"])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_dimensions_differing_from_requested() {
+        let (url, _) = serve_once(serde_json::json!({
+            "data": [{"index": 0, "embedding": [1.0, 0.0]}]
+        }))
+        .await;
+        let embedder = OpenAiEmbedder::new(url, "bge-small").with_dimensions(3);
+
+        let error = embedder.embed_inputs(inputs(&["a"])).await.unwrap_err();
+
+        assert!(matches!(error, DocError::Config(_)));
+        assert!(error.to_string().contains("expected 3"));
+    }
+
+    #[test]
+    fn test_vectors_in_order_rejects_duplicate_index() {
+        let embedder = OpenAiEmbedder::new("http://unused", "bge-small");
+        let response = OpenAiEmbeddingResponse {
+            data: vec![
+                OpenAiEmbedding {
+                    index: 0,
+                    embedding: vec![1.0],
+                },
+                OpenAiEmbedding {
+                    index: 0,
+                    embedding: vec![0.5],
+                },
+            ],
+            model: None,
+        };
+
+        assert!(embedder.vectors_in_order(response, 2).is_err());
+    }
+}
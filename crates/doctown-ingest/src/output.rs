@@ -0,0 +1,420 @@
+//! The ingest output artifact.
+//!
+//! Ingest produces chunks, their embedding vectors and the metadata of the
+//! symbols and documentation sections they belong to. [`IngestOutput`] keeps
+//! them together and serializes to the schema of the assembly worker's
+//! `AssembleRequest`, so it can be posted to `/assemble` as is.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use doctown_common::{
+    Attribute, ChunkId, ChunkLevel, DocError, ExternalSymbol, Language, SymbolId, SymbolKind,
+    SymbolMetrics,
+};
+use doctown_events::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkHierarchy;
+use crate::symbol::Symbol;
+
+/// Chunks with their vectors, and the symbols and documents they belong to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IngestOutput {
+    /// Job ID of the ingest run.
+    pub job_id: String,
+    /// Repository URL.
+    pub repo_url: String,
+    /// Git reference (branch/tag/commit).
+    pub git_ref: String,
+    /// Chunks that were embedded, with their vectors.
+    pub chunks: Vec<EmbeddedChunk>,
+    /// Symbols, with the IDs of their chunks.
+    pub symbols: Vec<SymbolRecord>,
+    /// Documentation sections, with the IDs of their chunks.
+    #[serde(default)]
+    pub documents: Vec<DocumentRecord>,
+    /// Model that made the vectors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    /// Dimensions of the vectors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_dimensions: Option<usize>,
+    /// Chunks created but left without a vector (embedding failed or was skipped).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unembedded_chunk_ids: Vec<String>,
+}
+
+/// A chunk with its embedding vector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddedChunk {
+    pub chunk_id: String,
+    pub vector: Vec<f32>,
+    /// The chunk content, without any context header.
+    pub content: String,
+    /// Granularity of the chunk, when chunked hierarchically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<ChunkLevel>,
+    /// The chunk one level up, when chunked hierarchically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_chunk_id: Option<String>,
+}
+
+/// Metadata of a symbol extracted during ingest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolRecord {
+    /// Canonical symbol ID.
+    pub symbol_id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub language: Language,
+    pub file_path: String,
+    /// Full path in the crate's module tree (Rust only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qualified_name: Option<String>,
+    /// The signature, or empty if the symbol has none.
+    #[serde(default)]
+    pub signature: String,
+    pub chunk_ids: Vec<String>,
+    #[serde(default)]
+    pub is_test: bool,
+    #[serde(default)]
+    pub metrics: Option<SymbolMetrics>,
+    #[serde(default)]
+    pub attributes: Vec<Attribute>,
+    /// IDs of the symbols this symbol calls.
+    #[serde(default)]
+    pub calls: Vec<String>,
+    /// Calls that matched several equally close symbols, as the ID of each
+    /// candidate and the confidence it is the one called (0.0-1.0).
+    #[serde(default)]
+    pub candidate_calls: Vec<(String, f32)>,
+    /// IDs of the symbols this symbol uses through one of its file's imports.
+    #[serde(default)]
+    pub imports: Vec<String>,
+    /// The import that justified each call resolved through one, as the ID
+    /// of the called symbol and the module path the import names.
+    #[serde(default)]
+    pub call_imports: Vec<(String, String)>,
+    /// IDs of the types this symbol inherits from.
+    #[serde(default)]
+    pub inherits: Vec<String>,
+    /// IDs of the traits or interfaces this symbol implements.
+    #[serde(default)]
+    pub implements: Vec<String>,
+    /// IDs of the types this symbol mentions in its signature, fields or body.
+    #[serde(default)]
+    pub references: Vec<String>,
+    /// Standard library and third-party symbols this symbol calls.
+    #[serde(default)]
+    pub external_calls: Vec<ExternalSymbol>,
+    /// Standard library and third-party symbols this symbol imports.
+    #[serde(default)]
+    pub external_imports: Vec<ExternalSymbol>,
+}
+
+/// Metadata of a documentation section chunked during ingest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentRecord {
+    pub document_id: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub heading: Option<String>,
+    #[serde(default)]
+    pub heading_path: Vec<String>,
+    pub chunk_ids: Vec<String>,
+    #[serde(default)]
+    pub mentions: Vec<String>,
+}
+
+impl IngestOutput {
+    /// Creates an empty output for the job in `context`.
+    pub fn new(context: &Context) -> Self {
+        Self {
+            job_id: context.job_id.to_string(),
+            repo_url: context.repo_url.clone(),
+            git_ref: context
+                .git_ref
+                .clone()
+                .unwrap_or_else(|| "HEAD".to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_symbols(mut self, symbols: Vec<SymbolRecord>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    pub fn with_documents(mut self, documents: Vec<DocumentRecord>) -> Self {
+        self.documents = documents;
+        self
+    }
+
+    pub fn with_embedding_model(mut self, model: impl Into<String>, dimensions: usize) -> Self {
+        self.embedding_model = Some(model.into());
+        self.embedding_dimensions = Some(dimensions);
+        self
+    }
+
+    /// Pairs each chunk's content with its vector, in chunk order.
+    ///
+    /// Chunks without a vector are recorded in `unembedded_chunk_ids`, since
+    /// assembly needs a vector for every chunk.
+    pub fn add_chunks(
+        &mut self,
+        contents: Vec<(ChunkId, String)>,
+        vectors: Vec<(ChunkId, Vec<f32>)>,
+    ) {
+        let mut vectors: HashMap<_, _> = vectors.into_iter().collect();
+        for (chunk_id, content) in contents {
+            match vectors.remove(&chunk_id) {
+                Some(vector) => self.chunks.push(EmbeddedChunk {
+                    chunk_id: chunk_id.to_string(),
+                    vector,
+                    content,
+                    level: None,
+                    parent_chunk_id: None,
+                }),
+                None => self.unembedded_chunk_ids.push(chunk_id.to_string()),
+            }
+        }
+    }
+
+    /// Records the level and parent of each embedded chunk in `hierarchy`.
+    pub fn set_hierarchy(&mut self, hierarchy: &ChunkHierarchy) {
+        let hierarchy: HashMap<&str, _> = hierarchy
+            .iter()
+            .map(|(chunk_id, placement)| (chunk_id.as_str(), placement))
+            .collect();
+        for chunk in &mut self.chunks {
+            if let Some((level, parent_id)) = hierarchy.get(chunk.chunk_id.as_str()) {
+                chunk.level = Some(*level);
+                chunk.parent_chunk_id = parent_id.as_ref().map(ChunkId::to_string);
+            }
+        }
+    }
+
+    /// Writes the output as JSON to `path`.
+    pub fn write_to(&self, path: &Path) -> Result<(), DocError> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Reads an output written by [`Self::write_to`].
+    pub fn read_from(path: &Path) -> Result<Self, DocError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+}
+
+impl SymbolRecord {
+    /// Record of `symbol`, whose content is in the chunk `chunk_id`.
+    pub fn new(
+        symbol_id: SymbolId,
+        symbol: &Symbol,
+        language: Language,
+        file_path: impl Into<String>,
+        chunk_id: &ChunkId,
+    ) -> Self {
+        Self {
+            symbol_id,
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            language,
+            file_path: file_path.into(),
+            qualified_name: symbol.qualified_name.clone(),
+            signature: symbol.signature.clone().unwrap_or_default(),
+            chunk_ids: vec![chunk_id.to_string()],
+            is_test: symbol.is_test,
+            metrics: Some(symbol.metrics),
+            attributes: symbol.attributes.clone(),
+            calls: Vec::new(),
+            candidate_calls: Vec::new(),
+            imports: Vec::new(),
+            call_imports: Vec::new(),
+            inherits: Vec::new(),
+            implements: Vec::new(),
+            references: Vec::new(),
+            external_calls: Vec::new(),
+            external_imports: Vec::new(),
+        }
+    }
+}
+
+impl DocumentRecord {
+    /// Record of a documentation section held in the chunk `chunk_id`.
+    pub fn new(chunk_id: &ChunkId, file_path: impl Into<String>) -> Self {
+        Self {
+            document_id: chunk_id.as_str().replacen("chunk_", "doc_", 1),
+            file_path: file_path.into(),
+            heading: None,
+            heading_path: Vec::new(),
+            chunk_ids: vec![chunk_id.to_string()],
+            mentions: Vec::new(),
+        }
+    }
+
+    pub fn with_heading(mut self, heading: Option<String>, heading_path: Vec<String>) -> Self {
+        self.heading = heading;
+        self.heading_path = heading_path;
+        self
+    }
+
+    pub fn with_mentions(mut self, mentions: Vec<String>) -> Self {
+        self.mentions = mentions;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use doctown_common::types::Visibility;
+    use doctown_common::{ByteRange, JobId};
+    use tempfile::tempdir;
+
+    fn chunk_id(id: &str) -> ChunkId {
+        ChunkId::new(id).unwrap()
+    }
+
+    fn symbol() -> Symbol {
+        Symbol {
+            kind: SymbolKind::Function,
+            name: "connect".to_string(),
+            range: ByteRange::new(0, 30),
+            name_range: ByteRange::new(3, 10),
+            signature: Some("connect(addr: &str)".to_string()),
+            visibility: Visibility::Public,
+            is_async: false,
+            is_test: false,
+            metrics: SymbolMetrics::default(),
+            attributes: Vec::new(),
+            qualified_name: Some("my_crate::net::connect".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_new_from_context() {
+        let context = Context::new(JobId::new("job_abc123").unwrap(), "https://github.com/a/b");
+        let output = IngestOutput::new(&context);
+
+        assert_eq!(output.job_id, "job_abc123");
+        assert_eq!(output.repo_url, "https://github.com/a/b");
+        assert_eq!(output.git_ref, "HEAD");
+    }
+
+    #[test]
+    fn test_add_chunks_pairs_vectors() {
+        let mut output = IngestOutput::default();
+        output.add_chunks(
+            vec![
+                (chunk_id("chunk_alpha"), "fn a() {}".to_string()),
+                (chunk_id("chunk_beta"), "fn b() {}".to_string()),
+            ],
+            vec![(chunk_id("chunk_beta"), vec![0.5, 0.5])],
+        );
+
+        assert_eq!(output.chunks.len(), 1);
+        assert_eq!(output.chunks[0].chunk_id, "chunk_beta");
+        assert_eq!(output.chunks[0].content, "fn b() {}");
+        assert_eq!(output.chunks[0].vector, vec![0.5, 0.5]);
+        assert_eq!(output.unembedded_chunk_ids, vec!["chunk_alpha".to_string()]);
+    }
+
+    #[test]
+    fn test_serializes_as_assemble_request() {
+        use doctown_assembly::{AssembleRequest, EdgeKind, GraphBuilder};
+        use doctown_common::DependencyOrigin;
+
+        let context = Context::new(JobId::new("job_abc123").unwrap(), "https://github.com/a/b")
+            .with_git_ref("main");
+        let id = chunk_id("chunk_alpha");
+        let connect_id = SymbolId::new("sym_rust_fn_my_crate_net_connect").unwrap();
+        let client_id = SymbolId::new("sym_rust_struct_my_crate_net_client").unwrap();
+        let client = Symbol {
+            kind: SymbolKind::Struct,
+            name: "Client".to_string(),
+            signature: None,
+            qualified_name: Some("my_crate::net::Client".to_string()),
+            ..symbol()
+        };
+        let external = ExternalSymbol::new("std::net::TcpStream", "std", DependencyOrigin::Stdlib);
+
+        let mut connect = SymbolRecord::new(
+            connect_id.clone(),
+            &symbol(),
+            Language::Rust,
+            "src/net.rs",
+            &id,
+        );
+        connect.calls = vec![client_id.to_string()];
+        connect.imports = vec![client_id.to_string()];
+        connect.references = vec![client_id.to_string()];
+        connect.external_calls = vec![external.clone()];
+        let mut client = SymbolRecord::new(
+            client_id.clone(),
+            &client,
+            Language::Rust,
+            "src/client.rs",
+            &chunk_id("chunk_beta"),
+        );
+        client.inherits = vec![connect_id.to_string()];
+        client.implements = vec![connect_id.to_string()];
+        client.external_imports = vec![external.clone()];
+
+        let mut output = IngestOutput::new(&context)
+            .with_symbols(vec![connect, client])
+            .with_documents(vec![DocumentRecord::new(
+                &chunk_id("chunk_readme"),
+                "README.md",
+            )
+            .with_heading(Some("Usage".to_string()), vec!["Usage".to_string()])
+            .with_mentions(vec!["connect".to_string()])]);
+        output.add_chunks(
+            vec![(id.clone(), "fn connect(addr: &str) {}".to_string())],
+            vec![(id, vec![1.0, 0.0])],
+        );
+
+        let json = serde_json::to_value(output.with_embedding_model("bge-small", 2)).unwrap();
+        let request: AssembleRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(request.git_ref, "main");
+        assert_eq!(request.chunks[0].chunk_id, "chunk_alpha");
+        assert_eq!(request.symbols[0].kind, "function");
+        assert_eq!(request.symbols[0].signature, "connect(addr: &str)");
+        assert_eq!(request.documents[0].document_id, "doc_readme");
+
+        let mut builder = GraphBuilder::new();
+        request.add_symbol_graph(&mut builder);
+        let graph = builder.build();
+        let has_edge = |source: &str, target: &str, kind: EdgeKind| {
+            graph
+                .edges
+                .iter()
+                .any(|e| e.source == source && e.target == target && e.kind == kind)
+        };
+        let (connect, client) = (connect_id.as_str(), client_id.as_str());
+        assert!(has_edge(connect, client, EdgeKind::Calls));
+        assert!(has_edge(connect, client, EdgeKind::Imports));
+        assert!(has_edge(connect, client, EdgeKind::References));
+        assert!(has_edge(client, connect, EdgeKind::Inherits));
+        assert!(has_edge(client, connect, EdgeKind::Implements));
+        assert!(has_edge(connect, &external.node_id(), EdgeKind::Calls));
+        assert!(has_edge(client, &external.node_id(), EdgeKind::Imports));
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("output.json");
+        let mut output = IngestOutput::default();
+        output.add_chunks(
+            vec![(chunk_id("chunk_alpha"), "x".to_string())],
+            vec![(chunk_id("chunk_alpha"), vec![0.25])],
+        );
+
+        output.write_to(&path).unwrap();
+
+        assert_eq!(IngestOutput::read_from(&path).unwrap(), output);
+    }
+}
@@ -28,7 +28,7 @@ impl Parser {
 
     /// Returns the tree-sitter language for the given Language enum.
     /// Returns None for unsupported languages.
-    fn get_ts_language(language: Language) -> Option<tree_sitter::Language> {
+    pub(crate) fn ts_language(language: Language) -> Option<tree_sitter::Language> {
         match language {
             Language::Rust => Some(tree_sitter_rust::LANGUAGE.into()),
             Language::Python => Some(tree_sitter_python::LANGUAGE.into()),
@@ -40,7 +40,7 @@ impl Parser {
 
     /// Checks if the given language is supported for parsing.
     pub fn is_supported(language: Language) -> bool {
-        Self::get_ts_language(language).is_some()
+        Self::ts_language(language).is_some()
     }
 
     /// Returns a list of all supported languages.
@@ -73,7 +73,7 @@ impl Parser {
     /// assert!(tree.is_some());
     /// ```
     pub fn parse(&self, source_code: &str, language: Language) -> Option<Tree> {
-        let ts_lang = Self::get_ts_language(language)?;
+        let ts_lang = Self::ts_language(language)?;
 
         let mut parsers = self.parsers.borrow_mut();
         let parser = parsers.entry(language).or_insert_with(|| {
@@ -102,7 +102,7 @@ impl Parser {
         language: Language,
         old_tree: Option<&Tree>,
     ) -> Option<Tree> {
-        let ts_lang = Self::get_ts_language(language)?;
+        let ts_lang = Self::ts_language(language)?;
 
         let mut parsers = self.parsers.borrow_mut();
         let parser = parsers.entry(language).or_insert_with(|| {
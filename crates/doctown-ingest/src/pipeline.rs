@@ -1,12 +1,29 @@
 //! Ingest pipeline orchestration.
 
-use crate::archive::{extract_zip, process_extracted_files};
-use crate::embedding::EmbeddingClient;
+use crate::archive::{extract_zip, process_files, ProcessingOptions};
+use crate::batching::{
+    embed_batches, estimate_tokens, pack_batches, BatchingConfig, ConcurrencyController,
+};
+use crate::chunk::{ChunkingConfig, TokenBudget};
+use crate::dedup::DedupedChunks;
+use crate::embedding::{EmbeddingClient, ResilientEmbedder, RetryPolicy};
+use crate::embedding_cache::EmbeddingCache;
+use crate::embedding_provider::{EmbeddingProvider, HashEmbedder, DEFAULT_HASH_DIMENSIONS};
 use crate::github::{GitHubClient, GitHubUrl};
-use doctown_common::{DocError, JobId};
-use doctown_events::{Context, Envelope, IngestCompletedPayload, IngestStartedPayload, Status};
+use crate::openai::OpenAiEmbedder;
+use crate::output::IngestOutput;
+use crate::queries::QueryRegistry;
+use crate::tokenizer::{TokenCounter, DEFAULT_TOKENIZER_PATH};
+use doctown_common::{ChunkId, DocError, JobId};
+use doctown_events::{
+    ChunkEmbedding, Context, Envelope, IngestChunksEmbeddedPayload, IngestCompletedPayload,
+    IngestStartedPayload, Status,
+};
 use serde_json;
 use std::env;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 use tempfile::tempdir;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
@@ -15,12 +32,18 @@ use tracing::{info, warn};
 /// Type alias for the event sender.
 pub type EventSender = mpsc::Sender<Envelope<serde_json::Value>>;
 
+/// Runs the ingest pipeline for a repository, streaming events to `sender`.
+///
+/// Returns the chunks with their vectors and symbol metadata, ready to be sent
+/// to assembly. The output is also written to `INGEST_OUTPUT_PATH` when set,
+/// and each embedded batch is streamed as an `ingest.chunks_embedded.v1` event
+/// when `STREAM_EMBEDDINGS` is set.
 pub async fn run_pipeline(
     job_id: JobId,
     github_url: &GitHubUrl,
     sender: EventSender,
     cancel: CancellationToken,
-) -> Result<(), DocError> {
+) -> Result<IngestOutput, DocError> {
     let started_at = std::time::Instant::now();
     let client = GitHubClient::new();
     let dir = tempdir()?;
@@ -61,100 +84,221 @@ pub async fn run_pipeline(
             let extract_dir = dir.path().join("extracted");
             extract_zip(&zip_path, &extract_dir)?;
 
-            // 3. Process the extracted files
-            let (files_processed, files_skipped, chunks_created, collected_chunks) =
-                process_extracted_files(&extract_dir, context.clone(), sender.clone()).await?;
+            // 3. Process the extracted files, using query files from QUERY_DIR when set
+            let queries = match env::var("QUERY_DIR") {
+                Ok(query_dir) => QueryRegistry::load_from_dir(Path::new(&query_dir))?,
+                Err(_) => QueryRegistry::new(),
+            };
+            let mut options = ProcessingOptions::new().with_queries(queries);
+            if let Some(ratio) = env::var("MAX_PARSE_ERROR_RATIO").ok().and_then(|r| r.parse().ok()) {
+                options = options.with_max_parse_error_ratio(ratio);
+            }
+            // Chunks and batches are sized in tokens of the embedding model when configured
+            let budget = token_budget_from_env();
+            let counter = budget.as_ref().map(|budget| budget.counter.clone());
+            if let Some(budget) = budget {
+                options = options.with_chunking(ChunkingConfig::new().with_token_budget(budget));
+            }
+            if env::var("CHUNK_CONTEXT_HEADERS").is_ok() {
+                options = options.with_context_headers(true);
+            }
+            if env::var("CHUNK_HIERARCHY").is_ok() {
+                options = options.with_hierarchy(true);
+            }
+            let mut summary = process_files(&extract_dir, context.clone(), sender.clone(), &options).await?;
+
+            // Identical chunks (copy-pasted or vendored code) are embedded once
+            let deduped = DedupedChunks::new(&summary.chunks);
+            let collected_chunks = deduped.unique();
 
-            // 4. Embed the chunks in batches (parallel with concurrency limit)
+            // 4. Embed the chunks in batches (parallel, with adaptive concurrency)
             // Skip embedding if SKIP_EMBEDDING is set (for serverless mode where embedding
             // is handled externally)
             let skip_embedding = env::var("SKIP_EMBEDDING").is_ok();
-            let chunks_embedded = if !collected_chunks.is_empty() && !skip_embedding {
-                let embedding_url = env::var("EMBEDDING_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
-                let embedding_client = EmbeddingClient::new(embedding_url);
-
-                // Small batch size optimized for CPU sequential processing (8 chunks per batch)
-                const BATCH_SIZE: usize = 8;
-                // Process up to 8 batches concurrently for maximum throughput without overwhelming CPU
-                const MAX_CONCURRENT: usize = 8;
-
-                // Collect all batches
-                let batches: Vec<_> = collected_chunks
-                    .chunks(BATCH_SIZE)
+            let stream_embeddings = env::var("STREAM_EMBEDDINGS").is_ok();
+            let mut failed_chunk_ids = Vec::new();
+            let mut cache_stats = (0, 0);
+            let mut embedding_model = None;
+            let mut embedding_dimensions = None;
+            let vectors = if !collected_chunks.is_empty() && !skip_embedding {
+                let mut retry_policy = RetryPolicy::default();
+                if let Some(max_retries) = env::var("EMBEDDING_MAX_RETRIES").ok().and_then(|r| r.parse().ok()) {
+                    retry_policy = retry_policy.with_max_retries(max_retries);
+                }
+                let embedder = ResilientEmbedder::from_arc(provider_from_env()?).with_retry_policy(retry_policy);
+                embedding_model = Some(embedder.provider().model_id().to_string());
+
+                // Vectors of unchanged chunks are reused from EMBEDDING_CACHE_PATH when set
+                let mut cache = match env::var("EMBEDDING_CACHE_PATH") {
+                    Ok(path) => match EmbeddingCache::open(path, embedder.provider().model_id()) {
+                        Ok(cache) => match env::var("EMBEDDING_CACHE_MAX_MB").ok().and_then(|mb| mb.parse::<usize>().ok()) {
+                            Some(max_mb) => Some(cache.with_max_bytes(max_mb * 1024 * 1024)),
+                            None => Some(cache),
+                        },
+                        Err(e) => {
+                            warn!("Embedding without cache, failed to open it: {}", e);
+                            None
+                        }
+                    },
+                    Err(_) => None,
+                };
+                let (cached_vectors, to_embed) = match cache.as_mut() {
+                    Some(cache) => cache.partition(collected_chunks),
+                    None => (Vec::new(), collected_chunks.to_vec()),
+                };
+                let mut all_vectors = Vec::new();
+                if !cached_vectors.is_empty() {
+                    info!("Found {} of {} chunks in the embedding cache", cached_vectors.len(), collected_chunks.len());
+                    let vectors = deduped.fan_out(cached_vectors);
+                    if stream_embeddings {
+                        let batch_id = format!("job_{}_cached", context.job_id);
+                        send_embeddings(&sender, &context, batch_id, &vectors).await?;
+                    }
+                    all_vectors.extend(vectors);
+                }
+
+                // Batches are packed by tokens, and concurrency adapts to the provider
+                let batching = batching_config_from_env();
+                let count_tokens = |input: &str| match &counter {
+                    Some(counter) => counter.count(input) + counter.special_tokens(),
+                    None => estimate_tokens(input),
+                };
+                let batches: Vec<_> = pack_batches(&to_embed, &batching, count_tokens)
+                    .into_iter()
                     .enumerate()
-                    .map(|(batch_num, chunk_batch)| {
+                    .map(|(batch_num, (chunk_batch, tokens))| {
                         let batch_id = format!("job_{}_batch_{}", context.job_id, batch_num);
-                        (batch_num, batch_id, chunk_batch.to_vec())
+                        (batch_num, batch_id, chunk_batch, tokens)
                     })
                     .collect();
 
-                let mut total_embedded = 0;
+                let mut controller = ConcurrencyController::new(&batching);
+                let results = embed_batches(&embedder, batches, &mut controller).await;
+                info!("Embedding batches done: final concurrency {}, error rate {:.1}%",
+                    controller.limit(), controller.error_rate() * 100.0);
+                // A misconfigured provider would make every vector of the run suspect
+                if let Some(error) = results.iter().find_map(|(_, _, outcome)| outcome.error.clone()) {
+                    return Err(DocError::Config(error));
+                }
 
-                // Process batches in parallel with concurrency limit
-                use futures_util::stream::{self, StreamExt};
+                // Process results
+                let mut fresh_vectors = Vec::new();
+                for (batch_num, batch_id, outcome) in results {
+                    if !outcome.failed.is_empty() {
+                        warn!("Failed to embed {} chunks of batch {}", outcome.failed.len(), batch_num + 1);
+                        failed_chunk_ids.extend(deduped.fan_out_ids(outcome.failed));
+                    }
+                    if outcome.vectors.is_empty() {
+                        continue;
+                    }
 
-                let results = stream::iter(batches)
-                    .map(|(batch_num, batch_id, chunk_batch)| {
-                        let client = embedding_client.clone();
-                        async move {
-                            (batch_num, client.embed_batch(batch_id, chunk_batch).await)
-                        }
-                    })
-                    .buffer_unordered(MAX_CONCURRENT)
-                    .collect::<Vec<_>>()
-                    .await;
+                    let batch_len = outcome.vectors.len();
+                    let duration_ms = outcome.duration_ms;
+                    if cache.is_some() {
+                        fresh_vectors.extend(outcome.vectors.iter().cloned());
+                    }
+                    // Duplicates of the batch's chunks share their vectors
+                    let vectors = deduped.fan_out(outcome.vectors);
+                    if stream_embeddings {
+                        send_embeddings(&sender, &context, batch_id, &vectors).await?;
+                    }
+                    all_vectors.extend(vectors);
+                    let chunks_per_sec = if duration_ms > 0 {
+                        (batch_len as f64 / (duration_ms as f64 / 1000.0)) as usize
+                    } else {
+                        0
+                    };
+                    info!("Embedded batch {}: {} chunks in {}ms (~{} chunks/sec)",
+                        batch_num + 1, batch_len, duration_ms, chunks_per_sec);
+                }
 
-                // Process results
-                for (batch_num, result) in results {
-                    match result {
-                        Ok((vectors, duration_ms)) => {
-                            total_embedded += vectors.len();
-                            let chunks_per_sec = if duration_ms > 0 {
-                                (vectors.len() as f64 / (duration_ms as f64 / 1000.0)) as usize
-                            } else {
-                                0
-                            };
-                            info!("Embedded batch {}: {} chunks in {}ms (~{} chunks/sec)",
-                                batch_num + 1, vectors.len(), duration_ms, chunks_per_sec);
-                        }
-                        Err(e) => {
-                            warn!("Failed to embed batch {}: {}", batch_num + 1, e);
-                        }
+                embedding_dimensions = embedder.dimensions();
+                if let Some(mut cache) = cache {
+                    cache.store(&to_embed, &fresh_vectors);
+                    cache_stats = (cache.hits(), cache.misses());
+                    info!("Embedding cache hit rate: {:.1}%", cache.hit_rate() * 100.0);
+                    if let Err(e) = cache.save() {
+                        warn!("Failed to save embedding cache to {}: {}", cache.path().display(), e);
                     }
                 }
 
-                total_embedded
+                all_vectors
             } else {
-                0
+                Vec::new()
             };
+            let chunks_embedded = vectors.len();
 
             info!("Embedding complete: {} chunks embedded", chunks_embedded);
 
+            // 5. Keep the vectors with their chunks and symbols for assembly
+            let mut output = IngestOutput::new(&context)
+                .with_symbols(std::mem::take(&mut summary.symbols))
+                .with_documents(std::mem::take(&mut summary.documents));
+            // Cached vectors weren't checked against this run's, so every vector is
+            let dimensions = vector_dimensions(&vectors, embedding_dimensions)?;
+            if let (Some(model), Some(dimensions)) = (embedding_model, dimensions) {
+                output = output.with_embedding_model(model, dimensions);
+            }
+            output.add_chunks(std::mem::take(&mut summary.chunk_contents), vectors);
+            output.set_hierarchy(&summary.chunk_hierarchy);
+            let output_path = match env::var("INGEST_OUTPUT_PATH") {
+                Ok(path) => {
+                    output.write_to(Path::new(&path))?;
+                    Some(path)
+                }
+                Err(_) => None,
+            };
+
             dir.close()?;
-            Ok((files_processed, files_skipped, chunks_created, chunks_embedded))
+            Ok((summary, deduped, chunks_embedded, failed_chunk_ids, cache_stats, output, output_path))
         } => res,
     };
 
     let duration_ms = started_at.elapsed().as_millis() as u64;
 
     match result {
-        Ok((files_processed, files_skipped, chunks_created, chunks_embedded)) => {
+        Ok((
+            summary,
+            deduped,
+            chunks_embedded,
+            failed_chunk_ids,
+            (cache_hits, cache_misses),
+            output,
+            output_path,
+        )) => {
             let payload = IngestCompletedPayload::success(
-                files_processed,
-                files_skipped,
-                chunks_created,
+                summary.files_processed,
+                summary.files_skipped,
+                summary.chunks_created,
                 duration_ms,
-            );
+            )
+            .with_parse_diagnostics(
+                summary.files_with_parse_errors,
+                summary.parse_error_nodes,
+                summary.files_fallback_chunked,
+            )
+            .with_dedup(deduped.unique_count(), deduped.duplicate_count())
+            .with_ambiguous_calls(summary.ambiguous_calls)
+            .with_failed_chunks(failed_chunk_ids)
+            .with_cache(cache_hits, cache_misses);
 
             let payload = if chunks_embedded > 0 {
                 payload.with_embeddings(chunks_embedded)
             } else {
                 payload
             };
+            let payload = match (&output.embedding_model, output.embedding_dimensions) {
+                (Some(model), Some(dimensions)) => payload.with_embedding_model(model, dimensions),
+                _ => payload,
+            };
+            let payload = match output_path {
+                Some(path) => payload.with_output_path(path),
+                None => payload,
+            };
 
             info!(
                 "Sending ingest.completed.v1 event: {} files, {} chunks, {} embedded",
-                files_processed, chunks_created, chunks_embedded
+                summary.files_processed, summary.chunks_created, chunks_embedded
             );
 
             sender
@@ -168,7 +312,7 @@ pub async fn run_pipeline(
                 )
                 .await
                 .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))?;
-            Ok(())
+            Ok(output)
         }
         Err(e) => {
             sender
@@ -192,12 +336,206 @@ pub async fn run_pipeline(
     }
 }
 
+/// Chunk sizes in tokens of the embedding model, when `EMBEDDING_TOKENIZER_PATH`
+/// or `CHUNK_MAX_TOKENS` is set.
+///
+/// The tokenizer is read from `EMBEDDING_TOKENIZER_PATH`, or
+/// [`DEFAULT_TOKENIZER_PATH`]. If it fails to load, chunks are sized in bytes.
+fn token_budget_from_env() -> Option<TokenBudget> {
+    let tokenizer_path = env::var("EMBEDDING_TOKENIZER_PATH").ok();
+    let max_tokens = env::var("CHUNK_MAX_TOKENS").ok();
+    if tokenizer_path.is_none() && max_tokens.is_none() {
+        return None;
+    }
+
+    let tokenizer_path = tokenizer_path.unwrap_or_else(|| DEFAULT_TOKENIZER_PATH.to_string());
+    let counter = match TokenCounter::from_file(&tokenizer_path) {
+        Ok(counter) => counter,
+        Err(e) => {
+            warn!(
+                "Sizing chunks in bytes: tokenizer {} failed to load: {}",
+                tokenizer_path, e
+            );
+            return None;
+        }
+    };
+    let mut budget = TokenBudget::new(counter);
+    if let Some(max_tokens) = max_tokens.and_then(|t| t.parse().ok()) {
+        budget = budget.with_max_tokens(max_tokens);
+    }
+    Some(budget)
+}
+
+/// Batching limits, from `EMBEDDING_MAX_BATCH_TOKENS`, `EMBEDDING_MAX_BATCH_SIZE`,
+/// `EMBEDDING_MIN_CONCURRENCY`, `EMBEDDING_INITIAL_CONCURRENCY`,
+/// `EMBEDDING_MAX_CONCURRENCY` and `EMBEDDING_TARGET_LATENCY_MS` where set.
+fn batching_config_from_env() -> BatchingConfig {
+    let var = |name: &str| env::var(name).ok().and_then(|v| v.parse::<usize>().ok());
+    let mut config = BatchingConfig::new();
+    if let Some(max_batch_tokens) = var("EMBEDDING_MAX_BATCH_TOKENS") {
+        config = config.with_max_batch_tokens(max_batch_tokens);
+    }
+    if let Some(max_batch_size) = var("EMBEDDING_MAX_BATCH_SIZE") {
+        config = config.with_max_batch_size(max_batch_size);
+    }
+    let min = var("EMBEDDING_MIN_CONCURRENCY").unwrap_or(config.min_concurrency);
+    let initial = var("EMBEDDING_INITIAL_CONCURRENCY").unwrap_or(config.initial_concurrency);
+    let max = var("EMBEDDING_MAX_CONCURRENCY").unwrap_or(config.max_concurrency);
+    config = config.with_concurrency(min, initial, max);
+    if let Some(latency_ms) = var("EMBEDDING_TARGET_LATENCY_MS") {
+        config = config.with_target_latency(Duration::from_millis(latency_ms as u64));
+    }
+    config
+}
+
+/// The embedding provider selected by `EMBEDDING_PROVIDER`.
+///
+/// - `http` (default): the embedding worker at `EMBEDDING_URL`, running
+///   `EMBEDDING_MODEL_ID`
+/// - `openai`: the OpenAI-compatible embeddings API at `EMBEDDING_URL` (e.g.
+///   `https://api.openai.com/v1`), running `EMBEDDING_MODEL_ID`, with optional
+///   `EMBEDDING_DIMENSIONS` and `EMBEDDING_API_KEY`, sent as a bearer token or
+///   in the `EMBEDDING_AUTH_HEADER` header
+/// - `onnx`: the model at `ONNX_MODEL_PATH` with the tokenizer at
+///   `EMBEDDING_TOKENIZER_PATH`, in-process, as `EMBEDDING_MODEL_ID` (requires
+///   the `onnx` feature)
+/// - `hash`: [`HashEmbedder`] with `HASH_EMBEDDING_DIMENSIONS`, for tests and
+///   offline runs
+fn provider_from_env() -> Result<Arc<dyn EmbeddingProvider>, DocError> {
+    match env::var("EMBEDDING_PROVIDER").as_deref() {
+        Ok("http") | Err(_) => {
+            let embedding_url =
+                env::var("EMBEDDING_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
+            let mut client = EmbeddingClient::new(embedding_url);
+            if let Ok(model_id) = env::var("EMBEDDING_MODEL_ID") {
+                client = client.with_model_id(model_id);
+            }
+            Ok(Arc::new(client))
+        }
+        Ok("openai") => {
+            let embedding_url = env::var("EMBEDDING_URL")
+                .unwrap_or_else(|_| "http://localhost:8080/v1".to_string());
+            let model = env::var("EMBEDDING_MODEL_ID").map_err(|_| {
+                DocError::Validation(
+                    "EMBEDDING_PROVIDER=openai requires EMBEDDING_MODEL_ID".to_string(),
+                )
+            })?;
+            let mut embedder = OpenAiEmbedder::new(embedding_url, model);
+            if let Some(dimensions) = env::var("EMBEDDING_DIMENSIONS")
+                .ok()
+                .and_then(|d| d.parse().ok())
+            {
+                embedder = embedder.with_dimensions(dimensions);
+            }
+            if let Ok(api_key) = env::var("EMBEDDING_API_KEY") {
+                embedder = match env::var("EMBEDDING_AUTH_HEADER") {
+                    Ok(header) => embedder.with_auth_header(header, api_key),
+                    Err(_) => embedder.with_api_key(api_key),
+                };
+            }
+            Ok(Arc::new(embedder))
+        }
+        Ok("hash") => {
+            let dimensions = env::var("HASH_EMBEDDING_DIMENSIONS")
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(DEFAULT_HASH_DIMENSIONS);
+            Ok(Arc::new(HashEmbedder::new(dimensions)))
+        }
+        #[cfg(feature = "onnx")]
+        Ok("onnx") => {
+            let model_path = env::var("ONNX_MODEL_PATH")
+                .unwrap_or_else(|_| "models/minilm-l6/model.onnx".to_string());
+            let tokenizer_path = env::var("EMBEDDING_TOKENIZER_PATH")
+                .unwrap_or_else(|_| crate::tokenizer::DEFAULT_TOKENIZER_PATH.to_string());
+            let mut embedder = crate::onnx::OnnxEmbedder::from_files(model_path, tokenizer_path)?;
+            if let Ok(model_id) = env::var("EMBEDDING_MODEL_ID") {
+                embedder = embedder.with_model_id(model_id);
+            }
+            Ok(Arc::new(embedder))
+        }
+        #[cfg(not(feature = "onnx"))]
+        Ok("onnx") => Err(DocError::Validation(
+            "EMBEDDING_PROVIDER=onnx requires building with the onnx feature".to_string(),
+        )),
+        Ok(other) => Err(DocError::Validation(format!(
+            "Unknown EMBEDDING_PROVIDER: {}",
+            other
+        ))),
+    }
+}
+
+/// Streams the vectors of a batch as an `ingest.chunks_embedded.v1` event.
+async fn send_embeddings(
+    sender: &EventSender,
+    context: &Context,
+    batch_id: String,
+    vectors: &[(ChunkId, Vec<f32>)],
+) -> Result<(), DocError> {
+    let embeddings = vectors
+        .iter()
+        .map(|(chunk_id, vector)| ChunkEmbedding::new(chunk_id.clone(), vector.clone()))
+        .collect();
+    let payload = IngestChunksEmbeddedPayload::new(batch_id, embeddings);
+    sender
+        .send(Envelope::new(
+            "ingest.chunks_embedded.v1",
+            context.clone(),
+            serde_json::to_value(payload)?,
+        ))
+        .await
+        .map_err(|e| DocError::Internal(format!("Failed to send event: {}", e)))
+}
+
+/// Dimensions of the run's vectors: `expected`, or else those of the first
+/// vector. Every vector must have them, fresh or cached.
+fn vector_dimensions(
+    vectors: &[(ChunkId, Vec<f32>)],
+    expected: Option<usize>,
+) -> Result<Option<usize>, DocError> {
+    let Some((_, first)) = vectors.first() else {
+        return Ok(None);
+    };
+    let dimensions = expected.unwrap_or(first.len());
+    if let Some((chunk_id, vector)) = vectors
+        .iter()
+        .find(|(_, vector)| vector.len() != dimensions)
+    {
+        return Err(DocError::Config(format!(
+            "Vector of chunk {} has {} dimensions, expected {}",
+            chunk_id,
+            vector.len(),
+            dimensions
+        )));
+    }
+    Ok(Some(dimensions))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use doctown_common::JobId;
     use tokio::sync::mpsc;
 
+    #[test]
+    fn test_vector_dimensions_checks_every_vector() {
+        let vector = |id: &str, dimensions| (ChunkId::new(id).unwrap(), vec![0.5; dimensions]);
+        let vectors = vec![vector("chunk_cached", 4), vector("chunk_fresh", 4)];
+
+        assert_eq!(vector_dimensions(&vectors, None).unwrap(), Some(4));
+        assert_eq!(vector_dimensions(&vectors, Some(4)).unwrap(), Some(4));
+        assert_eq!(vector_dimensions(&[], Some(4)).unwrap(), None);
+        assert!(matches!(
+            vector_dimensions(&vectors, Some(8)),
+            Err(DocError::Config(_))
+        ));
+
+        // A stale cached vector among fresh ones
+        let vectors = vec![vector("chunk_fresh", 4), vector("chunk_cached", 3)];
+        let error = vector_dimensions(&vectors, None).unwrap_err().to_string();
+        assert!(error.contains("chunk_cached has 3 dimensions, expected 4"));
+    }
+
     #[tokio::test]
     async fn test_run_pipeline() {
         let (sender, mut receiver) = mpsc::channel(100);
@@ -0,0 +1,781 @@
+//! Declarative extraction with tree-sitter query (`.scm`) files.
+//!
+//! Queries are loaded at runtime from a directory laid out per language:
+//!
+//! ```text
+//! queries/
+//!   rust/
+//!     symbols.scm
+//!     calls.scm
+//!     imports.scm
+//!   python/
+//!     symbols.scm
+//! ```
+//!
+//! Each file is optional. When a language has no query for a given kind of
+//! extraction, the hand-written extractors (`extract_symbols`, `extract_calls`,
+//! `extract_imports`) are used instead.
+//!
+//! # Capture conventions
+//!
+//! - `symbols.scm`: `@definition.<kind>` marks the whole definition, where
+//!   `<kind>` is a snake_case [`SymbolKind`] (`function`, `method`, `struct`,
+//!   `type_alias`, ...). `@name` marks the symbol name. Optional `@body` ends the
+//!   signature, and optional `@visibility` holds a visibility modifier.
+//!   When several patterns capture the same definition, the one listed first wins.
+//! - `calls.scm`: `@call.<kind>` marks the call expression, where `<kind>` is a
+//!   snake_case [`CallKind`] (`function`, `method`, `associated`, `constructor`).
+//!   `@name` marks the callee.
+//! - `imports.scm`: `@import` marks the statement and `@module` the module path.
+//!   Optional `@item` (may repeat), `@alias` and `@wildcard` captures fill in the
+//!   rest of the [`Import`].
+
+use doctown_common::types::{Call, CallKind, Import, SymbolKind, SymbolMetrics, Visibility};
+use doctown_common::{DocError, Language};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query, QueryCursor, QueryMatch, Tree};
+
+use crate::attributes::attach_attributes;
+use crate::calls::extract_calls;
+use crate::imports::extract_imports;
+use crate::metrics::attach_metrics;
+use crate::parsing::Parser;
+use crate::receiver_types::attach_receiver_types;
+use crate::symbol::{extract_symbols, visibility_from_text, Symbol};
+use crate::test_code::mark_test_symbols;
+use crate::traversal::{node_byte_range, node_text};
+
+/// The kind of extraction a query file drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKind {
+    /// Symbol definitions (`symbols.scm`)
+    Symbols,
+    /// Function and method calls (`calls.scm`)
+    Calls,
+    /// Import statements (`imports.scm`)
+    Imports,
+}
+
+impl QueryKind {
+    /// All query kinds.
+    pub const ALL: [QueryKind; 3] = [QueryKind::Symbols, QueryKind::Calls, QueryKind::Imports];
+
+    /// Returns the file name for this kind of query.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            QueryKind::Symbols => "symbols.scm",
+            QueryKind::Calls => "calls.scm",
+            QueryKind::Imports => "imports.scm",
+        }
+    }
+}
+
+/// Compiled extraction queries, keyed by language and kind.
+#[derive(Default)]
+pub struct QueryRegistry {
+    queries: HashMap<(Language, QueryKind), Query>,
+}
+
+impl QueryRegistry {
+    /// Creates an empty registry. All extraction falls back to the hand-written extractors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads all query files found under `dir`.
+    ///
+    /// Language directories are named after the lowercase language name
+    /// (`rust`, `python`, `typescript`, `javascript`, `go`). Missing directories
+    /// and files are skipped; queries that fail to compile are an error.
+    pub fn load_from_dir(dir: &Path) -> Result<Self, DocError> {
+        if !dir.is_dir() {
+            return Err(DocError::NotFound(format!(
+                "query directory {}",
+                dir.display()
+            )));
+        }
+
+        let mut registry = Self::new();
+        for &language in Parser::supported_languages() {
+            let language_dir = dir.join(language.to_string().to_lowercase());
+            for kind in QueryKind::ALL {
+                let path = language_dir.join(kind.file_name());
+                if !path.is_file() {
+                    continue;
+                }
+                let source = std::fs::read_to_string(&path)?;
+                registry
+                    .add_query(language, kind, &source)
+                    .map_err(|e| match e {
+                        DocError::Parse { message, .. } => DocError::Parse {
+                            file: path.display().to_string(),
+                            message,
+                        },
+                        other => other,
+                    })?;
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Compiles and registers a query, replacing any existing query for the same language and kind.
+    pub fn add_query(
+        &mut self,
+        language: Language,
+        kind: QueryKind,
+        source: &str,
+    ) -> Result<(), DocError> {
+        let parse_error = |message: String| DocError::Parse {
+            file: format!(
+                "{}/{}",
+                language.to_string().to_lowercase(),
+                kind.file_name()
+            ),
+            message,
+        };
+
+        let ts_language = Parser::ts_language(language)
+            .ok_or_else(|| parse_error(format!("unsupported language {}", language)))?;
+        let query = Query::new(&ts_language, source).map_err(|e| parse_error(e.to_string()))?;
+        validate_captures(&query, kind).map_err(parse_error)?;
+
+        self.queries.insert((language, kind), query);
+        Ok(())
+    }
+
+    /// Returns true if a query is registered for the given language and kind.
+    pub fn has_query(&self, language: Language, kind: QueryKind) -> bool {
+        self.queries.contains_key(&(language, kind))
+    }
+
+    /// Returns true if no queries are registered.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Extract symbols, using the language's symbol query if one is registered.
+    pub fn extract_symbols(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        language: Language,
+    ) -> Vec<Symbol> {
+        match self.queries.get(&(language, QueryKind::Symbols)) {
+            Some(query) => {
+                let mut symbols = query_symbols(query, tree, source_code, language);
+                mark_test_symbols(&mut symbols, tree, source_code, language);
+                attach_metrics(&mut symbols, tree, source_code, language);
+                attach_attributes(&mut symbols, tree, source_code, language);
+                symbols
+            }
+            None => extract_symbols(tree, source_code, language),
+        }
+    }
+
+    /// Extract calls, using the language's call query if one is registered.
+    pub fn extract_calls(&self, tree: &Tree, source_code: &str, language: Language) -> Vec<Call> {
+        match self.queries.get(&(language, QueryKind::Calls)) {
+            Some(query) => {
+                let mut calls = query_calls(query, tree, source_code);
+                attach_receiver_types(&mut calls, tree, source_code, language);
+                calls
+            }
+            None => extract_calls(tree, source_code, language),
+        }
+    }
+
+    /// Extract imports, using the language's import query if one is registered.
+    pub fn extract_imports(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        language: Language,
+    ) -> Vec<Import> {
+        match self.queries.get(&(language, QueryKind::Imports)) {
+            Some(query) => query_imports(query, tree, source_code),
+            None => extract_imports(tree, source_code, language),
+        }
+    }
+}
+
+impl std::fmt::Debug for QueryRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryRegistry")
+            .field("queries", &self.queries.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Check that a query uses the captures its kind requires, with known kind suffixes.
+fn validate_captures(query: &Query, kind: QueryKind) -> Result<(), String> {
+    let names = query.capture_names();
+    let require = |name: &str| {
+        if names.contains(&name) {
+            Ok(())
+        } else {
+            Err(format!("missing required @{} capture", name))
+        }
+    };
+
+    match kind {
+        QueryKind::Symbols => {
+            require("name")?;
+            for name in names {
+                if let Some(suffix) = name.strip_prefix("definition.") {
+                    symbol_kind_from_str(suffix)
+                        .ok_or_else(|| format!("unknown symbol kind in @{}", name))?;
+                }
+            }
+            if !names.iter().any(|n| n.starts_with("definition.")) {
+                return Err("missing @definition.<kind> capture".to_string());
+            }
+        }
+        QueryKind::Calls => {
+            require("name")?;
+            for name in names {
+                if let Some(suffix) = name.strip_prefix("call.") {
+                    call_kind_from_str(suffix)
+                        .ok_or_else(|| format!("unknown call kind in @{}", name))?;
+                }
+            }
+            if !names.iter().any(|n| n.starts_with("call.")) {
+                return Err("missing @call.<kind> capture".to_string());
+            }
+        }
+        QueryKind::Imports => {
+            require("import")?;
+            require("module")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn symbol_kind_from_str(s: &str) -> Option<SymbolKind> {
+    Some(match s {
+        "function" => SymbolKind::Function,
+        "class" => SymbolKind::Class,
+        "module" => SymbolKind::Module,
+        "struct" => SymbolKind::Struct,
+        "trait" => SymbolKind::Trait,
+        "enum" => SymbolKind::Enum,
+        "method" => SymbolKind::Method,
+        "const" => SymbolKind::Const,
+        "static" => SymbolKind::Static,
+        "interface" => SymbolKind::Interface,
+        "type_alias" => SymbolKind::TypeAlias,
+        "impl" => SymbolKind::Impl,
+        "macro" => SymbolKind::Macro,
+        _ => return None,
+    })
+}
+
+fn call_kind_from_str(s: &str) -> Option<CallKind> {
+    Some(match s {
+        "function" => CallKind::Function,
+        "method" => CallKind::Method,
+        "associated" => CallKind::Associated,
+        "constructor" => CallKind::Constructor,
+        _ => return None,
+    })
+}
+
+/// Run a query over the tree, invoking `f` with each match's captures keyed by name.
+fn for_each_match<'tree>(
+    query: &Query,
+    tree: &'tree Tree,
+    source_code: &str,
+    mut f: impl FnMut(&QueryMatch<'_, 'tree>, Vec<(&str, Node<'tree>)>),
+) {
+    let names = query.capture_names();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source_code.as_bytes());
+    while let Some(m) = matches.next() {
+        let captures = m
+            .captures
+            .iter()
+            .map(|c| (names[c.index as usize], c.node))
+            .collect();
+        f(m, captures);
+    }
+}
+
+fn capture<'tree>(captures: &[(&str, Node<'tree>)], name: &str) -> Option<Node<'tree>> {
+    captures
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, node)| *node)
+}
+
+/// Returns true if the definition node carries an `async` modifier.
+fn has_async_modifier(node: Node<'_>) -> bool {
+    node.children(&mut node.walk()).any(|child| {
+        child.kind() == "async"
+            || (child.kind() == "function_modifiers"
+                && child
+                    .children(&mut child.walk())
+                    .any(|c| c.kind() == "async"))
+    })
+}
+
+/// Visibility for symbols without a `@visibility` capture, following each language's rules.
+fn default_visibility(language: Language, name: &str) -> Visibility {
+    match language {
+        // Python has no visibility modifiers
+        Language::Python => Visibility::Public,
+        // Go exports are determined by capitalization
+        Language::Go if name.chars().next().is_some_and(|c| c.is_uppercase()) => Visibility::Public,
+        _ => Visibility::Private,
+    }
+}
+
+fn query_symbols(query: &Query, tree: &Tree, source_code: &str, language: Language) -> Vec<Symbol> {
+    // Definition range -> (pattern index, symbol)
+    let mut found: HashMap<(usize, usize), (usize, Symbol)> = HashMap::new();
+
+    for_each_match(query, tree, source_code, |m, captures| {
+        let Some((kind, def_node)) = captures.iter().find_map(|(name, node)| {
+            name.strip_prefix("definition.")
+                .and_then(symbol_kind_from_str)
+                .map(|kind| (kind, *node))
+        }) else {
+            return;
+        };
+        let Some(name_node) = capture(&captures, "name") else {
+            return;
+        };
+
+        // Signature spans from the name to the body, minus a trailing `:` (Python)
+        let signature = capture(&captures, "body").map(|body| {
+            source_code[name_node.start_byte()..body.start_byte()]
+                .trim()
+                .trim_end_matches(':')
+                .to_string()
+        });
+        let name = node_text(name_node, source_code).to_string();
+        let visibility = match capture(&captures, "visibility").map(|v| node_text(v, source_code)) {
+            Some(text) if text.starts_with("export") => Visibility::Public,
+            Some(text) => visibility_from_text(text),
+            None => default_visibility(language, &name),
+        };
+
+        let symbol = Symbol {
+            kind,
+            name,
+            range: node_byte_range(def_node),
+            name_range: node_byte_range(name_node),
+            signature,
+            visibility,
+            is_async: has_async_modifier(def_node),
+            is_test: false,
+            metrics: SymbolMetrics::default(),
+            attributes: Vec::new(),
+            qualified_name: None,
+        };
+
+        let key = (def_node.start_byte(), def_node.end_byte());
+        let pattern = m.pattern_index;
+        match found.get(&key) {
+            Some((existing, _)) if *existing <= pattern => {}
+            _ => {
+                found.insert(key, (pattern, symbol));
+            }
+        }
+    });
+
+    let mut symbols: Vec<_> = found.into_values().collect();
+    symbols.sort_by_key(|(pattern, s)| (s.range.start, *pattern));
+    symbols.into_iter().map(|(_, s)| s).collect()
+}
+
+fn query_calls(query: &Query, tree: &Tree, source_code: &str) -> Vec<Call> {
+    let mut calls = Vec::new();
+
+    for_each_match(query, tree, source_code, |_, captures| {
+        let Some((kind, call_node)) = captures.iter().find_map(|(name, node)| {
+            name.strip_prefix("call.")
+                .and_then(call_kind_from_str)
+                .map(|kind| (kind, *node))
+        }) else {
+            return;
+        };
+        let Some(name_node) = capture(&captures, "name") else {
+            return;
+        };
+
+        calls.push(Call {
+            name: node_text(name_node, source_code).to_string(),
+            range: node_byte_range(call_node),
+            kind,
+            is_resolved: false,
+            receiver_type: None,
+        });
+    });
+
+    calls.sort_by_key(|c| c.range.start);
+    calls.dedup();
+    calls
+}
+
+fn query_imports(query: &Query, tree: &Tree, source_code: &str) -> Vec<Import> {
+    let mut imports: Vec<Import> = Vec::new();
+
+    for_each_match(query, tree, source_code, |_, captures| {
+        let (Some(import_node), Some(module_node)) =
+            (capture(&captures, "import"), capture(&captures, "module"))
+        else {
+            return;
+        };
+
+        let range = node_byte_range(import_node);
+        let module_path = unquote(node_text(module_node, source_code)).to_string();
+        let items: Vec<String> = captures
+            .iter()
+            .filter(|(name, _)| *name == "item")
+            .map(|(_, node)| node_text(*node, source_code).to_string())
+            .collect();
+        let alias = capture(&captures, "alias").map(|n| node_text(n, source_code).to_string());
+        let is_wildcard = capture(&captures, "wildcard").is_some();
+
+        // Patterns that match once per imported item, or several patterns matching
+        // the same statement, are merged into one import
+        if let Some(existing) = imports
+            .iter_mut()
+            .find(|i| i.range == range && i.module_path == module_path)
+        {
+            if existing.alias.is_none() {
+                existing.alias = alias;
+            }
+            if !items.is_empty() {
+                let existing_items = existing.imported_items.get_or_insert_with(Vec::new);
+                existing_items.extend(items);
+                // Overlapping patterns capture the same items again
+                let mut seen = HashSet::new();
+                existing_items.retain(|item| seen.insert(item.clone()));
+            }
+            existing.is_wildcard |= is_wildcard;
+            return;
+        }
+
+        imports.push(Import {
+            module_path,
+            imported_items: if items.is_empty() { None } else { Some(items) },
+            alias,
+            range,
+            is_wildcard,
+        });
+    });
+
+    imports
+}
+
+/// Strip surrounding quotes from string-literal module paths (`"./utils"`, `"fmt"`).
+fn unquote(text: &str) -> &str {
+    text.trim_matches(|c| c == '"' || c == '\'' || c == '`')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use std::path::PathBuf;
+
+    fn shipped_queries_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("queries")
+    }
+
+    #[test]
+    fn test_empty_registry_falls_back() {
+        let code = "fn main() { helper(); }\nfn helper() {}";
+        let tree = parse(code, Language::Rust).unwrap();
+        let registry = QueryRegistry::new();
+
+        assert!(registry.is_empty());
+        assert_eq!(
+            registry.extract_symbols(&tree, code, Language::Rust),
+            extract_symbols(&tree, code, Language::Rust)
+        );
+        assert_eq!(
+            registry.extract_calls(&tree, code, Language::Rust),
+            extract_calls(&tree, code, Language::Rust)
+        );
+    }
+
+    #[test]
+    fn test_symbol_query() {
+        let mut registry = QueryRegistry::new();
+        registry
+            .add_query(
+                Language::Python,
+                QueryKind::Symbols,
+                r#"
+(class_definition
+  body: (block
+    (function_definition name: (identifier) @name body: (_) @body) @definition.method))
+(function_definition name: (identifier) @name body: (_) @body) @definition.function
+(class_definition name: (identifier) @name) @definition.class
+"#,
+            )
+            .unwrap();
+
+        let code = "async def top(x):\n    pass\n\nclass Foo:\n    def bar(self):\n        pass\n";
+        let tree = parse(code, Language::Python).unwrap();
+        let symbols = registry.extract_symbols(&tree, code, Language::Python);
+
+        let summary: Vec<_> = symbols.iter().map(|s| (s.kind, s.name.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SymbolKind::Function, "top"),
+                (SymbolKind::Class, "Foo"),
+                (SymbolKind::Method, "bar"),
+            ]
+        );
+        assert!(symbols[0].is_async);
+        assert_eq!(symbols[0].signature.as_deref(), Some("top(x)"));
+        assert!(!symbols[2].is_async);
+    }
+
+    #[test]
+    fn test_call_query() {
+        let mut registry = QueryRegistry::new();
+        registry
+            .add_query(
+                Language::Go,
+                QueryKind::Calls,
+                r#"
+(call_expression function: (identifier) @name) @call.function
+(call_expression function: (selector_expression) @name) @call.method
+"#,
+            )
+            .unwrap();
+
+        let code = "package main\nfunc main() { run(); fmt.Println() }";
+        let tree = parse(code, Language::Go).unwrap();
+        let calls = registry.extract_calls(&tree, code, Language::Go);
+
+        let summary: Vec<_> = calls.iter().map(|c| (c.kind, c.name.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (CallKind::Function, "run"),
+                (CallKind::Method, "fmt.Println")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_query_merges_items() {
+        let mut registry = QueryRegistry::new();
+        registry
+            .add_query(
+                Language::Python,
+                QueryKind::Imports,
+                r#"
+(import_from_statement
+  module_name: (dotted_name) @module
+  name: (dotted_name) @item) @import
+"#,
+            )
+            .unwrap();
+
+        let code = "from os.path import join, exists\n";
+        let tree = parse(code, Language::Python).unwrap();
+        let imports = registry.extract_imports(&tree, code, Language::Python);
+
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].module_path, "os.path");
+        assert_eq!(
+            imports[0].imported_items,
+            Some(vec!["join".to_string(), "exists".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_import_query_dedups_overlapping_items() {
+        let mut registry = QueryRegistry::new();
+        registry
+            .add_query(
+                Language::Python,
+                QueryKind::Imports,
+                r#"
+(import_from_statement
+  module_name: (dotted_name) @module
+  name: (dotted_name) @item) @import
+
+(import_from_statement
+  module_name: (dotted_name) @module
+  name: (dotted_name (identifier) @item)) @import
+"#,
+            )
+            .unwrap();
+
+        let code = "from os.path import join, exists\n";
+        let tree = parse(code, Language::Python).unwrap();
+        let imports = registry.extract_imports(&tree, code, Language::Python);
+
+        assert_eq!(imports.len(), 1);
+        assert_eq!(
+            imports[0].imported_items,
+            Some(vec!["join".to_string(), "exists".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_invalid_queries_rejected() {
+        let mut registry = QueryRegistry::new();
+
+        // Unknown node type
+        let err = registry
+            .add_query(
+                Language::Rust,
+                QueryKind::Symbols,
+                "(no_such_node) @definition.function",
+            )
+            .unwrap_err();
+        assert!(matches!(err, DocError::Parse { .. }));
+
+        // Unknown symbol kind
+        assert!(registry
+            .add_query(
+                Language::Rust,
+                QueryKind::Symbols,
+                "(function_item name: (identifier) @name) @definition.widget"
+            )
+            .is_err());
+
+        // Missing @name capture
+        assert!(registry
+            .add_query(
+                Language::Rust,
+                QueryKind::Calls,
+                "(call_expression) @call.function"
+            )
+            .is_err());
+
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_load_from_missing_dir() {
+        let err = QueryRegistry::load_from_dir(Path::new("/nonexistent/queries")).unwrap_err();
+        assert!(matches!(err, DocError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_load_reports_file_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("go")).unwrap();
+        std::fs::write(dir.path().join("go/calls.scm"), "(call_expression").unwrap();
+
+        match QueryRegistry::load_from_dir(dir.path()).unwrap_err() {
+            DocError::Parse { file, .. } => assert!(file.ends_with("calls.scm")),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_shipped_queries_compile() {
+        let registry = QueryRegistry::load_from_dir(&shipped_queries_dir()).unwrap();
+
+        for language in [Language::Rust, Language::Python, Language::Go] {
+            for kind in QueryKind::ALL {
+                assert!(registry.has_query(language, kind), "{language} {kind:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_shipped_rust_queries_match_fallback() {
+        let registry = QueryRegistry::load_from_dir(&shipped_queries_dir()).unwrap();
+        let code = r#"
+use std::collections::{HashMap, HashSet};
+
+pub struct Config { name: String }
+
+pub(crate) async fn load(path: &str) -> Config {
+    let map = HashMap::new();
+    Config::default()
+}
+
+impl Config {
+    pub fn name(&self) -> &str { self.name.as_str() }
+}
+"#;
+        let tree = parse(code, Language::Rust).unwrap();
+
+        let names = |symbols: Vec<Symbol>| {
+            let mut names: Vec<_> = symbols.into_iter().map(|s| (s.name, s.kind)).collect();
+            names.sort_by(|a, b| a.0.cmp(&b.0));
+            names
+        };
+        assert_eq!(
+            names(registry.extract_symbols(&tree, code, Language::Rust)),
+            names(extract_symbols(&tree, code, Language::Rust))
+        );
+
+        let symbols = registry.extract_symbols(&tree, code, Language::Rust);
+        let load = symbols.iter().find(|s| s.name == "load").unwrap();
+        assert!(load.is_async);
+        assert_eq!(load.visibility, Visibility::PublicCrate);
+        assert_eq!(
+            load.signature.as_deref(),
+            Some("load(path: &str) -> Config")
+        );
+
+        let imports = registry.extract_imports(&tree, code, Language::Rust);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].module_path, "std::collections");
+        assert_eq!(
+            imports[0].imported_items,
+            Some(vec!["HashMap".to_string(), "HashSet".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_shipped_go_queries() {
+        let registry = QueryRegistry::load_from_dir(&shipped_queries_dir()).unwrap();
+        let code = r#"
+package main
+
+import (
+    "fmt"
+    str "strings"
+)
+
+type Server struct{}
+type Handler interface{ Serve() }
+type ID string
+
+func (s *Server) Start() { fmt.Println(str.ToUpper("x")) }
+func helper() {}
+"#;
+        let tree = parse(code, Language::Go).unwrap();
+
+        let symbols = registry.extract_symbols(&tree, code, Language::Go);
+        let summary: Vec<_> = symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.visibility))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SymbolKind::Struct, "Server", Visibility::Public),
+                (SymbolKind::Interface, "Handler", Visibility::Public),
+                (SymbolKind::TypeAlias, "ID", Visibility::Public),
+                (SymbolKind::Method, "Start", Visibility::Public),
+                (SymbolKind::Function, "helper", Visibility::Private),
+            ]
+        );
+
+        let imports = registry.extract_imports(&tree, code, Language::Go);
+        let summary: Vec<_> = imports
+            .iter()
+            .map(|i| (i.module_path.as_str(), i.alias.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![("fmt", None), ("strings", Some("str"))]);
+    }
+}
@@ -0,0 +1,678 @@
+//! Receiver-type inference for method calls.
+//!
+//! Infers the type of the receiver in calls like `self.store.save()` or
+//! `user.validate()` from evidence in the same file:
+//!
+//! - `self`/`this` is the enclosing impl or class
+//! - variables take the type of their annotation (`let x: Foo`, `user: User`,
+//!   `func (s *Server)`) or of the constructor they are assigned from
+//!   (`x = Foo()`, `let x = Foo::new()`, `new Foo()`, `x := &Foo{}`)
+//! - fields take the type declared on their struct or class, or assigned to
+//!   them in its methods (`self.store = Store()`)
+//!
+//! Receivers that can't be typed this way (function results, chained calls on
+//! them, module aliases like `np.array()`) are left unknown.
+
+use doctown_common::types::{Call, CallKind};
+use doctown_common::Language;
+use tree_sitter::{Node, Tree};
+
+use crate::traversal::{ancestors, child_by_field, node_text, TreeCursor};
+
+/// How deep field and variable lookups may chain before giving up.
+const MAX_DEPTH: usize = 8;
+
+/// Generic wrappers whose methods are called through to the wrapped type.
+const WRAPPER_TYPES: &[&str] = &["Box", "Rc", "Arc", "Optional"];
+
+/// Set the inferred receiver type of each method call.
+pub fn attach_receiver_types(
+    calls: &mut [Call],
+    tree: &Tree,
+    source_code: &str,
+    language: Language,
+) {
+    let inference = Inference {
+        root: tree.root_node(),
+        source: source_code,
+        language,
+    };
+    for call in calls.iter_mut().filter(|c| c.kind == CallKind::Method) {
+        call.receiver_type = inference.call_receiver(call);
+    }
+}
+
+/// Normalize a type annotation to the name of the type it refers to.
+///
+/// `&mut Foo`, `*Foo`, `Arc<Foo>`, `Optional[Foo]`, `: Foo | null` and
+/// `models.Foo` all become `Foo`.
+pub(crate) fn type_name(annotation: &str) -> Option<String> {
+    let mut text = annotation.trim().trim_start_matches(':').trim();
+    loop {
+        let before = text;
+        text = text.trim_start_matches(['&', '*']).trim_start();
+        // Python forward references are quoted; Rust lifetimes are not closed
+        if text.len() > 1
+            && (text.starts_with('"') || text.starts_with('\''))
+            && text.ends_with(&text[..1])
+        {
+            text = &text[1..text.len() - 1];
+        } else if text.starts_with('\'') {
+            text = text.split_once(' ').map_or("", |(_, rest)| rest);
+        }
+        for prefix in ["mut ", "dyn ", "impl "] {
+            text = text.strip_prefix(prefix).unwrap_or(text).trim_start();
+        }
+        if text == before {
+            break;
+        }
+    }
+
+    let end = text
+        .find(['<', '[', '(', ' ', '|', '{', ','])
+        .unwrap_or(text.len());
+    let path = &text[..end];
+    let name = path.rsplit([':', '.']).next().unwrap_or(path);
+
+    if WRAPPER_TYPES.contains(&name) {
+        let open = text.find(['<', '['])?;
+        let close = text.rfind(['>', ']'])?;
+        return type_name(text.get(open + 1..close)?);
+    }
+
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    is_identifier.then(|| name.to_string())
+}
+
+/// A binding of a name to a type annotation and/or an assigned value.
+struct Binding<'tree> {
+    start: usize,
+    annotation: Option<Node<'tree>>,
+    value: Option<Node<'tree>>,
+}
+
+struct Inference<'tree, 'src> {
+    root: Node<'tree>,
+    source: &'src str,
+    language: Language,
+}
+
+impl<'tree> Inference<'tree, '_> {
+    fn text(&self, node: Node<'_>) -> &str {
+        node_text(node, self.source)
+    }
+
+    fn call_receiver(&self, call: &Call) -> Option<String> {
+        let node = self
+            .root
+            .descendant_for_byte_range(call.range.start, call.range.end)?;
+        let call_node = std::iter::once(node)
+            .chain(ancestors(node))
+            .take_while(|n| n.start_byte() == call.range.start && n.end_byte() == call.range.end)
+            .find(|n| matches!(n.kind(), "call_expression" | "call"))?;
+        let (receiver, _) = member_parts(child_by_field(call_node, "function")?)?;
+        self.expression_type(receiver, 0)
+    }
+
+    /// Infer the type of an expression.
+    fn expression_type(&self, expr: Node<'tree>, depth: usize) -> Option<String> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match expr.kind() {
+            "self" | "this" => self.enclosing_type(expr),
+            "identifier" => match self.text(expr) {
+                "self" | "cls" if self.language == Language::Python => self.enclosing_type(expr),
+                name => self.variable_type(expr, name, depth),
+            },
+            "parenthesized_expression" => self.expression_type(expr.named_child(0)?, depth + 1),
+            "field_expression" | "attribute" | "member_expression" | "selector_expression" => {
+                let (object, field) = member_parts(expr)?;
+                let owner = self.expression_type(object, depth + 1)?;
+                self.field_type(&owner, self.text(field), depth + 1)
+            }
+            _ => self.constructor_type(expr, depth),
+        }
+    }
+
+    /// The type an expression constructs: `Foo()`, `Foo::new()`, `new Foo()`,
+    /// `Foo { .. }`, `&Foo{}` or Go's `NewFoo()`.
+    fn constructor_type(&self, expr: Node<'tree>, depth: usize) -> Option<String> {
+        match (self.language, expr.kind()) {
+            (Language::Rust, "call_expression") => {
+                let function = child_by_field(expr, "function")?;
+                let path = child_by_field(function, "path")?;
+                self.named_type(path, expr)
+            }
+            (Language::Rust, "struct_expression") => {
+                self.named_type(child_by_field(expr, "name")?, expr)
+            }
+            (Language::Rust, "reference_expression") => {
+                self.expression_type(child_by_field(expr, "value")?, depth + 1)
+            }
+            // `Foo::open(path)?` is typed as the `Foo` it constructs
+            (Language::Rust, "try_expression") => {
+                self.expression_type(expr.named_child(0)?, depth + 1)
+            }
+            (Language::Python, "call") => {
+                let function = child_by_field(expr, "function")?;
+                let name = match function.kind() {
+                    "attribute" => child_by_field(function, "attribute")?,
+                    _ => function,
+                };
+                capitalized(self.text(name))
+            }
+            (Language::TypeScript | Language::JavaScript, "new_expression") => {
+                type_name(self.text(child_by_field(expr, "constructor")?))
+            }
+            (Language::TypeScript | Language::JavaScript, "await_expression") => {
+                self.expression_type(expr.named_child(0)?, depth + 1)
+            }
+            (Language::Go, "composite_literal") => {
+                type_name(self.text(child_by_field(expr, "type")?))
+            }
+            (Language::Go, "unary_expression") => {
+                self.expression_type(child_by_field(expr, "operand")?, depth + 1)
+            }
+            (Language::Go, "call_expression") => {
+                let function = child_by_field(expr, "function")?;
+                let name = self.text(function).rsplit('.').next()?;
+                capitalized(name.strip_prefix("New")?)
+            }
+            _ => None,
+        }
+    }
+
+    /// A type named in a path, with `Self` standing for the enclosing impl.
+    fn named_type(&self, path: Node<'tree>, at: Node<'tree>) -> Option<String> {
+        match type_name(self.text(path))?.as_str() {
+            "Self" => self.enclosing_type(at),
+            name => capitalized(name),
+        }
+    }
+
+    /// The impl or class a node is in.
+    fn enclosing_type(&self, node: Node<'tree>) -> Option<String> {
+        ancestors(node).find_map(|n| match (self.language, n.kind()) {
+            (Language::Rust, "impl_item") => type_name(self.text(child_by_field(n, "type")?)),
+            (Language::Python, "class_definition")
+            | (
+                Language::TypeScript | Language::JavaScript,
+                "class_declaration" | "abstract_class_declaration" | "class",
+            ) => Some(self.text(child_by_field(n, "name")?).to_string()),
+            _ => None,
+        })
+    }
+
+    /// The type of a variable at a use, from its last binding before the use in
+    /// the innermost function scope that binds it.
+    fn variable_type(&self, at: Node<'tree>, name: &str, depth: usize) -> Option<String> {
+        let scopes = ancestors(at)
+            .filter(|n| self.is_function(*n))
+            .chain(std::iter::once(self.root));
+        for scope in scopes {
+            let binding = TreeCursor::new(scope)
+                .dfs_iter()
+                .take_while(|n| n.start_byte() < at.start_byte())
+                .filter(|n| n.end_byte() <= at.start_byte())
+                .filter(|n| self.function_scope(*n) == Some(scope))
+                .flat_map(|n| self.bindings(n))
+                .filter(|(bound, _)| *bound == name)
+                .map(|(_, binding)| binding)
+                .max_by_key(|binding| binding.start);
+            if let Some(binding) = binding {
+                return self.binding_type(&binding, depth);
+            }
+        }
+        None
+    }
+
+    fn binding_type(&self, binding: &Binding<'tree>, depth: usize) -> Option<String> {
+        match (binding.annotation, binding.value) {
+            (Some(annotation), _) => type_name(self.text(annotation)),
+            (None, Some(value)) => self.expression_type(value, depth + 1),
+            (None, None) => None,
+        }
+    }
+
+    /// The innermost function containing a node, or the root for module-level code.
+    fn function_scope(&self, node: Node<'tree>) -> Option<Node<'tree>> {
+        ancestors(node)
+            .find(|n| self.is_function(*n))
+            .or(Some(self.root))
+    }
+
+    fn is_function(&self, node: Node<'_>) -> bool {
+        let kinds: &[&str] = match self.language {
+            Language::Rust => &["function_item", "closure_expression"],
+            Language::Python => &["function_definition", "lambda"],
+            Language::TypeScript | Language::JavaScript => &[
+                "function_declaration",
+                "function_expression",
+                "generator_function_declaration",
+                "arrow_function",
+                "method_definition",
+            ],
+            Language::Go => &["function_declaration", "method_declaration", "func_literal"],
+        };
+        kinds.contains(&node.kind())
+    }
+
+    /// The variables a node binds, with their annotations and values.
+    fn bindings(&self, node: Node<'tree>) -> Vec<(&str, Binding<'tree>)> {
+        let start = node.start_byte();
+        let single = |name: Option<Node<'tree>>, annotation, value| {
+            name.filter(|n| n.kind() == "identifier")
+                .map(|n| {
+                    vec![(
+                        self.text(n),
+                        Binding {
+                            start,
+                            annotation,
+                            value,
+                        },
+                    )]
+                })
+                .unwrap_or_default()
+        };
+        let field = |name| child_by_field(node, name);
+
+        match (self.language, node.kind()) {
+            (Language::Rust, "let_declaration") => {
+                single(field("pattern"), field("type"), field("value"))
+            }
+            (Language::Rust, "parameter") => single(field("pattern"), field("type"), None),
+            (Language::Python, "assignment") => {
+                single(field("left"), field("type"), field("right"))
+            }
+            (Language::Python, "typed_parameter") => {
+                single(node.named_child(0), field("type"), None)
+            }
+            (Language::Python, "typed_default_parameter") => {
+                single(field("name"), field("type"), None)
+            }
+            (Language::TypeScript | Language::JavaScript, "variable_declarator") => {
+                single(field("name"), field("type"), field("value"))
+            }
+            (Language::TypeScript | Language::JavaScript, "assignment_expression") => {
+                single(field("left"), None, field("right"))
+            }
+            (
+                Language::TypeScript | Language::JavaScript,
+                "required_parameter" | "optional_parameter",
+            ) => single(field("pattern"), field("type"), None),
+            (Language::Go, "short_var_declaration" | "assignment_statement") => {
+                let (Some(left), Some(right)) = (field("left"), field("right")) else {
+                    return Vec::new();
+                };
+                named_children(left)
+                    .zip(named_children(right))
+                    .filter(|(name, _)| name.kind() == "identifier")
+                    .map(|(name, value)| {
+                        (
+                            self.text(name),
+                            Binding {
+                                start,
+                                annotation: None,
+                                value: Some(value),
+                            },
+                        )
+                    })
+                    .collect()
+            }
+            (Language::Go, "var_spec" | "parameter_declaration") => {
+                let annotation = field("type");
+                let values: Vec<_> = field("value")
+                    .map(named_children)
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                node.children_by_field_name("name", &mut node.walk())
+                    .enumerate()
+                    .map(|(i, name)| {
+                        (
+                            self.text(name),
+                            Binding {
+                                start,
+                                annotation,
+                                value: values.get(i).copied(),
+                            },
+                        )
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The type of a field of `owner`, from its declaration on the struct or
+    /// class, or from what the class's methods assign to it.
+    fn field_type(&self, owner: &str, field: &str, depth: usize) -> Option<String> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let definition = TreeCursor::new(self.root).dfs_iter().find(|n| {
+            let kinds: &[&str] = match self.language {
+                Language::Rust => &["struct_item"],
+                Language::Python => &["class_definition"],
+                Language::TypeScript | Language::JavaScript => {
+                    &["class_declaration", "abstract_class_declaration", "class"]
+                }
+                Language::Go => &["type_spec"],
+            };
+            kinds.contains(&n.kind())
+                && child_by_field(*n, "name").is_some_and(|name| self.text(name) == owner)
+        })?;
+
+        TreeCursor::new(definition).dfs_iter().find_map(|node| {
+            // The first binding that yields a type wins
+            let binding = self.field_binding(node, field)?;
+            self.binding_type(&binding, depth)
+        })
+    }
+
+    /// A declaration of or assignment to the field within a struct or class definition.
+    fn field_binding(&self, node: Node<'tree>, field: &str) -> Option<Binding<'tree>> {
+        let start = node.start_byte();
+        match (self.language, node.kind()) {
+            (Language::Rust | Language::Go, "field_declaration") => node
+                .children_by_field_name("name", &mut node.walk())
+                .any(|name| self.text(name) == field)
+                .then(|| Binding {
+                    start,
+                    annotation: child_by_field(node, "type"),
+                    value: None,
+                }),
+            // `store: Store` in the class body, or `self.store = ...` in a method
+            (Language::Python, "assignment") => {
+                let left = child_by_field(node, "left")?;
+                let name = match left.kind() {
+                    "identifier" if self.function_scope(node) == Some(self.root) => left,
+                    "attribute" => match member_parts(left)? {
+                        (object, name) if self.text(object) == "self" => name,
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                (self.text(name) == field).then(|| Binding {
+                    start,
+                    annotation: child_by_field(node, "type"),
+                    value: child_by_field(node, "right"),
+                })
+            }
+            (Language::TypeScript | Language::JavaScript, "public_field_definition") => {
+                (self.text(child_by_field(node, "name")?) == field).then(|| Binding {
+                    start,
+                    annotation: child_by_field(node, "type"),
+                    value: child_by_field(node, "value"),
+                })
+            }
+            // `constructor(private store: Store)` declares a field
+            (Language::TypeScript | Language::JavaScript, "required_parameter") => {
+                let is_field = node
+                    .children(&mut node.walk())
+                    .any(|c| c.kind() == "accessibility_modifier");
+                (is_field && self.text(child_by_field(node, "pattern")?) == field).then(|| {
+                    Binding {
+                        start,
+                        annotation: child_by_field(node, "type"),
+                        value: None,
+                    }
+                })
+            }
+            (Language::TypeScript | Language::JavaScript, "assignment_expression") => {
+                let (object, name) = member_parts(child_by_field(node, "left")?)?;
+                (self.text(object) == "this" && self.text(name) == field).then(|| Binding {
+                    start,
+                    annotation: None,
+                    value: child_by_field(node, "right"),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Splits a member access (`obj.field`, `obj::field`) into object and member.
+fn member_parts(node: Node<'_>) -> Option<(Node<'_>, Node<'_>)> {
+    let (object, member) = match node.kind() {
+        "field_expression" => ("value", "field"),
+        "attribute" => ("object", "attribute"),
+        "member_expression" => ("object", "property"),
+        "selector_expression" => ("operand", "field"),
+        _ => return None,
+    };
+    Some((child_by_field(node, object)?, child_by_field(node, member)?))
+}
+
+fn named_children(node: Node<'_>) -> impl Iterator<Item = Node<'_>> {
+    (0..node.named_child_count()).filter_map(move |i| node.named_child(i))
+}
+
+/// Returns the name if it is capitalized like a type.
+fn capitalized(name: &str) -> Option<String> {
+    name.chars()
+        .next()
+        .is_some_and(char::is_uppercase)
+        .then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calls::extract_calls;
+    use crate::parsing::parse;
+
+    /// Returns (call name, receiver type) for each method call.
+    fn receivers(code: &str, language: Language) -> Vec<(String, Option<String>)> {
+        let tree = parse(code, language).unwrap();
+        extract_calls(&tree, code, language)
+            .into_iter()
+            .filter(|c| c.kind == CallKind::Method)
+            .map(|c| (c.name, c.receiver_type))
+            .collect()
+    }
+
+    fn receiver_of(receivers: &[(String, Option<String>)], name: &str) -> Option<String> {
+        receivers
+            .iter()
+            .find(|(call, _)| call == name)
+            .unwrap_or_else(|| panic!("no call {name}"))
+            .1
+            .clone()
+    }
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("&mut Foo").as_deref(), Some("Foo"));
+        assert_eq!(type_name("&'a Foo<T>").as_deref(), Some("Foo"));
+        assert_eq!(type_name("Arc<dyn Store>").as_deref(), Some("Store"));
+        assert_eq!(type_name(": models.User | null").as_deref(), Some("User"));
+        assert_eq!(type_name("Optional['User']").as_deref(), Some("User"));
+        assert_eq!(type_name("*pkg.Server").as_deref(), Some("Server"));
+        assert_eq!(type_name("[]int"), None);
+    }
+
+    #[test]
+    fn test_rust_receivers() {
+        let code = r#"
+struct Service {
+    store: Arc<Store>,
+}
+
+impl Service {
+    fn run(&self, user: &User, count: usize) {
+        self.store.save();
+        self.validate();
+        user.validate();
+        let order = Order::new();
+        order.submit();
+        let cache: Cache = build();
+        cache.clear();
+        Self::new().start();
+        count.pow(2);
+        build().finish();
+    }
+}
+"#;
+        let receivers = receivers(code, Language::Rust);
+
+        assert_eq!(receiver_of(&receivers, "save").as_deref(), Some("Store"));
+        assert_eq!(
+            receiver_of(&receivers, "self.validate").as_deref(),
+            Some("Service")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "user.validate").as_deref(),
+            Some("User")
+        );
+        assert_eq!(receiver_of(&receivers, "submit").as_deref(), Some("Order"));
+        assert_eq!(receiver_of(&receivers, "clear").as_deref(), Some("Cache"));
+        assert_eq!(receiver_of(&receivers, "start").as_deref(), Some("Service"));
+        assert_eq!(receiver_of(&receivers, "pow").as_deref(), Some("usize"));
+        assert_eq!(receiver_of(&receivers, "finish"), None);
+    }
+
+    #[test]
+    fn test_python_receivers() {
+        let code = r#"
+class Service:
+    cache: Cache
+
+    def __init__(self, db: Database):
+        self.store = models.Store()
+        self.db = db
+
+    def run(self, user: User, order=None):
+        self.store.save()
+        self.db.query()
+        self.cache.clear()
+        user.validate()
+        order.submit()
+        report = Report()
+        report.render()
+
+def main():
+    user.validate()
+"#;
+        let receivers = receivers(code, Language::Python);
+
+        assert_eq!(
+            receiver_of(&receivers, "self.store.save").as_deref(),
+            Some("Store")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "self.db.query").as_deref(),
+            Some("Database")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "self.cache.clear").as_deref(),
+            Some("Cache")
+        );
+        assert_eq!(receiver_of(&receivers, "order.submit"), None);
+        assert_eq!(
+            receiver_of(&receivers, "report.render").as_deref(),
+            Some("Report")
+        );
+        // `user` is only annotated in `run`
+        let validates: Vec<_> = receivers
+            .iter()
+            .filter(|(name, _)| name == "user.validate")
+            .map(|(_, ty)| ty.as_deref())
+            .collect();
+        assert_eq!(validates, vec![Some("User"), None]);
+    }
+
+    #[test]
+    fn test_typescript_receivers() {
+        let code = r#"
+class Service {
+    private cache: Cache;
+
+    constructor(private db: Database) {
+        this.store = new Store();
+    }
+
+    run(user: User) {
+        this.store.save();
+        this.db.query();
+        this.cache.clear();
+        this.validate();
+        user.validate();
+        const report = new reports.Report();
+        report.render();
+    }
+}
+"#;
+        let receivers = receivers(code, Language::TypeScript);
+
+        assert_eq!(
+            receiver_of(&receivers, "this.store.save").as_deref(),
+            Some("Store")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "this.db.query").as_deref(),
+            Some("Database")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "this.cache.clear").as_deref(),
+            Some("Cache")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "this.validate").as_deref(),
+            Some("Service")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "user.validate").as_deref(),
+            Some("User")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "report.render").as_deref(),
+            Some("Report")
+        );
+    }
+
+    #[test]
+    fn test_go_receivers() {
+        let code = r#"
+package service
+
+type Service struct {
+    store *Store
+}
+
+func (s *Service) Run(user User) {
+    s.store.Save()
+    user.Validate()
+    order := &Order{}
+    order.Submit()
+    srv := api.NewServer()
+    srv.Start()
+    fmt.Println("done")
+}
+"#;
+        let receivers = receivers(code, Language::Go);
+
+        assert_eq!(
+            receiver_of(&receivers, "s.store.Save").as_deref(),
+            Some("Store")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "user.Validate").as_deref(),
+            Some("User")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "order.Submit").as_deref(),
+            Some("Order")
+        );
+        assert_eq!(
+            receiver_of(&receivers, "srv.Start").as_deref(),
+            Some("Server")
+        );
+        assert_eq!(receiver_of(&receivers, "fmt.Println"), None);
+    }
+}
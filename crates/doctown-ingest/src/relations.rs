@@ -0,0 +1,359 @@
+//! Type relationship (inheritance and implementation) extraction from ASTs.
+
+use doctown_common::types::{RelationKind, TypeRelation};
+use tree_sitter::{Node, Tree};
+
+use crate::traversal::{
+    child_by_field, find_child_by_kind, find_nodes_by_kind, node_byte_range, node_text,
+};
+
+/// Extract all inheritance and implementation relationships from a parsed syntax tree.
+pub fn extract_type_relations(
+    tree: &Tree,
+    source_code: &str,
+    language: doctown_common::Language,
+) -> Vec<TypeRelation> {
+    match language {
+        doctown_common::Language::Rust => extract_rust_relations(tree, source_code),
+        doctown_common::Language::Python => extract_python_relations(tree, source_code),
+        doctown_common::Language::TypeScript => extract_typescript_relations(tree, source_code),
+        doctown_common::Language::JavaScript => extract_javascript_relations(tree, source_code),
+        doctown_common::Language::Go => extract_go_relations(tree, source_code),
+    }
+}
+
+/// Strip type arguments from a type reference: `Foo<T>` -> `Foo`, `Generic[T]` -> `Generic`.
+fn base_type_name(text: &str) -> String {
+    text.split(['<', '['])
+        .next()
+        .unwrap_or(text)
+        .trim()
+        .trim_start_matches(['&', '*'])
+        .to_string()
+}
+
+fn relation(source: &str, target_node: Node<'_>, kind: RelationKind, src: &str) -> TypeRelation {
+    TypeRelation {
+        source: source.to_string(),
+        target: base_type_name(node_text(target_node, src)),
+        kind,
+        range: node_byte_range(target_node),
+    }
+}
+
+/// Extract relations from Rust source code.
+///
+/// - `impl Trait for Type` -> Type implements Trait
+/// - `trait A: B + C` -> A inherits B and C
+fn extract_rust_relations(tree: &Tree, source_code: &str) -> Vec<TypeRelation> {
+    let mut relations = Vec::new();
+    let root = tree.root_node();
+
+    for node in find_nodes_by_kind(root, "impl_item") {
+        let (Some(trait_node), Some(type_node)) =
+            (child_by_field(node, "trait"), child_by_field(node, "type"))
+        else {
+            continue;
+        };
+        let type_name = base_type_name(node_text(type_node, source_code));
+        relations.push(relation(
+            &type_name,
+            trait_node,
+            RelationKind::Implements,
+            source_code,
+        ));
+    }
+
+    for node in find_nodes_by_kind(root, "trait_item") {
+        let Some(name_node) = child_by_field(node, "name") else {
+            continue;
+        };
+        let Some(bounds) = child_by_field(node, "bounds") else {
+            continue;
+        };
+        let name = node_text(name_node, source_code);
+        for bound in bounds.named_children(&mut bounds.walk()) {
+            // Skip lifetime bounds like `'static`
+            if bound.kind() == "lifetime" {
+                continue;
+            }
+            relations.push(relation(name, bound, RelationKind::Inherits, source_code));
+        }
+    }
+
+    relations
+}
+
+/// Extract relations from Python source code (base classes).
+fn extract_python_relations(tree: &Tree, source_code: &str) -> Vec<TypeRelation> {
+    let mut relations = Vec::new();
+    let root = tree.root_node();
+
+    for node in find_nodes_by_kind(root, "class_definition") {
+        let Some(name_node) = child_by_field(node, "name") else {
+            continue;
+        };
+        let Some(superclasses) = child_by_field(node, "superclasses") else {
+            continue;
+        };
+        let name = node_text(name_node, source_code);
+        for base in superclasses.named_children(&mut superclasses.walk()) {
+            // Keyword arguments like `metaclass=Meta` are not base classes
+            if matches!(base.kind(), "identifier" | "attribute" | "subscript") {
+                relations.push(relation(name, base, RelationKind::Inherits, source_code));
+            }
+        }
+    }
+
+    relations
+}
+
+/// Extract relations from TypeScript source code.
+///
+/// - `class A extends B` -> A inherits B
+/// - `class A implements I` -> A implements I
+/// - `interface A extends B` -> A inherits B
+fn extract_typescript_relations(tree: &Tree, source_code: &str) -> Vec<TypeRelation> {
+    let mut relations = extract_class_heritage_relations(tree, source_code);
+
+    for node in find_nodes_by_kind(tree.root_node(), "interface_declaration") {
+        let Some(name_node) = child_by_field(node, "name") else {
+            continue;
+        };
+        let Some(extends) = find_child_by_kind(node, "extends_type_clause") else {
+            continue;
+        };
+        let name = node_text(name_node, source_code);
+        for base in extends.named_children(&mut extends.walk()) {
+            relations.push(relation(name, base, RelationKind::Inherits, source_code));
+        }
+    }
+
+    relations
+}
+
+/// Extract relations from JavaScript source code.
+fn extract_javascript_relations(tree: &Tree, source_code: &str) -> Vec<TypeRelation> {
+    // JavaScript classes share the TypeScript heritage structure (minus implements)
+    extract_class_heritage_relations(tree, source_code)
+}
+
+/// Extract `extends`/`implements` relations from TS/JS class declarations.
+fn extract_class_heritage_relations(tree: &Tree, source_code: &str) -> Vec<TypeRelation> {
+    let mut relations = Vec::new();
+    let root = tree.root_node();
+
+    for node in find_nodes_by_kind(root, "class_declaration") {
+        let Some(name_node) = child_by_field(node, "name") else {
+            continue;
+        };
+        let Some(heritage) = find_child_by_kind(node, "class_heritage") else {
+            continue;
+        };
+        let name = node_text(name_node, source_code);
+
+        for clause in heritage.named_children(&mut heritage.walk()) {
+            match clause.kind() {
+                // TypeScript: `extends_clause` wraps the base class expression
+                "extends_clause" => {
+                    if let Some(value) = child_by_field(clause, "value") {
+                        relations.push(relation(name, value, RelationKind::Inherits, source_code));
+                    }
+                }
+                "implements_clause" => {
+                    for iface in clause.named_children(&mut clause.walk()) {
+                        relations.push(relation(
+                            name,
+                            iface,
+                            RelationKind::Implements,
+                            source_code,
+                        ));
+                    }
+                }
+                // JavaScript: the base class expression is a direct child of `class_heritage`
+                "identifier" | "member_expression" => {
+                    relations.push(relation(name, clause, RelationKind::Inherits, source_code));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    relations
+}
+
+/// Extract relations from Go source code (interface embedding).
+fn extract_go_relations(tree: &Tree, source_code: &str) -> Vec<TypeRelation> {
+    let mut relations = Vec::new();
+    let root = tree.root_node();
+
+    for spec in find_nodes_by_kind(root, "type_spec") {
+        let Some(name_node) = child_by_field(spec, "name") else {
+            continue;
+        };
+        let Some(type_node) = child_by_field(spec, "type") else {
+            continue;
+        };
+        if type_node.kind() != "interface_type" {
+            continue;
+        }
+        let name = node_text(name_node, source_code);
+
+        // Embedded interfaces appear as `type_elem` children; methods are `method_elem`
+        for elem in type_node.named_children(&mut type_node.walk()) {
+            if elem.kind() != "type_elem" {
+                continue;
+            }
+            for embedded in elem.named_children(&mut elem.walk()) {
+                if matches!(embedded.kind(), "type_identifier" | "qualified_type") {
+                    relations.push(relation(
+                        name,
+                        embedded,
+                        RelationKind::Inherits,
+                        source_code,
+                    ));
+                }
+            }
+        }
+    }
+
+    relations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use doctown_common::Language;
+
+    fn pairs(relations: &[TypeRelation]) -> Vec<(&str, &str, RelationKind)> {
+        relations
+            .iter()
+            .map(|r| (r.source.as_str(), r.target.as_str(), r.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_trait_impl() {
+        let code = r#"
+struct Point;
+impl Point { fn new() -> Self { Point } }
+impl Default for Point { fn default() -> Self { Point } }
+impl<T> std::fmt::Display for Wrapper<T> {}
+"#;
+        let tree = parse(code, Language::Rust).unwrap();
+        let relations = extract_type_relations(&tree, code, Language::Rust);
+
+        assert_eq!(
+            pairs(&relations),
+            vec![
+                ("Point", "Default", RelationKind::Implements),
+                ("Wrapper", "std::fmt::Display", RelationKind::Implements),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rust_supertraits() {
+        let code = "trait Shape: Clone + std::fmt::Debug + 'static {}";
+        let tree = parse(code, Language::Rust).unwrap();
+        let relations = extract_type_relations(&tree, code, Language::Rust);
+
+        assert_eq!(
+            pairs(&relations),
+            vec![
+                ("Shape", "Clone", RelationKind::Inherits),
+                ("Shape", "std::fmt::Debug", RelationKind::Inherits),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_base_classes() {
+        let code = r#"
+class Animal:
+    pass
+
+class Dog(Animal, mixins.Friendly, Generic[T], metaclass=Meta):
+    pass
+"#;
+        let tree = parse(code, Language::Python).unwrap();
+        let relations = extract_type_relations(&tree, code, Language::Python);
+
+        assert_eq!(
+            pairs(&relations),
+            vec![
+                ("Dog", "Animal", RelationKind::Inherits),
+                ("Dog", "mixins.Friendly", RelationKind::Inherits),
+                ("Dog", "Generic", RelationKind::Inherits),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typescript_extends_implements() {
+        let code = r#"
+class Employee extends Person<T> implements Payable, hr.Reviewable {}
+interface Admin extends User, Auditable<Log> {}
+"#;
+        let tree = parse(code, Language::TypeScript).unwrap();
+        let relations = extract_type_relations(&tree, code, Language::TypeScript);
+
+        assert_eq!(
+            pairs(&relations),
+            vec![
+                ("Employee", "Person", RelationKind::Inherits),
+                ("Employee", "Payable", RelationKind::Implements),
+                ("Employee", "hr.Reviewable", RelationKind::Implements),
+                ("Admin", "User", RelationKind::Inherits),
+                ("Admin", "Auditable", RelationKind::Inherits),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_javascript_extends() {
+        let code = "class Button extends ui.Component {}\nclass Plain {}";
+        let tree = parse(code, Language::JavaScript).unwrap();
+        let relations = extract_type_relations(&tree, code, Language::JavaScript);
+
+        assert_eq!(
+            pairs(&relations),
+            vec![("Button", "ui.Component", RelationKind::Inherits)]
+        );
+    }
+
+    #[test]
+    fn test_go_interface_embedding() {
+        let code = r#"
+package main
+
+type ReadWriteCloser interface {
+    io.Reader
+    Writer
+    Close() error
+}
+"#;
+        let tree = parse(code, Language::Go).unwrap();
+        let relations = extract_type_relations(&tree, code, Language::Go);
+
+        assert_eq!(
+            pairs(&relations),
+            vec![
+                ("ReadWriteCloser", "io.Reader", RelationKind::Inherits),
+                ("ReadWriteCloser", "Writer", RelationKind::Inherits),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relation_range_points_at_supertype() {
+        let code = "class Dog(Animal):\n    pass";
+        let tree = parse(code, Language::Python).unwrap();
+        let relations = extract_type_relations(&tree, code, Language::Python);
+
+        assert_eq!(relations.len(), 1);
+        let range = relations[0].range;
+        assert_eq!(&code[range.start..range.end], "Animal");
+    }
+}
@@ -1,132 +1,572 @@
 //! Symbol resolution and call graph construction.
+//!
+//! Names are resolved through scopes, from most to least specific: the calling
+//! file, its module, the symbols it imports, and finally the whole repository.
+//! Names matching several symbols in the best scope are returned as multiple
+//! candidates with split confidence rather than collapsed to one.
+//!
+//! With an [`ImportResolver`], names bound by an import (`helper` after
+//! `from util import helper`, `np.array` after `import numpy as np`) resolve to
+//! the symbols of the imported files, and each candidate records the import.
 
-use doctown_common::ids::SymbolId;
-use doctown_common::types::{Call, Import};
+use doctown_common::ids::{SymbolId, SymbolIdAllocator};
+use doctown_common::types::{Call, CallKind, Import, SymbolKind, TypeReference};
+use doctown_common::Language;
 use std::collections::HashMap;
+use std::path::Path;
 
+use crate::import_resolution::ImportResolver;
+use crate::receiver_types::type_name;
 use crate::symbol::Symbol;
 
-/// A table mapping symbol names to their IDs for resolution.
+/// Path segments that refer to the current crate or module rather than naming one.
+const RELATIVE_SEGMENTS: &[&str] = &["crate", "self", "super", "Self"];
+
+/// The scope in which a name was matched to a symbol, from most to least specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResolutionScope {
+    /// Defined in the file making the reference.
+    SameFile,
+    /// Defined in another file of the same module or package.
+    SameModule,
+    /// Imported by the file making the reference.
+    Imported,
+    /// Found elsewhere in the repository by name alone.
+    Global,
+}
+
+impl ResolutionScope {
+    /// Confidence of a match in this scope when it is the only candidate.
+    pub fn confidence(self) -> f32 {
+        match self {
+            ResolutionScope::SameFile => 1.0,
+            ResolutionScope::SameModule => 0.9,
+            ResolutionScope::Imported => 0.85,
+            ResolutionScope::Global => 0.6,
+        }
+    }
+}
+
+/// A symbol a name may refer to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub symbol_id: SymbolId,
+    pub scope: ResolutionScope,
+    /// Confidence in this candidate (0.0-1.0), split between ambiguous candidates.
+    pub confidence: f32,
+    /// The import that brought the symbol into scope, for imported candidates.
+    pub import: Option<Import>,
+}
+
+/// The candidates a name resolves to, all from the most specific scope that matched.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resolution {
+    pub candidates: Vec<Candidate>,
+}
+
+impl Resolution {
+    /// Returns true if the name matched at least one symbol.
+    pub fn is_resolved(&self) -> bool {
+        !self.candidates.is_empty()
+    }
+
+    /// Returns true if the name matched several symbols in the same scope.
+    pub fn is_ambiguous(&self) -> bool {
+        self.candidates.len() > 1
+    }
+
+    /// Returns the symbol if the name matched exactly one.
+    pub fn unique(&self) -> Option<&SymbolId> {
+        match self.candidates.as_slice() {
+            [only] => Some(&only.symbol_id),
+            _ => None,
+        }
+    }
+
+    /// Builds a resolution splitting the scope's confidence between the symbols.
+    fn scoped(mut ids: Vec<&SymbolId>, scope: ResolutionScope, import: Option<&Import>) -> Self {
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        ids.dedup();
+        let confidence = scope.confidence() / ids.len().max(1) as f32;
+        Self {
+            candidates: ids
+                .into_iter()
+                .map(|id| Candidate {
+                    symbol_id: id.clone(),
+                    scope,
+                    confidence,
+                    import: import.cloned(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A table of symbols for resolving names, indexed by name and qualified name.
 #[derive(Debug, Clone)]
 pub struct SymbolTable {
-    /// Map from symbol name to symbol ID
-    symbols: HashMap<String, SymbolId>,
+    /// Map from symbol name to the IDs of every symbol with that name
+    by_name: HashMap<String, Vec<SymbolId>>,
+    /// Map from qualified name (e.g., "my_crate::net::Client") to symbol ID
+    by_qualified_name: HashMap<String, SymbolId>,
     /// Map from symbol ID to symbol info
     symbol_info: HashMap<SymbolId, SymbolInfo>,
-    /// Imports that are available in the current scope
+    /// Map from file path to its module path
+    file_modules: HashMap<String, String>,
+    /// Imports available in every file
     imports: Vec<Import>,
+    /// Imports of each file
+    file_imports: HashMap<String, Vec<Import>>,
+    /// Maps imports to the files they load
+    import_resolver: Option<ImportResolver>,
+    /// Assigns canonical IDs to added symbols
+    ids: SymbolIdAllocator,
 }
 
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 struct SymbolInfo {
     name: String,
     file_path: String,
+    /// Qualified module path (Rust) or directory of the defining file
+    module: String,
+    /// Type a method belongs to
+    owner: Option<String>,
+}
+
+/// Returns the module a file belongs to when no qualified path is known: its directory.
+fn directory_module(file_path: &str) -> String {
+    Path::new(file_path)
+        .parent()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Splits a module path or file path into its meaningful segments.
+///
+/// `crate::net::client`, `./net/client.ts` and `..net.client` all become
+/// `["net", "client"]`.
+fn path_segments(path: &str) -> Vec<&str> {
+    path.split(['/', '.', ':'])
+        .filter(|s| !s.is_empty() && !RELATIVE_SEGMENTS.contains(s))
+        .collect()
 }
 
 impl SymbolTable {
     /// Create a new empty symbol table.
     pub fn new() -> Self {
         Self {
-            symbols: HashMap::new(),
+            by_name: HashMap::new(),
+            by_qualified_name: HashMap::new(),
             symbol_info: HashMap::new(),
+            file_modules: HashMap::new(),
             imports: Vec::new(),
+            file_imports: HashMap::new(),
+            import_resolver: None,
+            ids: SymbolIdAllocator::new(),
         }
     }
 
+    /// Resolve names bound by imports through the files the imports load.
+    pub fn with_import_resolver(mut self, resolver: ImportResolver) -> Self {
+        self.import_resolver = Some(resolver);
+        self
+    }
+
     /// Add a symbol to the table.
     pub fn add_symbol(&mut self, name: String, symbol_id: SymbolId, file_path: String) {
-        self.symbols.insert(name.clone(), symbol_id.clone());
-        self.symbol_info
-            .insert(symbol_id, SymbolInfo { name, file_path });
+        let module = self.file_module(&file_path);
+        self.insert(name, symbol_id, file_path, module);
+    }
+
+    /// Add a method of `owner` (e.g., `save` of `Store`).
+    ///
+    /// Method calls whose receiver type is known resolve to the methods of
+    /// that type.
+    pub fn add_method(
+        &mut self,
+        owner: &str,
+        name: String,
+        symbol_id: SymbolId,
+        file_path: String,
+    ) {
+        self.add_symbol(name, symbol_id.clone(), file_path);
+        if let Some(info) = self.symbol_info.get_mut(&symbol_id) {
+            info.owner = Some(owner.to_string());
+        }
+    }
+
+    /// Add a symbol with its qualified name (e.g., "my_crate::net::Client").
+    ///
+    /// The qualified name's parent becomes the symbol's module, so files of the
+    /// same Rust module share a scope even across directories.
+    pub fn add_qualified_symbol(
+        &mut self,
+        qualified_name: String,
+        symbol_id: SymbolId,
+        file_path: String,
+    ) {
+        let (module, name) = qualified_name
+            .rsplit_once("::")
+            .unwrap_or(("", qualified_name.as_str()));
+        let (module, name) = (module.to_string(), name.to_string());
+
+        // A file's module is the shortest qualified parent of its symbols
+        // (longer ones are inline modules)
+        let file_module = self
+            .file_modules
+            .entry(file_path.clone())
+            .or_insert_with(|| module.clone());
+        if module.len() < file_module.len() {
+            *file_module = module.clone();
+        }
+
+        self.by_qualified_name
+            .insert(qualified_name, symbol_id.clone());
+        self.insert(name, symbol_id, file_path, module);
+    }
+
+    fn insert(&mut self, name: String, symbol_id: SymbolId, file_path: String, module: String) {
+        let ids = self.by_name.entry(name.clone()).or_default();
+        if !ids.contains(&symbol_id) {
+            ids.push(symbol_id.clone());
+        }
+        self.symbol_info.insert(
+            symbol_id,
+            SymbolInfo {
+                name,
+                file_path,
+                module,
+                owner: None,
+            },
+        );
     }
 
     /// Add multiple symbols from a list.
+    ///
+    /// Symbols get their canonical [`SymbolId`]; symbols sharing a kind and
+    /// path (e.g., overloads) are numbered in the order they are added.
     pub fn add_symbols(&mut self, symbols: &[Symbol], file_path: &str) {
+        let Some(language) = Language::from_path(Path::new(file_path)) else {
+            return;
+        };
         for symbol in symbols {
-            // Generate a simple symbol ID based on name and file
-            let id_string = format!("sym_{}::{}", file_path, symbol.name);
-            if let Ok(symbol_id) = SymbolId::new(id_string) {
-                self.add_symbol(symbol.name.clone(), symbol_id, file_path.to_string());
-            }
+            let symbol_id =
+                self.ids
+                    .allocate(language, symbol.kind, &symbol.canonical_path(file_path));
+            self.add_symbol_with_id(symbol, symbol_id, file_path);
         }
     }
 
-    /// Add imports to the table.
+    /// Add a symbol under an ID allocated elsewhere, such as during ingest.
+    pub fn add_symbol_with_id(&mut self, symbol: &Symbol, symbol_id: SymbolId, file_path: &str) {
+        match &symbol.qualified_name {
+            Some(qualified_name) => self.add_qualified_symbol(
+                qualified_name.clone(),
+                symbol_id.clone(),
+                file_path.to_string(),
+            ),
+            None => self.add_symbol(
+                symbol.name.clone(),
+                symbol_id.clone(),
+                file_path.to_string(),
+            ),
+        }
+        if let Some(info) = self.symbol_info.get_mut(&symbol_id) {
+            info.owner = method_owner(symbol);
+        }
+    }
+
+    /// Add imports available in every file.
     pub fn add_imports(&mut self, imports: Vec<Import>) {
         self.imports.extend(imports);
     }
 
-    /// Look up a symbol by name, returning its ID if found.
+    /// Add the imports of one file.
+    pub fn add_file_imports(&mut self, file_path: &str, imports: Vec<Import>) {
+        self.file_imports
+            .entry(file_path.to_string())
+            .or_default()
+            .extend(imports);
+    }
+
+    /// Look up a symbol by name or qualified name.
+    ///
+    /// Returns None if no symbol or several symbols have this name; use
+    /// [`SymbolTable::lookup_all`] to get every match.
     pub fn lookup(&self, name: &str) -> Option<SymbolId> {
-        self.symbols.get(name).cloned()
+        if let Some(id) = self.by_qualified_name.get(name) {
+            return Some(id.clone());
+        }
+        match self.by_name.get(name).map(Vec::as_slice) {
+            Some([only]) => Some(only.clone()),
+            _ => None,
+        }
     }
 
-    /// Try to resolve a call to a symbol ID.
-    /// Returns Some(symbol_id) if the call can be resolved locally,
-    /// None if it's an external call.
-    pub fn resolve_call(&self, call: &Call) -> Option<SymbolId> {
-        // Simple resolution: try direct name lookup
-        if let Some(id) = self.lookup(&call.name) {
-            return Some(id);
+    /// Returns every symbol with this name.
+    pub fn lookup_all(&self, name: &str) -> &[SymbolId] {
+        self.by_name
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Resolve a name as referenced from `from_file`, or from anywhere if None.
+    ///
+    /// Qualified names (`net::Client::new`) are matched against qualified symbol
+    /// paths first; otherwise, and for method calls (`obj.process`), the last
+    /// segment is matched by name. Candidates are then narrowed to the most
+    /// specific scope that contains any of them.
+    pub fn resolve(&self, name: &str, from_file: Option<&str>) -> Resolution {
+        if let Some(resolution) =
+            from_file.and_then(|from_file| self.resolve_through_imports(name, from_file))
+        {
+            return resolution;
         }
 
-        // For method calls, try to extract just the method name
-        if call.name.contains('.') {
-            // Extract the last part after the dot
-            if let Some(method_name) = call.name.split('.').next_back() {
-                if let Some(id) = self.lookup(method_name) {
-                    return Some(id);
-                }
-            }
+        self.narrow(self.name_candidates(name), from_file)
+    }
+
+    /// Resolve a method call on a receiver of known type to that type's methods.
+    ///
+    /// Falls back to resolving the method name, without the methods of other
+    /// types, when the type has no method of that name (e.g., it is inherited
+    /// or provided by a trait).
+    fn resolve_method(
+        &self,
+        name: &str,
+        receiver_type: &str,
+        from_file: Option<&str>,
+    ) -> Resolution {
+        let method = name.rsplit(['.', ':']).next().unwrap_or(name);
+        let owned_by = |id: &&SymbolId, ty: Option<&str>| {
+            self.symbol_info
+                .get(*id)
+                .is_some_and(|info| info.owner.as_deref() == ty)
+        };
+        let methods: Vec<&SymbolId> = self
+            .lookup_all(method)
+            .iter()
+            .filter(|id| owned_by(id, Some(receiver_type)))
+            .collect();
+        if !methods.is_empty() {
+            return self.narrow(methods, from_file);
+        }
+
+        let mut resolution = self.resolve(name, from_file);
+        resolution.candidates.retain(|candidate| {
+            self.symbol_info
+                .get(&candidate.symbol_id)
+                .is_none_or(|info| info.owner.is_none())
+        });
+        resolution
+    }
+
+    /// Narrow candidates to the most specific scope containing any of them.
+    fn narrow(&self, ids: Vec<&SymbolId>, from_file: Option<&str>) -> Resolution {
+        let scoped: Vec<(&SymbolId, ResolutionScope, Option<&Import>)> = ids
+            .into_iter()
+            .map(|id| {
+                let (scope, import) = self.scope_of(id, from_file);
+                (id, scope, import)
+            })
+            .collect();
+        let Some((_, best, import)) = scoped.iter().min_by_key(|(_, scope, _)| *scope) else {
+            return Resolution::default();
+        };
+
+        let ids = scoped
+            .iter()
+            .filter(|(_, scope, _)| scope == best)
+            .map(|(id, _, _)| *id)
+            .collect();
+        Resolution::scoped(ids, *best, *import)
+    }
+
+    /// Resolve a name bound by one of the file's imports to the symbols of the
+    /// files that import loads.
+    ///
+    /// Bare names defined in the file itself shadow imports.
+    fn resolve_through_imports(&self, name: &str, from_file: &str) -> Option<Resolution> {
+        let resolver = self.import_resolver.as_ref()?;
+        let language = Language::from_path(Path::new(from_file))?;
+        let segments: Vec<&str> = name.split(['.', ':']).filter(|s| !s.is_empty()).collect();
+        let (head, member) = match segments.as_slice() {
+            [] => return None,
+            [only] => (None, *only),
+            [head, .., last] => (Some(*head), *last),
+        };
+        if head.is_none() && self.defined_in(member, from_file) {
+            return None;
         }
 
-        // For Rust associated calls (Type::function), try to extract function name
-        if call.name.contains("::") {
-            if let Some(function_name) = call.name.split("::").last() {
-                if let Some(id) = self.lookup(function_name) {
-                    return Some(id);
+        let imports = self
+            .file_imports
+            .get(from_file)
+            .into_iter()
+            .flatten()
+            .chain(&self.imports);
+        for import in imports {
+            let binding = import_binding(import, language);
+            let items = import.imported_items.as_deref().unwrap_or_default();
+            let member = match head {
+                // `np.array`, `utils.helper`, `Client::new`
+                Some(head) if binding == Some(head) || items.iter().any(|i| i == head) => member,
+                Some(_) => continue,
+                // `use net::connect as dial;` binds `dial` to `connect`
+                None if binding == Some(member) && language == Language::Rust => {
+                    import.module_path.rsplit("::").next().unwrap_or(member)
                 }
+                None if binding == Some(member)
+                    || items.iter().any(|i| i == member)
+                    || (import.is_wildcard && import.alias.is_none()) =>
+                {
+                    member
+                }
+                None => continue,
+            };
+
+            let files = resolver.resolve(from_file, import);
+            // `np.mean` after `import numpy as np` is numpy's, not a local `mean`
+            if files.is_empty() && head.is_some() {
+                return Some(Resolution::default());
+            }
+            let ids: Vec<&SymbolId> = self
+                .lookup_all(member)
+                .iter()
+                .filter(|id| {
+                    self.symbol_info
+                        .get(*id)
+                        .is_some_and(|info| files.contains(&info.file_path))
+                })
+                .collect();
+            if !ids.is_empty() {
+                return Some(Resolution::scoped(
+                    ids,
+                    ResolutionScope::Imported,
+                    Some(import),
+                ));
             }
         }
+        None
+    }
 
-        // Check if the call matches any imported names
-        for import in &self.imports {
-            // Check if it's a direct import match
-            if import.module_path == call.name {
-                // This is an imported module/function - consider it external
-                return None;
-            }
+    /// Returns true if a symbol with this name is defined in the file.
+    fn defined_in(&self, name: &str, file_path: &str) -> bool {
+        self.lookup_all(name).iter().any(|id| {
+            self.symbol_info
+                .get(id)
+                .is_some_and(|info| info.file_path == file_path)
+        })
+    }
 
-            // Check if call matches an imported item
-            if let Some(ref items) = import.imported_items {
-                if items.iter().any(|item| item == &call.name) {
-                    // This is an imported item - external
-                    return None;
-                }
+    /// Returns the symbols a name could refer to, before scoping.
+    fn name_candidates(&self, name: &str) -> Vec<&SymbolId> {
+        // `crate::net::Client::new` matches qualified paths ending in `net::Client::new`
+        let segments: Vec<&str> = name
+            .split("::")
+            .filter(|s| !RELATIVE_SEGMENTS.contains(s))
+            .collect();
+        if segments.len() > 1 {
+            let path = segments.join("::");
+            let suffix = format!("::{}", path);
+            let qualified: Vec<&SymbolId> = self
+                .by_qualified_name
+                .iter()
+                .filter(|(qualified_name, _)| {
+                    **qualified_name == path || qualified_name.ends_with(&suffix)
+                })
+                .map(|(_, id)| id)
+                .collect();
+            if !qualified.is_empty() {
+                return qualified;
             }
+        }
 
-            // Check alias match
-            if let Some(ref alias) = import.alias {
-                if call.name.starts_with(alias) {
-                    // This uses an aliased import - external
-                    return None;
-                }
+        let last = name.rsplit(['.', ':']).next().unwrap_or(name);
+        self.by_name.get(last).into_iter().flatten().collect()
+    }
+
+    /// Returns the module of a file: the module of its qualified symbols, or its directory.
+    fn file_module(&self, file_path: &str) -> String {
+        self.file_modules
+            .get(file_path)
+            .cloned()
+            .unwrap_or_else(|| directory_module(file_path))
+    }
+
+    fn scope_of(
+        &self,
+        symbol_id: &SymbolId,
+        from_file: Option<&str>,
+    ) -> (ResolutionScope, Option<&Import>) {
+        let (Some(info), Some(from_file)) = (self.symbol_info.get(symbol_id), from_file) else {
+            return (ResolutionScope::Global, None);
+        };
+        if info.file_path == from_file {
+            return (ResolutionScope::SameFile, None);
+        }
+        if info.module == self.file_module(from_file) {
+            return (ResolutionScope::SameModule, None);
+        }
+        let mut file_imports = self
+            .file_imports
+            .get(from_file)
+            .into_iter()
+            .flatten()
+            .chain(&self.imports);
+        match file_imports.find(|import| import_names(import, info)) {
+            Some(import) => (ResolutionScope::Imported, Some(import)),
+            None => (ResolutionScope::Global, None),
+        }
+    }
+
+    /// Try to resolve a call to a symbol ID.
+    ///
+    /// Returns Some(symbol_id) if the call names exactly one symbol in the
+    /// repository, None if it is external or ambiguous.
+    pub fn resolve_call(&self, call: &Call) -> Option<SymbolId> {
+        self.resolve_call_in(call, None).unique().cloned()
+    }
+
+    /// Resolve a call made from `file_path` to its candidate symbols.
+    pub fn resolve_call_from(&self, call: &Call, file_path: &str) -> Resolution {
+        self.resolve_call_in(call, Some(file_path))
+    }
+
+    /// Resolve a call, through its receiver's type for typed method calls.
+    fn resolve_call_in(&self, call: &Call, from_file: Option<&str>) -> Resolution {
+        match (&call.kind, &call.receiver_type) {
+            (CallKind::Method, Some(receiver_type)) => {
+                self.resolve_method(&call.name, receiver_type, from_file)
             }
+            _ => self.resolve(&call.name, from_file),
         }
+    }
 
-        // Could not resolve - assume external
-        None
+    /// Try to resolve a type reference to a symbol ID.
+    /// Qualified names (`models::User`, `models.User`) fall back to their last segment.
+    pub fn resolve_type_reference(&self, reference: &TypeReference) -> Option<SymbolId> {
+        self.resolve(&reference.name, None).unique().cloned()
+    }
+
+    /// Resolve a type reference made from `file_path` to its candidate symbols.
+    pub fn resolve_type_reference_from(
+        &self,
+        reference: &TypeReference,
+        file_path: &str,
+    ) -> Resolution {
+        self.resolve(&reference.name, Some(file_path))
     }
 
     /// Get the number of symbols in the table.
     pub fn len(&self) -> usize {
-        self.symbols.len()
+        self.symbol_info.len()
     }
 
     /// Check if the table is empty.
     pub fn is_empty(&self) -> bool {
-        self.symbols.is_empty()
+        self.symbol_info.is_empty()
     }
 
     /// Get all symbol IDs in the table.
@@ -141,21 +581,126 @@ impl Default for SymbolTable {
     }
 }
 
+/// Returns the name an import binds in the importing file, if it binds one name.
+///
+/// `import numpy as np` binds `np`, `import os.path` binds `os`, `use crate::net;`
+/// binds `net` and a Go import binds its package name. Imports of specific
+/// items bind the items instead.
+fn import_binding(import: &Import, language: Language) -> Option<&str> {
+    if let Some(alias) = &import.alias {
+        return Some(alias);
+    }
+    if import.is_wildcard || import.imported_items.is_some() {
+        return None;
+    }
+    let path = import.module_path.as_str();
+    match language {
+        Language::Python => path.split('.').next(),
+        Language::Rust => path.rsplit("::").next(),
+        Language::Go => path.rsplit('/').next(),
+        // Side-effect imports (`import './polyfill'`) bind nothing
+        Language::TypeScript | Language::JavaScript => None,
+    }
+}
+
+/// Returns true if an import brings the symbol into scope.
+///
+/// The import must name the symbol (or be a wildcard), and its module path must
+/// end in the symbol's module or file, so `use std::collections::HashMap` does not
+/// claim a local `HashMap`.
+fn import_names(import: &Import, info: &SymbolInfo) -> bool {
+    let segments = path_segments(&import.module_path);
+    let (module, names_symbol) = match &import.imported_items {
+        Some(items) => (segments.as_slice(), items.contains(&info.name)),
+        None if import.is_wildcard => (segments.as_slice(), true),
+        // `use net::Client;` names its last segment
+        None => match segments.split_last() {
+            Some((last, module)) => (module, *last == info.name),
+            None => (&[][..], false),
+        },
+    };
+    if !names_symbol {
+        return false;
+    }
+
+    if module.is_empty() {
+        // `from . import helper`: relative to the importing package
+        return true;
+    }
+    // The import's module path and the symbol's module path (or its module
+    // path plus file name, for file-based modules) must end the same way
+    let module_segments = path_segments(&info.module);
+    let mut file_segments = module_segments.clone();
+    if let Some(stem) = Path::new(&info.file_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+    {
+        file_segments.push(stem);
+    }
+    [module_segments, file_segments]
+        .iter()
+        .any(|segments| ends_alike(module, segments))
+}
+
+/// Returns the type a method symbol belongs to: the parent of its qualified
+/// name (`store::Store::save`) or its Go receiver (`(s *Store) Save()`).
+fn method_owner(symbol: &Symbol) -> Option<String> {
+    if symbol.kind != SymbolKind::Method {
+        return None;
+    }
+    if let Some((parent, _)) = symbol
+        .qualified_name
+        .as_deref()
+        .and_then(|name| name.rsplit_once("::"))
+    {
+        return type_name(parent);
+    }
+    let receiver = symbol.signature.as_deref()?.strip_prefix('(')?;
+    let (receiver, _) = receiver.split_once(')')?;
+    type_name(receiver.split_whitespace().last()?)
+}
+
+/// Returns true if the shorter path is a suffix of the longer one.
+fn ends_alike(a: &[&str], b: &[&str]) -> bool {
+    let n = a.len().min(b.len());
+    n > 0 && a[a.len() - n..] == b[b.len() - n..]
+}
+
 /// Resolve calls against a symbol table, marking which calls are resolved.
 pub fn resolve_calls(calls: &mut [Call], symbol_table: &SymbolTable) {
     for call in calls {
-        if let Some(_symbol_id) = symbol_table.resolve_call(call) {
-            call.is_resolved = true;
-        } else {
-            call.is_resolved = false;
-        }
+        call.is_resolved = symbol_table.resolve_call_in(call, None).is_resolved();
+    }
+}
+
+/// Resolve calls made from `file_path`, marking which are resolved and returning
+/// each call's candidates.
+pub fn resolve_calls_from(
+    calls: &mut [Call],
+    symbol_table: &SymbolTable,
+    file_path: &str,
+) -> Vec<Resolution> {
+    calls
+        .iter_mut()
+        .map(|call| {
+            let resolution = symbol_table.resolve_call_from(call, file_path);
+            call.is_resolved = resolution.is_resolved();
+            resolution
+        })
+        .collect()
+}
+
+/// Resolve type references against a symbol table, marking which are resolved.
+pub fn resolve_type_references(references: &mut [TypeReference], symbol_table: &SymbolTable) {
+    for reference in references {
+        reference.is_resolved = symbol_table.resolve(&reference.name, None).is_resolved();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use doctown_common::types::{ByteRange, CallKind, SymbolKind, Visibility};
+    use doctown_common::types::{ByteRange, CallKind, SymbolKind, SymbolMetrics, Visibility};
 
     fn create_test_symbol(name: &str) -> Symbol {
         Symbol {
@@ -166,6 +711,10 @@ mod tests {
             signature: None,
             visibility: Visibility::Public,
             is_async: false,
+            is_test: false,
+            metrics: SymbolMetrics::default(),
+            attributes: Vec::new(),
+            qualified_name: None,
         }
     }
 
@@ -208,6 +757,7 @@ mod tests {
             range: ByteRange::new(20, 30),
             kind: CallKind::Function,
             is_resolved: false,
+            receiver_type: None,
         };
 
         resolve_calls(std::slice::from_mut(&mut call), &table);
@@ -224,6 +774,7 @@ mod tests {
             range: ByteRange::new(20, 30),
             kind: CallKind::Function,
             is_resolved: false,
+            receiver_type: None,
         };
 
         resolve_calls(std::slice::from_mut(&mut call), &table);
@@ -242,6 +793,7 @@ mod tests {
             range: ByteRange::new(20, 30),
             kind: CallKind::Method,
             is_resolved: false,
+            receiver_type: None,
         };
 
         resolve_calls(std::slice::from_mut(&mut call), &table);
@@ -260,6 +812,7 @@ mod tests {
             range: ByteRange::new(20, 30),
             kind: CallKind::Associated,
             is_resolved: false,
+            receiver_type: None,
         };
 
         resolve_calls(std::slice::from_mut(&mut call), &table);
@@ -285,6 +838,7 @@ mod tests {
             range: ByteRange::new(20, 30),
             kind: CallKind::Constructor,
             is_resolved: false,
+            receiver_type: None,
         };
 
         resolve_calls(std::slice::from_mut(&mut call), &table);
@@ -292,4 +846,429 @@ mod tests {
         // Imported items are considered external
         assert!(!call.is_resolved);
     }
+
+    #[test]
+    fn test_resolve_type_references() {
+        let mut table = SymbolTable::new();
+        table.add_symbols(
+            &[create_test_symbol("User"), create_test_symbol("Order")],
+            "models.rs",
+        );
+
+        let mut references = vec![
+            TypeReference {
+                name: "User".to_string(),
+                range: ByteRange::new(0, 4),
+                is_resolved: false,
+            },
+            TypeReference {
+                name: "crate::models::Order".to_string(),
+                range: ByteRange::new(10, 30),
+                is_resolved: false,
+            },
+            TypeReference {
+                name: "String".to_string(),
+                range: ByteRange::new(40, 46),
+                is_resolved: false,
+            },
+        ];
+
+        resolve_type_references(&mut references, &table);
+
+        assert!(references[0].is_resolved);
+        assert!(references[1].is_resolved);
+        assert!(!references[2].is_resolved);
+    }
+
+    fn call(name: &str) -> Call {
+        Call {
+            name: name.to_string(),
+            range: ByteRange::new(0, 0),
+            kind: CallKind::Function,
+            is_resolved: false,
+            receiver_type: None,
+        }
+    }
+
+    fn qualified_symbol(name: &str, qualified_name: &str, start: usize) -> Symbol {
+        let mut symbol = create_test_symbol(name);
+        symbol.range = ByteRange::new(start, start + 10);
+        symbol.qualified_name = Some(qualified_name.to_string());
+        symbol
+    }
+
+    /// The canonical ID of a function without a qualified name.
+    fn function_id(file: &str, name: &str) -> String {
+        let language = Language::from_path(Path::new(file)).unwrap();
+        SymbolId::derive(
+            language,
+            SymbolKind::Function,
+            &format!("{}::{}", file, name),
+            0,
+        )
+        .to_string()
+    }
+
+    /// The canonical ID of a Rust function with a qualified name.
+    fn qualified_id(qualified_name: &str) -> String {
+        SymbolId::derive(Language::Rust, SymbolKind::Function, qualified_name, 0).to_string()
+    }
+
+    fn ids(resolution: &Resolution) -> Vec<&str> {
+        resolution
+            .candidates
+            .iter()
+            .map(|c| c.symbol_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_names_kept_apart() {
+        let mut table = SymbolTable::new();
+        table.add_symbols(&[create_test_symbol("run")], "a.rs");
+        table.add_symbols(&[create_test_symbol("run")], "b.rs");
+        table.add_symbols(
+            &[
+                qualified_symbol("new", "app::Client::new", 0),
+                qualified_symbol("new", "app::Server::new", 100),
+            ],
+            "c.rs",
+        );
+
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.lookup_all("run").len(), 2);
+        // Ambiguous names don't silently pick one
+        assert_eq!(table.lookup("run"), None);
+        assert!(table.lookup("app::Server::new").is_some());
+
+        let resolution = table.resolve("run", None);
+        assert!(resolution.is_ambiguous());
+        assert_eq!(resolution.unique(), None);
+        assert!(resolution
+            .candidates
+            .iter()
+            .all(|c| c.scope == ResolutionScope::Global && c.confidence == 0.3));
+    }
+
+    #[test]
+    fn test_scope_preference() {
+        let mut table = SymbolTable::new();
+        table.add_symbols(&[create_test_symbol("helper")], "src/api/handlers.py");
+        table.add_symbols(&[create_test_symbol("helper")], "src/api/util.py");
+        table.add_symbols(&[create_test_symbol("helper")], "src/db/util.py");
+
+        // Same file beats same module
+        let resolution = table.resolve_call_from(&call("helper"), "src/api/handlers.py");
+        assert_eq!(
+            ids(&resolution),
+            vec![function_id("src/api/handlers.py", "helper")]
+        );
+        assert_eq!(resolution.candidates[0].scope, ResolutionScope::SameFile);
+        assert_eq!(resolution.candidates[0].confidence, 1.0);
+
+        // Same module (directory) beats the rest of the repository
+        let resolution = table.resolve_call_from(&call("helper"), "src/api/routes.py");
+        assert_eq!(
+            ids(&resolution),
+            vec![
+                function_id("src/api/handlers.py", "helper"),
+                function_id("src/api/util.py", "helper")
+            ]
+        );
+        assert_eq!(resolution.candidates[0].scope, ResolutionScope::SameModule);
+
+        // Nothing in scope: every match is a global candidate
+        let resolution = table.resolve_call_from(&call("helper"), "src/main.py");
+        assert_eq!(resolution.candidates.len(), 3);
+        assert_eq!(resolution.candidates[0].scope, ResolutionScope::Global);
+    }
+
+    #[test]
+    fn test_imported_scope() {
+        let mut table = SymbolTable::new();
+        table.add_symbols(&[create_test_symbol("helper")], "src/api/util.py");
+        table.add_symbols(&[create_test_symbol("helper")], "src/db/util.py");
+        table.add_symbols(&[create_test_symbol("HashMap")], "src/db/maps.py");
+        table.add_file_imports(
+            "src/main.py",
+            vec![
+                Import {
+                    module_path: "db.util".to_string(),
+                    imported_items: Some(vec!["helper".to_string()]),
+                    alias: None,
+                    range: ByteRange::new(0, 10),
+                    is_wildcard: false,
+                },
+                Import {
+                    module_path: "std::collections::HashMap".to_string(),
+                    imported_items: None,
+                    alias: None,
+                    range: ByteRange::new(10, 20),
+                    is_wildcard: false,
+                },
+            ],
+        );
+
+        let resolution = table.resolve_call_from(&call("helper"), "src/main.py");
+        assert_eq!(
+            ids(&resolution),
+            vec![function_id("src/db/util.py", "helper")]
+        );
+        assert_eq!(resolution.candidates[0].scope, ResolutionScope::Imported);
+
+        // An external import of the same name doesn't claim the local symbol
+        let resolution = table.resolve_call_from(&call("HashMap"), "src/main.py");
+        assert_eq!(resolution.candidates[0].scope, ResolutionScope::Global);
+    }
+
+    #[test]
+    fn test_qualified_call_resolution() {
+        let mut table = SymbolTable::new();
+        table.add_symbols(
+            &[
+                qualified_symbol("new", "app::net::Client::new", 0),
+                qualified_symbol("new", "app::net::Server::new", 100),
+            ],
+            "src/net.rs",
+        );
+        table.add_symbols(&[qualified_symbol("start", "app::start", 0)], "src/lib.rs");
+
+        let resolution = table.resolve_call_from(&call("Client::new"), "src/lib.rs");
+        assert_eq!(
+            resolution.unique(),
+            Some(&SymbolId::new(qualified_id("app::net::Client::new")).unwrap())
+        );
+
+        let resolution = table.resolve_call_from(&call("crate::net::Server::new"), "src/lib.rs");
+        assert_eq!(
+            resolution.unique(),
+            Some(&SymbolId::new(qualified_id("app::net::Server::new")).unwrap())
+        );
+
+        // Unknown qualifier: falls back to the bare name, which is ambiguous
+        let resolution = table.resolve_call_from(&call("Other::new"), "src/lib.rs");
+        assert_eq!(resolution.candidates.len(), 2);
+    }
+
+    fn import(module_path: &str, items: Option<&[&str]>, alias: Option<&str>) -> Import {
+        Import {
+            module_path: module_path.to_string(),
+            imported_items: items.map(|items| items.iter().map(|i| i.to_string()).collect()),
+            alias: alias.map(str::to_string),
+            range: ByteRange::new(0, 0),
+            is_wildcard: false,
+        }
+    }
+
+    /// A table of `helper` functions in several files, resolving imports over them.
+    fn import_table(files: &[&str]) -> SymbolTable {
+        let mut table =
+            SymbolTable::new().with_import_resolver(ImportResolver::new(files.iter().copied()));
+        for file in files {
+            table.add_symbols(&[create_test_symbol("helper")], file);
+        }
+        table
+    }
+
+    #[test]
+    fn test_python_import_resolution() {
+        let mut table = import_table(&["app/util.py", "app/db/util.py", "scripts/util.py"]);
+        table.add_file_imports(
+            "app/main.py",
+            vec![
+                import("app.db.util", Some(&["helper"]), None),
+                import("app.util", None, Some("u")),
+            ],
+        );
+
+        // `from app.db.util import helper`
+        let resolution = table.resolve_call_from(&call("helper"), "app/main.py");
+        assert_eq!(
+            ids(&resolution),
+            vec![function_id("app/db/util.py", "helper")]
+        );
+        let candidate = &resolution.candidates[0];
+        assert_eq!(candidate.scope, ResolutionScope::Imported);
+        assert_eq!(
+            candidate.import.as_ref().map(|i| i.module_path.as_str()),
+            Some("app.db.util")
+        );
+
+        // `import app.util as u` then `u.helper()`
+        let resolution = table.resolve_call_from(&call("u.helper"), "app/main.py");
+        assert_eq!(ids(&resolution), vec![function_id("app/util.py", "helper")]);
+        assert_eq!(
+            resolution.candidates[0]
+                .import
+                .as_ref()
+                .unwrap()
+                .alias
+                .as_deref(),
+            Some("u")
+        );
+
+        // Relative import from inside the package
+        table.add_file_imports(
+            "app/db/models.py",
+            vec![import("..util", Some(&["helper"]), None)],
+        );
+        let resolution = table.resolve_call_from(&call("helper"), "app/db/models.py");
+        assert_eq!(ids(&resolution), vec![function_id("app/util.py", "helper")]);
+    }
+
+    #[test]
+    fn test_local_definition_shadows_import() {
+        let mut table = import_table(&["app/util.py", "app/main.py"]);
+        table.add_file_imports(
+            "app/main.py",
+            vec![import("app.util", Some(&["helper"]), None)],
+        );
+
+        let resolution = table.resolve_call_from(&call("helper"), "app/main.py");
+        assert_eq!(ids(&resolution), vec![function_id("app/main.py", "helper")]);
+        assert_eq!(resolution.candidates[0].scope, ResolutionScope::SameFile);
+        assert_eq!(resolution.candidates[0].import, None);
+    }
+
+    #[test]
+    fn test_member_of_external_import_is_unresolved() {
+        let mut table = import_table(&["app/util.py"]);
+        table.add_file_imports("app/main.py", vec![import("numpy", None, Some("np"))]);
+
+        // `np.helper()` is numpy's, not the repository's `helper`
+        let resolution = table.resolve_call_from(&call("np.helper"), "app/main.py");
+        assert!(!resolution.is_resolved());
+    }
+
+    #[test]
+    fn test_script_and_go_import_resolution() {
+        let files = [
+            "web/util/index.ts",
+            "web/other/util.ts",
+            "svc/db/db.go",
+            "svc/cache/db.go",
+        ];
+        let mut table = SymbolTable::new().with_import_resolver(
+            ImportResolver::new(files).with_go_module("github.com/acme/svc", "svc"),
+        );
+        for file in files {
+            table.add_symbols(&[create_test_symbol("helper")], file);
+        }
+        table.add_file_imports(
+            "web/app.ts",
+            vec![import("./util", Some(&["helper"]), None)],
+        );
+        table.add_file_imports(
+            "svc/main.go",
+            vec![import("github.com/acme/svc/db", None, None)],
+        );
+
+        let resolution = table.resolve_call_from(&call("helper"), "web/app.ts");
+        assert_eq!(
+            ids(&resolution),
+            vec![function_id("web/util/index.ts", "helper")]
+        );
+
+        // Go calls are qualified by the package name
+        let resolution = table.resolve_call_from(&call("db.helper"), "svc/main.go");
+        assert_eq!(
+            ids(&resolution),
+            vec![function_id("svc/db/db.go", "helper")]
+        );
+        assert_eq!(resolution.candidates[0].scope, ResolutionScope::Imported);
+    }
+
+    #[test]
+    fn test_rust_use_resolution() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub mod net;\npub mod disk;\n").unwrap();
+        std::fs::write(root.join("src/net.rs"), "pub fn connect() {}\n").unwrap();
+        std::fs::write(root.join("src/disk.rs"), "pub fn connect() {}\n").unwrap();
+
+        let mut table = SymbolTable::new().with_import_resolver(ImportResolver::build(root));
+        table.add_symbols(
+            &[qualified_symbol("connect", "demo::net::connect", 0)],
+            "src/net.rs",
+        );
+        table.add_symbols(
+            &[qualified_symbol("connect", "demo::disk::connect", 0)],
+            "src/disk.rs",
+        );
+        table.add_file_imports(
+            "src/lib.rs",
+            vec![
+                import("crate::net::connect", None, Some("dial")),
+                import("crate::disk", None, None),
+            ],
+        );
+
+        let resolution = table.resolve_call_from(&call("dial"), "src/lib.rs");
+        assert_eq!(ids(&resolution), vec![qualified_id("demo::net::connect")]);
+
+        let resolution = table.resolve_call_from(&call("disk::connect"), "src/lib.rs");
+        assert_eq!(ids(&resolution), vec![qualified_id("demo::disk::connect")]);
+        assert_eq!(
+            resolution.candidates[0]
+                .import
+                .as_ref()
+                .unwrap()
+                .module_path,
+            "crate::disk"
+        );
+    }
+
+    #[test]
+    fn test_method_call_resolved_by_receiver_type() {
+        let mut table = SymbolTable::new();
+        for (owner, file) in [("Store", "src/store.rs"), ("Order", "src/order.rs")] {
+            table.add_method(
+                owner,
+                "save".to_string(),
+                SymbolId::new(format!("sym_{}::{}::save", file, owner)).unwrap(),
+                file.to_string(),
+            );
+        }
+        // Go methods carry their receiver in the signature
+        let mut submit = create_test_symbol("Submit");
+        submit.kind = SymbolKind::Method;
+        submit.signature = Some("(o *Order) Submit() error".to_string());
+        table.add_symbols(&[submit], "svc/order.go");
+
+        let method_call = |name: &str, receiver_type: Option<&str>| Call {
+            kind: CallKind::Method,
+            receiver_type: receiver_type.map(str::to_string),
+            ..call(name)
+        };
+
+        let resolution =
+            table.resolve_call_from(&method_call("self.store.save", Some("Store")), "src/app.rs");
+        assert_eq!(ids(&resolution), vec!["sym_src/store.rs::Store::save"]);
+
+        // Without a receiver type both methods are candidates
+        let resolution = table.resolve_call_from(&method_call("save", None), "src/app.rs");
+        assert!(resolution.is_ambiguous());
+
+        // Methods of other types are never candidates for a typed receiver
+        let resolution = table.resolve_call_from(&method_call("save", Some("User")), "src/app.rs");
+        assert!(!resolution.is_resolved());
+
+        let resolution =
+            table.resolve_call_from(&method_call("o.Submit", Some("Order")), "svc/main.go");
+        assert_eq!(
+            resolution.unique(),
+            Some(&SymbolId::derive(
+                Language::Go,
+                SymbolKind::Method,
+                "svc/order.go::Submit",
+                0
+            ))
+        );
+    }
 }
@@ -1,7 +1,10 @@
 //! Symbol extraction from ASTs.
-use doctown_common::types::{ByteRange, SymbolKind, Visibility};
+use doctown_common::types::{Attribute, ByteRange, SymbolKind, SymbolMetrics, Visibility};
 use tree_sitter::{Node, Tree};
 
+use crate::attributes::attach_attributes;
+use crate::metrics::attach_metrics;
+use crate::test_code::mark_test_symbols;
 use crate::traversal::{
     ancestors, child_by_field, find_child_by_kind, find_nodes_by_kind, node_byte_range, node_text,
 };
@@ -23,6 +26,28 @@ pub struct Symbol {
     pub visibility: Visibility,
     /// Whether this is an async function
     pub is_async: bool,
+    /// Whether this symbol is test code (a test function, or inside a test module or file)
+    pub is_test: bool,
+    /// Size and complexity metrics
+    pub metrics: SymbolMetrics,
+    /// Attributes and decorators attached to the symbol, in source order
+    pub attributes: Vec<Attribute>,
+    /// Full path in the crate's module tree (e.g. `my_crate::net::Client`), for Rust symbols
+    pub qualified_name: Option<String>,
+}
+
+impl Symbol {
+    /// The path identifying the symbol in its canonical [`SymbolId`]: the
+    /// qualified name where known, otherwise the file path and name
+    /// (e.g. `src/app.py::main`).
+    ///
+    /// [`SymbolId`]: doctown_common::SymbolId
+    pub fn canonical_path(&self, file_path: &str) -> String {
+        match &self.qualified_name {
+            Some(qualified_name) => qualified_name.clone(),
+            None => format!("{}::{}", file_path, self.name),
+        }
+    }
 }
 
 /// Extract all symbols from a parsed syntax tree.
@@ -31,13 +56,31 @@ pub fn extract_symbols(
     source_code: &str,
     language: doctown_common::Language,
 ) -> Vec<Symbol> {
-    match language {
+    let mut symbols = match language {
         doctown_common::Language::Rust => extract_rust_symbols(tree, source_code),
         doctown_common::Language::Python => extract_python_symbols(tree, source_code),
         doctown_common::Language::TypeScript => extract_typescript_symbols(tree, source_code),
         doctown_common::Language::JavaScript => extract_javascript_symbols(tree, source_code),
         doctown_common::Language::Go => extract_go_symbols(tree, source_code),
+    };
+    mark_test_symbols(&mut symbols, tree, source_code, language);
+    attach_metrics(&mut symbols, tree, source_code, language);
+    attach_attributes(&mut symbols, tree, source_code, language);
+    symbols
+}
+
+/// Returns the outermost syntax node spanning exactly the symbol's range.
+pub(crate) fn symbol_node<'tree>(tree: &'tree Tree, symbol: &Symbol) -> Option<Node<'tree>> {
+    let mut node = tree
+        .root_node()
+        .descendant_for_byte_range(symbol.range.start, symbol.range.end)?;
+    while let Some(parent) = node.parent() {
+        if parent.byte_range() != node.byte_range() {
+            break;
+        }
+        node = parent;
     }
+    Some(node)
 }
 
 /// Check if a node is inside an impl or trait block.
@@ -158,6 +201,10 @@ fn extract_rust_function(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -168,9 +215,11 @@ fn extract_visibility(node: Node<'_>, source: &str) -> Visibility {
         None => return Visibility::Private,
     };
 
-    let vis_text = node_text(vis_node, source);
+    visibility_from_text(node_text(vis_node, source))
+}
 
-    // Parse the visibility modifier
+/// Parse a Rust visibility modifier (`pub`, `pub(crate)`, ...).
+pub(crate) fn visibility_from_text(vis_text: &str) -> Visibility {
     match vis_text {
         "pub" => Visibility::Public,
         s if s.starts_with("pub(crate)") => Visibility::PublicCrate,
@@ -229,6 +278,10 @@ fn extract_rust_struct(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -253,6 +306,10 @@ fn extract_rust_enum(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -277,6 +334,10 @@ fn extract_rust_trait(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -313,6 +374,10 @@ fn extract_rust_impl(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility: Visibility::Private, // impl blocks don't have visibility
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -348,6 +413,10 @@ fn extract_rust_module(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -371,6 +440,10 @@ fn extract_rust_const(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -394,6 +467,10 @@ fn extract_rust_static(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -417,6 +494,10 @@ fn extract_rust_type_alias(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -442,6 +523,10 @@ fn extract_rust_macro(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -509,9 +594,6 @@ fn extract_python_function(node: Node<'_>, source: &str) -> Option<Symbol> {
     // Extract signature (function name + parameters + return type annotation)
     let signature = extract_python_function_signature(node, source);
 
-    // Get decorators
-    let _decorators = extract_python_decorators(node, source);
-
     Some(Symbol {
         kind: SymbolKind::Function,
         name,
@@ -520,6 +602,10 @@ fn extract_python_function(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility: Visibility::Public, // Python doesn't have visibility modifiers
         is_async,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -543,27 +629,6 @@ fn extract_python_function_signature(node: Node<'_>, source: &str) -> Option<Str
     ))
 }
 
-/// Extract decorators from a Python function or class.
-fn extract_python_decorators(node: Node<'_>, source: &str) -> Vec<String> {
-    let mut decorators = Vec::new();
-
-    // Look for decorator siblings that appear before this node
-    // In tree-sitter-python, decorators are children of a decorated_definition node
-    if let Some(parent) = node.parent() {
-        if parent.kind() == "decorated_definition" {
-            for i in 0..parent.child_count() {
-                if let Some(child) = parent.child(i) {
-                    if child.kind() == "decorator" {
-                        decorators.push(node_text(child, source).to_string());
-                    }
-                }
-            }
-        }
-    }
-
-    decorators
-}
-
 /// Extract a Python class definition.
 fn extract_python_class(node: Node<'_>, source: &str) -> Option<Symbol> {
     let name_node = child_by_field(node, "name")?;
@@ -582,6 +647,10 @@ fn extract_python_class(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility: Visibility::Public,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -639,6 +708,10 @@ fn extract_python_assignment(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility: Visibility::Public,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -729,6 +802,10 @@ fn extract_ts_function(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -773,6 +850,10 @@ fn extract_ts_arrow_function(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -831,6 +912,10 @@ fn extract_ts_class(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -877,6 +962,10 @@ fn extract_ts_interface(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -908,6 +997,10 @@ fn extract_ts_type_alias(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -991,6 +1084,10 @@ fn extract_go_function(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -1023,6 +1120,10 @@ fn extract_go_method(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -1093,6 +1194,10 @@ fn extract_go_type_spec(node: Node<'_>, source: &str) -> Option<Symbol> {
         signature,
         visibility,
         is_async: false,
+        is_test: false,
+        metrics: SymbolMetrics::default(),
+        attributes: Vec::new(),
+        qualified_name: None,
     })
 }
 
@@ -0,0 +1,280 @@
+//! Test code detection.
+//!
+//! Identifies test files by path convention and test symbols by their syntax:
+//! Rust `#[test]` functions and `#[cfg(test)]` modules, Python `test_*` functions
+//! and `Test*` classes, and JavaScript/TypeScript `describe`/`it`/`test` blocks.
+
+use doctown_common::types::ByteRange;
+use doctown_common::Language;
+use std::path::Path;
+use tree_sitter::Tree;
+
+use crate::attributes::rust_attributes;
+use crate::symbol::Symbol;
+use crate::traversal::{child_by_field, find_nodes_by_kind, node_byte_range, node_text};
+
+/// Directory names whose contents are treated as test code.
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__"];
+
+/// Call names that open a test block in JavaScript/TypeScript test frameworks.
+const JS_TEST_BLOCKS: &[&str] = &["describe", "it", "test"];
+
+/// Returns true if the file at `path` is test code by naming convention.
+///
+/// - Any language: files under a `tests/`, `test/` or `__tests__/` directory
+/// - Rust: files under `benches/`
+/// - Python: `test_*.py`, `*_test.py` and `conftest.py`
+/// - Go: `*_test.go`
+/// - JavaScript/TypeScript: `*.test.*` and `*.spec.*`
+pub fn is_test_file(path: &Path, language: Language) -> bool {
+    let in_test_dir = path
+        .parent()
+        .into_iter()
+        .flat_map(|p| p.components())
+        .filter_map(|c| c.as_os_str().to_str())
+        .any(|dir| TEST_DIRS.contains(&dir) || (language == Language::Rust && dir == "benches"));
+    if in_test_dir {
+        return true;
+    }
+
+    let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+
+    match language {
+        Language::Rust => false,
+        Language::Python => {
+            stem.starts_with("test_") || stem.ends_with("_test") || stem == "conftest"
+        }
+        Language::Go => stem.ends_with("_test"),
+        Language::TypeScript | Language::JavaScript => {
+            file_name.contains(".test.") || file_name.contains(".spec.")
+        }
+    }
+}
+
+/// Set `is_test` on symbols that are test code according to the syntax tree.
+///
+/// A symbol is test code if its name lies within a test region: a test function,
+/// a test module or class, or a test framework block.
+pub fn mark_test_symbols(
+    symbols: &mut [Symbol],
+    tree: &Tree,
+    source_code: &str,
+    language: Language,
+) {
+    let regions = test_regions(tree, source_code, language);
+    if regions.is_empty() {
+        return;
+    }
+    for symbol in symbols {
+        if regions.iter().any(|r| r.contains(&symbol.name_range)) {
+            symbol.is_test = true;
+        }
+    }
+}
+
+/// Returns the byte ranges of test functions, modules and blocks in the tree.
+pub fn test_regions(tree: &Tree, source_code: &str, language: Language) -> Vec<ByteRange> {
+    match language {
+        Language::Rust => rust_test_regions(tree, source_code),
+        Language::Python => python_test_regions(tree, source_code),
+        Language::TypeScript | Language::JavaScript => js_test_regions(tree, source_code),
+        // Go tests are identified by file name alone
+        Language::Go => Vec::new(),
+    }
+}
+
+/// Returns true for `#[test]` and framework variants like `#[tokio::test]`.
+fn is_rust_test_attribute(attribute: &str) -> bool {
+    let inner = attribute
+        .trim_start_matches("#[")
+        .trim_end_matches(']')
+        .trim();
+    let path = inner.split('(').next().unwrap_or(inner).trim();
+    path == "test" || path.ends_with("::test")
+}
+
+fn rust_test_regions(tree: &Tree, source_code: &str) -> Vec<ByteRange> {
+    let root = tree.root_node();
+    let mut regions = Vec::new();
+
+    for node in find_nodes_by_kind(root, "function_item") {
+        if rust_attributes(node, source_code)
+            .iter()
+            .any(|a| is_rust_test_attribute(a))
+        {
+            regions.push(node_byte_range(node));
+        }
+    }
+
+    for node in find_nodes_by_kind(root, "mod_item") {
+        let is_cfg_test = rust_attributes(node, source_code).iter().any(|a| {
+            let compact: String = a.chars().filter(|c| !c.is_whitespace()).collect();
+            compact == "#[cfg(test)]"
+        });
+        if is_cfg_test {
+            regions.push(node_byte_range(node));
+        }
+    }
+
+    regions
+}
+
+fn python_test_regions(tree: &Tree, source_code: &str) -> Vec<ByteRange> {
+    let root = tree.root_node();
+    let mut regions = Vec::new();
+
+    for node in find_nodes_by_kind(root, "function_definition") {
+        if child_by_field(node, "name")
+            .is_some_and(|name| node_text(name, source_code).starts_with("test_"))
+        {
+            regions.push(node_byte_range(node));
+        }
+    }
+
+    for node in find_nodes_by_kind(root, "class_definition") {
+        if child_by_field(node, "name")
+            .is_some_and(|name| node_text(name, source_code).starts_with("Test"))
+        {
+            regions.push(node_byte_range(node));
+        }
+    }
+
+    regions
+}
+
+fn js_test_regions(tree: &Tree, source_code: &str) -> Vec<ByteRange> {
+    let mut regions = Vec::new();
+
+    for node in find_nodes_by_kind(tree.root_node(), "call_expression") {
+        let Some(function) = child_by_field(node, "function") else {
+            continue;
+        };
+        // `describe(...)`, `it.each(...)`, `test.skip(...)`
+        let callee = match function.kind() {
+            "identifier" => node_text(function, source_code),
+            "member_expression" => child_by_field(function, "object")
+                .map(|object| node_text(object, source_code))
+                .unwrap_or_default(),
+            _ => continue,
+        };
+        if JS_TEST_BLOCKS.contains(&callee) {
+            regions.push(node_byte_range(node));
+        }
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use crate::symbol::extract_symbols;
+
+    fn test_names(code: &str, language: Language) -> Vec<String> {
+        let tree = parse(code, language).unwrap();
+        extract_symbols(&tree, code, language)
+            .into_iter()
+            .filter(|s| s.is_test)
+            .map(|s| s.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_test_functions_and_modules() {
+        let code = r#"
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+#[cfg(test)]
+mod tests {
+    fn helper() -> i32 { 1 }
+
+    #[test]
+    fn adds() { assert_eq!(add(1, 2), 3); }
+}
+
+#[tokio::test]
+async fn integration() {}
+"#;
+        assert_eq!(
+            test_names(code, Language::Rust),
+            vec!["helper", "adds", "integration", "tests"]
+        );
+    }
+
+    #[test]
+    fn test_rust_cfg_not_test_is_production() {
+        let code = "#[cfg(feature = \"x\")]\nmod extra { fn f() {} }\n#[inline]\nfn g() {}";
+        assert!(test_names(code, Language::Rust).is_empty());
+    }
+
+    #[test]
+    fn test_python_test_functions_and_classes() {
+        let code = r#"
+def parse(text):
+    return text
+
+def test_parse():
+    assert parse("a") == "a"
+
+class TestParser:
+    def test_empty(self):
+        assert parse("") == ""
+"#;
+        assert_eq!(
+            test_names(code, Language::Python),
+            vec!["test_parse", "TestParser"]
+        );
+    }
+
+    #[test]
+    fn test_javascript_describe_it_blocks() {
+        let code = r#"
+function sum(a, b) { return a + b; }
+
+describe("sum", () => {
+    function fixture() { return [1, 2]; }
+    it.each([[1, 2]])("adds", () => {});
+});
+"#;
+        assert_eq!(test_names(code, Language::JavaScript), vec!["fixture"]);
+    }
+
+    #[test]
+    fn test_is_test_file() {
+        assert!(is_test_file(Path::new("tests/api.rs"), Language::Rust));
+        assert!(is_test_file(Path::new("benches/speed.rs"), Language::Rust));
+        assert!(!is_test_file(Path::new("src/testing.rs"), Language::Rust));
+
+        assert!(is_test_file(
+            Path::new("pkg/test_models.py"),
+            Language::Python
+        ));
+        assert!(is_test_file(
+            Path::new("pkg/models_test.py"),
+            Language::Python
+        ));
+        assert!(is_test_file(Path::new("conftest.py"), Language::Python));
+        assert!(!is_test_file(Path::new("pkg/testing.py"), Language::Python));
+
+        assert!(is_test_file(Path::new("server_test.go"), Language::Go));
+        assert!(!is_test_file(Path::new("server.go"), Language::Go));
+
+        assert!(is_test_file(
+            Path::new("src/app.test.ts"),
+            Language::TypeScript
+        ));
+        assert!(is_test_file(
+            Path::new("src/app.spec.js"),
+            Language::JavaScript
+        ));
+        assert!(is_test_file(
+            Path::new("src/__tests__/app.js"),
+            Language::JavaScript
+        ));
+        assert!(!is_test_file(Path::new("src/app.ts"), Language::TypeScript));
+    }
+}
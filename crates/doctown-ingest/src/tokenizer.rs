@@ -0,0 +1,163 @@
+//! Token counting with the embedding model's tokenizer.
+//!
+//! The embedding model only sees a fixed window of tokens, so chunk sizes
+//! are best measured in the same tokens it uses. This module wraps the
+//! bundled MiniLM `tokenizer.json` for counting tokens and mapping them back
+//! to byte offsets in the source.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use doctown_common::DocError;
+use tokenizers::Tokenizer;
+
+/// Path of the bundled MiniLM tokenizer, relative to the repository root.
+pub const DEFAULT_TOKENIZER_PATH: &str = "models/minilm-l6/tokenizer.json";
+
+/// Maximum sequence length of the embedding model, in tokens.
+///
+/// This includes the special tokens (such as `</s>`) the tokenizer adds to
+/// every input.
+pub const MODEL_MAX_TOKENS: usize = 256;
+
+/// A token in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// Byte offset where the token starts.
+    pub start: usize,
+    /// Byte offset where the token ends.
+    pub end: usize,
+    /// Index of the word the token belongs to.
+    ///
+    /// Pieces of the same whitespace-separated word share an index, so
+    /// chunks can be cut between words.
+    pub word: Option<u32>,
+}
+
+/// Counts tokens the way the embedding model does.
+///
+/// Cheap to clone; the underlying tokenizer is shared.
+#[derive(Clone)]
+pub struct TokenCounter {
+    tokenizer: Arc<Tokenizer>,
+    special_tokens: usize,
+}
+
+impl TokenCounter {
+    /// Loads a tokenizer from a `tokenizer.json` file.
+    ///
+    /// Truncation and padding configured in the file are disabled, so counts
+    /// reflect the full text rather than what the model would keep.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, DocError> {
+        let path = path.as_ref();
+        let mut tokenizer = Tokenizer::from_file(path).map_err(|e| {
+            DocError::Internal(format!(
+                "Failed to load tokenizer {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        tokenizer
+            .with_truncation(None)
+            .map_err(|e| DocError::Internal(format!("Failed to configure tokenizer: {}", e)))?;
+        tokenizer.with_padding(None);
+
+        let special_tokens = tokenizer
+            .encode("", true)
+            .map_err(|e| DocError::Internal(format!("Failed to tokenize: {}", e)))?
+            .len();
+
+        Ok(Self {
+            tokenizer: Arc::new(tokenizer),
+            special_tokens,
+        })
+    }
+
+    /// Number of special tokens the model adds to every input.
+    pub fn special_tokens(&self) -> usize {
+        self.special_tokens
+    }
+
+    /// Number of tokens in `text`, excluding special tokens.
+    pub fn count(&self, text: &str) -> usize {
+        self.tokens(text).len()
+    }
+
+    /// Tokens of `text` with their byte offsets, excluding special tokens.
+    ///
+    /// Text the tokenizer cannot handle yields no tokens.
+    pub fn tokens(&self, text: &str) -> Vec<Token> {
+        let Ok(encoding) = self.tokenizer.encode(text, false) else {
+            return Vec::new();
+        };
+
+        encoding
+            .get_offsets()
+            .iter()
+            .zip(encoding.get_word_ids())
+            .map(|(&(start, end), &word)| Token { start, end, word })
+            .collect()
+    }
+}
+
+impl fmt::Debug for TokenCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCounter")
+            .field("special_tokens", &self.special_tokens)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn bundled_counter() -> TokenCounter {
+    TokenCounter::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../models/minilm-l6/tokenizer.json"
+    ))
+    .expect("bundled tokenizer should load")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_special_tokens() {
+        let counter = bundled_counter();
+        // The bundled tokenizer appends `</s>` to every input
+        assert_eq!(counter.special_tokens(), 1);
+    }
+
+    #[test]
+    fn test_count_word_pieces() {
+        let counter = bundled_counter();
+        assert_eq!(counter.count(""), 0);
+        assert_eq!(counter.count("hello world"), 2);
+        // Identifiers split into several pieces and punctuation
+        assert!(counter.count("fn parse_tokenizer_config() {}") > 5);
+    }
+
+    #[test]
+    fn test_count_ignores_file_truncation() {
+        let counter = bundled_counter();
+        let text = "word ".repeat(1000);
+        assert_eq!(counter.count(&text), 1000);
+    }
+
+    #[test]
+    fn test_token_offsets_are_bytes() {
+        let counter = bundled_counter();
+        let text = "é foo";
+        let tokens = counter.tokens(text);
+        let foo: Vec<_> = tokens.iter().filter(|t| t.word == Some(1)).collect();
+        assert_eq!(foo.first().unwrap().start, 3);
+        assert_eq!(foo.last().unwrap().end, text.len());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let result = TokenCounter::from_file("/nonexistent/tokenizer.json");
+        assert!(matches!(result, Err(DocError::Internal(_))));
+    }
+}
@@ -0,0 +1,354 @@
+//! Type reference extraction from ASTs.
+//!
+//! Collects the named types a piece of code mentions in parameter and return
+//! types, fields, and local annotations. References are reported for the whole
+//! tree; callers attribute them to symbols by byte range.
+
+use doctown_common::types::{ByteRange, TypeReference};
+use tree_sitter::{Node, Tree};
+
+use crate::traversal::{find_nodes_by_kind, node_byte_range, node_text};
+
+/// Extract all type references from a parsed syntax tree.
+pub fn extract_type_references(
+    tree: &Tree,
+    source_code: &str,
+    language: doctown_common::Language,
+) -> Vec<TypeReference> {
+    match language {
+        doctown_common::Language::Rust => extract_rust_type_refs(tree, source_code),
+        doctown_common::Language::Python => extract_python_type_refs(tree, source_code),
+        doctown_common::Language::TypeScript => extract_typescript_type_refs(tree, source_code),
+        // JavaScript has no type annotations
+        doctown_common::Language::JavaScript => Vec::new(),
+        doctown_common::Language::Go => extract_go_type_refs(tree, source_code),
+    }
+}
+
+/// Returns the type references that fall within the given range (e.g., a symbol's body).
+pub fn type_references_in_range<'a>(
+    references: &'a [TypeReference],
+    range: &ByteRange,
+) -> impl Iterator<Item = &'a TypeReference> {
+    let range = *range;
+    references.iter().filter(move |r| range.contains(&r.range))
+}
+
+fn type_reference(node: Node<'_>, source_code: &str) -> TypeReference {
+    let text = node_text(node, source_code);
+    TypeReference {
+        name: text.split(['<', '[']).next().unwrap_or(text).to_string(),
+        range: node_byte_range(node),
+        is_resolved: false,
+    }
+}
+
+/// Returns true if `node` is the `name` field of its parent and the parent is one of `kinds`.
+fn is_name_of(node: Node<'_>, kinds: &[&str]) -> bool {
+    node.parent().is_some_and(|parent| {
+        kinds.contains(&parent.kind()) && parent.child_by_field_name("name") == Some(node)
+    })
+}
+
+/// Walk `node` collecting type identifiers.
+///
+/// Nodes of a `qualified` kind are recorded whole (e.g., `std::io::Error`) without
+/// descending into their segments. Subtrees for which `skip` returns true are ignored.
+fn collect_type_refs(
+    node: Node<'_>,
+    source_code: &str,
+    simple: &[&str],
+    qualified: &[&str],
+    skip: &dyn Fn(Node<'_>) -> bool,
+    refs: &mut Vec<TypeReference>,
+) {
+    if skip(node) {
+        return;
+    }
+    if qualified.contains(&node.kind()) {
+        refs.push(type_reference(node, source_code));
+        return;
+    }
+    if simple.contains(&node.kind()) {
+        refs.push(type_reference(node, source_code));
+    }
+    for child in node.named_children(&mut node.walk()) {
+        collect_type_refs(child, source_code, simple, qualified, skip, refs);
+    }
+}
+
+/// Extract type references from Rust source code.
+fn extract_rust_type_refs(tree: &Tree, source_code: &str) -> Vec<TypeReference> {
+    let mut refs = Vec::new();
+
+    let skip = |node: Node<'_>| {
+        if node.kind() == "type_parameters" {
+            return true;
+        }
+        if is_name_of(
+            node,
+            &[
+                "struct_item",
+                "enum_item",
+                "union_item",
+                "trait_item",
+                "type_item",
+                "associated_type",
+            ],
+        ) {
+            return true;
+        }
+        // `impl Trait for Type` and supertraits are captured as type relations
+        let Some(parent) = node.parent() else {
+            return false;
+        };
+        (parent.kind() == "impl_item" && parent.child_by_field_name("trait") == Some(node))
+            || (parent.kind() == "trait_item" && parent.child_by_field_name("bounds") == Some(node))
+            || node_text(node, source_code) == "Self"
+    };
+
+    collect_type_refs(
+        tree.root_node(),
+        source_code,
+        &["type_identifier"],
+        &["scoped_type_identifier"],
+        &skip,
+        &mut refs,
+    );
+
+    refs
+}
+
+/// Extract type references from Python annotations.
+fn extract_python_type_refs(tree: &Tree, source_code: &str) -> Vec<TypeReference> {
+    let mut refs = Vec::new();
+    let no_skip = |_: Node<'_>| false;
+
+    // Parameter, return, and variable annotations are all wrapped in `type` nodes
+    for node in find_nodes_by_kind(tree.root_node(), "type") {
+        // Nested `type` nodes (e.g., `List[User]`) are handled by the outermost one
+        if node.parent().is_some_and(|p| has_type_ancestor(p)) {
+            continue;
+        }
+        collect_type_refs(
+            node,
+            source_code,
+            &["identifier"],
+            &["attribute"],
+            &no_skip,
+            &mut refs,
+        );
+    }
+
+    refs
+}
+
+fn has_type_ancestor(node: Node<'_>) -> bool {
+    let mut current = Some(node);
+    while let Some(n) = current {
+        if n.kind() == "type" {
+            return true;
+        }
+        current = n.parent();
+    }
+    false
+}
+
+/// Extract type references from TypeScript source code.
+fn extract_typescript_type_refs(tree: &Tree, source_code: &str) -> Vec<TypeReference> {
+    let mut refs = Vec::new();
+
+    let skip = |node: Node<'_>| {
+        matches!(
+            node.kind(),
+            // Heritage clauses are captured as type relations
+            "class_heritage" | "extends_type_clause" | "type_parameters"
+        ) || is_name_of(
+            node,
+            &[
+                "class_declaration",
+                "abstract_class_declaration",
+                "interface_declaration",
+                "type_alias_declaration",
+                "enum_declaration",
+            ],
+        )
+    };
+
+    collect_type_refs(
+        tree.root_node(),
+        source_code,
+        &["type_identifier"],
+        &["nested_type_identifier"],
+        &skip,
+        &mut refs,
+    );
+
+    refs
+}
+
+/// Extract type references from Go source code.
+fn extract_go_type_refs(tree: &Tree, source_code: &str) -> Vec<TypeReference> {
+    let mut refs = Vec::new();
+
+    let skip = |node: Node<'_>| {
+        if is_name_of(node, &["type_spec", "type_alias"]) {
+            return true;
+        }
+        // Embedded interfaces are captured as type relations
+        node.kind() == "type_elem" && node.parent().is_some_and(|p| p.kind() == "interface_type")
+    };
+
+    collect_type_refs(
+        tree.root_node(),
+        source_code,
+        &["type_identifier"],
+        &["qualified_type"],
+        &skip,
+        &mut refs,
+    );
+
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+    use doctown_common::Language;
+
+    fn names(refs: &[TypeReference]) -> Vec<&str> {
+        refs.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rust_type_refs() {
+        let code = r#"
+struct Order {
+    customer: Customer,
+    items: Vec<LineItem>,
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), Error> {
+        let total: Money = Money::zero();
+        Ok(())
+    }
+}
+"#;
+        let tree = parse(code, Language::Rust).unwrap();
+        let refs = extract_type_references(&tree, code, Language::Rust);
+
+        assert_eq!(
+            names(&refs),
+            vec![
+                "Customer",
+                "Vec",
+                "LineItem",
+                "Order",
+                "std::fmt::Formatter",
+                "Result",
+                "Error",
+                "Money",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rust_skips_definitions_and_generics() {
+        let code = "struct Wrapper<T> { inner: T }\ntrait Shape: Clone {}\ntype Alias = Target;";
+        let tree = parse(code, Language::Rust).unwrap();
+        let refs = extract_type_references(&tree, code, Language::Rust);
+
+        // `T` in the field is still a reference; it simply won't resolve
+        assert_eq!(names(&refs), vec!["T", "Target"]);
+    }
+
+    #[test]
+    fn test_python_annotations() {
+        let code = r#"
+class Service(Base):
+    repo: models.Repository
+
+    def get(self, user_id: int, ctx: Context) -> List[User]:
+        result: Optional[User] = None
+        return result
+"#;
+        let tree = parse(code, Language::Python).unwrap();
+        let refs = extract_type_references(&tree, code, Language::Python);
+
+        assert_eq!(
+            names(&refs),
+            vec![
+                "models.Repository",
+                "int",
+                "Context",
+                "List",
+                "User",
+                "Optional",
+                "User",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typescript_type_refs() {
+        let code = r#"
+interface Props extends Base { user: User; tags: Array<Tag> }
+class View<T> implements Widget {
+    render(props: Props): ui.Element { return null; }
+}
+"#;
+        let tree = parse(code, Language::TypeScript).unwrap();
+        let refs = extract_type_references(&tree, code, Language::TypeScript);
+
+        assert_eq!(
+            names(&refs),
+            vec!["User", "Array", "Tag", "Props", "ui.Element"]
+        );
+    }
+
+    #[test]
+    fn test_javascript_has_no_type_refs() {
+        let code = "function f(a, b) { return a + b; }";
+        let tree = parse(code, Language::JavaScript).unwrap();
+        assert!(extract_type_references(&tree, code, Language::JavaScript).is_empty());
+    }
+
+    #[test]
+    fn test_go_type_refs() {
+        let code = r#"
+package main
+
+type Server struct {
+    cfg  Config
+    log  *log.Logger
+}
+
+type Handler interface {
+    Reader
+    Serve(req Request) Response
+}
+"#;
+        let tree = parse(code, Language::Go).unwrap();
+        let refs = extract_type_references(&tree, code, Language::Go);
+
+        assert_eq!(
+            names(&refs),
+            vec!["Config", "log.Logger", "Request", "Response"]
+        );
+    }
+
+    #[test]
+    fn test_type_references_in_range() {
+        let code = "fn a(x: Foo) {}\nfn b(y: Bar) {}";
+        let tree = parse(code, Language::Rust).unwrap();
+        let refs = extract_type_references(&tree, code, Language::Rust);
+
+        let first_fn = ByteRange::new(0, 15);
+        let in_a: Vec<_> = type_references_in_range(&refs, &first_fn)
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(in_a, vec!["Foo"]);
+    }
+}
@@ -0,0 +1,37 @@
+//! Tests for the content hashes shared by duplicate chunks.
+
+mod common;
+
+use common::{ingest_dir, payloads};
+use doctown_ingest::archive::ProcessingOptions;
+use doctown_ingest::DedupedChunks;
+
+#[tokio::test]
+async fn test_duplicate_chunks_share_content_hash() {
+    let helper = "pub fn clamp(x: i32) -> i32 {\n    x.max(0).min(100)\n}\n";
+    let (summary, events) = ingest_dir(
+        &[
+            ("util.rs", helper),
+            ("copy/util.rs", helper),
+            ("main.rs", "fn main() {}\n"),
+        ],
+        &ProcessingOptions::new(),
+    )
+    .await;
+
+    let chunks = payloads(&events, "ingest.chunk_created.v1");
+    let hash_of = |path: &str| {
+        chunks
+            .iter()
+            .find(|c| c["file_path"] == path)
+            .map(|c| c["content_hash"].clone())
+            .unwrap()
+    };
+    assert_eq!(hash_of("util.rs"), hash_of("copy/util.rs"));
+    assert_ne!(hash_of("util.rs"), hash_of("main.rs"));
+
+    let deduped = DedupedChunks::new(&summary.chunks);
+    assert_eq!(summary.chunks.len(), 3);
+    assert_eq!(deduped.unique_count(), 2);
+    assert_eq!(deduped.duplicate_count(), 1);
+}
@@ -0,0 +1,119 @@
+//! Tests for the file, symbol and sub-symbol hierarchy of code chunks.
+
+mod common;
+
+use common::{ingest_dir, payloads};
+use doctown_assembly::{ChunkInfo, PackRequest, Packer, SourceFileInfo};
+use doctown_common::{ChunkLevel, JobId};
+use doctown_docpack::DocpackReader;
+use doctown_ingest::archive::ProcessingOptions;
+use doctown_ingest::IngestOutput;
+use std::collections::HashMap;
+
+/// Test that a method's parent chunk travels from ingest to the docpack source map
+#[tokio::test]
+async fn test_parent_chunk_reaches_source_map() {
+    let (mut summary, events) = ingest_dir(
+        &[(
+            "lib.rs",
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn parse(&self) -> bool {\n        true\n    }\n}\n",
+        )],
+        &ProcessingOptions::new().with_hierarchy(true),
+    )
+    .await;
+
+    let chunks = payloads(&events, "ingest.chunk_created.v1");
+    let chunk_id = |level: &str, kind: Option<&str>| {
+        chunks
+            .iter()
+            .find(|c| c["level"] == level && kind.is_none_or(|k| c["symbol_kind"] == k))
+            .map(|c| c["chunk_id"].as_str().unwrap().to_string())
+            .unwrap()
+    };
+    let file_chunk = chunk_id("file", None);
+    let impl_chunk = chunk_id("symbol", Some("impl"));
+    let method_chunk = chunk_id("sub_symbol", Some("method"));
+
+    // Every chunk is embedded, and the output records where it sits
+    let vectors = summary
+        .chunk_contents
+        .iter()
+        .map(|(id, _)| (id.clone(), vec![0.1, 0.2, 0.3]))
+        .collect();
+    let context = doctown_events::Context::new(
+        JobId::new("job_hierarchy").unwrap(),
+        "https://github.com/test/repo",
+    );
+    let mut output = IngestOutput::new(&context);
+    output.add_chunks(std::mem::take(&mut summary.chunk_contents), vectors);
+    output.set_hierarchy(&summary.chunk_hierarchy);
+
+    let embedded: HashMap<&str, _> = output
+        .chunks
+        .iter()
+        .map(|c| (c.chunk_id.as_str(), c))
+        .collect();
+    assert_eq!(
+        embedded[method_chunk.as_str()].level,
+        Some(ChunkLevel::SubSymbol)
+    );
+    assert_eq!(
+        embedded[method_chunk.as_str()].parent_chunk_id.as_deref(),
+        Some(impl_chunk.as_str())
+    );
+    assert_eq!(
+        embedded[impl_chunk.as_str()].parent_chunk_id.as_deref(),
+        Some(file_chunk.as_str())
+    );
+    assert_eq!(embedded[file_chunk.as_str()].parent_chunk_id, None);
+
+    // Pack the chunks with the parents from the output
+    let source_chunks = chunks
+        .iter()
+        .map(|c| {
+            let chunk_id = c["chunk_id"].as_str().unwrap();
+            ChunkInfo {
+                chunk_id: chunk_id.to_string(),
+                byte_range: (
+                    c["byte_range"]["start"].as_u64().unwrap() as usize,
+                    c["byte_range"]["end"].as_u64().unwrap() as usize,
+                ),
+                symbol_ids: c["symbol_id"]
+                    .as_str()
+                    .map(str::to_string)
+                    .into_iter()
+                    .collect(),
+                parent_chunk_id: embedded[chunk_id].parent_chunk_id.clone(),
+            }
+        })
+        .collect();
+    let request = PackRequest {
+        repo_url: "https://github.com/test/repo".to_string(),
+        git_ref: "main".to_string(),
+        commit_hash: None,
+        source_files: vec![SourceFileInfo {
+            file_path: "lib.rs".to_string(),
+            language: "rust".to_string(),
+            chunks: source_chunks,
+        }],
+        cluster_assignments: HashMap::new(),
+        cluster_labels: HashMap::new(),
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        documents: Vec::new(),
+        externals: Vec::new(),
+        embeddings: None,
+        symbol_contexts: None,
+        deterministic_timestamp: None,
+    };
+    let docpack = Packer::new().pack(request).unwrap();
+
+    let reader = DocpackReader::read(&docpack.docpack_bytes).unwrap();
+    let source_map = reader.source_map();
+    let method = source_map.files[0]
+        .chunks
+        .iter()
+        .find(|c| c.chunk_id == method_chunk)
+        .unwrap();
+    assert_eq!(method.parent_chunk_id.as_deref(), Some(impl_chunk.as_str()));
+}
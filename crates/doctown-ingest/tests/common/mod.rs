@@ -0,0 +1,52 @@
+//! Helpers shared by the ingest integration tests.
+
+#![allow(dead_code)]
+
+use doctown_common::JobId;
+use doctown_events::Envelope;
+use doctown_ingest::archive::{process_files, ProcessingOptions, ProcessingSummary};
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+use tokio::sync::mpsc;
+
+/// Ingests the files of a repository extracted under `demo-main/`, returning the
+/// processing summary and every event emitted along the way.
+pub async fn ingest_dir(
+    files: &[(&str, &str)],
+    options: &ProcessingOptions,
+) -> (ProcessingSummary, Vec<Envelope<Value>>) {
+    let dir = tempdir().unwrap();
+    let repo = dir.path().join("demo-main");
+    for (path, content) in files {
+        let path = repo.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    let (tx, mut rx) = mpsc::channel(100);
+    let context = doctown_events::Context::new(
+        JobId::new("job_test").unwrap(),
+        "https://github.com/test/repo",
+    );
+    let events = tokio::spawn(async move {
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        events
+    });
+    let summary = process_files(dir.path(), context, tx, options)
+        .await
+        .unwrap();
+    (summary, events.await.unwrap())
+}
+
+/// The payloads of the events of one type.
+pub fn payloads<'a>(events: &'a [Envelope<Value>], event_type: &str) -> Vec<&'a Value> {
+    events
+        .iter()
+        .filter(|e| e.event_type == event_type)
+        .map(|e| &e.payload)
+        .collect()
+}
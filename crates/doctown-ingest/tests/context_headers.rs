@@ -0,0 +1,34 @@
+//! Tests for the context headers prepended to the text sent for embedding.
+
+mod common;
+
+use common::{ingest_dir, payloads};
+use doctown_ingest::archive::ProcessingOptions;
+
+#[tokio::test]
+async fn test_context_headers_prefix_embedding_input_only() {
+    let (summary, events) = ingest_dir(
+        &[(
+            "net.rs",
+            "/// Opens a connection.\npub fn connect(addr: &str) -> bool {\n    true\n}\n",
+        )],
+        &ProcessingOptions::new().with_context_headers(true),
+    )
+    .await;
+
+    // The stored chunk keeps the raw code
+    let chunks = payloads(&events, "ingest.chunk_created.v1");
+    assert_eq!(chunks.len(), 1);
+    let content = chunks[0]["content"].as_str().unwrap();
+    assert!(content.starts_with("pub fn connect"));
+
+    // The text sent for embedding carries the context header
+    assert_eq!(summary.chunks.len(), 1);
+    assert_eq!(
+        summary.chunks[0].1,
+        format!(
+            "File: net.rs\nSignature: connect(addr: &str) -> bool\nDoc: Opens a connection.\n\n{}",
+            content
+        )
+    );
+}
@@ -0,0 +1,55 @@
+//! Tests for the ingest output handed to assembly.
+
+mod common;
+
+use common::ingest_dir;
+use doctown_common::JobId;
+use doctown_ingest::archive::ProcessingOptions;
+use doctown_ingest::IngestOutput;
+
+#[tokio::test]
+async fn test_summary_builds_ingest_output() {
+    let (mut summary, _) = ingest_dir(
+        &[
+            ("lib.rs", "pub fn add(a: i32, b: i32) -> i32 { a + b }\n"),
+            ("README.md", "# Usage\n\nCall `add`.\n"),
+        ],
+        &ProcessingOptions::new(),
+    )
+    .await;
+
+    assert_eq!(summary.symbols.len(), 1);
+    assert_eq!(summary.documents.len(), 1);
+    assert_eq!(summary.chunk_contents.len(), 2);
+
+    // Embed only the code chunk
+    let code_chunk = summary.symbols[0].chunk_ids[0].clone();
+    let vectors = summary
+        .chunk_contents
+        .iter()
+        .filter(|(id, _)| id.as_str() == code_chunk)
+        .map(|(id, _)| (id.clone(), vec![0.1, 0.2, 0.3]))
+        .collect();
+    let context = doctown_events::Context::new(
+        JobId::new("job_output").unwrap(),
+        "https://github.com/test/repo",
+    );
+    let mut output = IngestOutput::new(&context)
+        .with_symbols(std::mem::take(&mut summary.symbols))
+        .with_documents(std::mem::take(&mut summary.documents));
+    output.add_chunks(std::mem::take(&mut summary.chunk_contents), vectors);
+
+    assert_eq!(output.chunks.len(), 1);
+    assert_eq!(
+        output.chunks[0].content,
+        "pub fn add(a: i32, b: i32) -> i32 { a + b }"
+    );
+    assert_eq!(output.unembedded_chunk_ids, output.documents[0].chunk_ids);
+
+    let json = serde_json::to_value(&output).unwrap();
+    assert_eq!(json["job_id"], "job_output");
+    assert_eq!(json["symbols"][0]["name"], "add");
+    assert_eq!(json["symbols"][0]["kind"], "function");
+    assert_eq!(json["documents"][0]["heading"], "Usage");
+    assert_eq!(json["documents"][0]["mentions"][0], "add");
+}
@@ -0,0 +1,36 @@
+//! Tests for the section chunks of Markdown documents.
+
+mod common;
+
+use common::{ingest_dir, payloads};
+use doctown_ingest::archive::ProcessingOptions;
+
+#[tokio::test]
+async fn test_markdown_files_chunked_by_section() {
+    let (summary, events) = ingest_dir(
+        &[
+            (
+                "README.md",
+                "# Demo\n\nA demo crate.\n\n## Usage\n\nCall `Client::connect()`.\n\n```rust\nlet c = Client::connect();\n```\n",
+            ),
+            ("docs/empty.md", ""),
+        ],
+        &ProcessingOptions::new(),
+    )
+    .await;
+
+    assert!(payloads(&events, "ingest.file_skipped.v1").is_empty());
+    let documents = payloads(&events, "ingest.document_chunk_created.v1");
+
+    assert_eq!(summary.files_processed, 2);
+    assert_eq!(summary.chunks_created, 2);
+    assert_eq!(summary.chunks.len(), 2);
+    assert_eq!(documents.len(), 2);
+
+    let usage = documents[1];
+    assert_eq!(usage["file_path"], "README.md");
+    assert_eq!(usage["heading"], "Usage");
+    assert_eq!(usage["heading_path"], serde_json::json!(["Demo", "Usage"]));
+    assert_eq!(usage["mentions"], serde_json::json!(["Client::connect"]));
+    assert_eq!(usage["code_blocks"][0]["language"], "rust");
+}
@@ -0,0 +1,58 @@
+//! Tests for the parse warnings emitted for files with syntax errors.
+
+mod common;
+
+use common::{ingest_dir, payloads};
+use doctown_ingest::archive::ProcessingOptions;
+
+/// Test that files with syntax errors emit parse warnings, and mostly broken files
+/// fall back to a single file-level chunk
+#[tokio::test]
+async fn test_parse_warnings_and_file_chunk_fallback() {
+    let (summary, events) = ingest_dir(
+        &[
+            ("clean.rs", "fn ok() {}\n"),
+            (
+                "minor.rs",
+                "fn first() {}\n\nfn second() {\n    let x = 1\n}\n",
+            ),
+            (
+                "garbage.rs",
+                "@@@ ### $$$ %%% ^^^ &&& *** !!! ~~~ @@@ ### $$$\nfn tail() {}\n",
+            ),
+        ],
+        &ProcessingOptions::new(),
+    )
+    .await;
+
+    let warnings = payloads(&events, "ingest.file_parse_warning.v1");
+    assert_eq!(warnings.len(), 2, "Only the broken files should warn");
+
+    let minor = warnings
+        .iter()
+        .find(|w| w["file_path"] == "minor.rs")
+        .unwrap();
+    assert_eq!(minor["missing_nodes"], 1);
+    assert_eq!(minor["error_lines"][0]["start_line"], 4);
+    assert_eq!(minor["fell_back_to_file_chunk"], false);
+
+    let garbage = warnings
+        .iter()
+        .find(|w| w["file_path"] == "garbage.rs")
+        .unwrap();
+    assert_eq!(garbage["fell_back_to_file_chunk"], true);
+
+    // The fallback chunk covers the whole file and has no symbol
+    let garbage_chunks: Vec<_> = payloads(&events, "ingest.chunk_created.v1")
+        .into_iter()
+        .filter(|p| p["file_path"] == "garbage.rs")
+        .collect();
+    assert_eq!(garbage_chunks.len(), 1);
+    assert!(garbage_chunks[0].get("symbol_name").is_none());
+    assert_eq!(garbage_chunks[0]["byte_range"]["start"], 0);
+
+    assert_eq!(summary.files_processed, 3);
+    assert_eq!(summary.files_with_parse_errors, 2);
+    assert_eq!(summary.files_fallback_chunked, 1);
+    assert!(summary.parse_error_nodes >= 2);
+}
@@ -0,0 +1,57 @@
+//! Tests for the module-tree paths and symbol IDs of Rust chunks.
+
+mod common;
+
+use common::{ingest_dir, payloads};
+use doctown_common::{Language, SymbolId, SymbolKind};
+use doctown_ingest::archive::ProcessingOptions;
+use serde_json::Value;
+
+/// Test that Rust chunks carry their full path in the crate's module tree
+#[tokio::test]
+async fn test_rust_chunks_have_qualified_names() {
+    let (_, events) = ingest_dir(
+        &[
+            (
+                "Cargo.toml",
+                "[package]\nname = \"demo-net\"\nversion = \"0.1.0\"\n",
+            ),
+            ("src/lib.rs", "pub mod net;\n\npub fn init() {}\n"),
+            ("src/net/mod.rs", "pub struct Client;\n"),
+            ("src/orphan.rs", "pub fn unused() {}\n"),
+        ],
+        &ProcessingOptions::new(),
+    )
+    .await;
+
+    let chunks = payloads(&events, "ingest.chunk_created.v1");
+    let field = |name: &str, field: &str| {
+        chunks
+            .iter()
+            .find(|c| c["symbol_name"] == name)
+            .map(|c| c[field].clone())
+            .unwrap()
+    };
+
+    assert_eq!(field("init", "qualified_name"), "demo_net::init");
+    assert_eq!(field("net", "qualified_name"), "demo_net::net");
+    assert_eq!(field("Client", "qualified_name"), "demo_net::net::Client");
+    // Files outside the module tree have no path
+    assert_eq!(field("unused", "qualified_name"), Value::Null);
+
+    // Symbol IDs are derived from the qualified name, or the file path without one
+    let canonical = |path| {
+        serde_json::to_value(SymbolId::derive(
+            Language::Rust,
+            SymbolKind::Function,
+            path,
+            0,
+        ))
+        .unwrap()
+    };
+    assert_eq!(field("init", "symbol_id"), canonical("demo_net::init"));
+    assert_eq!(
+        field("unused", "symbol_id"),
+        canonical("src/orphan.rs::unused")
+    );
+}
@@ -0,0 +1,262 @@
+//! Tests for the links between symbols recorded during ingest.
+
+mod common;
+
+use common::ingest_dir;
+use doctown_assembly::{AssembleRequest, EdgeKind, Graph, GraphBuilder};
+use doctown_common::{DependencyOrigin, ExternalSymbol, JobId};
+use doctown_ingest::archive::{ProcessingOptions, ProcessingSummary};
+use doctown_ingest::{IngestOutput, SymbolRecord};
+
+/// Processes the files of a repository extracted under `demo-main/`.
+async fn process(files: &[(&str, &str)]) -> ProcessingSummary {
+    ingest_dir(files, &ProcessingOptions::new()).await.0
+}
+
+/// Builds assembly's symbol graph from the ingest output of `summary`.
+fn assemble(summary: &ProcessingSummary) -> Graph {
+    let context = doctown_events::Context::new(
+        JobId::new("job_links").unwrap(),
+        "https://github.com/test/repo",
+    );
+    let output = IngestOutput::new(&context).with_symbols(summary.symbols.clone());
+    let request: AssembleRequest =
+        serde_json::from_value(serde_json::to_value(&output).unwrap()).unwrap();
+
+    let mut builder = GraphBuilder::new();
+    request.add_symbol_graph(&mut builder);
+    builder.build()
+}
+
+fn has_edge(graph: &Graph, source: &str, target: &str, kind: EdgeKind) -> bool {
+    graph
+        .edges
+        .iter()
+        .any(|e| e.source == source && e.target == target && e.kind == kind)
+}
+
+fn symbol<'a>(summary: &'a ProcessingSummary, name: &str) -> &'a SymbolRecord {
+    summary
+        .symbols
+        .iter()
+        .find(|s| s.name == name && s.kind != doctown_common::SymbolKind::Impl)
+        .unwrap_or_else(|| panic!("no symbol named {}", name))
+}
+
+fn id(summary: &ProcessingSummary, name: &str) -> String {
+    symbol(summary, name).symbol_id.to_string()
+}
+
+#[tokio::test]
+async fn test_relations_link_to_symbol_ids_across_files() {
+    let summary = process(&[
+        (
+            "Cargo.toml",
+            "[package]\nname = \"shapes\"\nversion = \"0.1.0\"\n",
+        ),
+        ("src/area.rs", "pub trait Area {}\n\npub trait Shape: Area {}\n"),
+        (
+            "src/lib.rs",
+            "mod area;\n\nuse crate::area::Area;\n\npub struct Square;\n\nimpl Area for Square {}\n\nimpl std::fmt::Debug for Square {\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { Ok(()) }\n}\n",
+        ),
+        ("models.py", "class Animal:\n    pass\n"),
+        ("dog.py", "from models import Animal\n\nclass Dog(Animal):\n    pass\n"),
+    ])
+    .await;
+
+    let square = symbol(&summary, "Square");
+    assert_eq!(square.implements, vec![id(&summary, "Area")]);
+    assert!(square.inherits.is_empty());
+
+    assert_eq!(
+        symbol(&summary, "Shape").inherits,
+        vec![id(&summary, "Area")]
+    );
+    assert_eq!(
+        symbol(&summary, "Dog").inherits,
+        vec![id(&summary, "Animal")]
+    );
+}
+
+#[tokio::test]
+async fn test_type_references_become_graph_edges() {
+    let summary = process(&[
+        ("models.py", "class User:\n    pass\n"),
+        (
+            "service.py",
+            "from models import User\n\n\ndef load(user_id: int) -> User:\n    return User()\n",
+        ),
+    ])
+    .await;
+
+    let load = symbol(&summary, "load");
+    assert_eq!(load.references, vec![id(&summary, "User")]);
+
+    let graph = assemble(&summary);
+    assert!(has_edge(
+        &graph,
+        &id(&summary, "load"),
+        &id(&summary, "User"),
+        EdgeKind::References
+    ));
+}
+
+#[tokio::test]
+async fn test_external_dependencies_are_classified_from_manifests() {
+    let summary = process(&[
+        (
+            "Cargo.toml",
+            "[package]\nname = \"fetcher\"\nversion = \"0.1.0\"\n\n[dependencies]\nreqwest = \"0.12\"\n",
+        ),
+        (
+            "src/lib.rs",
+            "use reqwest::Client;\n\npub fn fetch() {\n    let client = Client::new();\n    std::fs::read(\"x\").unwrap();\n}\n",
+        ),
+        ("stats.py", "import numpy as np\n\n\ndef mean(xs):\n    return np.mean(xs)\n"),
+    ])
+    .await;
+
+    let client = ExternalSymbol::new("reqwest::Client", "reqwest", DependencyOrigin::ThirdParty);
+    let fetch = symbol(&summary, "fetch");
+    assert!(fetch.external_calls.contains(&client));
+    assert!(fetch
+        .external_calls
+        .iter()
+        .any(|e| e.package == "std" && e.origin == DependencyOrigin::Stdlib));
+    assert_eq!(fetch.external_imports, vec![client.clone()]);
+
+    let mean = symbol(&summary, "mean");
+    assert_eq!(
+        mean.external_calls,
+        vec![ExternalSymbol::new(
+            "numpy.mean",
+            "numpy",
+            DependencyOrigin::ThirdParty
+        )]
+    );
+    assert_eq!(mean.external_imports[0].name, "numpy");
+
+    let graph = assemble(&summary);
+    assert!(has_edge(
+        &graph,
+        &id(&summary, "fetch"),
+        &client.node_id(),
+        EdgeKind::Calls
+    ));
+    assert!(has_edge(
+        &graph,
+        &id(&summary, "fetch"),
+        &client.node_id(),
+        EdgeKind::Imports
+    ));
+}
+
+#[tokio::test]
+async fn test_calls_and_imports_link_to_symbol_ids() {
+    let summary = process(&[
+        ("utils.py", "def slugify(text):\n    return text.lower()\n"),
+        (
+            "views.py",
+            "from utils import slugify\n\n\ndef title(text):\n    return text.title()\n\n\ndef render(text):\n    return slugify(title(text))\n",
+        ),
+    ])
+    .await;
+
+    let render = symbol(&summary, "render");
+    let mut calls = render.calls.clone();
+    calls.sort();
+    let mut expected = vec![id(&summary, "slugify"), id(&summary, "title")];
+    expected.sort();
+    assert_eq!(calls, expected);
+    assert_eq!(render.imports, vec![id(&summary, "slugify")]);
+
+    let graph = assemble(&summary);
+    let render = id(&summary, "render");
+    assert!(has_edge(
+        &graph,
+        &render,
+        &id(&summary, "title"),
+        EdgeKind::Calls
+    ));
+    assert!(has_edge(
+        &graph,
+        &render,
+        &id(&summary, "slugify"),
+        EdgeKind::Calls
+    ));
+    assert!(has_edge(
+        &graph,
+        &render,
+        &id(&summary, "slugify"),
+        EdgeKind::Imports
+    ));
+    assert!(!has_edge(
+        &graph,
+        &render,
+        &id(&summary, "title"),
+        EdgeKind::Imports
+    ));
+}
+
+#[tokio::test]
+async fn test_resolved_calls_record_their_import() {
+    let summary = process(&[
+        ("utils.py", "def slugify(text):\n    return text.lower()\n"),
+        (
+            "views.py",
+            "import utils as u\n\n\ndef title(text):\n    return text.title()\n\n\ndef render(text):\n    return u.slugify(title(text))\n",
+        ),
+    ])
+    .await;
+
+    // Only the call through the import records one
+    let render = symbol(&summary, "render");
+    assert_eq!(
+        render.call_imports,
+        vec![(id(&summary, "slugify"), "utils".to_string())]
+    );
+
+    // And it survives the ingest output written for assembly
+    let context = doctown_events::Context::new(
+        JobId::new("job_links").unwrap(),
+        "https://github.com/test/repo",
+    );
+    let output = IngestOutput::new(&context).with_symbols(summary.symbols.clone());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ingest.json");
+    output.write_to(&path).unwrap();
+    let read = IngestOutput::read_from(&path).unwrap();
+    let render = read.symbols.iter().find(|s| s.name == "render").unwrap();
+    assert_eq!(
+        render.call_imports,
+        vec![(id(&summary, "slugify"), "utils".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn test_ambiguous_calls_keep_their_candidates() {
+    let summary = process(&[
+        ("a.py", "def helper():\n    pass\n"),
+        ("b.py", "def helper():\n    pass\n"),
+        ("main.py", "def run():\n    helper()\n"),
+    ])
+    .await;
+
+    let helpers: Vec<String> = summary
+        .symbols
+        .iter()
+        .filter(|s| s.name == "helper")
+        .map(|s| s.symbol_id.to_string())
+        .collect();
+    assert_eq!(helpers.len(), 2);
+
+    // Neither candidate is called, but both are kept with split confidence
+    let run = symbol(&summary, "run");
+    assert!(run.calls.is_empty());
+    assert_eq!(run.candidate_calls.len(), 2);
+    for (candidate, confidence) in &run.candidate_calls {
+        assert!(helpers.contains(candidate));
+        assert!(*confidence > 0.0 && *confidence < 0.5);
+    }
+    assert_eq!(summary.ambiguous_calls, 1);
+}
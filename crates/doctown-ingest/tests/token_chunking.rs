@@ -0,0 +1,51 @@
+//! Tests for splitting code chunks to fit the embedding model's token budget.
+
+mod common;
+
+use common::{ingest_dir, payloads};
+use doctown_ingest::archive::ProcessingOptions;
+use doctown_ingest::{ChunkingConfig, TokenBudget, TokenCounter};
+
+#[tokio::test]
+async fn test_oversized_function_split_into_budgeted_chunks() {
+    let counter = TokenCounter::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../models/minilm-l6/tokenizer.json"
+    ))
+    .unwrap();
+    let budget = TokenBudget::new(counter.clone()).with_max_tokens(64);
+    let content_tokens = budget.content_tokens();
+
+    let body: String = (0..40)
+        .map(|i| format!("    let value_{i} = compute(value_{i}, {i});\n"))
+        .collect();
+    let source = format!("pub fn small() {{}}\n\npub fn large() {{\n{body}}}\n");
+    let (summary, events) = ingest_dir(
+        &[("lib.rs", &source)],
+        &ProcessingOptions::new().with_chunking(ChunkingConfig::new().with_token_budget(budget)),
+    )
+    .await;
+
+    let chunks = payloads(&events, "ingest.chunk_created.v1");
+    let large = summary.symbols.iter().find(|s| s.name == "large").unwrap();
+    let small = summary.symbols.iter().find(|s| s.name == "small").unwrap();
+    assert!(large.chunk_ids.len() > 1, "the large function is split");
+    assert_eq!(small.chunk_ids.len(), 1);
+    assert_eq!(chunks.len(), large.chunk_ids.len() + 1);
+
+    // Every split fits the budget, names its symbol, and they cover the function in order
+    let splits: Vec<_> = chunks
+        .into_iter()
+        .filter(|c| c["symbol_name"] == "large")
+        .collect();
+    assert_eq!(splits.len(), large.chunk_ids.len());
+    let mut end = source.find("pub fn large").unwrap();
+    for split in splits {
+        let content = split["content"].as_str().unwrap();
+        assert!(counter.count(content) <= content_tokens);
+        assert_eq!(split["symbol_id"], large.symbol_id.to_string());
+        assert_eq!(split["byte_range"]["start"], end);
+        end = split["byte_range"]["end"].as_u64().unwrap() as usize;
+    }
+    assert_eq!(end, source.trim_end().len());
+}